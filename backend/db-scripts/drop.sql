@@ -1,6 +1,26 @@
+DROP TABLE IDEMPOTENT_REQUEST;
+DROP TABLE USER_SESSION;
+DROP TABLE APP_USER;
+DROP TABLE CONTRACT_DEPOSIT;
+DROP TABLE CONTRACT_FIELD_VALUE;
+DROP TABLE CUSTOMER_FIELD_VALUE;
+DROP TABLE CUSTOM_FIELD;
+DROP TABLE CONTRACT_TAG;
+DROP TABLE CUSTOMER_TAG;
+DROP TABLE TAG;
+DROP TABLE CUSTOMER_NOTE;
 DROP TABLE PAYMENT;
+DROP TABLE INVOICE_LINE;
 DROP TABLE INVOICE;
+DROP TABLE CONTRACT_ADDON;
+DROP TABLE ADDON;
 DROP TABLE CONTRACT;
+DROP TABLE BUNDLE_SUBSCRIPTION;
+DROP TABLE BUNDLE;
+DROP TABLE PROMOTION;
+DROP TABLE CUSTOMER_MERGE;
+DROP TABLE CUSTOMER_CONTACT;
+DROP TABLE CUSTOMER_ADDRESS;
 DROP TABLE CUSTOMER;
 DROP TABLE SUBSCRIPTION;
 
@@ -16,12 +16,15 @@ END get_unpaid_invoices_proc;
 
 CREATE OR REPLACE TYPE invoice_row AS OBJECT
 (
-    id          NUMBER,
-    contract_id NUMBER,
-    status      NVARCHAR2(10),
-    amount      NUMBER,
-    issue_date  DATE,
-    due_date    DATE
+    id             NUMBER,
+    contract_id    NUMBER,
+    status         NVARCHAR2(10),
+    amount         NUMBER,
+    issue_date     DATE,
+    due_date       DATE,
+    vat_rate       NUMBER,
+    reverse_charge NUMBER,
+    version        NUMBER
 );
 
 CREATE OR REPLACE TYPE invoice_table AS TABLE OF invoice_row;
@@ -37,7 +40,8 @@ BEGIN
                           AND i.STATUS = 'UNPAID')
         LOOP
             PIPE ROW (invoice_row(invoice_rec.ID, invoice_rec.CONTRACT_ID, invoice_rec.STATUS, invoice_rec.AMOUNT,
-                                  invoice_rec.ISSUE_DATE, invoice_rec.DUE_DATE));
+                                  invoice_rec.ISSUE_DATE, invoice_rec.DUE_DATE, invoice_rec.VAT_RATE,
+                                  invoice_rec.REVERSE_CHARGE, invoice_rec.VERSION));
         END LOOP;
     RETURN;
 END get_unpaid_invoices;
@@ -46,11 +50,16 @@ END get_unpaid_invoices;
 
 CREATE OR REPLACE TYPE contract_row AS OBJECT
 (
-    id              NUMBER,
-    customer_id     NUMBER,
-    subscription_id NUMBER,
-    start_date      DATE,
-    end_date        DATE
+    id                 NUMBER,
+    customer_id        NUMBER,
+    subscription_id    NUMBER,
+    bundle_id          NUMBER,
+    start_date         DATE,
+    end_date           DATE,
+    penalty_rate       NUMBER,
+    promotion_id       NUMBER,
+    service_address_id NUMBER,
+    version            NUMBER
 );
 
 CREATE OR REPLACE TYPE contract_table AS TABLE OF contract_row;
@@ -64,7 +73,9 @@ BEGIN
                          WHERE CUSTOMER_ID = p_customer_id)
         LOOP
             PIPE ROW (contract_row(contract_rec.ID, contract_rec.CUSTOMER_ID, contract_rec.SUBSCRIPTION_ID,
-                                   contract_rec.START_DATE, contract_rec.END_DATE));
+                                   contract_rec.BUNDLE_ID, contract_rec.START_DATE, contract_rec.END_DATE,
+                                   contract_rec.PENALTY_RATE, contract_rec.PROMOTION_ID,
+                                   contract_rec.SERVICE_ADDRESS_ID, contract_rec.VERSION));
         END LOOP;
     RETURN;
 END get_contracts;
@@ -80,7 +91,8 @@ BEGIN
                         WHERE CONTRACT_ID = p_contract_id)
         LOOP
             PIPE ROW (invoice_row(invoice_rec.ID, invoice_rec.CONTRACT_ID, invoice_rec.STATUS, invoice_rec.AMOUNT,
-                                  invoice_rec.ISSUE_DATE, invoice_rec.DUE_DATE));
+                                  invoice_rec.ISSUE_DATE, invoice_rec.DUE_DATE, invoice_rec.VAT_RATE,
+                                  invoice_rec.REVERSE_CHARGE, invoice_rec.VERSION));
         END LOOP;
     RETURN;
 END get_invoices;
@@ -92,7 +104,8 @@ CREATE OR REPLACE TYPE payment_row AS OBJECT
     id           NUMBER,
     invoice_id   NUMBER,
     amount       NUMBER,
-    payment_date DATE
+    payment_date DATE,
+    version      NUMBER
 );
 
 CREATE OR REPLACE TYPE payment_table AS TABLE OF payment_row;
@@ -106,7 +119,7 @@ BEGIN
                         WHERE INVOICE_ID = p_invoice_id)
         LOOP
             PIPE ROW (payment_row(payment_rec.ID, payment_rec.INVOICE_ID, payment_rec.AMOUNT,
-                                  payment_rec.PAYMENT_DATE));
+                                  payment_rec.PAYMENT_DATE, payment_rec.VERSION));
         END LOOP;
     RETURN;
 END get_payments;
\ No newline at end of file
@@ -1,7 +1,14 @@
 -- Insert data
 
 INSERT INTO customer (name, fullname, address, phone, cnp)
-VALUES ('John', 'John Doe', 'Some address', '0123456789', '1234567890123');
+VALUES ('John', 'John Doe', 'Some address', '+40722123456', '1900101400012');
+
+INSERT INTO customer_address (customer_id, address_type, street, street_number, city, county, postal_code)
+VALUES ((SELECT id FROM customer WHERE name = 'John'), 'SERVICE', 'Strada Exemplu', '1', 'București',
+        'București - Sector 1', '010011');
+
+INSERT INTO customer_contact (customer_id, role, name, email)
+VALUES ((SELECT id FROM customer WHERE name = 'John'), 'PRIMARY', 'John Doe', 'john.doe@example.com');
 
 INSERT INTO subscription (description, type, traffic, price, extra_traffic_price)
 VALUES ('Some description', 'MOBILE', 100, 10, 1);
@@ -26,4 +33,34 @@ VALUES ((SELECT id
                                  WHERE contract_id = (SELECT id
                                                       FROM contract
                                                       WHERE customer_id = (SELECT id FROM customer WHERE name = 'John')))),
-        TO_DATE('01-01-2019', 'DD-MM-YYYY'), 10);
\ No newline at end of file
+        TO_DATE('01-01-2019', 'DD-MM-YYYY'), 10);
+
+INSERT INTO customer_note (customer_id, contract_id, kind, category, author, body, pinned)
+VALUES ((SELECT id FROM customer WHERE name = 'John'),
+        (SELECT id FROM contract WHERE customer_id = (SELECT id FROM customer WHERE name = 'John')), 'CALL',
+        'TECHNICAL', 'Support', 'Reported a slow connection in the evening, scheduled a line check.', 1);
+
+INSERT INTO tag (name, description)
+VALUES ('VIP', 'High value customer');
+INSERT INTO tag (name, description)
+VALUES ('RESELLER', 'Resells the services');
+INSERT INTO tag (name, description)
+VALUES ('EMPLOYEE', 'Company employee');
+INSERT INTO tag (name, description)
+VALUES ('RISK', 'Payment risk');
+
+INSERT INTO customer_tag (customer_id, tag_id)
+VALUES ((SELECT id FROM customer WHERE name = 'John'), (SELECT id FROM tag WHERE name = 'VIP'));
+
+INSERT INTO custom_field (entity, field_key, label, field_type, enum_options)
+VALUES ('CUSTOMER', 'account_manager', 'Account manager', 'STRING', NULL);
+INSERT INTO custom_field (entity, field_key, label, field_type, enum_options)
+VALUES ('CUSTOMER', 'segment', 'Segment', 'ENUM', 'RESIDENTIAL,SOHO,SME,ENTERPRISE');
+INSERT INTO custom_field (entity, field_key, label, field_type, enum_options)
+VALUES ('CONTRACT', 'installed_on', 'Installation date', 'DATE', NULL);
+INSERT INTO custom_field (entity, field_key, label, field_type, enum_options)
+VALUES ('CONTRACT', 'bandwidth_mbps', 'Bandwidth (Mbps)', 'NUMBER', NULL);
+
+INSERT INTO customer_field_value (customer_id, field_id, field_value)
+VALUES ((SELECT id FROM customer WHERE name = 'John'), (SELECT id FROM custom_field WHERE field_key = 'segment'),
+        'RESIDENTIAL');
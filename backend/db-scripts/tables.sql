@@ -1,20 +1,96 @@
 -- Create tables
 
+-- the version of a record is incremented on each update, and conditional requests are checked against it
+
 CREATE TABLE customer
 (
-    id       NUMBER GENERATED ALWAYS AS IDENTITY (START WITH 1 INCREMENT BY 1) PRIMARY KEY,
-    name     NVARCHAR2(100) NOT NULL,
-    fullname NVARCHAR2(100) NOT NULL,
-    address  NVARCHAR2(100) NOT NULL,
-    phone    NVARCHAR2(12)  NOT NULL,
-    cnp      NVARCHAR2(13)  NOT NULL,
+    id                    NUMBER GENERATED ALWAYS AS IDENTITY (START WITH 1 INCREMENT BY 1) PRIMARY KEY,
+    kind                  NVARCHAR2(20) DEFAULT 'INDIVIDUAL' NOT NULL,
+    name                  NVARCHAR2(100)                     NOT NULL,
+    fullname              NVARCHAR2(100)                     NOT NULL,
+    address               NVARCHAR2(100)                     NOT NULL,
+    -- phone numbers are stored in the E.164 format, as normalized by common::phone::Phone
+    phone                 NVARCHAR2(16),
+    cnp                   NVARCHAR2(13),
+    -- CUI of Romanian businesses, VAT number of the reverse charged ones, see common::cui
+    cui                   NVARCHAR2(14),
+    trade_register_number NVARCHAR2(20),
+    vat_payer             NUMBER(1) DEFAULT 0                NOT NULL,
+    -- VAT payers established in another EU member state are invoiced with reverse charge
+    reverse_charge        NUMBER(1) DEFAULT 0                NOT NULL,
+    legal_representative  NVARCHAR2(100),
+    -- anonymized customers keep their financial records but lose their personal data, see common::privacy
+    anonymized_at         DATE,
+    version               NUMBER DEFAULT 1                   NOT NULL,
+
+    CONSTRAINT valid_kind CHECK (kind IN ('INDIVIDUAL', 'BUSINESS')),
+    CONSTRAINT valid_phone CHECK (REGEXP_LIKE(phone, '^\+[1-9][0-9]{7,14}$')),
+    CONSTRAINT valid_anonymized_phone CHECK (phone IS NOT NULL OR anonymized_at IS NOT NULL),
+    CONSTRAINT valid_cnp CHECK (REGEXP_LIKE(cnp, '^[0-9]{13}$')),
+    CONSTRAINT valid_cui CHECK (REGEXP_LIKE(cui, '^(RO)?[0-9]{2,10}$') OR
+                                (reverse_charge = 1 AND REGEXP_LIKE(cui, '^[A-Z]{2}[0-9A-Z]{2,12}$')
+                                    AND cui NOT LIKE 'RO%')),
+    CONSTRAINT valid_vat_payer CHECK (vat_payer IN (0, 1)),
+    CONSTRAINT valid_customer_reverse_charge CHECK (reverse_charge IN (0, 1) AND
+                                                    (reverse_charge = 0 OR vat_payer = 1)),
+    CONSTRAINT valid_legal_entity CHECK (
+        (kind = 'INDIVIDUAL' AND cui IS NULL AND vat_payer = 0 AND (cnp IS NOT NULL OR anonymized_at IS NOT NULL)) OR
+        (kind = 'BUSINESS' AND cnp IS NULL AND cui IS NOT NULL AND (reverse_charge = 1 OR
+         (trade_register_number IS NOT NULL AND (legal_representative IS NOT NULL OR anonymized_at IS NOT NULL)))))
+);
+
+CREATE TABLE customer_address
+(
+    id            NUMBER GENERATED ALWAYS AS IDENTITY (START WITH 1 INCREMENT BY 1) PRIMARY KEY,
+    customer_id   NUMBER         NOT NULL,
+    address_type  NVARCHAR2(20)  NOT NULL,
+    street        NVARCHAR2(100) NOT NULL,
+    street_number NVARCHAR2(20)  NOT NULL,
+    city          NVARCHAR2(50)  NOT NULL,
+    county        NVARCHAR2(50)  NOT NULL,
+    postal_code   NVARCHAR2(6)   NOT NULL,
+    version       NUMBER DEFAULT 1 NOT NULL,
+
+    CONSTRAINT valid_address_type CHECK (address_type IN ('BILLING', 'SERVICE', 'CORRESPONDENCE')),
+    CONSTRAINT valid_postal_code CHECK (REGEXP_LIKE(postal_code, '^[0-9]{6}$')),
+    CONSTRAINT fk_address_customer FOREIGN KEY (customer_id) REFERENCES customer (id) ON DELETE CASCADE
+);
+
+CREATE TABLE customer_contact
+(
+    id          NUMBER GENERATED ALWAYS AS IDENTITY (START WITH 1 INCREMENT BY 1) PRIMARY KEY,
+    customer_id NUMBER         NOT NULL,
+    role        NVARCHAR2(20)  NOT NULL,
+    name        NVARCHAR2(100) NOT NULL,
+    email       NVARCHAR2(100) NOT NULL,
+    phone       NVARCHAR2(16),
+    version     NUMBER DEFAULT 1 NOT NULL,
 
-    CONSTRAINT valid_phone CHECK (REGEXP_LIKE(phone, '^[0-9]{10,12}$')),
-    CONSTRAINT valid_cnp CHECK (REGEXP_LIKE(cnp, '^[0-9]{13}$'))
+    CONSTRAINT valid_contact_role CHECK (role IN ('PRIMARY', 'BILLING', 'TECHNICAL')),
+    CONSTRAINT valid_contact_email CHECK (REGEXP_LIKE(email, '^[^@ ]+@[^@ ]+$')),
+    CONSTRAINT valid_contact_phone CHECK (REGEXP_LIKE(phone, '^\+[1-9][0-9]{7,14}$')),
+    CONSTRAINT fk_contact_customer FOREIGN KEY (customer_id) REFERENCES customer (id) ON DELETE CASCADE
 );
 
-ALTER TABLE customer DROP CONSTRAINT valid_phone;
-ALTER TABLE customer ADD CONSTRAINT valid_phone CHECK (REGEXP_LIKE(phone, '^\+[0-9]{11}$'));
+-- the source customer is removed by the merge, so only its identity is kept
+CREATE TABLE customer_merge
+(
+    id                 NUMBER GENERATED ALWAYS AS IDENTITY (START WITH 1 INCREMENT BY 1) PRIMARY KEY,
+    target_customer_id NUMBER         NOT NULL,
+    source_customer_id NUMBER         NOT NULL,
+    source_name        NVARCHAR2(100) NOT NULL,
+    source_fullname    NVARCHAR2(100) NOT NULL,
+    source_cnp         NVARCHAR2(13),
+    source_cui         NVARCHAR2(14),
+    contracts_moved    NUMBER         NOT NULL,
+    invoices_moved     NUMBER         NOT NULL,
+    payments_moved     NUMBER         NOT NULL,
+    merged_at          DATE DEFAULT SYSDATE NOT NULL,
+    version            NUMBER DEFAULT 1     NOT NULL,
+
+    CONSTRAINT valid_merge CHECK (target_customer_id <> source_customer_id),
+    CONSTRAINT fk_merge_customer FOREIGN KEY (target_customer_id) REFERENCES customer (id) ON DELETE CASCADE
+);
 
 CREATE TABLE subscription
 (
@@ -24,6 +100,7 @@ CREATE TABLE subscription
     traffic             NUMBER         NOT NULL,
     price               NUMBER         NOT NULL,
     extra_traffic_price NUMBER         NOT NULL,
+    version             NUMBER DEFAULT 1 NOT NULL,
 
     CONSTRAINT valid_type CHECK (type IN ('MOBILE', 'FIXED', 'TV', 'MOBILE_INTERNET', 'FIXED_INTERNET')),
     CONSTRAINT valid_traffic CHECK (traffic > 0),
@@ -31,45 +108,303 @@ CREATE TABLE subscription
     CONSTRAINT valid_extra_traffic_price CHECK (extra_traffic_price > 0)
 );
 
-CREATE TABLE contract
+CREATE TABLE promotion
 (
-    id              NUMBER GENERATED ALWAYS AS IDENTITY (START WITH 1 INCREMENT BY 1) PRIMARY KEY,
-    customer_id     NUMBER NOT NULL,
+    id                NUMBER GENERATED ALWAYS AS IDENTITY (START WITH 1 INCREMENT BY 1) PRIMARY KEY,
+    code              NVARCHAR2(30)  NOT NULL,
+    description       NVARCHAR2(100) NOT NULL,
+    discount_type     NVARCHAR2(20)  NOT NULL,
+    discount_value    NUMBER         NOT NULL,
+    duration_cycles   NUMBER         NOT NULL,
+    subscription_type NVARCHAR2(100),
+    valid_from        DATE           NOT NULL,
+    valid_until       DATE           NOT NULL,
+    version           NUMBER DEFAULT 1 NOT NULL,
+
+    CONSTRAINT unique_promotion_code UNIQUE (code),
+    CONSTRAINT valid_discount_type CHECK (discount_type IN ('PERCENTAGE', 'FIXED')),
+    CONSTRAINT valid_discount_value CHECK (discount_value >= 0 AND
+                                           (discount_type = 'FIXED' OR discount_value <= 100)),
+    CONSTRAINT valid_duration_cycles CHECK (duration_cycles > 0),
+    CONSTRAINT valid_promotion_type CHECK (subscription_type IS NULL OR subscription_type IN
+                                           ('MOBILE', 'FIXED', 'TV', 'MOBILE_INTERNET', 'FIXED_INTERNET')),
+    CONSTRAINT valid_promotion_period CHECK (valid_from <= valid_until)
+);
+
+CREATE TABLE bundle
+(
+    id          NUMBER GENERATED ALWAYS AS IDENTITY (START WITH 1 INCREMENT BY 1) PRIMARY KEY,
+    description NVARCHAR2(100) NOT NULL,
+    price       NUMBER         NOT NULL,
+    version     NUMBER DEFAULT 1 NOT NULL,
+
+    CONSTRAINT valid_bundle_price CHECK (price >= 0)
+);
+
+CREATE TABLE bundle_subscription
+(
+    bundle_id       NUMBER NOT NULL,
     subscription_id NUMBER NOT NULL,
-    start_date      DATE    NOT NULL,
-    end_date        DATE    NOT NULL,
+    version         NUMBER DEFAULT 1 NOT NULL,
+
+    CONSTRAINT pk_bundle_subscription PRIMARY KEY (bundle_id, subscription_id),
+    CONSTRAINT fk_component_bundle FOREIGN KEY (bundle_id) REFERENCES bundle (id) ON DELETE CASCADE,
+    CONSTRAINT fk_component_subscription FOREIGN KEY (subscription_id) REFERENCES subscription (id)
+);
+
+CREATE TABLE contract
+(
+    id                 NUMBER GENERATED ALWAYS AS IDENTITY (START WITH 1 INCREMENT BY 1) PRIMARY KEY,
+    customer_id        NUMBER           NOT NULL,
+    subscription_id    NUMBER,
+    bundle_id          NUMBER,
+    start_date         DATE             NOT NULL,
+    end_date           DATE             NOT NULL,
+    penalty_rate       NUMBER DEFAULT 0 NOT NULL,
+    promotion_id       NUMBER,
+    service_address_id NUMBER,
+    version            NUMBER DEFAULT 1 NOT NULL,
 
     CONSTRAINT valid_start_date CHECK (start_date < end_date),
+    CONSTRAINT valid_penalty_rate CHECK (penalty_rate >= 0 AND penalty_rate <= 100),
+    CONSTRAINT valid_offering CHECK ((subscription_id IS NULL AND bundle_id IS NOT NULL) OR
+                                     (subscription_id IS NOT NULL AND bundle_id IS NULL)),
     CONSTRAINT fk_customer FOREIGN KEY (customer_id) REFERENCES customer (id),
-    CONSTRAINT fk_subscription FOREIGN KEY (subscription_id) REFERENCES subscription (id)
+    CONSTRAINT fk_subscription FOREIGN KEY (subscription_id) REFERENCES subscription (id),
+    CONSTRAINT fk_bundle FOREIGN KEY (bundle_id) REFERENCES bundle (id),
+    CONSTRAINT fk_promotion FOREIGN KEY (promotion_id) REFERENCES promotion (id),
+    CONSTRAINT fk_service_address FOREIGN KEY (service_address_id) REFERENCES customer_address (id)
 );
 
-CREATE TABLE invoice
+CREATE TABLE addon
+(
+    id          NUMBER GENERATED ALWAYS AS IDENTITY (START WITH 1 INCREMENT BY 1) PRIMARY KEY,
+    description NVARCHAR2(100) NOT NULL,
+    price       NUMBER         NOT NULL,
+    version     NUMBER DEFAULT 1 NOT NULL,
+
+    CONSTRAINT valid_addon_price CHECK (price >= 0)
+);
+
+CREATE TABLE contract_addon
 (
     id          NUMBER GENERATED ALWAYS AS IDENTITY (START WITH 1 INCREMENT BY 1) PRIMARY KEY,
     contract_id NUMBER NOT NULL,
-    issue_date  DATE    NOT NULL,
-    due_date    DATE    NOT NULL,
-    amount      NUMBER  NOT NULL,
-    status      NVARCHAR2(10) DEFAULT 'unpaid' NOT NULL,
+    addon_id    NUMBER NOT NULL,
+    start_date  DATE   NOT NULL,
+    end_date    DATE   NOT NULL,
+    price       NUMBER NOT NULL,
+    version     NUMBER DEFAULT 1 NOT NULL,
+
+    CONSTRAINT valid_contract_addon_period CHECK (start_date <= end_date),
+    CONSTRAINT valid_contract_addon_price CHECK (price >= 0),
+    CONSTRAINT fk_addon_contract FOREIGN KEY (contract_id) REFERENCES contract (id) ON DELETE CASCADE,
+    CONSTRAINT fk_addon FOREIGN KEY (addon_id) REFERENCES addon (id)
+);
+
+CREATE TABLE invoice
+(
+    id             NUMBER GENERATED ALWAYS AS IDENTITY (START WITH 1 INCREMENT BY 1) PRIMARY KEY,
+    contract_id    NUMBER                         NOT NULL,
+    issue_date     DATE                           NOT NULL,
+    due_date       DATE                           NOT NULL,
+    amount         NUMBER                         NOT NULL,
+    status         NVARCHAR2(10) DEFAULT 'UNPAID' NOT NULL,
+    vat_rate       NUMBER DEFAULT 0               NOT NULL,
+    reverse_charge NUMBER(1) DEFAULT 0            NOT NULL,
+    version        NUMBER DEFAULT 1               NOT NULL,
 
     CONSTRAINT valid_issue_date CHECK (issue_date < due_date),
-    CONSTRAINT valid_amount CHECK (amount > 0),
-    CONSTRAINT valid_status CHECK (status IN ('unpaid', 'paid')),
+    CONSTRAINT valid_amount CHECK (amount >= 0),
+    CONSTRAINT valid_status CHECK (status IN ('UNPAID', 'PAID')),
+    CONSTRAINT valid_vat_rate CHECK (vat_rate >= 0 AND vat_rate <= 100),
+    CONSTRAINT valid_reverse_charge CHECK (reverse_charge IN (0, 1)),
     CONSTRAINT fk_contract FOREIGN KEY (contract_id) REFERENCES contract (id)
 );
 
-ALTER TABLE invoice MODIFY status DEFAULT 'UNPAID';
-ALTER TABLE invoice DROP CONSTRAINT valid_status;
-ALTER TABLE invoice ADD CONSTRAINT valid_status CHECK (status IN ('UNPAID', 'PAID'));
-
 CREATE TABLE payment
 (
     id           NUMBER GENERATED ALWAYS AS IDENTITY (START WITH 1 INCREMENT BY 1) PRIMARY KEY,
     invoice_id   NUMBER NOT NULL,
     payment_date DATE    NOT NULL,
     amount       NUMBER  NOT NULL,
+    version      NUMBER DEFAULT 1 NOT NULL,
 
     CONSTRAINT valid_payment_amount CHECK (amount > 0),
     CONSTRAINT fk_invoice FOREIGN KEY (invoice_id) REFERENCES invoice (id)
 );
+
+CREATE TABLE invoice_line
+(
+    id                NUMBER GENERATED ALWAYS AS IDENTITY (START WITH 1 INCREMENT BY 1) PRIMARY KEY,
+    invoice_id        NUMBER         NOT NULL,
+    line_type         NVARCHAR2(20)  NOT NULL,
+    description       NVARCHAR2(200) NOT NULL,
+    amount            NUMBER         NOT NULL,
+    source_invoice_id NUMBER,
+    version           NUMBER DEFAULT 1 NOT NULL,
+
+    CONSTRAINT valid_line_type CHECK (line_type IN ('SUBSCRIPTION', 'PENALTY', 'DISCOUNT', 'BUNDLE_COMPONENT',
+                                                    'ADDON', 'VAT')),
+    CONSTRAINT fk_line_invoice FOREIGN KEY (invoice_id) REFERENCES invoice (id) ON DELETE CASCADE,
+    CONSTRAINT fk_line_source_invoice FOREIGN KEY (source_invoice_id) REFERENCES invoice (id) ON DELETE SET NULL
+);
+
+CREATE TABLE customer_note
+(
+    id          NUMBER GENERATED ALWAYS AS IDENTITY (START WITH 1 INCREMENT BY 1) PRIMARY KEY,
+    customer_id NUMBER          NOT NULL,
+    contract_id NUMBER,
+    invoice_id  NUMBER,
+    kind        NVARCHAR2(10)   NOT NULL,
+    category    NVARCHAR2(20)   NOT NULL,
+    author      NVARCHAR2(100)  NOT NULL,
+    body        NVARCHAR2(2000) NOT NULL,
+    pinned      NUMBER(1) DEFAULT 0 NOT NULL,
+    created_at  DATE DEFAULT SYSDATE NOT NULL,
+    version     NUMBER DEFAULT 1     NOT NULL,
+
+    CONSTRAINT valid_note_kind CHECK (kind IN ('NOTE', 'CALL', 'EMAIL', 'VISIT')),
+    CONSTRAINT valid_note_category CHECK (category IN ('GENERAL', 'BILLING', 'TECHNICAL', 'SALES', 'COMPLAINT')),
+    CONSTRAINT valid_note_pinned CHECK (pinned IN (0, 1)),
+    CONSTRAINT fk_note_customer FOREIGN KEY (customer_id) REFERENCES customer (id) ON DELETE CASCADE,
+    CONSTRAINT fk_note_contract FOREIGN KEY (contract_id) REFERENCES contract (id) ON DELETE SET NULL,
+    CONSTRAINT fk_note_invoice FOREIGN KEY (invoice_id) REFERENCES invoice (id) ON DELETE SET NULL
+);
+
+CREATE TABLE tag
+(
+    id          NUMBER GENERATED ALWAYS AS IDENTITY (START WITH 1 INCREMENT BY 1) PRIMARY KEY,
+    name        NVARCHAR2(30) NOT NULL,
+    description NVARCHAR2(100),
+    version     NUMBER DEFAULT 1 NOT NULL,
+
+    CONSTRAINT unique_tag_name UNIQUE (name),
+    CONSTRAINT valid_tag_name CHECK (REGEXP_LIKE(name, '^[A-Z][A-Z0-9_]{1,29}$'))
+);
+
+CREATE TABLE customer_tag
+(
+    customer_id NUMBER NOT NULL,
+    tag_id      NUMBER NOT NULL,
+    version     NUMBER DEFAULT 1 NOT NULL,
+
+    CONSTRAINT pk_customer_tag PRIMARY KEY (customer_id, tag_id),
+    CONSTRAINT fk_customer_tag_customer FOREIGN KEY (customer_id) REFERENCES customer (id) ON DELETE CASCADE,
+    CONSTRAINT fk_customer_tag_tag FOREIGN KEY (tag_id) REFERENCES tag (id) ON DELETE CASCADE
+);
+
+CREATE TABLE contract_tag
+(
+    contract_id NUMBER NOT NULL,
+    tag_id      NUMBER NOT NULL,
+    version     NUMBER DEFAULT 1 NOT NULL,
+
+    CONSTRAINT pk_contract_tag PRIMARY KEY (contract_id, tag_id),
+    CONSTRAINT fk_contract_tag_contract FOREIGN KEY (contract_id) REFERENCES contract (id) ON DELETE CASCADE,
+    CONSTRAINT fk_contract_tag_tag FOREIGN KEY (tag_id) REFERENCES tag (id) ON DELETE CASCADE
+);
+
+CREATE TABLE custom_field
+(
+    id           NUMBER GENERATED ALWAYS AS IDENTITY (START WITH 1 INCREMENT BY 1) PRIMARY KEY,
+    entity       NVARCHAR2(10)   NOT NULL,
+    field_key    NVARCHAR2(30)   NOT NULL,
+    label        NVARCHAR2(50)   NOT NULL,
+    field_type   NVARCHAR2(10)   NOT NULL,
+    enum_options NVARCHAR2(2000),
+    version      NUMBER DEFAULT 1 NOT NULL,
+
+    CONSTRAINT unique_custom_field_key UNIQUE (entity, field_key),
+    CONSTRAINT valid_custom_field_entity CHECK (entity IN ('CUSTOMER', 'CONTRACT')),
+    CONSTRAINT valid_custom_field_key CHECK (REGEXP_LIKE(field_key, '^[a-z][a-z0-9_]{1,29}$')),
+    CONSTRAINT valid_custom_field_type CHECK (field_type IN ('STRING', 'NUMBER', 'DATE', 'ENUM')),
+    CONSTRAINT valid_custom_field_options CHECK ((field_type = 'ENUM' AND enum_options IS NOT NULL) OR
+                                                (field_type <> 'ENUM' AND enum_options IS NULL))
+);
+
+CREATE TABLE customer_field_value
+(
+    customer_id NUMBER         NOT NULL,
+    field_id    NUMBER         NOT NULL,
+    field_value NVARCHAR2(500) NOT NULL,
+    version     NUMBER DEFAULT 1 NOT NULL,
+
+    CONSTRAINT pk_customer_field_value PRIMARY KEY (customer_id, field_id),
+    CONSTRAINT fk_customer_value_customer FOREIGN KEY (customer_id) REFERENCES customer (id) ON DELETE CASCADE,
+    CONSTRAINT fk_customer_value_field FOREIGN KEY (field_id) REFERENCES custom_field (id) ON DELETE CASCADE
+);
+
+CREATE TABLE contract_field_value
+(
+    contract_id NUMBER         NOT NULL,
+    field_id    NUMBER         NOT NULL,
+    field_value NVARCHAR2(500) NOT NULL,
+    version     NUMBER DEFAULT 1 NOT NULL,
+
+    CONSTRAINT pk_contract_field_value PRIMARY KEY (contract_id, field_id),
+    CONSTRAINT fk_contract_value_contract FOREIGN KEY (contract_id) REFERENCES contract (id) ON DELETE CASCADE,
+    CONSTRAINT fk_contract_value_field FOREIGN KEY (field_id) REFERENCES custom_field (id) ON DELETE CASCADE
+);
+
+CREATE TABLE contract_deposit
+(
+    id              NUMBER GENERATED ALWAYS AS IDENTITY (START WITH 1 INCREMENT BY 1) PRIMARY KEY,
+    contract_id     NUMBER        NOT NULL,
+    amount          NUMBER        NOT NULL,
+    status          NVARCHAR2(10) DEFAULT 'PENDING' NOT NULL,
+    created_at      DATE DEFAULT SYSDATE NOT NULL,
+    paid_at         DATE,
+    refunded_at     DATE,
+    refunded_amount NUMBER,
+    version         NUMBER DEFAULT 1 NOT NULL,
+
+    CONSTRAINT uq_deposit_contract UNIQUE (contract_id),
+    CONSTRAINT valid_deposit_amount CHECK (amount > 0),
+    CONSTRAINT valid_deposit_status CHECK (status IN ('PENDING', 'HELD', 'REFUNDED')),
+    CONSTRAINT valid_refunded_amount CHECK (refunded_amount >= 0 AND refunded_amount <= amount),
+    CONSTRAINT fk_deposit_contract FOREIGN KEY (contract_id) REFERENCES contract (id) ON DELETE CASCADE
+);
+
+-- accounts signing in to the API, and their sessions, whose tokens are only stored hashed
+
+CREATE TABLE app_user
+(
+    id            NUMBER GENERATED ALWAYS AS IDENTITY (START WITH 1 INCREMENT BY 1) PRIMARY KEY,
+    username      NVARCHAR2(50)                       NOT NULL,
+    password_hash NVARCHAR2(255)                      NOT NULL,
+    role          NVARCHAR2(20) DEFAULT 'OPERATOR'    NOT NULL,
+    active        NUMBER(1) DEFAULT 1                 NOT NULL,
+    created_at    TIMESTAMP DEFAULT SYSTIMESTAMP      NOT NULL,
+    version       NUMBER DEFAULT 1                    NOT NULL,
+
+    CONSTRAINT unique_username UNIQUE (username),
+    CONSTRAINT valid_user_role CHECK (role IN ('ADMIN', 'OPERATOR')),
+    CONSTRAINT valid_user_active CHECK (active IN (0, 1))
+);
+
+CREATE TABLE user_session
+(
+    token_hash CHAR(64) PRIMARY KEY,
+    user_id    NUMBER                              NOT NULL,
+    created_at TIMESTAMP DEFAULT SYSTIMESTAMP      NOT NULL,
+    expires_at TIMESTAMP                           NOT NULL,
+
+    CONSTRAINT fk_session_user FOREIGN KEY (user_id) REFERENCES app_user (id) ON DELETE CASCADE
+);
+
+-- POST requests sent with an Idempotency-Key header, whose response is replayed to the retries of their user
+
+CREATE TABLE idempotent_request
+(
+    user_id         NUMBER                              NOT NULL,
+    idempotency_key NVARCHAR2(255)                      NOT NULL,
+    request_hash    CHAR(64)                            NOT NULL,
+    status          NUMBER(3),
+    content_type    NVARCHAR2(100),
+    body            NCLOB,
+    created_at      TIMESTAMP DEFAULT SYSTIMESTAMP      NOT NULL,
+
+    CONSTRAINT pk_idempotent_request PRIMARY KEY (user_id, idempotency_key),
+    CONSTRAINT fk_idempotent_request_user FOREIGN KEY (user_id) REFERENCES app_user (id) ON DELETE CASCADE
+);
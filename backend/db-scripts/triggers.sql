@@ -1,13 +1,39 @@
--- update the amount for unpaid invoices when the associated subscription price is changed
+-- update the amount for unpaid invoices when the associated subscription price is changed,
+-- keeping the other lines (e.g. late payment penalties) billed on them and recomputing their VAT
 
 CREATE OR REPLACE TRIGGER update_invoice_amount
     AFTER UPDATE OF price
     ON subscription
     FOR EACH ROW
 BEGIN
-    UPDATE invoice
+    UPDATE invoice_line
     SET AMOUNT = :NEW.PRICE
-    WHERE CONTRACT_ID IN (SELECT CONTRACT_ID
+    WHERE LINE_TYPE = 'SUBSCRIPTION'
+      AND INVOICE_ID IN (SELECT i.ID
+                         FROM invoice i
+                                  JOIN contract c ON i.CONTRACT_ID = c.ID
+                         WHERE c.SUBSCRIPTION_ID = :NEW.ID
+                           AND i.STATUS = 'UNPAID');
+
+    UPDATE invoice_line l
+    SET AMOUNT = ROUND((SELECT NVL(SUM(t.AMOUNT), 0)
+                        FROM invoice_line t
+                        WHERE t.INVOICE_ID = l.INVOICE_ID
+                          AND t.LINE_TYPE NOT IN ('PENALTY', 'VAT')) *
+                       (SELECT i.VAT_RATE FROM invoice i WHERE i.ID = l.INVOICE_ID) / 100, 2)
+    WHERE LINE_TYPE = 'VAT'
+      AND INVOICE_ID IN (SELECT i.ID
+                         FROM invoice i
+                                  JOIN contract c ON i.CONTRACT_ID = c.ID
+                         WHERE c.SUBSCRIPTION_ID = :NEW.ID
+                           AND i.STATUS = 'UNPAID');
+
+    UPDATE invoice i
+    SET AMOUNT = :NEW.PRICE + (SELECT NVL(SUM(l.AMOUNT), 0)
+                               FROM invoice_line l
+                               WHERE l.INVOICE_ID = i.ID
+                                 AND l.LINE_TYPE <> 'SUBSCRIPTION')
+    WHERE CONTRACT_ID IN (SELECT ID
                           FROM contract
                           WHERE SUBSCRIPTION_ID = :NEW.ID)
       AND STATUS = 'UNPAID';
@@ -39,7 +65,7 @@ BEGIN
     FROM invoice
     WHERE INVOICE.ID = :NEW.INVOICE_ID;
 
-    SELECT SUM(AMOUNT)
+    SELECT NVL(SUM(AMOUNT), 0)
     INTO paid_amount
     FROM payment
     WHERE INVOICE_ID = :NEW.INVOICE_ID;
@@ -52,3 +78,16 @@ BEGIN
         WHERE ID = :NEW.INVOICE_ID;
     END IF;
 END;
+
+-- increment the version of a record on each update, for every table having one
+
+BEGIN
+    FOR table_rec IN (SELECT TABLE_NAME
+                      FROM user_tab_columns
+                      WHERE COLUMN_NAME = 'VERSION')
+        LOOP
+            EXECUTE IMMEDIATE 'CREATE OR REPLACE TRIGGER version_' || table_rec.TABLE_NAME || ' ' ||
+                              'BEFORE UPDATE ON ' || table_rec.TABLE_NAME || ' FOR EACH ROW ' ||
+                              'BEGIN :NEW.VERSION := :OLD.VERSION + 1; END;';
+        END LOOP;
+END;
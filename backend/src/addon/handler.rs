@@ -0,0 +1,98 @@
+use crate::addon::repository;
+use crate::error::application::Error;
+use crate::etag::{self, IfMatch};
+use crate::{DBPool, Result};
+use common::addon::{AddonRequest, AddonResponse};
+use validator::Validate;
+use warp::reply::json;
+use warp::{reject, Buf, Reply};
+
+pub async fn list_addons_handler(db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Listing add-ons");
+
+    let addons = repository::fetch(&db_pool).await.map_err(reject::custom)?;
+    Ok(json::<Vec<_>>(
+        &addons.into_iter().map(AddonResponse::from).collect(),
+    ))
+}
+
+pub async fn fetch_addon_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Fetching add-on with id {}", id);
+
+    let addon = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    let version = addon.version;
+    Ok(etag::reply(json(&AddonResponse::from(addon)), version))
+}
+
+pub async fn create_addon_handler(buf: impl Buf, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Creating a new add-on");
+
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: AddonRequest = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    body.validate()
+        .map_err(|e| reject::custom(Error::Validation(e)))?;
+
+    let created_addon = repository::create(&db_pool, body)
+        .await
+        .map_err(reject::custom)?;
+
+    let response = json(&AddonResponse::from(created_addon));
+
+    Ok(warp::reply::with_status(
+        response,
+        warp::http::StatusCode::CREATED,
+    ))
+}
+
+pub async fn update_addon_handler(
+    id: u32,
+    if_match: IfMatch,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Updating add-on with id {}", id);
+
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: AddonRequest = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    body.validate()
+        .map_err(|e| reject::custom(Error::Validation(e)))?;
+
+    let addon = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    if_match
+        .check("add-on", id, addon.version)
+        .map_err(reject::custom)?;
+
+    let addon = repository::update(&db_pool, id, addon.version, body)
+        .await
+        .map_err(reject::custom)?;
+    let version = addon.version;
+    Ok(etag::reply(json(&AddonResponse::from(addon)), version))
+}
+
+pub async fn delete_addon_handler(
+    id: u32,
+    if_match: IfMatch,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Deleting add-on with id {}", id);
+
+    let addon = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    if_match
+        .check("add-on", id, addon.version)
+        .map_err(reject::custom)?;
+
+    repository::delete(&db_pool, id, addon.version)
+        .await
+        .map_err(reject::custom)?;
+    Ok(warp::http::StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,37 @@
+use crate::{etag, with_db, DBPool};
+use warp::Filter;
+
+pub mod handler;
+pub mod repository;
+
+pub fn get_routes(
+    db_pool: DBPool,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let addon = warp::path!("addon");
+    let addon_param = warp::path!("addon" / u32);
+
+    addon
+        .and(warp::get())
+        .and(with_db(db_pool.clone()))
+        .and_then(handler::list_addons_handler)
+        .or(addon_param
+            .and(warp::get())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::fetch_addon_handler))
+        .or(addon
+            .and(warp::post())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::create_addon_handler))
+        .or(addon_param
+            .and(warp::put())
+            .and(etag::if_match())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::update_addon_handler))
+        .or(addon_param
+            .and(warp::delete())
+            .and(etag::if_match())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::delete_addon_handler))
+}
@@ -0,0 +1,129 @@
+use crate::db::addon::{row_to_addon, SELECT_FIELDS, TABLE};
+use crate::db::{get_db_con, Result};
+use crate::error::application::Error;
+use crate::DBPool;
+use common::addon::{Addon, AddonRequest};
+use oracle::sql_type::OracleType;
+
+pub async fn fetch(db_pool: &DBPool) -> Result<Vec<Addon>> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!("SELECT {} FROM {}", SELECT_FIELDS, TABLE);
+
+    let rows = con.query(query.as_str(), &[]).map_err(Error::DBQuery)?;
+
+    Ok(rows
+        .filter(|r| r.is_ok())
+        .map(|r| row_to_addon(&r.unwrap()))
+        .collect())
+}
+
+pub async fn fetch_one(db_pool: &DBPool, id: u32) -> Result<Addon> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &id)])
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::AddonNotFound(id),
+            _ => Error::DBQuery(e),
+        })?;
+
+    Ok(row_to_addon(&row))
+}
+
+pub async fn create(db_pool: &DBPool, body: AddonRequest) -> Result<Addon> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "INSERT INTO {} (description, price) VALUES (:description, :price) RETURNING id INTO :id",
+        TABLE
+    );
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("description", &body.description),
+                ("price", &body.price),
+                ("id", &OracleType::Number(0, 0)),
+            ],
+        )
+        .map_err(Error::DBQuery)?;
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    let row_id: u32 = stmt.returned_values("id").map_err(Error::DBQuery)?[0];
+    let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &row_id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(row_to_addon(&row))
+}
+
+pub async fn update(db_pool: &DBPool, id: u32, version: u32, body: AddonRequest) -> Result<Addon> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "UPDATE {} SET description = :description, price = :price \
+        WHERE id = :id AND version = :version",
+        TABLE
+    );
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("id", &id),
+                ("version", &version),
+                ("description", &body.description),
+                ("price", &body.price),
+            ],
+        )
+        .map_err(Error::DBQuery)?;
+
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("add-on", id));
+    }
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &id)])
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::AddonNotFound(id),
+            _ => Error::DBQuery(e),
+        })?;
+
+    Ok(row_to_addon(&row))
+}
+
+pub async fn delete(db_pool: &DBPool, id: u32, version: u32) -> Result<()> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "DELETE FROM {} WHERE id = :id AND version = :version",
+        TABLE
+    );
+
+    let stmt = con
+        .execute_named(query.as_str(), &[("id", &id), ("version", &version)])
+        .map_err(Error::DBQuery)?;
+
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("add-on", id));
+    }
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use crate::auth::repository;
+use crate::error::application::Error;
+use crate::{DBPool, Result};
+use common::user::{LoginRequest, LoginResponse, User, UserResponse};
+use validator::Validate;
+use warp::reply::json;
+use warp::{reject, Buf, Reply};
+
+pub async fn login_handler(buf: impl Buf, db_pool: DBPool) -> Result<impl Reply> {
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: LoginRequest = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    body.validate()
+        .map_err(|e| reject::custom(Error::Validation(e)))?;
+
+    log::info!("Signing in user {}", body.username);
+
+    let (user, token, expires_at) = repository::login(&db_pool, &body.username, &body.password)
+        .await
+        .map_err(reject::custom)?;
+
+    Ok(json(&LoginResponse {
+        token,
+        expires_at,
+        user: UserResponse::from(user),
+    }))
+}
+
+pub async fn logout_handler(token: Option<String>, db_pool: DBPool) -> Result<impl Reply> {
+    let token = token.ok_or_else(|| reject::custom(Error::Unauthenticated))?;
+    log::info!("Signing out");
+
+    repository::logout(&db_pool, &token)
+        .await
+        .map_err(reject::custom)?;
+    Ok(warp::http::StatusCode::NO_CONTENT)
+}
+
+pub async fn me_handler(user: User) -> Result<impl Reply> {
+    Ok(json(&UserResponse::from(user)))
+}
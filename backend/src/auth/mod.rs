@@ -0,0 +1,121 @@
+use crate::error::application::Error;
+use crate::export;
+use crate::{with_db, DBPool};
+use common::export::ExportFormat;
+use common::user::{Role, User};
+use serde::Deserialize;
+use warp::path::Peek;
+use warp::{reject, Filter, Rejection};
+
+pub mod handler;
+pub mod password;
+pub mod repository;
+
+/// Query of the downloads, which carry their session token as they cannot send
+/// headers.
+#[derive(Deserialize)]
+struct DownloadQuery {
+    access_token: Option<String>,
+    format: Option<ExportFormat>,
+}
+
+pub fn get_routes(
+    db_pool: DBPool,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let login = warp::path!("auth" / "login");
+    let logout = warp::path!("auth" / "logout");
+    let me = warp::path!("auth" / "me");
+
+    login
+        .and(warp::post())
+        .and(warp::body::aggregate())
+        .and(with_db(db_pool.clone()))
+        .and_then(handler::login_handler)
+        .or(logout
+            .and(warp::post())
+            .and(token())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::logout_handler))
+        .or(me
+            .and(warp::get())
+            .and(authenticated(db_pool))
+            .and_then(handler::me_handler))
+}
+
+/// Session token of the request, from its `Authorization: Bearer` header.
+pub fn token() -> impl Filter<Extract = (Option<String>,), Error = Rejection> + Clone {
+    warp::header::optional::<String>("authorization").map(|authorization: Option<String>| {
+        authorization
+            .and_then(|value| value.strip_prefix("Bearer ").map(str::to_string))
+            .map(|token| token.trim().to_string())
+            .filter(|token| !token.is_empty())
+    })
+}
+
+/// Session token of the request, or else of the `common::user::ACCESS_TOKEN` query
+/// parameter of the downloads. Query strings end up in logs and in the history of
+/// the browser, so the parameter is accepted by no other request.
+fn download_token() -> impl Filter<Extract = (Option<String>,), Error = Rejection> + Clone {
+    let query = warp::get()
+        .and(warp::path::peek())
+        .and(warp::query::<DownloadQuery>())
+        .map(|path: Peek, query: DownloadQuery| {
+            query
+                .access_token
+                .filter(|_| export::is_download(path.as_str(), query.format))
+        })
+        .or(warp::any().map(|| None))
+        .unify();
+
+    token()
+        .and(query)
+        .map(|token: Option<String>, access_token: Option<String>| {
+            token.or_else(|| {
+                access_token
+                    .map(|token| token.trim().to_string())
+                    .filter(|token| !token.is_empty())
+            })
+        })
+}
+
+/// User signed in with the session token extracted by `token`.
+fn signed_in_with<T>(
+    token: T,
+    db_pool: DBPool,
+) -> impl Filter<Extract = (User,), Error = Rejection> + Clone
+where
+    T: Filter<Extract = (Option<String>,), Error = Rejection> + Clone,
+{
+    token
+        .and(with_db(db_pool))
+        .and_then(|token: Option<String>, db_pool: DBPool| async move {
+            let token = token.ok_or_else(|| reject::custom(Error::Unauthenticated))?;
+
+            repository::authenticate(&db_pool, &token)
+                .await
+                .map_err(reject::custom)
+        })
+}
+
+/// User signed in with the session token of the request.
+pub fn authenticated(db_pool: DBPool) -> impl Filter<Extract = (User,), Error = Rejection> + Clone {
+    signed_in_with(token(), db_pool)
+}
+
+/// Lets through the requests of signed in users only.
+pub fn signed_in(db_pool: DBPool) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    signed_in_with(download_token(), db_pool)
+        .map(|_| ())
+        .untuple_one()
+}
+
+/// Administrator signed in with the session token of the request.
+pub fn admin(db_pool: DBPool) -> impl Filter<Extract = (User,), Error = Rejection> + Clone {
+    authenticated(db_pool).and_then(|user: User| async move {
+        if user.role == Role::Admin {
+            Ok(user)
+        } else {
+            Err(reject::custom(Error::AdminRequired(user.id)))
+        }
+    })
+}
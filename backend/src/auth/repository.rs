@@ -0,0 +1,165 @@
+use crate::auth::password;
+use crate::config::CONFIG;
+use crate::db::user::{row_to_user, SELECT_FIELDS, SESSION_TABLE, TABLE};
+use crate::db::{get_db_con, Result};
+use crate::error::application::Error;
+use crate::DBPool;
+use chrono::{DateTime, Duration, Utc};
+use common::user::{Role, User};
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+/// Random bytes of a session token.
+const TOKEN_BYTES: usize = 32;
+
+/// Signs the user in, opening a session whose token is returned along with the
+/// time it expires at.
+pub async fn login(
+    db_pool: &DBPool,
+    username: &str,
+    password: &str,
+) -> Result<(User, String, DateTime<Utc>)> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "SELECT {}, password_hash FROM {} WHERE username = :username",
+        SELECT_FIELDS, TABLE
+    );
+
+    let row = con
+        .query_row_named(query.as_str(), &[("username", &username)])
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::InvalidCredentials(username.to_string()),
+            _ => Error::DBQuery(e),
+        })?;
+    let user = row_to_user(&row);
+    let password_hash: String = row.get(5).map_err(Error::DBQuery)?;
+
+    if !user.active || !password::verify(password, &password_hash) {
+        return Err(Error::InvalidCredentials(username.to_string()));
+    }
+
+    let now = Utc::now();
+    let expires_at = now + Duration::hours(i64::from(CONFIG.session_hours));
+    let token = new_token();
+
+    // expired sessions are purged as new ones are opened
+    let query = format!("DELETE FROM {} WHERE expires_at <= :now", SESSION_TABLE);
+    con.execute_named(query.as_str(), &[("now", &now)])
+        .map_err(Error::DBQuery)?;
+
+    let query = format!(
+        "INSERT INTO {} (token_hash, user_id, expires_at) VALUES (:token_hash, :user_id, :expires_at)",
+        SESSION_TABLE
+    );
+    con.execute_named(
+        query.as_str(),
+        &[
+            ("token_hash", &token_hash(&token)),
+            ("user_id", &user.id),
+            ("expires_at", &expires_at),
+        ],
+    )
+    .map_err(Error::DBQuery)?;
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    Ok((user, token, expires_at))
+}
+
+/// Active user signed in with the token, whose session has not expired.
+pub async fn authenticate(db_pool: &DBPool, token: &str) -> Result<User> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "SELECT {} FROM {} WHERE active = 1 AND id = \
+        (SELECT user_id FROM {} WHERE token_hash = :token_hash AND expires_at > :now)",
+        SELECT_FIELDS, TABLE, SESSION_TABLE
+    );
+
+    let row = con
+        .query_row_named(
+            query.as_str(),
+            &[("token_hash", &token_hash(token)), ("now", &Utc::now())],
+        )
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::Unauthenticated,
+            _ => Error::DBQuery(e),
+        })?;
+
+    Ok(row_to_user(&row))
+}
+
+/// Closes the session of the token.
+pub async fn logout(db_pool: &DBPool, token: &str) -> Result<()> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "DELETE FROM {} WHERE token_hash = :token_hash",
+        SESSION_TABLE
+    );
+
+    con.execute_named(query.as_str(), &[("token_hash", &token_hash(token))])
+        .map_err(Error::DBQuery)?;
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    Ok(())
+}
+
+/// Creates the administrator of the config while there is no user yet, so that
+/// someone can sign in to create the others.
+pub async fn bootstrap(db_pool: &DBPool) -> Result<()> {
+    let Some(admin_password) = CONFIG.admin_password.as_deref() else {
+        return Ok(());
+    };
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!("SELECT COUNT(*) FROM {}", TABLE);
+    let users: u32 = con
+        .query_row_as(query.as_str(), &[])
+        .map_err(Error::DBQuery)?;
+    if users > 0 {
+        return Ok(());
+    }
+
+    log::info!("Creating the administrator {}", CONFIG.admin_username);
+
+    let query = format!(
+        "INSERT INTO {} (username, password_hash, role) VALUES (:username, :password_hash, :role)",
+        TABLE
+    );
+    con.execute_named(
+        query.as_str(),
+        &[
+            ("username", &CONFIG.admin_username),
+            ("password_hash", &password::hash(admin_password)?),
+            ("role", &String::from(Role::Admin)),
+        ],
+    )
+    .map_err(Error::DBQuery)?;
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    Ok(())
+}
+
+/// New random session token, hex encoded.
+fn new_token() -> String {
+    let mut bytes = [0u8; TOKEN_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Hash of a session token, under which its session is stored so that the tokens
+/// cannot be read from the database.
+fn token_hash(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
@@ -0,0 +1,204 @@
+use crate::db::{self, get_db_con};
+use crate::error::{self, application::Error};
+use crate::{DBCon, DBPool, Result};
+use common::bulk::{BulkItemResult, BulkItemStatus, BulkMode, BulkResponse};
+use common::error::{ErrorCode, FieldError};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use validator::Validate;
+use warp::http::StatusCode;
+use warp::reply::json;
+use warp::{reject, Buf, Reply};
+
+/// Items a bulk request may contain, all of which are written in one transaction.
+pub const MAX_ITEMS: usize = 1000;
+
+/// Items of a bulk request, whose body must be a JSON array.
+pub fn items(buf: impl Buf) -> Result<Vec<Value>> {
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let items: Vec<Value> = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    if items.len() > MAX_ITEMS {
+        return Err(reject::custom(Error::TooManyBulkItems(items.len())));
+    }
+
+    Ok(items)
+}
+
+/// Deserializes and validates an item, as the body of a request of its own would be.
+pub fn parse<T: DeserializeOwned + Validate>(item: Value) -> Result<T> {
+    let item: T = serde_path_to_error::deserialize(item)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    item.validate()
+        .map_err(|e| reject::custom(Error::Validation(e)))?;
+
+    Ok(item)
+}
+
+/// Bulk request whose items are written in a single transaction, each behind a
+/// savepoint so that the changes of a failed item are undone on their own.
+pub struct Bulk<T> {
+    con: DBCon,
+    mode: BulkMode,
+    applied: BulkItemStatus,
+    dry_run: bool,
+    items: Vec<BulkItemResult<T>>,
+}
+
+impl<T: Serialize> Bulk<T> {
+    /// Starts a bulk request whose succeeded items are reported as `applied`.
+    pub async fn begin(db_pool: &DBPool, mode: BulkMode, applied: BulkItemStatus) -> Result<Self> {
+        let con = get_db_con(db_pool).await.map_err(reject::custom)?;
+
+        Ok(Bulk {
+            con,
+            mode,
+            applied,
+            dry_run: false,
+            items: vec![],
+        })
+    }
+
+    /// Makes the request a dry run, whose items are written only to be validated
+    /// by the database and are then rolled back.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Writes the next item, undoing its changes if it fails.
+    pub fn write<R>(&self, write: impl FnOnce(&DBCon) -> db::Result<R>) -> Result<R> {
+        self.con
+            .execute("SAVEPOINT bulk_item", &[])
+            .map_err(|e| reject::custom(Error::DBQuery(e)))?;
+
+        write(&self.con).map_err(|e| {
+            if let Err(e) = self.con.execute("ROLLBACK TO SAVEPOINT bulk_item", &[]) {
+                log::error!("error rolling back a bulk item: {}", e);
+            }
+            reject::custom(e)
+        })
+    }
+
+    /// Records the outcome of the next item.
+    pub fn record(&mut self, result: Result<T>) {
+        let index = self.items.len();
+        let item = match result {
+            Ok(record) => BulkItemResult {
+                index,
+                status: self.applied,
+                record: Some(record),
+                code: None,
+                error_code: None,
+                message: None,
+                errors: None,
+            },
+            Err(rejection) => {
+                let (code, error_code, message, errors) = error::describe(&rejection);
+                BulkItemResult {
+                    index,
+                    status: BulkItemStatus::Failed,
+                    record: None,
+                    code: Some(code.as_u16()),
+                    error_code: Some(error_code),
+                    message: Some(message),
+                    errors,
+                }
+            }
+        };
+
+        self.items.push(item);
+    }
+
+    /// Records the next item as failed with the errors of its fields.
+    pub fn record_errors(&mut self, errors: Vec<FieldError>) {
+        self.items.push(BulkItemResult {
+            index: self.items.len(),
+            status: BulkItemStatus::Failed,
+            record: None,
+            code: Some(StatusCode::BAD_REQUEST.as_u16()),
+            error_code: Some(ErrorCode::ValidationFailed),
+            message: Some("field errors".to_string()),
+            errors: Some(errors),
+        });
+    }
+
+    /// Commits the succeeded items, unless an item of a transactional request failed,
+    /// and replies with the outcome of each item.
+    pub fn finish(self) -> Result<impl Reply> {
+        let (code, response) = self.report()?;
+
+        Ok(warp::reply::with_status(json(&response), code))
+    }
+
+    /// Commits the succeeded items, unless the request is a dry run or an item of a
+    /// transactional request failed, and reports the outcome of each item along with
+    /// the status code of the response.
+    pub fn report(mut self) -> Result<(StatusCode, BulkResponse<T>)> {
+        let failed = self
+            .items
+            .iter()
+            .filter(|item| item.status == BulkItemStatus::Failed)
+            .count();
+        let committed = !self.dry_run && (failed == 0 || self.mode == BulkMode::BestEffort);
+
+        if committed {
+            if let Err(e) = self.con.commit() {
+                self.con
+                    .rollback()
+                    .map_err(|e| reject::custom(Error::DBQuery(e)))?;
+                return Err(reject::custom(Error::DBQuery(e)));
+            }
+        } else {
+            self.con
+                .rollback()
+                .map_err(|e| reject::custom(Error::DBQuery(e)))?;
+
+            let undone = if self.dry_run {
+                BulkItemStatus::Valid
+            } else {
+                BulkItemStatus::RolledBack
+            };
+            for item in self
+                .items
+                .iter_mut()
+                .filter(|item| item.status != BulkItemStatus::Failed)
+            {
+                item.status = undone;
+                item.record = None;
+            }
+        }
+
+        let code = if failed == 0 {
+            match self.applied {
+                BulkItemStatus::Created if committed => StatusCode::CREATED,
+                _ => StatusCode::OK,
+            }
+        } else if self.mode == BulkMode::BestEffort {
+            StatusCode::MULTI_STATUS
+        } else {
+            StatusCode::UNPROCESSABLE_ENTITY
+        };
+
+        let succeeded = self
+            .items
+            .iter()
+            .filter(|item| item.status != BulkItemStatus::Failed)
+            .filter(|item| item.status != BulkItemStatus::RolledBack)
+            .count();
+
+        Ok((
+            code,
+            BulkResponse {
+                mode: self.mode,
+                committed,
+                succeeded,
+                failed,
+                items: self.items,
+            },
+        ))
+    }
+}
@@ -0,0 +1,127 @@
+use crate::bundle::repository;
+use crate::error::application::Error;
+use crate::etag::{self, IfMatch};
+use crate::{DBPool, Result};
+use common::bundle::{BundleRequest, BundleResponse};
+use common::subscription::SubscriptionResponse;
+use validator::Validate;
+use warp::reply::json;
+use warp::{reject, Buf, Reply};
+
+pub async fn list_bundles_handler(db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Listing bundles");
+
+    let bundles = repository::fetch(&db_pool).await.map_err(reject::custom)?;
+    Ok(json::<Vec<_>>(
+        &bundles.into_iter().map(BundleResponse::from).collect(),
+    ))
+}
+
+pub async fn fetch_bundle_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Fetching bundle with id {}", id);
+
+    let bundle = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    let version = bundle.version;
+    Ok(etag::reply(json(&BundleResponse::from(bundle)), version))
+}
+
+pub async fn fetch_subscriptions_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Fetching subscriptions of bundle with id {}", id);
+
+    let subscriptions = repository::fetch_subscriptions(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    Ok(json::<Vec<_>>(
+        &subscriptions
+            .into_iter()
+            .map(SubscriptionResponse::from)
+            .collect(),
+    ))
+}
+
+pub async fn create_bundle_handler(buf: impl Buf, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Creating a new bundle");
+
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: BundleRequest = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    body.validate()
+        .map_err(|e| reject::custom(Error::Validation(e)))?;
+
+    check_subscriptions(&db_pool, &body).await?;
+
+    let created_bundle = repository::create(&db_pool, body)
+        .await
+        .map_err(reject::custom)?;
+
+    let response = json(&BundleResponse::from(created_bundle));
+
+    Ok(warp::reply::with_status(
+        response,
+        warp::http::StatusCode::CREATED,
+    ))
+}
+
+pub async fn update_bundle_handler(
+    id: u32,
+    if_match: IfMatch,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Updating bundle with id {}", id);
+
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: BundleRequest = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    body.validate()
+        .map_err(|e| reject::custom(Error::Validation(e)))?;
+
+    check_subscriptions(&db_pool, &body).await?;
+
+    let bundle = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    if_match
+        .check("bundle", id, bundle.version)
+        .map_err(reject::custom)?;
+
+    let bundle = repository::update(&db_pool, id, bundle.version, body)
+        .await
+        .map_err(reject::custom)?;
+    let version = bundle.version;
+    Ok(etag::reply(json(&BundleResponse::from(bundle)), version))
+}
+
+pub async fn delete_bundle_handler(
+    id: u32,
+    if_match: IfMatch,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Deleting bundle with id {}", id);
+
+    let bundle = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    if_match
+        .check("bundle", id, bundle.version)
+        .map_err(reject::custom)?;
+
+    repository::delete(&db_pool, id, bundle.version)
+        .await
+        .map_err(reject::custom)?;
+    Ok(warp::http::StatusCode::NO_CONTENT)
+}
+
+async fn check_subscriptions(db_pool: &DBPool, body: &BundleRequest) -> Result<()> {
+    for subscription_id in body.subscription_ids.iter() {
+        crate::subscription::repository::fetch_one(db_pool, *subscription_id)
+            .await
+            .map_err(|_| reject::custom(Error::SubscriptionNotFound(*subscription_id)))?;
+    }
+
+    Ok(())
+}
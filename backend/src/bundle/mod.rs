@@ -0,0 +1,42 @@
+use crate::{etag, with_db, DBPool};
+use warp::Filter;
+
+pub mod handler;
+pub mod repository;
+
+pub fn get_routes(
+    db_pool: DBPool,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let bundle = warp::path!("bundle");
+    let bundle_param = warp::path!("bundle" / u32);
+    let bundle_subscription = warp::path!("bundle" / u32 / "subscription");
+
+    bundle
+        .and(warp::get())
+        .and(with_db(db_pool.clone()))
+        .and_then(handler::list_bundles_handler)
+        .or(bundle_param
+            .and(warp::get())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::fetch_bundle_handler))
+        .or(bundle_subscription
+            .and(warp::get())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::fetch_subscriptions_handler))
+        .or(bundle
+            .and(warp::post())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::create_bundle_handler))
+        .or(bundle_param
+            .and(warp::put())
+            .and(etag::if_match())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::update_bundle_handler))
+        .or(bundle_param
+            .and(warp::delete())
+            .and(etag::if_match())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::delete_bundle_handler))
+}
@@ -0,0 +1,227 @@
+use crate::db::bundle::{row_to_bundle, COMPONENT_TABLE, SELECT_FIELDS, TABLE};
+use crate::db::subscription::{
+    row_to_subscription, SELECT_FIELDS as SUBSCRIPTION_SELECT_FIELDS, TABLE as SUBSCRIPTION_TABLE,
+};
+use crate::db::{get_db_con, Result};
+use crate::error::application::Error;
+use crate::{DBCon, DBPool};
+use common::bundle::{Bundle, BundleRequest};
+use common::subscription::Subscription;
+use oracle::sql_type::OracleType;
+
+pub async fn fetch(db_pool: &DBPool) -> Result<Vec<Bundle>> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!("SELECT {} FROM {}", SELECT_FIELDS, TABLE);
+
+    let rows = con.query(query.as_str(), &[]).map_err(Error::DBQuery)?;
+
+    let mut bundles: Vec<Bundle> = rows
+        .filter(|r| r.is_ok())
+        .map(|r| row_to_bundle(&r.unwrap()))
+        .collect();
+
+    let query = format!(
+        "SELECT bundle_id, subscription_id FROM {} ORDER BY subscription_id",
+        COMPONENT_TABLE
+    );
+
+    let rows = con.query(query.as_str(), &[]).map_err(Error::DBQuery)?;
+
+    for row in rows.flatten() {
+        let bundle_id: u32 = row.get(0).unwrap();
+        let subscription_id: u32 = row.get(1).unwrap();
+
+        if let Some(bundle) = bundles.iter_mut().find(|b| b.id == bundle_id) {
+            bundle.subscription_ids.push(subscription_id);
+        }
+    }
+
+    Ok(bundles)
+}
+
+pub async fn fetch_one(db_pool: &DBPool, id: u32) -> Result<Bundle> {
+    let con = get_db_con(db_pool).await?;
+
+    fetch_with_components(&con, id)
+}
+
+pub async fn fetch_subscriptions(db_pool: &DBPool, id: u32) -> Result<Vec<Subscription>> {
+    let con = get_db_con(db_pool).await?;
+
+    fetch_bundle_subscriptions(&con, id)
+}
+
+pub fn fetch_bundle_subscriptions(con: &DBCon, id: u32) -> Result<Vec<Subscription>> {
+    fetch_with_components(con, id)?;
+
+    let query = format!(
+        "SELECT {} FROM {} WHERE id IN (SELECT subscription_id FROM {} WHERE bundle_id = :id) \
+        ORDER BY id",
+        SUBSCRIPTION_SELECT_FIELDS, SUBSCRIPTION_TABLE, COMPONENT_TABLE
+    );
+
+    let rows = con
+        .query_named(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(rows
+        .filter(|r| r.is_ok())
+        .map(|r| row_to_subscription(&r.unwrap()))
+        .collect())
+}
+
+pub async fn create(db_pool: &DBPool, body: BundleRequest) -> Result<Bundle> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "INSERT INTO {} (description, price) VALUES (:description, :price) RETURNING id INTO :id",
+        TABLE
+    );
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("description", &body.description),
+                ("price", &body.price),
+                ("id", &OracleType::Number(0, 0)),
+            ],
+        )
+        .map_err(Error::DBQuery)?;
+
+    let row_id: u32 = stmt.returned_values("id").map_err(Error::DBQuery)?[0];
+
+    if let Err(e) = insert_components(&con, row_id, &body.subscription_ids) {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    fetch_with_components(&con, row_id)
+}
+
+pub async fn update(
+    db_pool: &DBPool,
+    id: u32,
+    version: u32,
+    body: BundleRequest,
+) -> Result<Bundle> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "UPDATE {} SET description = :description, price = :price \
+        WHERE id = :id AND version = :version",
+        TABLE
+    );
+    let delete_query = format!("DELETE FROM {} WHERE bundle_id = :id", COMPONENT_TABLE);
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("id", &id),
+                ("version", &version),
+                ("description", &body.description),
+                ("price", &body.price),
+            ],
+        )
+        .map_err(Error::DBQuery)?;
+
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("bundle", id));
+    }
+
+    let result = con
+        .execute_named(delete_query.as_str(), &[("id", &id)])
+        .and_then(|_| insert_components(&con, id, &body.subscription_ids));
+
+    if let Err(e) = result {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    fetch_with_components(&con, id)
+}
+
+pub async fn delete(db_pool: &DBPool, id: u32, version: u32) -> Result<()> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "DELETE FROM {} WHERE id = :id AND version = :version",
+        TABLE
+    );
+
+    let stmt = con
+        .execute_named(query.as_str(), &[("id", &id), ("version", &version)])
+        .map_err(Error::DBQuery)?;
+
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("bundle", id));
+    }
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    Ok(())
+}
+
+pub fn fetch_with_components(con: &DBCon, id: u32) -> Result<Bundle> {
+    let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &id)])
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::BundleNotFound(id),
+            _ => Error::DBQuery(e),
+        })?;
+
+    let mut bundle = row_to_bundle(&row);
+
+    let query = format!(
+        "SELECT subscription_id FROM {} WHERE bundle_id = :id ORDER BY subscription_id",
+        COMPONENT_TABLE
+    );
+
+    let rows = con
+        .query_as_named::<u32>(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    bundle.subscription_ids = rows.filter_map(|r| r.ok()).collect();
+
+    Ok(bundle)
+}
+
+fn insert_components(
+    con: &DBCon,
+    bundle_id: u32,
+    subscription_ids: &[u32],
+) -> std::result::Result<(), oracle::Error> {
+    let query = format!(
+        "INSERT INTO {} (bundle_id, subscription_id) VALUES (:bundle_id, :subscription_id)",
+        COMPONENT_TABLE
+    );
+
+    let mut subscription_ids = subscription_ids.to_vec();
+    subscription_ids.sort_unstable();
+    subscription_ids.dedup();
+
+    for subscription_id in subscription_ids {
+        con.execute_named(
+            query.as_str(),
+            &[
+                ("bundle_id", &bundle_id),
+                ("subscription_id", &subscription_id),
+            ],
+        )?;
+    }
+
+    Ok(())
+}
@@ -1,3 +1,6 @@
+use crate::version::Deprecation;
+use chrono::NaiveDate;
+use common::credit::CreditRules;
 use lazy_static::lazy_static;
 use serde::Deserialize;
 
@@ -6,6 +9,140 @@ pub struct Config {
     pub db_dsn: String,
     pub db_user: String,
     pub db_pass: String,
+    /// Standard VAT percentage charged on invoices.
+    #[serde(default = "default_vat_rate")]
+    pub vat_rate: f64,
+    /// Years the financial records of a customer are kept after its last invoice,
+    /// even once its personal data is anonymized.
+    #[serde(default = "default_financial_retention_years")]
+    pub financial_retention_years: u32,
+    /// Overdue invoices from which a customer cannot sign new contracts, 0 to disable.
+    #[serde(default = "default_credit_block_overdue_invoices")]
+    pub credit_block_overdue_invoices: u32,
+    /// Outstanding balance from which a customer cannot sign new contracts, 0 to disable.
+    #[serde(default = "default_credit_block_balance")]
+    pub credit_block_balance: f64,
+    /// Overdue invoices from which new contracts require a security deposit, 0 to disable.
+    #[serde(default = "default_credit_deposit_overdue_invoices")]
+    pub credit_deposit_overdue_invoices: u32,
+    /// Outstanding balance from which new contracts require a security deposit, 0 to disable.
+    #[serde(default = "default_credit_deposit_balance")]
+    pub credit_deposit_balance: f64,
+    /// Comma separated tags of the customers whose new contracts require a security deposit.
+    #[serde(default = "default_credit_deposit_tags")]
+    pub credit_deposit_tags: Vec<String>,
+    /// Security deposit, in months of the price of the subscription or bundle.
+    #[serde(default = "default_credit_deposit_months")]
+    pub credit_deposit_months: f64,
+    /// Day from which the `/api/v1` paths are announced deprecated.
+    #[serde(default)]
+    pub api_v1_deprecated_on: Option<NaiveDate>,
+    /// Day after which the `/api/v1` paths may stop being served.
+    #[serde(default)]
+    pub api_v1_sunset_on: Option<NaiveDate>,
+    /// Day from which the unversioned `/api` paths are announced deprecated in favour of `/api/v1`.
+    #[serde(default)]
+    pub api_unversioned_deprecated_on: Option<NaiveDate>,
+    /// Day after which the unversioned `/api` paths may stop being served.
+    #[serde(default)]
+    pub api_unversioned_sunset_on: Option<NaiveDate>,
+    /// Hours for which the response to a request with an `Idempotency-Key` is replayed.
+    #[serde(default = "default_idempotency_key_hours")]
+    pub idempotency_key_hours: u32,
+    /// Hours a session stays valid after signing in.
+    #[serde(default = "default_session_hours")]
+    pub session_hours: u32,
+    /// Administrator created on startup while there is no user yet.
+    #[serde(default = "default_admin_username")]
+    pub admin_username: String,
+    /// Password of the administrator created on startup, which is not created without one.
+    #[serde(default)]
+    pub admin_password: Option<String>,
+    /// Comma separated origins of the frontend, the only ones allowed to call the API
+    /// from a browser.
+    #[serde(default = "default_frontend_origins")]
+    pub frontend_origins: Vec<String>,
+}
+
+impl Config {
+    pub fn credit_rules(&self) -> CreditRules {
+        CreditRules {
+            block_overdue_invoices: self.credit_block_overdue_invoices,
+            block_balance: self.credit_block_balance,
+            deposit_overdue_invoices: self.credit_deposit_overdue_invoices,
+            deposit_balance: self.credit_deposit_balance,
+            deposit_tags: self.credit_deposit_tags.clone(),
+            deposit_months: self.credit_deposit_months,
+        }
+    }
+
+    pub fn v1_deprecation(&self) -> Deprecation {
+        Deprecation {
+            deprecated_on: self.api_v1_deprecated_on,
+            sunset_on: self.api_v1_sunset_on,
+            successor: None,
+        }
+    }
+
+    /// Deprecation of the unversioned paths, which are served as an alias of v1.
+    pub fn unversioned_deprecation(&self) -> Deprecation {
+        Deprecation {
+            deprecated_on: self.api_unversioned_deprecated_on,
+            sunset_on: self.api_unversioned_sunset_on,
+            successor: Some("/api/v1"),
+        }
+    }
+}
+
+fn default_vat_rate() -> f64 {
+    21.0
+}
+
+fn default_financial_retention_years() -> u32 {
+    10
+}
+
+fn default_credit_block_overdue_invoices() -> u32 {
+    3
+}
+
+fn default_credit_block_balance() -> f64 {
+    1000.0
+}
+
+fn default_credit_deposit_overdue_invoices() -> u32 {
+    1
+}
+
+fn default_credit_deposit_balance() -> f64 {
+    100.0
+}
+
+fn default_credit_deposit_tags() -> Vec<String> {
+    vec!["RISK".to_string()]
+}
+
+fn default_credit_deposit_months() -> f64 {
+    2.0
+}
+
+fn default_idempotency_key_hours() -> u32 {
+    24
+}
+
+fn default_session_hours() -> u32 {
+    12
+}
+
+fn default_admin_username() -> String {
+    "admin".to_string()
+}
+
+fn default_frontend_origins() -> Vec<String> {
+    vec![
+        "http://127.0.0.1:8080".to_string(),
+        "http://localhost:8080".to_string(),
+    ]
 }
 
 lazy_static! {
@@ -0,0 +1,214 @@
+use common::error::ErrorCode;
+
+/// ORA-00001, raised when a unique constraint is violated.
+pub const UNIQUE_VIOLATION: i32 = 1;
+/// ORA-02290, raised when a check constraint is violated.
+pub const CHECK_VIOLATION: i32 = 2290;
+/// ORA-02291, raised when a foreign key references a record that does not exist.
+pub const PARENT_KEY_NOT_FOUND: i32 = 2291;
+/// ORA-02292, raised when a record still referenced by a foreign key is deleted.
+pub const CHILD_RECORD_FOUND: i32 = 2292;
+/// Raised by the `update_invoice_status` trigger when the invoice is already paid.
+pub const INVOICE_ALREADY_PAID: i32 = 20001;
+/// Raised by the `update_invoice_status` trigger when the payment exceeds what is
+/// left to pay of the invoice.
+pub const OVERPAYMENT: i32 = 20000;
+
+/// Field of the requests checked by each check, unique and primary key constraint
+/// of `tables.sql`, `__all__` for those checking several fields together.
+const FIELDS: [(&str, &str); 60] = [
+    ("valid_phone", "phone"),
+    ("valid_cnp", "cnp"),
+    ("valid_kind", "kind"),
+    ("valid_cui", "cui"),
+    ("valid_vat_payer", "vat_payer"),
+    ("valid_customer_reverse_charge", "reverse_charge"),
+    ("valid_legal_entity", "__all__"),
+    ("valid_anonymized_phone", "phone"),
+    ("valid_merge", "source_customer_id"),
+    ("valid_address_type", "address_type"),
+    ("valid_postal_code", "postal_code"),
+    ("valid_contact_role", "role"),
+    ("valid_contact_email", "email"),
+    ("valid_contact_phone", "phone"),
+    ("valid_type", "type"),
+    ("valid_traffic", "traffic"),
+    ("valid_price", "price"),
+    ("valid_extra_traffic_price", "extra_traffic_price"),
+    ("unique_promotion_code", "code"),
+    ("valid_discount_type", "discount_type"),
+    ("valid_discount_value", "discount_value"),
+    ("valid_duration_cycles", "duration_cycles"),
+    ("valid_promotion_type", "subscription_type"),
+    ("valid_promotion_period", "__all__"),
+    ("valid_bundle_price", "price"),
+    ("pk_bundle_subscription", "subscription_ids"),
+    ("valid_start_date", "__all__"),
+    ("valid_penalty_rate", "penalty_rate"),
+    ("valid_offering", "__all__"),
+    ("valid_addon_price", "price"),
+    ("valid_contract_addon_period", "__all__"),
+    ("valid_contract_addon_price", "price"),
+    ("valid_issue_date", "__all__"),
+    ("valid_amount", "amount"),
+    ("valid_status", "status"),
+    ("valid_vat_rate", "vat_rate"),
+    ("valid_reverse_charge", "reverse_charge"),
+    ("valid_payment_amount", "amount"),
+    ("valid_line_type", "line_type"),
+    ("valid_note_kind", "kind"),
+    ("valid_note_category", "category"),
+    ("valid_note_pinned", "pinned"),
+    ("unique_tag_name", "name"),
+    ("valid_tag_name", "name"),
+    ("pk_customer_tag", "tags"),
+    ("pk_contract_tag", "tags"),
+    ("unique_custom_field_key", "key"),
+    ("valid_custom_field_entity", "entity"),
+    ("valid_custom_field_key", "key"),
+    ("valid_custom_field_type", "field_type"),
+    ("valid_custom_field_options", "options"),
+    ("pk_customer_field_value", "fields"),
+    ("pk_contract_field_value", "fields"),
+    ("uq_deposit_contract", "contract_id"),
+    ("valid_deposit_amount", "amount"),
+    ("valid_deposit_status", "status"),
+    ("valid_refunded_amount", "amount"),
+    ("unique_username", "username"),
+    ("valid_user_role", "role"),
+    ("valid_user_active", "active"),
+];
+
+/// Field of the requests holding each foreign key of `tables.sql`, with the code of
+/// the error when the record it references does not exist.
+const FOREIGN_KEYS: [(&str, &str, ErrorCode); 29] = [
+    (
+        "fk_address_customer",
+        "customer_id",
+        ErrorCode::CustomerNotFound,
+    ),
+    (
+        "fk_contact_customer",
+        "customer_id",
+        ErrorCode::CustomerNotFound,
+    ),
+    (
+        "fk_merge_customer",
+        "target_customer_id",
+        ErrorCode::CustomerNotFound,
+    ),
+    (
+        "fk_component_bundle",
+        "bundle_id",
+        ErrorCode::BundleNotFound,
+    ),
+    (
+        "fk_component_subscription",
+        "subscription_ids",
+        ErrorCode::SubscriptionNotFound,
+    ),
+    ("fk_customer", "customer_id", ErrorCode::CustomerNotFound),
+    (
+        "fk_subscription",
+        "subscription_id",
+        ErrorCode::SubscriptionNotFound,
+    ),
+    ("fk_promotion", "promotion_id", ErrorCode::PromotionNotFound),
+    ("fk_bundle", "bundle_id", ErrorCode::BundleNotFound),
+    (
+        "fk_service_address",
+        "service_address_id",
+        ErrorCode::AddressNotFound,
+    ),
+    (
+        "fk_addon_contract",
+        "contract_id",
+        ErrorCode::ContractNotFound,
+    ),
+    ("fk_addon", "addon_id", ErrorCode::AddonNotFound),
+    ("fk_contract", "contract_id", ErrorCode::ContractNotFound),
+    ("fk_invoice", "invoice_id", ErrorCode::InvoiceNotFound),
+    ("fk_line_invoice", "invoice_id", ErrorCode::InvoiceNotFound),
+    (
+        "fk_line_source_invoice",
+        "source_invoice_id",
+        ErrorCode::InvoiceNotFound,
+    ),
+    (
+        "fk_note_customer",
+        "customer_id",
+        ErrorCode::CustomerNotFound,
+    ),
+    (
+        "fk_note_contract",
+        "contract_id",
+        ErrorCode::ContractNotFound,
+    ),
+    ("fk_note_invoice", "invoice_id", ErrorCode::InvoiceNotFound),
+    (
+        "fk_customer_tag_customer",
+        "customer_id",
+        ErrorCode::CustomerNotFound,
+    ),
+    ("fk_customer_tag_tag", "tags", ErrorCode::TagNotFound),
+    (
+        "fk_contract_tag_contract",
+        "contract_id",
+        ErrorCode::ContractNotFound,
+    ),
+    ("fk_contract_tag_tag", "tags", ErrorCode::TagNotFound),
+    (
+        "fk_customer_value_customer",
+        "customer_id",
+        ErrorCode::CustomerNotFound,
+    ),
+    (
+        "fk_customer_value_field",
+        "fields",
+        ErrorCode::CustomFieldNotFound,
+    ),
+    (
+        "fk_contract_value_contract",
+        "contract_id",
+        ErrorCode::ContractNotFound,
+    ),
+    (
+        "fk_contract_value_field",
+        "fields",
+        ErrorCode::CustomFieldNotFound,
+    ),
+    (
+        "fk_deposit_contract",
+        "contract_id",
+        ErrorCode::ContractNotFound,
+    ),
+    ("fk_session_user", "user_id", ErrorCode::UserNotFound),
+];
+
+/// Name of the constraint violated by the database error, in lowercase, from its
+/// message, e.g. `ORA-02291: integrity constraint (ISP.FK_CUSTOMER) violated`.
+pub fn violated(error: &oracle::DbError) -> Option<String> {
+    let message = error.message();
+    let start = message.find('(')? + 1;
+    let end = start + message[start..].find(')')?;
+    let name = &message[start..end];
+
+    Some(name.rsplit('.').next().unwrap_or(name).to_lowercase())
+}
+
+/// Field checked by the check, unique or primary key constraint.
+pub fn field(constraint: &str) -> Option<&'static str> {
+    FIELDS
+        .iter()
+        .find(|(name, _)| *name == constraint)
+        .map(|(_, field)| *field)
+}
+
+/// Field holding the foreign key, with the code of the error when the record it
+/// references does not exist.
+pub fn foreign_key(constraint: &str) -> Option<(&'static str, ErrorCode)> {
+    FOREIGN_KEYS
+        .iter()
+        .find(|(name, _, _)| *name == constraint)
+        .map(|(_, field, code)| (*field, *code))
+}
@@ -1,19 +1,73 @@
+use crate::bulk::{self, Bulk};
 use crate::contract::repository;
+use crate::db::{self, get_db_con};
 use crate::error::application::Error;
-use crate::{customer, subscription, DBPool, Result};
-use common::contract::{ContractResponse, CreateContractRequest, UpdateContractRequest};
+use crate::etag::{self, IfMatch};
+use crate::import::Import;
+use crate::{
+    addon, bundle, credit, customer, export, field, page, patch, promotion, subscription, tag,
+    DBCon, DBPool, Result,
+};
+use chrono::Utc;
+use common::addon::{ContractAddonRequest, ContractAddonResponse};
+use common::address::AddressType;
+use common::bulk::{BulkItemStatus, BulkQuery, BulkUpdate};
+use common::contract::{
+    ContractListQuery, ContractResponse, CreateContractQuery, CreateContractRequest,
+    UpdateContractRequest,
+};
+use common::credit::CreditDecision;
+use common::export::ExportFormat;
+use common::field::{CustomFieldValueResponse, CustomFieldValues, Entity};
+use common::import::ImportQuery;
 use common::invoice::InvoiceResponse;
+use common::page::PageQuery;
+use common::tag::{TagAssignmentRequest, TagResponse};
+use serde_json::Value;
 use validator::Validate;
+use warp::multipart::FormData;
 use warp::reply::json;
 use warp::{reject, Buf, Reply};
 
-pub async fn list_contracts_handler(db_pool: DBPool) -> Result<impl Reply> {
+pub async fn list_contracts_handler(
+    query: ContractListQuery,
+    page: PageQuery,
+    format: ExportFormat,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
     log::info!("Listing contracts");
 
-    let contracts = repository::fetch(&db_pool).await.map_err(reject::custom)?;
-    Ok(json::<Vec<_>>(
-        &contracts.into_iter().map(ContractResponse::from).collect(),
-    ))
+    let value = field::handler::parse_filter(
+        &db_pool,
+        Entity::Contract,
+        &query.field,
+        query.value.clone(),
+    )
+    .await?;
+    let filter = ContractListQuery {
+        value,
+        ..query.clone()
+    };
+
+    if format != ExportFormat::Json {
+        return export::reply(&db_pool, "contracts", format, move |con, each| {
+            repository::export(con, &filter, &page, &mut |contract| {
+                each(ContractResponse::from(contract))
+            })
+        })
+        .await;
+    }
+
+    let contracts = repository::fetch(&db_pool, &filter, &page)
+        .await
+        .map_err(reject::custom)?;
+    Ok(page::reply(
+        "/api/v1/contract",
+        &query,
+        &page,
+        contracts.map(ContractResponse::from),
+    )
+    .into_response())
 }
 
 pub async fn fetch_contract_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
@@ -22,7 +76,11 @@ pub async fn fetch_contract_handler(id: u32, db_pool: DBPool) -> Result<impl Rep
     let contract = repository::fetch_one(&db_pool, id)
         .await
         .map_err(reject::custom)?;
-    Ok(json(&ContractResponse::from(contract)))
+    let version = contract.version;
+    Ok(etag::reply(
+        json(&ContractResponse::from(contract)),
+        version,
+    ))
 }
 
 pub async fn fetch_invoices(id: u32, db_pool: DBPool) -> Result<impl Reply> {
@@ -36,35 +94,108 @@ pub async fn fetch_invoices(id: u32, db_pool: DBPool) -> Result<impl Reply> {
     ))
 }
 
-pub async fn create_contract_handler(buf: impl Buf, db_pool: DBPool) -> Result<impl Reply> {
-    log::info!("Creating a new contract");
+pub async fn fetch_addons(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Fetching add-ons for contract with id {}", id);
+
+    let addons = repository::fetch_addons(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    Ok(json::<Vec<_>>(
+        &addons
+            .into_iter()
+            .map(ContractAddonResponse::from)
+            .collect(),
+    ))
+}
+
+pub async fn add_addon_handler(id: u32, buf: impl Buf, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Adding an add-on to contract with id {}", id);
 
     let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
-    let body: CreateContractRequest = serde_path_to_error::deserialize(deserialized)
+    let body: ContractAddonRequest = serde_path_to_error::deserialize(deserialized)
         .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
 
     body.validate()
         .map_err(|e| reject::custom(Error::Validation(e)))?;
 
-    // check if customer exists
-    if customer::repository::fetch_one(&db_pool, body.customer_id)
+    let contract = repository::fetch_one(&db_pool, id)
         .await
-        .is_err()
-    {
-        return Err(reject::custom(Error::CustomerNotFound(body.customer_id)));
-    }
+        .map_err(|_| reject::custom(Error::ContractNotFound(id)))?;
 
-    // check if subscription exists
-    if subscription::repository::fetch_one(&db_pool, body.subscription_id)
+    let addon = addon::repository::fetch_one(&db_pool, body.addon_id)
         .await
-        .is_err()
-    {
-        return Err(reject::custom(Error::SubscriptionNotFound(
-            body.subscription_id,
+        .map_err(reject::custom)?;
+
+    // check if the add-on period is in contract availability period
+    if body.start_date < contract.start_date || body.end_date > contract.end_date {
+        return Err(reject::custom(Error::AddonNotInContractAvailabilityPeriod(
+            contract.id,
+            body.start_date,
+            body.end_date,
         )));
     }
 
-    let created_contract = repository::create(&db_pool, body)
+    let price = body.price.unwrap_or(addon.price);
+    let contract_addon = repository::add_addon(&db_pool, id, body, price)
+        .await
+        .map_err(reject::custom)?;
+
+    let response = json(&ContractAddonResponse::from(contract_addon));
+
+    Ok(warp::reply::with_status(
+        response,
+        warp::http::StatusCode::CREATED,
+    ))
+}
+
+pub async fn remove_addon_handler(
+    id: u32,
+    contract_addon_id: u32,
+    if_match: IfMatch,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!(
+        "Removing add-on {} from contract with id {}",
+        contract_addon_id,
+        id
+    );
+
+    let contract_addon = repository::fetch_addon(&db_pool, id, contract_addon_id)
+        .await
+        .map_err(reject::custom)?;
+    if_match
+        .check("contract add-on", contract_addon_id, contract_addon.version)
+        .map_err(reject::custom)?;
+
+    repository::remove_addon(&db_pool, id, contract_addon_id, contract_addon.version)
+        .await
+        .map_err(reject::custom)?;
+    Ok(warp::http::StatusCode::NO_CONTENT)
+}
+
+/// Contracts are only created for customers passing the credit check. Customers
+/// requiring a security deposit have to accept it, in which case it is recorded
+/// as pending along with the contract.
+pub async fn create_contract_handler(
+    query: CreateContractQuery,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Creating a new contract");
+
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: CreateContractRequest = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    body.validate()
+        .map_err(|e| reject::custom(Error::Validation(e)))?;
+
+    let (promotion_id, deposit) = {
+        let con = get_db_con(&db_pool).await.map_err(reject::custom)?;
+        check_contract(&con, &body, query.accept_deposit).map_err(reject::custom)?
+    };
+
+    let created_contract = repository::create(&db_pool, body, promotion_id, deposit)
         .await
         .map_err(reject::custom)?;
 
@@ -76,8 +207,69 @@ pub async fn create_contract_handler(buf: impl Buf, db_pool: DBPool) -> Result<i
     ))
 }
 
+pub async fn bulk_create_contracts_handler(
+    bulk_query: BulkQuery,
+    query: CreateContractQuery,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Creating contracts in bulk");
+
+    let items = bulk::items(buf)?;
+    let mut bulk = Bulk::begin(&db_pool, bulk_query.mode, BulkItemStatus::Created).await?;
+
+    for item in items {
+        let result = create_item(&bulk, item, query.accept_deposit);
+        bulk.record(result);
+    }
+
+    bulk.finish()
+}
+
+pub async fn import_contracts_handler(
+    import_query: ImportQuery,
+    query: CreateContractQuery,
+    form: FormData,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Importing contracts");
+
+    let import = Import::read::<CreateContractRequest>(form).await?;
+    let mut bulk = Bulk::begin(&db_pool, import_query.mode, BulkItemStatus::Created)
+        .await?
+        .dry_run(!import_query.commit);
+
+    for item in import.items() {
+        match item {
+            Ok(item) => {
+                let result = create_item(&bulk, item, query.accept_deposit);
+                bulk.record(result);
+            }
+            Err(errors) => bulk.record_errors(errors),
+        }
+    }
+
+    import.reply(bulk)
+}
+
+/// Creates a contract of a bulk request or an import, checked within its transaction.
+fn create_item(
+    bulk: &Bulk<ContractResponse>,
+    item: Value,
+    accept_deposit: bool,
+) -> Result<ContractResponse> {
+    let body: CreateContractRequest = bulk::parse(item)?;
+
+    let contract = bulk.write(|con| {
+        let (promotion_id, deposit) = check_contract(con, &body, accept_deposit)?;
+        repository::create_contract(con, &body, promotion_id, deposit)
+    })?;
+    Ok(ContractResponse::from(contract))
+}
+
 pub async fn update_contract_handler(
     id: u32,
+    if_match: IfMatch,
     buf: impl Buf,
     db_pool: DBPool,
 ) -> Result<impl Reply> {
@@ -90,18 +282,293 @@ pub async fn update_contract_handler(
     body.validate()
         .map_err(|e| reject::custom(Error::Validation(e)))?;
 
-    Ok(json(&ContractResponse::from(
-        repository::update(&db_pool, id, body)
-            .await
-            .map_err(reject::custom)?,
-    )))
+    let contract = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(|_| reject::custom(Error::ContractNotFound(id)))?;
+    if_match
+        .check("contract", id, contract.version)
+        .map_err(reject::custom)?;
+
+    {
+        let con = get_db_con(&db_pool).await.map_err(reject::custom)?;
+        check_service_address(&con, contract.customer_id, body.service_address_id)
+            .map_err(reject::custom)?;
+    }
+
+    let contract = repository::update(&db_pool, id, contract.version, body)
+        .await
+        .map_err(reject::custom)?;
+    let version = contract.version;
+    Ok(etag::reply(
+        json(&ContractResponse::from(contract)),
+        version,
+    ))
+}
+
+pub async fn bulk_update_contracts_handler(
+    query: BulkQuery,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Updating contracts in bulk");
+
+    let items = bulk::items(buf)?;
+    let mut bulk = Bulk::begin(&db_pool, query.mode, BulkItemStatus::Updated).await?;
+
+    for item in items {
+        let result: Result<ContractResponse> = async {
+            let item: BulkUpdate<UpdateContractRequest> = bulk::parse(item)?;
+
+            let contract = repository::fetch_one(&db_pool, item.id)
+                .await
+                .map_err(|_| reject::custom(Error::ContractNotFound(item.id)))?;
+
+            let contract = bulk.write(|con| {
+                check_service_address(con, contract.customer_id, item.request.service_address_id)?;
+                repository::update_contract(con, item.id, item.version, &item.request)
+            })?;
+            Ok(ContractResponse::from(contract))
+        }
+        .await;
+
+        bulk.record(result);
+    }
+
+    bulk.finish()
 }
 
-pub async fn delete_contract_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+pub async fn patch_contract_handler(
+    id: u32,
+    if_match: IfMatch,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Patching contract with id {}", id);
+
+    let contract = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(|_| reject::custom(Error::ContractNotFound(id)))?;
+    if_match
+        .check("contract", id, contract.version)
+        .map_err(reject::custom)?;
+
+    let (customer_id, version) = (contract.customer_id, contract.version);
+    let body = patch::apply(&UpdateContractRequest::from(contract), buf).map_err(reject::custom)?;
+
+    {
+        let con = get_db_con(&db_pool).await.map_err(reject::custom)?;
+        check_service_address(&con, customer_id, body.service_address_id)
+            .map_err(reject::custom)?;
+    }
+
+    let contract = repository::update(&db_pool, id, version, body)
+        .await
+        .map_err(reject::custom)?;
+    let version = contract.version;
+    Ok(etag::reply(
+        json(&ContractResponse::from(contract)),
+        version,
+    ))
+}
+
+pub async fn delete_contract_handler(
+    id: u32,
+    if_match: IfMatch,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
     log::info!("Deleting contract with id {}", id);
 
-    repository::delete(&db_pool, id)
+    let contract = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(|_| reject::custom(Error::ContractNotFound(id)))?;
+    if_match
+        .check("contract", id, contract.version)
+        .map_err(reject::custom)?;
+
+    repository::delete(&db_pool, id, contract.version)
         .await
         .map_err(reject::custom)?;
     Ok(warp::http::StatusCode::NO_CONTENT)
 }
+
+/// Checks that the customer, offering and service address of the contract exist,
+/// that its coupon applies and that the customer passes the credit check, returning
+/// the promotion of the coupon and the security deposit required, if any.
+fn check_contract(
+    con: &DBCon,
+    body: &CreateContractRequest,
+    accept_deposit: bool,
+) -> db::Result<(Option<u32>, Option<f64>)> {
+    // check if customer exists
+    if customer::repository::fetch_customer(con, body.customer_id).is_err() {
+        return Err(Error::CustomerNotFound(body.customer_id));
+    }
+
+    check_service_address(con, body.customer_id, body.service_address_id)?;
+
+    // check if the subscription or bundle exists
+    let subscriptions = match (body.subscription_id, body.bundle_id) {
+        (Some(subscription_id), _) => {
+            vec![
+                subscription::repository::fetch_subscription(con, subscription_id)
+                    .map_err(|_| Error::SubscriptionNotFound(subscription_id))?,
+            ]
+        }
+        (None, Some(bundle_id)) => bundle::repository::fetch_bundle_subscriptions(con, bundle_id)?,
+        (None, None) => vec![],
+    };
+
+    // check if the coupon, if any, can be applied to the contract
+    let mut promotion_id = None;
+
+    if let Some(code) = body.coupon_code.as_deref().filter(|code| !code.is_empty()) {
+        let promotion = promotion::repository::fetch_promotion_by_code(con, code)?;
+
+        // a promotion is active for the whole of its first and last days
+        let today = Utc::now().date_naive();
+        if today < promotion.valid_from.date_naive() || today > promotion.valid_until.date_naive() {
+            return Err(Error::CouponNotApplicable(
+                promotion.code,
+                "the promotion is not active".to_string(),
+            ));
+        }
+
+        // a bundle is eligible as long as one of its components is
+        if let Some(subscription_type) = &promotion.subscription_type {
+            if !subscriptions
+                .iter()
+                .any(|s| s.subscription_type == *subscription_type)
+            {
+                return Err(Error::CouponNotApplicable(
+                    promotion.code,
+                    format!(
+                        "the promotion is only valid for {} subscriptions",
+                        subscription_type
+                    ),
+                ));
+            }
+        }
+
+        promotion_id = Some(promotion.id);
+    }
+
+    let monthly_price = credit::handler::monthly_price(con, body.subscription_id, body.bundle_id)?;
+    let assessment = credit::handler::assess_customer(con, body.customer_id, monthly_price)?;
+
+    let deposit = match assessment.decision {
+        CreditDecision::Approved => None,
+        CreditDecision::Blocked => {
+            return Err(Error::CreditCheckFailed(
+                body.customer_id,
+                assessment.reasons.join(", "),
+            ));
+        }
+        CreditDecision::DepositRequired if !accept_deposit => {
+            return Err(Error::DepositRequired(
+                body.customer_id,
+                assessment.required_deposit,
+                assessment.reasons.join(", "),
+            ));
+        }
+        CreditDecision::DepositRequired => {
+            Some(assessment.required_deposit).filter(|amount| *amount > 0.0)
+        }
+    };
+
+    Ok((promotion_id, deposit))
+}
+
+/// The line of a contract is installed at one of the service installation
+/// addresses of its customer.
+fn check_service_address(con: &DBCon, customer_id: u32, address_id: Option<u32>) -> db::Result<()> {
+    let Some(address_id) = address_id else {
+        return Ok(());
+    };
+
+    let address = customer::repository::fetch_customer_address(con, customer_id, address_id)?;
+
+    if address.address_type != AddressType::Service {
+        return Err(Error::NotAServiceAddress(address_id, customer_id));
+    }
+
+    Ok(())
+}
+
+pub async fn list_contract_tags_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Listing tags of contract with id {}", id);
+
+    let tags = tag::repository::fetch_assigned(&db_pool, &Entity::Contract, id)
+        .await
+        .map_err(reject::custom)?;
+    Ok(json::<Vec<_>>(
+        &tags.into_iter().map(TagResponse::from).collect(),
+    ))
+}
+
+pub async fn set_contract_tags_handler(
+    id: u32,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Setting tags of contract with id {}", id);
+
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: TagAssignmentRequest = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    body.validate()
+        .map_err(|e| reject::custom(Error::Validation(e)))?;
+
+    if repository::fetch_one(&db_pool, id).await.is_err() {
+        return Err(reject::custom(Error::ContractNotFound(id)));
+    }
+
+    let tags = tag::repository::assign(&db_pool, &Entity::Contract, id, &body.tags)
+        .await
+        .map_err(reject::custom)?;
+    Ok(json::<Vec<_>>(
+        &tags.into_iter().map(TagResponse::from).collect(),
+    ))
+}
+
+pub async fn list_contract_fields_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Listing custom fields of contract with id {}", id);
+
+    let values = field::repository::fetch_values(&db_pool, &Entity::Contract, id)
+        .await
+        .map_err(reject::custom)?;
+    Ok(json::<Vec<_>>(
+        &values
+            .into_iter()
+            .map(CustomFieldValueResponse::from)
+            .collect(),
+    ))
+}
+
+pub async fn set_contract_fields_handler(
+    id: u32,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Setting custom fields of contract with id {}", id);
+
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: CustomFieldValues = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    if repository::fetch_one(&db_pool, id).await.is_err() {
+        return Err(reject::custom(Error::ContractNotFound(id)));
+    }
+
+    let values = field::handler::parse_values(&db_pool, Entity::Contract, body).await?;
+
+    let values = field::repository::set_values(&db_pool, &Entity::Contract, id, &values)
+        .await
+        .map_err(reject::custom)?;
+    Ok(json::<Vec<_>>(
+        &values
+            .into_iter()
+            .map(CustomFieldValueResponse::from)
+            .collect(),
+    ))
+}
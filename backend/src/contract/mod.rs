@@ -1,4 +1,8 @@
-use crate::{with_db, DBPool};
+use crate::{etag, export, import, with_db, DBPool};
+use common::bulk::BulkQuery;
+use common::contract::{ContractListQuery, CreateContractQuery};
+use common::import::ImportQuery;
+use common::page::PageQuery;
 use warp::Filter;
 
 pub mod handler;
@@ -7,12 +11,21 @@ pub mod repository;
 pub fn get_routes(
     db_pool: DBPool,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
-    let contract = warp::path!("api" / "contract");
-    let contract_param = warp::path!("api" / "contract" / u32);
-    let contract_invoices = warp::path!("api" / "contract" / u32 / "invoice");
+    let contract = warp::path!("contract");
+    let contract_param = warp::path!("contract" / u32);
+    let contract_bulk = warp::path!("contract" / "bulk");
+    let contract_import = warp::path!("contract" / "import");
+    let contract_invoices = warp::path!("contract" / u32 / "invoice");
+    let contract_addons = warp::path!("contract" / u32 / "addon");
+    let contract_addon_param = warp::path!("contract" / u32 / "addon" / u32);
+    let contract_tags = warp::path!("contract" / u32 / "tag");
+    let contract_fields = warp::path!("contract" / u32 / "field");
 
     contract
         .and(warp::get())
+        .and(warp::query::<ContractListQuery>())
+        .and(warp::query::<PageQuery>())
+        .and(export::format())
         .and(with_db(db_pool.clone()))
         .and_then(handler::list_contracts_handler)
         .or(contract_param
@@ -21,20 +34,81 @@ pub fn get_routes(
             .and_then(handler::fetch_contract_handler))
         .or(contract
             .and(warp::post())
+            .and(warp::query::<CreateContractQuery>())
             .and(warp::body::aggregate())
             .and(with_db(db_pool.clone()))
             .and_then(handler::create_contract_handler))
+        .or(contract_bulk
+            .and(warp::post())
+            .and(warp::query::<BulkQuery>())
+            .and(warp::query::<CreateContractQuery>())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::bulk_create_contracts_handler))
+        .or(contract_bulk
+            .and(warp::put())
+            .and(warp::query::<BulkQuery>())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::bulk_update_contracts_handler))
+        .or(contract_import
+            .and(warp::post())
+            .and(warp::query::<ImportQuery>())
+            .and(warp::query::<CreateContractQuery>())
+            .and(import::form())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::import_contracts_handler))
         .or(contract_param
             .and(warp::put())
+            .and(etag::if_match())
             .and(warp::body::aggregate())
             .and(with_db(db_pool.clone()))
             .and_then(handler::update_contract_handler))
+        .or(contract_param
+            .and(warp::patch())
+            .and(etag::if_match())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::patch_contract_handler))
         .or(contract_param
             .and(warp::delete())
+            .and(etag::if_match())
             .and(with_db(db_pool.clone()))
             .and_then(handler::delete_contract_handler))
         .or(contract_invoices
             .and(warp::get())
             .and(with_db(db_pool.clone()))
             .and_then(handler::fetch_invoices))
+        .or(contract_addons
+            .and(warp::get())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::fetch_addons))
+        .or(contract_addons
+            .and(warp::post())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::add_addon_handler))
+        .or(contract_addon_param
+            .and(warp::delete())
+            .and(etag::if_match())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::remove_addon_handler))
+        .or(contract_tags
+            .and(warp::get())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::list_contract_tags_handler))
+        .or(contract_tags
+            .and(warp::put())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::set_contract_tags_handler))
+        .or(contract_fields
+            .and(warp::get())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::list_contract_fields_handler))
+        .or(contract_fields
+            .and(warp::put())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::set_contract_fields_handler))
 }
@@ -1,22 +1,92 @@
 use crate::db::contract::{row_to_contract, SELECT_FIELDS, TABLE};
+use crate::db::contract_addon::row_to_contract_addon;
 use crate::db::invoice::row_to_invoice;
 use crate::db::{get_db_con, Result};
 use crate::error::application::Error;
-use crate::DBPool;
-use common::contract::{Contract, CreateContractRequest, UpdateContractRequest};
+use crate::page::{date_range, Listing, Page};
+use crate::{DBCon, DBPool};
+use common::addon::{ContractAddon, ContractAddonRequest};
+use common::contract::{Contract, ContractListQuery, CreateContractRequest, UpdateContractRequest};
+use common::field::Entity;
 use common::invoice::Invoice;
-use oracle::sql_type::OracleType;
+use common::page::PageQuery;
+use oracle::sql_type::{OracleType, ToSql};
 
-pub async fn fetch(db_pool: &DBPool) -> Result<Vec<Contract>> {
+const LISTING: Listing = Listing {
+    table: TABLE,
+    fields: SELECT_FIELDS,
+    sort_columns: &[
+        ("id", "id"),
+        ("customer_id", "customer_id"),
+        ("start_date", "start_date"),
+        ("end_date", "end_date"),
+    ],
+};
+
+/// Page of the contracts of the customer and offering, started in the date range,
+/// having the tag and the custom field value of the filter, when given.
+pub async fn fetch(
+    db_pool: &DBPool,
+    filter: &ContractListQuery,
+    page: &PageQuery,
+) -> Result<Page<Contract>> {
     let con = get_db_con(db_pool).await?;
-    let query = format!("SELECT {} FROM {}", SELECT_FIELDS, TABLE);
 
-    let rows = con.query(query.as_str(), &[]).map_err(Error::DBQuery)?;
+    filtered(filter, |condition, params| {
+        crate::page::fetch(&con, &LISTING, condition, params, page, row_to_contract)
+    })
+}
 
-    Ok(rows
-        .filter(|r| r.is_ok())
-        .map(|r| row_to_contract(&r.unwrap()))
-        .collect())
+/// Passes each contract matching the filter to `each` as it is read, sorted as by the page.
+pub fn export(
+    con: &DBCon,
+    filter: &ContractListQuery,
+    page: &PageQuery,
+    each: &mut dyn FnMut(Contract) -> Result<()>,
+) -> Result<()> {
+    filtered(filter, |condition, params| {
+        crate::page::for_each(
+            con,
+            &LISTING,
+            condition,
+            params,
+            page,
+            row_to_contract,
+            each,
+        )
+    })
+}
+
+/// Calls `list` with the condition selecting the contracts matching the filter and its
+/// parameters.
+fn filtered<R>(
+    filter: &ContractListQuery,
+    list: impl FnOnce(&str, &[(&str, &dyn ToSql)]) -> R,
+) -> R {
+    let condition = format!(
+        "(:customer_id IS NULL OR customer_id = :customer_id) \
+        AND (:subscription_id IS NULL OR subscription_id = :subscription_id) \
+        AND (:bundle_id IS NULL OR bundle_id = :bundle_id) \
+        AND (:date_from IS NULL OR start_date >= :date_from) \
+        AND (:date_to IS NULL OR start_date < :date_to) AND {} AND {}",
+        crate::db::tag::filter_condition(&Entity::Contract),
+        crate::db::custom_field::filter_condition(&Entity::Contract)
+    );
+    let (date_from, date_to) = date_range(filter.from, filter.to);
+
+    list(
+        condition.as_str(),
+        &[
+            ("customer_id", &filter.customer_id),
+            ("subscription_id", &filter.subscription_id),
+            ("bundle_id", &filter.bundle_id),
+            ("date_from", &date_from),
+            ("date_to", &date_to),
+            ("tag", &filter.tag),
+            ("field", &filter.field),
+            ("value", &filter.value),
+        ],
+    )
 }
 
 pub async fn fetch_one(db_pool: &DBPool, id: u32) -> Result<Contract> {
@@ -30,11 +100,45 @@ pub async fn fetch_one(db_pool: &DBPool, id: u32) -> Result<Contract> {
     Ok(row_to_contract(&row))
 }
 
-pub async fn create(db_pool: &DBPool, body: CreateContractRequest) -> Result<Contract> {
+/// Creates the contract along with its pending security deposit, if any, in one
+/// transaction.
+pub async fn create(
+    db_pool: &DBPool,
+    body: CreateContractRequest,
+    promotion_id: Option<u32>,
+    deposit: Option<f64>,
+) -> Result<Contract> {
     let con = get_db_con(db_pool).await?;
+
+    let contract = match create_contract(&con, &body, promotion_id, deposit) {
+        Ok(contract) => contract,
+        Err(e) => {
+            con.rollback().map_err(Error::DBQuery)?;
+            return Err(e);
+        }
+    };
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    Ok(contract)
+}
+
+/// Inserts the contract, along with its security deposit if any, within the
+/// transaction of the connection, without committing it.
+pub fn create_contract(
+    con: &DBCon,
+    body: &CreateContractRequest,
+    promotion_id: Option<u32>,
+    deposit: Option<f64>,
+) -> Result<Contract> {
     let query = format!(
-        "INSERT INTO {} (customer_id, subscription_id, start_date, end_date) \
-        VALUES (:customer_id, :subscription_id, :start_date, :end_date) RETURNING id INTO :id",
+        "INSERT INTO {} (customer_id, subscription_id, bundle_id, start_date, end_date, penalty_rate, \
+        promotion_id, service_address_id) VALUES (:customer_id, :subscription_id, :bundle_id, \
+        :start_date, :end_date, :penalty_rate, :promotion_id, :service_address_id) \
+        RETURNING id INTO :id",
         TABLE
     );
 
@@ -44,19 +148,32 @@ pub async fn create(db_pool: &DBPool, body: CreateContractRequest) -> Result<Con
             &[
                 ("customer_id", &body.customer_id),
                 ("subscription_id", &body.subscription_id),
+                ("bundle_id", &body.bundle_id),
                 ("start_date", &body.start_date),
                 ("end_date", &body.end_date),
+                ("penalty_rate", &body.penalty_rate),
+                ("promotion_id", &promotion_id),
+                ("service_address_id", &body.service_address_id),
                 ("id", &OracleType::Number(0, 0)),
             ],
         )
         .map_err(Error::DBQuery)?;
 
-    if let Err(e) = con.commit() {
-        con.rollback().map_err(Error::DBQuery)?;
-        return Err(Error::DBQuery(e));
+    let row_id: u32 = stmt.returned_values("id").map_err(Error::DBQuery)?[0];
+
+    if let Some(amount) = deposit {
+        let query = format!(
+            "INSERT INTO {} (contract_id, amount) VALUES (:contract_id, :amount)",
+            crate::db::contract_deposit::TABLE
+        );
+
+        con.execute_named(
+            query.as_str(),
+            &[("contract_id", &row_id), ("amount", &amount)],
+        )
+        .map_err(Error::DBQuery)?;
     }
 
-    let row_id: u32 = stmt.returned_values("id").map_err(Error::DBQuery)?[0];
     let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
 
     let row = con
@@ -66,31 +183,65 @@ pub async fn create(db_pool: &DBPool, body: CreateContractRequest) -> Result<Con
     Ok(row_to_contract(&row))
 }
 
-pub async fn update(db_pool: &DBPool, id: u32, body: UpdateContractRequest) -> Result<Contract> {
+pub async fn update(
+    db_pool: &DBPool,
+    id: u32,
+    version: u32,
+    body: UpdateContractRequest,
+) -> Result<Contract> {
     let con = get_db_con(db_pool).await?;
-    let query = format!(
-        "UPDATE {} SET start_date = :start_date, end_date = :end_date WHERE id = :id",
-        TABLE
-    );
 
-    con.execute_named(
-        query.as_str(),
-        &[
-            ("start_date", &body.start_date),
-            ("end_date", &body.end_date),
-            ("id", &id),
-        ],
-    )
-    .map_err(|e| match e {
-        oracle::Error::NoDataFound => Error::ContractNotFound(id),
-        _ => Error::DBQuery(e),
-    })?;
+    let contract = match update_contract(&con, id, version, &body) {
+        Ok(contract) => contract,
+        Err(e) => {
+            con.rollback().map_err(Error::DBQuery)?;
+            return Err(e);
+        }
+    };
 
     if let Err(e) = con.commit() {
         con.rollback().map_err(Error::DBQuery)?;
         return Err(Error::DBQuery(e));
     }
 
+    Ok(contract)
+}
+
+/// Updates the contract at the version within the transaction of the connection,
+/// without committing it.
+pub fn update_contract(
+    con: &DBCon,
+    id: u32,
+    version: u32,
+    body: &UpdateContractRequest,
+) -> Result<Contract> {
+    let query = format!(
+        "UPDATE {} SET start_date = :start_date, end_date = :end_date, \
+        penalty_rate = NVL(:penalty_rate, penalty_rate), service_address_id = :service_address_id WHERE id = :id AND version = :version",
+        TABLE
+    );
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("start_date", &body.start_date),
+                ("end_date", &body.end_date),
+                ("penalty_rate", &body.penalty_rate),
+                ("service_address_id", &body.service_address_id),
+                ("id", &id),
+                ("version", &version),
+            ],
+        )
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::ContractNotFound(id),
+            _ => Error::DBQuery(e),
+        })?;
+
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("contract", id));
+    }
+
     let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
 
     let row = con
@@ -100,13 +251,21 @@ pub async fn update(db_pool: &DBPool, id: u32, body: UpdateContractRequest) -> R
     Ok(row_to_contract(&row))
 }
 
-pub async fn delete(db_pool: &DBPool, id: u32) -> Result<()> {
+pub async fn delete(db_pool: &DBPool, id: u32, version: u32) -> Result<()> {
     let con = get_db_con(db_pool).await?;
-    let query = format!("DELETE FROM {} WHERE id = :id", TABLE);
+    let query = format!(
+        "DELETE FROM {} WHERE id = :id AND version = :version",
+        TABLE
+    );
 
-    con.execute_named(query.as_str(), &[("id", &id)])
+    let stmt = con
+        .execute_named(query.as_str(), &[("id", &id), ("version", &version)])
         .map_err(Error::DBQuery)?;
 
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("contract", id));
+    }
+
     if let Err(e) = con.commit() {
         con.rollback().map_err(Error::DBQuery)?;
         return Err(Error::DBQuery(e));
@@ -130,3 +289,130 @@ pub async fn fetch_invoices(db_pool: &DBPool, id: u32) -> Result<Vec<Invoice>> {
         .map(|r| row_to_invoice(&r.unwrap()))
         .collect())
 }
+
+pub async fn fetch_addons(db_pool: &DBPool, id: u32) -> Result<Vec<ContractAddon>> {
+    use crate::db::contract_addon::{SELECT_FIELDS, TABLE};
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "SELECT {} FROM {} WHERE contract_id = :id ORDER BY start_date",
+        SELECT_FIELDS, TABLE
+    );
+
+    let rows = con
+        .query_named(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(rows
+        .filter(|r| r.is_ok())
+        .map(|r| row_to_contract_addon(&r.unwrap()))
+        .collect())
+}
+
+pub async fn fetch_addon(
+    db_pool: &DBPool,
+    id: u32,
+    contract_addon_id: u32,
+) -> Result<ContractAddon> {
+    use crate::db::contract_addon::{SELECT_FIELDS, TABLE};
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "SELECT {} FROM {} WHERE id = :contract_addon_id AND contract_id = :id",
+        SELECT_FIELDS, TABLE
+    );
+
+    let row = con
+        .query_row_named(
+            query.as_str(),
+            &[("contract_addon_id", &contract_addon_id), ("id", &id)],
+        )
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::AddonNotFound(contract_addon_id),
+            _ => Error::DBQuery(e),
+        })?;
+
+    Ok(row_to_contract_addon(&row))
+}
+
+pub async fn add_addon(
+    db_pool: &DBPool,
+    id: u32,
+    body: ContractAddonRequest,
+    price: f64,
+) -> Result<ContractAddon> {
+    use crate::db::contract_addon::{SELECT_FIELDS, TABLE};
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "INSERT INTO {} (contract_id, addon_id, start_date, end_date, price) \
+        VALUES (:contract_id, :addon_id, :start_date, :end_date, :price) RETURNING id INTO :id",
+        TABLE
+    );
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("contract_id", &id),
+                ("addon_id", &body.addon_id),
+                ("start_date", &body.start_date),
+                ("end_date", &body.end_date),
+                ("price", &price),
+                ("id", &OracleType::Number(0, 0)),
+            ],
+        )
+        .map_err(Error::DBQuery)?;
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    let row_id: u32 = stmt.returned_values("id").map_err(Error::DBQuery)?[0];
+    let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &row_id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(row_to_contract_addon(&row))
+}
+
+pub async fn remove_addon(
+    db_pool: &DBPool,
+    id: u32,
+    contract_addon_id: u32,
+    version: u32,
+) -> Result<()> {
+    use crate::db::contract_addon::TABLE;
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "DELETE FROM {} WHERE id = :contract_addon_id AND contract_id = :id \
+        AND version = :version",
+        TABLE
+    );
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("contract_addon_id", &contract_addon_id),
+                ("version", &version),
+                ("id", &id),
+            ],
+        )
+        .map_err(Error::DBQuery)?;
+
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("contract add-on", contract_addon_id));
+    }
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    Ok(())
+}
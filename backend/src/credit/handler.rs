@@ -0,0 +1,130 @@
+use crate::config::CONFIG;
+use crate::credit::repository;
+use crate::db::{self, get_db_con};
+use crate::error::application::Error;
+use crate::{bundle, contract, customer, subscription, tag, DBCon, DBPool, Result};
+use chrono::Utc;
+use common::credit::{
+    assess, CreditAssessmentResponse, CreditCheckQuery, DepositResponse, DepositStatus,
+};
+use common::field::Entity;
+use warp::reply::json;
+use warp::{reject, Reply};
+
+pub async fn credit_check_handler(
+    id: u32,
+    query: CreditCheckQuery,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Checking the credit of customer with id {}", id);
+
+    let con = get_db_con(&db_pool).await.map_err(reject::custom)?;
+
+    if customer::repository::fetch_customer(&con, id).is_err() {
+        return Err(reject::custom(Error::CustomerNotFound(id)));
+    }
+
+    let monthly_price =
+        monthly_price(&con, query.subscription_id, query.bundle_id).map_err(reject::custom)?;
+
+    Ok(json(
+        &assess_customer(&con, id, monthly_price).map_err(reject::custom)?,
+    ))
+}
+
+/// Price per month of the subscription or bundle of a contract, 0 when none is given.
+pub fn monthly_price(
+    con: &DBCon,
+    subscription_id: Option<u32>,
+    bundle_id: Option<u32>,
+) -> db::Result<f64> {
+    match (subscription_id, bundle_id) {
+        (Some(subscription_id), _) => {
+            subscription::repository::fetch_subscription(con, subscription_id)
+                .map(|subscription| subscription.price)
+                .map_err(|_| Error::SubscriptionNotFound(subscription_id))
+        }
+        (None, Some(bundle_id)) => {
+            bundle::repository::fetch_with_components(con, bundle_id).map(|bundle| bundle.price)
+        }
+        (None, None) => Ok(0.0),
+    }
+}
+
+/// Risk assessment of the customer `id` about to sign a contract with the given
+/// monthly price, based on its unpaid invoices and its tags.
+pub fn assess_customer(
+    con: &DBCon,
+    id: u32,
+    monthly_price: f64,
+) -> db::Result<CreditAssessmentResponse> {
+    let exposure = repository::fetch_customer_exposure(con, id)?;
+
+    let tags: Vec<String> = tag::repository::fetch_assigned_tags(con, &Entity::Customer, id)?
+        .into_iter()
+        .map(|tag| tag.name)
+        .collect();
+
+    Ok(assess(
+        id,
+        &exposure,
+        &tags,
+        monthly_price,
+        &CONFIG.credit_rules(),
+    ))
+}
+
+pub async fn fetch_deposit_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Fetching the security deposit of contract with id {}", id);
+
+    let deposit = repository::fetch_deposit(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    Ok(json(&DepositResponse::from(deposit)))
+}
+
+pub async fn pay_deposit_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Paying the security deposit of contract with id {}", id);
+
+    repository::fetch_deposit(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+
+    let deposit = repository::pay(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    Ok(json(&DepositResponse::from(deposit)))
+}
+
+/// The security deposit is refunded once the contract ended, less the balance
+/// its invoices still have, which is paid out of it.
+pub async fn refund_deposit_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Refunding the security deposit of contract with id {}", id);
+
+    let contract = contract::repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(|_| reject::custom(Error::ContractNotFound(id)))?;
+
+    let deposit = repository::fetch_deposit(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+
+    if deposit.status != DepositStatus::Held {
+        return Err(reject::custom(Error::DepositNotRefundable(
+            id,
+            format!("the deposit is {}", deposit.status),
+        )));
+    }
+
+    if contract.end_date > Utc::now() {
+        return Err(reject::custom(Error::DepositNotRefundable(
+            id,
+            format!("the contract ends on {}", contract.end_date.date_naive()),
+        )));
+    }
+
+    let deposit = repository::refund(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    Ok(json(&DepositResponse::from(deposit)))
+}
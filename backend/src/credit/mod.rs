@@ -0,0 +1,33 @@
+use crate::{with_db, DBPool};
+use common::credit::CreditCheckQuery;
+use warp::Filter;
+
+pub mod handler;
+pub mod repository;
+
+pub fn get_routes(
+    db_pool: DBPool,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let customer_credit = warp::path!("customer" / u32 / "credit");
+    let contract_deposit = warp::path!("contract" / u32 / "deposit");
+    let contract_deposit_payment = warp::path!("contract" / u32 / "deposit" / "pay");
+    let contract_deposit_refund = warp::path!("contract" / u32 / "deposit" / "refund");
+
+    customer_credit
+        .and(warp::get())
+        .and(warp::query::<CreditCheckQuery>())
+        .and(with_db(db_pool.clone()))
+        .and_then(handler::credit_check_handler)
+        .or(contract_deposit
+            .and(warp::get())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::fetch_deposit_handler))
+        .or(contract_deposit_payment
+            .and(warp::post())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::pay_deposit_handler))
+        .or(contract_deposit_refund
+            .and(warp::post())
+            .and(with_db(db_pool))
+            .and_then(handler::refund_deposit_handler))
+}
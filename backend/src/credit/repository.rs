@@ -0,0 +1,169 @@
+use crate::db::contract_deposit::{row_to_deposit, SELECT_FIELDS, TABLE};
+use crate::db::invoice::OUTSTANDING_AMOUNT;
+use crate::db::{get_db_con, Result};
+use crate::error::application::Error;
+use crate::{payment, DBCon, DBPool};
+use chrono::Utc;
+use common::credit::{CreditExposure, Deposit};
+use common::payment::CreatePaymentRequest;
+use common::penalty::round_to_cents;
+
+/// Unpaid invoices of the contracts of the customer `id`.
+pub fn fetch_customer_exposure(con: &DBCon, id: u32) -> Result<CreditExposure> {
+    let query = format!(
+        "SELECT COUNT(CASE WHEN i.due_date < SYSDATE THEN 1 END), NVL(SUM({}), 0) \
+        FROM invoice i JOIN contract c ON c.id = i.contract_id \
+        WHERE c.customer_id = :id AND i.status = 'UNPAID'",
+        OUTSTANDING_AMOUNT
+    );
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(CreditExposure {
+        overdue_invoices: row.get(0).map_err(Error::DBQuery)?,
+        outstanding_balance: row.get(1).map_err(Error::DBQuery)?,
+    })
+}
+
+/// Security deposit of the contract `id`.
+pub async fn fetch_deposit(db_pool: &DBPool, id: u32) -> Result<Deposit> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "SELECT {} FROM {} WHERE contract_id = :id",
+        SELECT_FIELDS, TABLE
+    );
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &id)])
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::DepositNotFound(id),
+            _ => Error::DBQuery(e),
+        })?;
+
+    Ok(row_to_deposit(&row))
+}
+
+/// Records the payment of the pending security deposit of the contract `id`.
+pub async fn pay(db_pool: &DBPool, id: u32) -> Result<Deposit> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "UPDATE {} SET status = 'HELD', paid_at = SYSDATE \
+        WHERE contract_id = :id AND status = 'PENDING'",
+        TABLE
+    );
+
+    let stmt = con
+        .execute_named(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::DepositNotPending(id));
+    }
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    let query = format!(
+        "SELECT {} FROM {} WHERE contract_id = :id",
+        SELECT_FIELDS, TABLE
+    );
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(row_to_deposit(&row))
+}
+
+/// Records the refund of the held security deposit of the contract `id`, which first
+/// pays off the unpaid invoices of the contract, the oldest first.
+pub async fn refund(db_pool: &DBPool, id: u32) -> Result<Deposit> {
+    let con = get_db_con(db_pool).await?;
+
+    let deposit = match refund_deposit(&con, id) {
+        Ok(deposit) => deposit,
+        Err(e) => {
+            con.rollback().map_err(Error::DBQuery)?;
+            return Err(e);
+        }
+    };
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    Ok(deposit)
+}
+
+fn refund_deposit(con: &DBCon, id: u32) -> Result<Deposit> {
+    // locked so that concurrent refunds do not pay the invoices twice
+    let query = format!(
+        "SELECT {} FROM {} WHERE contract_id = :id AND status = 'HELD' FOR UPDATE",
+        SELECT_FIELDS, TABLE
+    );
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &id)])
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => {
+                Error::DepositNotRefundable(id, "the deposit is not held".to_string())
+            }
+            _ => Error::DBQuery(e),
+        })?;
+    let deposit = row_to_deposit(&row);
+
+    let query = format!(
+        "SELECT i.id, {} FROM invoice i WHERE i.contract_id = :id AND i.status = 'UNPAID' \
+        ORDER BY i.due_date, i.id",
+        OUTSTANDING_AMOUNT
+    );
+
+    let invoices: Vec<(u32, f64)> = con
+        .query_as_named::<(u32, f64)>(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut left = deposit.amount;
+    for (invoice_id, outstanding) in invoices {
+        let amount = round_to_cents(outstanding.min(left));
+        if amount <= 0.0 {
+            break;
+        }
+
+        payment::repository::create_payment(
+            con,
+            &CreatePaymentRequest {
+                invoice_id,
+                payment_date: Utc::now(),
+                amount,
+            },
+        )?;
+        left = round_to_cents(left - amount);
+    }
+
+    let query = format!(
+        "UPDATE {} SET status = 'REFUNDED', refunded_at = SYSDATE, refunded_amount = :amount \
+        WHERE contract_id = :id",
+        TABLE
+    );
+
+    con.execute_named(query.as_str(), &[("id", &id), ("amount", &left)])
+        .map_err(Error::DBQuery)?;
+
+    let query = format!(
+        "SELECT {} FROM {} WHERE contract_id = :id",
+        SELECT_FIELDS, TABLE
+    );
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(row_to_deposit(&row))
+}
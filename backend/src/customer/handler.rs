@@ -1,19 +1,100 @@
+use crate::bulk::{self, Bulk};
+use crate::config::CONFIG;
 use crate::customer::repository;
+use crate::db;
 use crate::error::application::Error;
-use crate::{DBPool, Result};
+use crate::etag::{self, IfMatch};
+use crate::import::Import;
+use crate::{export, field, page, patch, tag, DBPool, Result};
+use chrono::{Months, Utc};
+use common::addon::ContractAddonResponse;
+use common::address::{AddressRequest, AddressResponse};
+use common::bulk::{BulkItemStatus, BulkQuery, BulkUpdate};
+use common::contact::{ContactRequest, ContactResponse};
 use common::contract::ContractResponse;
-use common::customer::{CustomerRequest, CustomerResponse};
-use common::invoice::InvoiceResponse;
+use common::credit::DepositResponse;
+use common::customer::{
+    CreateCustomerQuery, Customer, CustomerListQuery, CustomerRequest, CustomerResponse,
+    CustomerSearchQuery, CustomerSearchResponse,
+};
+use common::export::ExportFormat;
+use common::field::{CustomFieldValueResponse, CustomFieldValues, Entity};
+use common::import::ImportQuery;
+use common::invoice::{InvoiceLineResponse, InvoiceResponse};
+use common::merge::{
+    CustomerDuplicateResponse, CustomerMergeRequest, CustomerMergeResponse, DuplicateReason,
+};
+use common::note::{NoteRequest, NoteResponse};
+use common::page::PageQuery;
+use common::payment::PaymentResponse;
+use common::privacy::{AnonymizationResponse, ContractExport, CustomerExport, InvoiceExport};
+use common::tag::{TagAssignmentRequest, TagResponse};
+use serde_json::Value;
+use std::collections::HashMap;
 use validator::Validate;
+use warp::multipart::FormData;
 use warp::reply::json;
 use warp::{reject, Buf, Reply};
 
-pub async fn list_customers_handler(db_pool: DBPool) -> Result<impl Reply> {
+pub async fn list_customers_handler(
+    query: CustomerListQuery,
+    page: PageQuery,
+    format: ExportFormat,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
     log::info!("Listing customers");
 
-    let customers = repository::fetch(&db_pool).await.map_err(reject::custom)?;
+    let value = field::handler::parse_filter(
+        &db_pool,
+        Entity::Customer,
+        &query.field,
+        query.value.clone(),
+    )
+    .await?;
+    let filter = CustomerListQuery {
+        value,
+        ..query.clone()
+    };
+
+    if format != ExportFormat::Json {
+        return export::reply(&db_pool, "customers", format, move |con, each| {
+            repository::export(con, &filter, &page, &mut |customer| {
+                each(CustomerResponse::from(customer))
+            })
+        })
+        .await;
+    }
+
+    let customers = repository::fetch(&db_pool, &filter, &page)
+        .await
+        .map_err(reject::custom)?;
+    Ok(page::reply(
+        "/api/v1/customer",
+        &query,
+        &page,
+        customers.map(CustomerResponse::from),
+    )
+    .into_response())
+}
+
+pub async fn search_customers_handler(
+    query: CustomerSearchQuery,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Searching customers matching {:?}", query.q);
+
+    query
+        .validate()
+        .map_err(|e| reject::custom(Error::Validation(e)))?;
+
+    let matches = repository::search(&db_pool, &query.q)
+        .await
+        .map_err(reject::custom)?;
     Ok(json::<Vec<_>>(
-        &customers.into_iter().map(CustomerResponse::from).collect(),
+        &matches
+            .into_iter()
+            .map(CustomerSearchResponse::from)
+            .collect(),
     ))
 }
 
@@ -23,7 +104,11 @@ pub async fn fetch_customer_handler(id: u32, db_pool: DBPool) -> Result<impl Rep
     let customer = repository::fetch_one(&db_pool, id)
         .await
         .map_err(reject::custom)?;
-    Ok(json(&CustomerResponse::from(customer)))
+    let version = customer.version;
+    Ok(etag::reply(
+        json(&CustomerResponse::from(customer)),
+        version,
+    ))
 }
 
 pub async fn list_customer_unpaid_invoices_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
@@ -48,7 +133,11 @@ pub async fn list_customer_contracts_handler(id: u32, db_pool: DBPool) -> Result
     ))
 }
 
-pub async fn create_customer_handler(buf: impl Buf, db_pool: DBPool) -> Result<impl Reply> {
+pub async fn create_customer_handler(
+    query: CreateCustomerQuery,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
     log::info!("Creating a new customer");
 
     let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
@@ -58,6 +147,11 @@ pub async fn create_customer_handler(buf: impl Buf, db_pool: DBPool) -> Result<i
     body.validate()
         .map_err(|e| reject::custom(Error::Validation(e)))?;
 
+    let duplicates = repository::find_duplicates(&db_pool, &body)
+        .await
+        .map_err(reject::custom)?;
+    check_duplicates(duplicates, query.force).map_err(reject::custom)?;
+
     let created_customer = repository::create(&db_pool, body)
         .await
         .map_err(reject::custom)?;
@@ -70,8 +164,169 @@ pub async fn create_customer_handler(buf: impl Buf, db_pool: DBPool) -> Result<i
     ))
 }
 
+pub async fn bulk_create_customers_handler(
+    bulk_query: BulkQuery,
+    query: CreateCustomerQuery,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Creating customers in bulk");
+
+    let items = bulk::items(buf)?;
+    let mut bulk = Bulk::begin(&db_pool, bulk_query.mode, BulkItemStatus::Created).await?;
+
+    for item in items {
+        let result = create_item(&bulk, item, query.force);
+        bulk.record(result);
+    }
+
+    bulk.finish()
+}
+
+pub async fn import_customers_handler(
+    import_query: ImportQuery,
+    query: CreateCustomerQuery,
+    form: FormData,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Importing customers");
+
+    let import = Import::read::<CustomerRequest>(form).await?;
+    let mut bulk = Bulk::begin(&db_pool, import_query.mode, BulkItemStatus::Created)
+        .await?
+        .dry_run(!import_query.commit);
+
+    for item in import.items() {
+        match item {
+            Ok(item) => {
+                let result = create_item(&bulk, item, query.force);
+                bulk.record(result);
+            }
+            Err(errors) => bulk.record_errors(errors),
+        }
+    }
+
+    import.reply(bulk)
+}
+
+/// Creates a customer of a bulk request or an import, checked within its transaction
+/// so that it duplicates neither the customers already stored nor those created
+/// by the previous items.
+fn create_item(
+    bulk: &Bulk<CustomerResponse>,
+    item: Value,
+    force: bool,
+) -> Result<CustomerResponse> {
+    let body: CustomerRequest = bulk::parse(item)?;
+
+    let customer = bulk.write(|con| {
+        check_duplicates(repository::find_customer_duplicates(con, &body)?, force)?;
+        repository::create_customer(con, &body)
+    })?;
+    Ok(CustomerResponse::from(customer))
+}
+
+pub async fn bulk_update_customers_handler(
+    query: BulkQuery,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Updating customers in bulk");
+
+    let items = bulk::items(buf)?;
+    let mut bulk = Bulk::begin(&db_pool, query.mode, BulkItemStatus::Updated).await?;
+
+    for item in items {
+        let result: Result<CustomerResponse> = async {
+            let item: BulkUpdate<CustomerRequest> = bulk::parse(item)?;
+
+            let customer = repository::fetch_one(&db_pool, item.id)
+                .await
+                .map_err(|_| reject::custom(Error::CustomerNotFound(item.id)))?;
+
+            if customer.anonymized_at.is_some() {
+                return Err(reject::custom(Error::CustomerAnonymized(item.id)));
+            }
+
+            let customer = bulk.write(|con| {
+                repository::update_customer(con, item.id, item.version, &item.request)
+            })?;
+            Ok(CustomerResponse::from(customer))
+        }
+        .await;
+
+        bulk.record(result);
+    }
+
+    bulk.finish()
+}
+
+/// Fails if the customer already exists or, unless `force`d, may already exist.
+fn check_duplicates(duplicates: Vec<(Customer, DuplicateReason)>, force: bool) -> db::Result<()> {
+    if let Some((customer, reason)) = duplicates
+        .into_iter()
+        .find(|(_, reason)| reason.is_certain() || !force)
+    {
+        return Err(Error::DuplicateCustomer(customer.id, reason));
+    }
+
+    Ok(())
+}
+
+pub async fn list_duplicate_customers_handler(db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Listing duplicate customers");
+
+    let duplicates = repository::fetch_duplicates(&db_pool)
+        .await
+        .map_err(reject::custom)?;
+    Ok(json::<Vec<_>>(
+        &duplicates
+            .into_iter()
+            .map(CustomerDuplicateResponse::from)
+            .collect(),
+    ))
+}
+
+pub async fn merge_customer_handler(id: u32, buf: impl Buf, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Merging a customer into customer with id {}", id);
+
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: CustomerMergeRequest = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    if body.source_customer_id == id {
+        return Err(reject::custom(Error::SelfMerge(id)));
+    }
+
+    let merge = repository::merge(&db_pool, id, body.source_customer_id)
+        .await
+        .map_err(reject::custom)?;
+
+    let response = json(&CustomerMergeResponse::from(merge));
+
+    Ok(warp::reply::with_status(
+        response,
+        warp::http::StatusCode::CREATED,
+    ))
+}
+
+pub async fn list_customer_merges_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Listing merges into customer with id {}", id);
+
+    let merges = repository::fetch_merges(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    Ok(json::<Vec<_>>(
+        &merges
+            .into_iter()
+            .map(CustomerMergeResponse::from)
+            .collect(),
+    ))
+}
+
 pub async fn update_customer_handler(
     id: u32,
+    if_match: IfMatch,
     buf: impl Buf,
     db_pool: DBPool,
 ) -> Result<impl Reply> {
@@ -84,18 +339,628 @@ pub async fn update_customer_handler(
     body.validate()
         .map_err(|e| reject::custom(Error::Validation(e)))?;
 
-    Ok(json(&CustomerResponse::from(
-        repository::update(&db_pool, id, body)
+    let customer = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(|_| reject::custom(Error::CustomerNotFound(id)))?;
+
+    if customer.anonymized_at.is_some() {
+        return Err(reject::custom(Error::CustomerAnonymized(id)));
+    }
+    if_match
+        .check("customer", id, customer.version)
+        .map_err(reject::custom)?;
+
+    let customer = repository::update(&db_pool, id, customer.version, body)
+        .await
+        .map_err(reject::custom)?;
+    let version = customer.version;
+    Ok(etag::reply(
+        json(&CustomerResponse::from(customer)),
+        version,
+    ))
+}
+
+pub async fn patch_customer_handler(
+    id: u32,
+    if_match: IfMatch,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Patching customer with id {}", id);
+
+    let customer = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(|_| reject::custom(Error::CustomerNotFound(id)))?;
+
+    if customer.anonymized_at.is_some() {
+        return Err(reject::custom(Error::CustomerAnonymized(id)));
+    }
+    if_match
+        .check("customer", id, customer.version)
+        .map_err(reject::custom)?;
+
+    let version = customer.version;
+    let body = patch::apply(&CustomerRequest::from(customer), buf).map_err(reject::custom)?;
+
+    let customer = repository::update(&db_pool, id, version, body)
+        .await
+        .map_err(reject::custom)?;
+    let version = customer.version;
+    Ok(etag::reply(
+        json(&CustomerResponse::from(customer)),
+        version,
+    ))
+}
+
+pub async fn export_customer_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Exporting personal data of customer with id {}", id);
+
+    let customer = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(|_| reject::custom(Error::CustomerNotFound(id)))?;
+
+    let addresses = repository::fetch_addresses(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    let contacts = repository::fetch_contacts(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    let notes = repository::fetch_notes(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    let tags = tag::repository::fetch_assigned(&db_pool, &Entity::Customer, id)
+        .await
+        .map_err(reject::custom)?;
+    let custom_fields = field::repository::fetch_values(&db_pool, &Entity::Customer, id)
+        .await
+        .map_err(reject::custom)?;
+    let merges = repository::fetch_merges(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    let contracts = repository::fetch_contracts(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    let mut contract_fields = HashMap::new();
+    for contract in &contracts {
+        let values = field::repository::fetch_values(&db_pool, &Entity::Contract, contract.id)
             .await
-            .map_err(reject::custom)?,
-    )))
+            .map_err(reject::custom)?;
+        contract_fields.insert(contract.id, values);
+    }
+    let addons = repository::fetch_contract_addons(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    let deposits = repository::fetch_deposits(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    let invoices = repository::fetch_invoices(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    let lines = repository::fetch_invoice_lines(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    let payments = repository::fetch_payments(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+
+    let export = CustomerExport {
+        exported_at: Utc::now(),
+        customer: CustomerResponse::from(customer),
+        addresses: addresses.into_iter().map(AddressResponse::from).collect(),
+        contacts: contacts.into_iter().map(ContactResponse::from).collect(),
+        notes: notes.into_iter().map(NoteResponse::from).collect(),
+        tags: tags.into_iter().map(TagResponse::from).collect(),
+        custom_fields: custom_fields
+            .into_iter()
+            .map(CustomFieldValueResponse::from)
+            .collect(),
+        merges: merges
+            .into_iter()
+            .map(CustomerMergeResponse::from)
+            .collect(),
+        contracts: contracts
+            .into_iter()
+            .map(|contract| ContractExport {
+                custom_fields: contract_fields
+                    .remove(&contract.id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(CustomFieldValueResponse::from)
+                    .collect(),
+                deposit: deposits
+                    .iter()
+                    .find(|deposit| deposit.contract_id == contract.id)
+                    .cloned()
+                    .map(DepositResponse::from),
+                addons: addons
+                    .iter()
+                    .filter(|addon| addon.contract_id == contract.id)
+                    .cloned()
+                    .map(ContractAddonResponse::from)
+                    .collect(),
+                invoices: invoices
+                    .iter()
+                    .filter(|invoice| invoice.contract_id == contract.id)
+                    .map(|invoice| InvoiceExport {
+                        lines: lines
+                            .iter()
+                            .filter(|line| line.invoice_id == invoice.id)
+                            .cloned()
+                            .map(InvoiceLineResponse::from)
+                            .collect(),
+                        payments: payments
+                            .iter()
+                            .filter(|payment| payment.invoice_id == invoice.id)
+                            .cloned()
+                            .map(PaymentResponse::from)
+                            .collect(),
+                        invoice: InvoiceResponse::from(invoice.clone()),
+                    })
+                    .collect(),
+                contract: ContractResponse::from(contract),
+            })
+            .collect(),
+    };
+
+    Ok(warp::reply::with_header(
+        json(&export),
+        "Content-Disposition",
+        format!("attachment; filename=\"customer-{}.json\"", id),
+    ))
 }
 
-pub async fn delete_customer_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+pub async fn anonymize_customer_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Anonymizing customer with id {}", id);
+
+    let customer = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(|_| reject::custom(Error::CustomerNotFound(id)))?;
+
+    if customer.anonymized_at.is_some() {
+        return Err(reject::custom(Error::CustomerAnonymized(id)));
+    }
+
+    let anonymization = repository::anonymize(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    let invoices = repository::fetch_invoices(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+
+    let retention_months = CONFIG.financial_retention_years * 12;
+    let financial_records_retained_until = invoices
+        .iter()
+        .map(|invoice| invoice.issue_date)
+        .max()
+        .and_then(|issue_date| issue_date.checked_add_months(Months::new(retention_months)));
+
+    Ok(json(&AnonymizationResponse {
+        customer: CustomerResponse::from(anonymization.customer),
+        addresses_scrubbed: anonymization.addresses_scrubbed,
+        contacts_removed: anonymization.contacts_removed,
+        financial_records_retained_until,
+    }))
+}
+
+pub async fn delete_customer_handler(
+    id: u32,
+    if_match: IfMatch,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
     log::info!("Deleting customer with id {}", id);
 
-    repository::delete(&db_pool, id)
+    let customer = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(|_| reject::custom(Error::CustomerNotFound(id)))?;
+    if_match
+        .check("customer", id, customer.version)
+        .map_err(reject::custom)?;
+
+    repository::delete(&db_pool, id, customer.version)
+        .await
+        .map_err(reject::custom)?;
+    Ok(warp::http::StatusCode::NO_CONTENT)
+}
+
+pub async fn list_customer_addresses_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Listing addresses for customer with id {}", id);
+
+    let addresses = repository::fetch_addresses(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    Ok(json::<Vec<_>>(
+        &addresses.into_iter().map(AddressResponse::from).collect(),
+    ))
+}
+
+pub async fn add_customer_address_handler(
+    id: u32,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Adding an address to customer with id {}", id);
+
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: AddressRequest = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    body.validate()
+        .map_err(|e| reject::custom(Error::Validation(e)))?;
+
+    // check if customer exists
+    if repository::fetch_one(&db_pool, id).await.is_err() {
+        return Err(reject::custom(Error::CustomerNotFound(id)));
+    }
+
+    let address = repository::add_address(&db_pool, id, body)
+        .await
+        .map_err(reject::custom)?;
+
+    let response = json(&AddressResponse::from(address));
+
+    Ok(warp::reply::with_status(
+        response,
+        warp::http::StatusCode::CREATED,
+    ))
+}
+
+pub async fn update_customer_address_handler(
+    id: u32,
+    address_id: u32,
+    if_match: IfMatch,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Updating address {} of customer with id {}", address_id, id);
+
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: AddressRequest = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    body.validate()
+        .map_err(|e| reject::custom(Error::Validation(e)))?;
+
+    let address = repository::fetch_address(&db_pool, id, address_id)
+        .await
+        .map_err(reject::custom)?;
+    if_match
+        .check("address", address_id, address.version)
+        .map_err(reject::custom)?;
+
+    let address = repository::update_address(&db_pool, id, address_id, address.version, body)
+        .await
+        .map_err(reject::custom)?;
+    let version = address.version;
+    Ok(etag::reply(json(&AddressResponse::from(address)), version))
+}
+
+pub async fn remove_customer_address_handler(
+    id: u32,
+    address_id: u32,
+    if_match: IfMatch,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Removing address {} of customer with id {}", address_id, id);
+
+    let address = repository::fetch_address(&db_pool, id, address_id)
+        .await
+        .map_err(reject::custom)?;
+    if_match
+        .check("address", address_id, address.version)
+        .map_err(reject::custom)?;
+
+    repository::remove_address(&db_pool, id, address_id, address.version)
+        .await
+        .map_err(reject::custom)?;
+    Ok(warp::http::StatusCode::NO_CONTENT)
+}
+
+pub async fn list_customer_contacts_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Listing contacts for customer with id {}", id);
+
+    let contacts = repository::fetch_contacts(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    Ok(json::<Vec<_>>(
+        &contacts.into_iter().map(ContactResponse::from).collect(),
+    ))
+}
+
+pub async fn add_customer_contact_handler(
+    id: u32,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Adding a contact to customer with id {}", id);
+
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: ContactRequest = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    body.validate()
+        .map_err(|e| reject::custom(Error::Validation(e)))?;
+
+    // check if customer exists
+    if repository::fetch_one(&db_pool, id).await.is_err() {
+        return Err(reject::custom(Error::CustomerNotFound(id)));
+    }
+
+    let contact = repository::add_contact(&db_pool, id, body)
+        .await
+        .map_err(reject::custom)?;
+
+    let response = json(&ContactResponse::from(contact));
+
+    Ok(warp::reply::with_status(
+        response,
+        warp::http::StatusCode::CREATED,
+    ))
+}
+
+pub async fn update_customer_contact_handler(
+    id: u32,
+    contact_id: u32,
+    if_match: IfMatch,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Updating contact {} of customer with id {}", contact_id, id);
+
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: ContactRequest = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    body.validate()
+        .map_err(|e| reject::custom(Error::Validation(e)))?;
+
+    let contact = repository::fetch_contact(&db_pool, id, contact_id)
+        .await
+        .map_err(reject::custom)?;
+    if_match
+        .check("contact", contact_id, contact.version)
+        .map_err(reject::custom)?;
+
+    let contact = repository::update_contact(&db_pool, id, contact_id, contact.version, body)
+        .await
+        .map_err(reject::custom)?;
+    let version = contact.version;
+    Ok(etag::reply(json(&ContactResponse::from(contact)), version))
+}
+
+pub async fn remove_customer_contact_handler(
+    id: u32,
+    contact_id: u32,
+    if_match: IfMatch,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Removing contact {} of customer with id {}", contact_id, id);
+
+    let contact = repository::fetch_contact(&db_pool, id, contact_id)
+        .await
+        .map_err(reject::custom)?;
+    if_match
+        .check("contact", contact_id, contact.version)
+        .map_err(reject::custom)?;
+
+    repository::remove_contact(&db_pool, id, contact_id, contact.version)
         .await
         .map_err(reject::custom)?;
     Ok(warp::http::StatusCode::NO_CONTENT)
 }
+
+pub async fn list_customer_notes_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Listing notes for customer with id {}", id);
+
+    let notes = repository::fetch_notes(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    Ok(json::<Vec<_>>(
+        &notes.into_iter().map(NoteResponse::from).collect(),
+    ))
+}
+
+pub async fn add_customer_note_handler(
+    id: u32,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Adding a note to customer with id {}", id);
+
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: NoteRequest = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    body.validate()
+        .map_err(|e| reject::custom(Error::Validation(e)))?;
+
+    // check if customer exists
+    if repository::fetch_one(&db_pool, id).await.is_err() {
+        return Err(reject::custom(Error::CustomerNotFound(id)));
+    }
+
+    check_note_records(&db_pool, id, &body).await?;
+
+    let note = repository::add_note(&db_pool, id, body)
+        .await
+        .map_err(reject::custom)?;
+
+    let response = json(&NoteResponse::from(note));
+
+    Ok(warp::reply::with_status(
+        response,
+        warp::http::StatusCode::CREATED,
+    ))
+}
+
+pub async fn update_customer_note_handler(
+    id: u32,
+    note_id: u32,
+    if_match: IfMatch,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Updating note {} of customer with id {}", note_id, id);
+
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: NoteRequest = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    body.validate()
+        .map_err(|e| reject::custom(Error::Validation(e)))?;
+
+    check_note_records(&db_pool, id, &body).await?;
+
+    let note = repository::fetch_note(&db_pool, id, note_id)
+        .await
+        .map_err(reject::custom)?;
+    if_match
+        .check("note", note_id, note.version)
+        .map_err(reject::custom)?;
+
+    let note = repository::update_note(&db_pool, id, note_id, note.version, body)
+        .await
+        .map_err(reject::custom)?;
+    let version = note.version;
+    Ok(etag::reply(json(&NoteResponse::from(note)), version))
+}
+
+pub async fn remove_customer_note_handler(
+    id: u32,
+    note_id: u32,
+    if_match: IfMatch,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Removing note {} of customer with id {}", note_id, id);
+
+    let note = repository::fetch_note(&db_pool, id, note_id)
+        .await
+        .map_err(reject::custom)?;
+    if_match
+        .check("note", note_id, note.version)
+        .map_err(reject::custom)?;
+
+    repository::remove_note(&db_pool, id, note_id, note.version)
+        .await
+        .map_err(reject::custom)?;
+    Ok(warp::http::StatusCode::NO_CONTENT)
+}
+
+pub async fn list_customer_tags_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Listing tags of customer with id {}", id);
+
+    let tags = tag::repository::fetch_assigned(&db_pool, &Entity::Customer, id)
+        .await
+        .map_err(reject::custom)?;
+    Ok(json::<Vec<_>>(
+        &tags.into_iter().map(TagResponse::from).collect(),
+    ))
+}
+
+pub async fn set_customer_tags_handler(
+    id: u32,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Setting tags of customer with id {}", id);
+
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: TagAssignmentRequest = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    body.validate()
+        .map_err(|e| reject::custom(Error::Validation(e)))?;
+
+    if repository::fetch_one(&db_pool, id).await.is_err() {
+        return Err(reject::custom(Error::CustomerNotFound(id)));
+    }
+
+    let tags = tag::repository::assign(&db_pool, &Entity::Customer, id, &body.tags)
+        .await
+        .map_err(reject::custom)?;
+    Ok(json::<Vec<_>>(
+        &tags.into_iter().map(TagResponse::from).collect(),
+    ))
+}
+
+pub async fn list_customer_fields_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Listing custom fields of customer with id {}", id);
+
+    let values = field::repository::fetch_values(&db_pool, &Entity::Customer, id)
+        .await
+        .map_err(reject::custom)?;
+    Ok(json::<Vec<_>>(
+        &values
+            .into_iter()
+            .map(CustomFieldValueResponse::from)
+            .collect(),
+    ))
+}
+
+pub async fn set_customer_fields_handler(
+    id: u32,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Setting custom fields of customer with id {}", id);
+
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: CustomFieldValues = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    let customer = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(|_| reject::custom(Error::CustomerNotFound(id)))?;
+
+    if customer.anonymized_at.is_some() {
+        return Err(reject::custom(Error::CustomerAnonymized(id)));
+    }
+
+    let values = field::handler::parse_values(&db_pool, Entity::Customer, body).await?;
+
+    let values = field::repository::set_values(&db_pool, &Entity::Customer, id, &values)
+        .await
+        .map_err(reject::custom)?;
+    Ok(json::<Vec<_>>(
+        &values
+            .into_iter()
+            .map(CustomFieldValueResponse::from)
+            .collect(),
+    ))
+}
+
+/// Checks that the contract and invoice a note is about belong to the customer,
+/// and the invoice to the contract when both are given.
+async fn check_note_records(db_pool: &DBPool, customer_id: u32, body: &NoteRequest) -> Result<()> {
+    if let Some(contract_id) = body.contract_id {
+        let contracts = repository::fetch_contracts(db_pool, customer_id)
+            .await
+            .map_err(reject::custom)?;
+
+        if !contracts.iter().any(|contract| contract.id == contract_id) {
+            return Err(reject::custom(Error::ContractNotOfCustomer(
+                contract_id,
+                customer_id,
+            )));
+        }
+    }
+
+    if let Some(invoice_id) = body.invoice_id {
+        let invoices = repository::fetch_invoices(db_pool, customer_id)
+            .await
+            .map_err(reject::custom)?;
+
+        if !invoices.iter().any(|invoice| {
+            invoice.id == invoice_id
+                && body
+                    .contract_id
+                    .is_none_or(|contract_id| invoice.contract_id == contract_id)
+        }) {
+            return Err(reject::custom(Error::InvoiceNotOfCustomer(
+                invoice_id,
+                customer_id,
+            )));
+        }
+    }
+
+    Ok(())
+}
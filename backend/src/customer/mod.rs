@@ -1,4 +1,8 @@
-use crate::{with_db, DBPool};
+use crate::{etag, export, import, with_db, DBPool};
+use common::bulk::BulkQuery;
+use common::customer::{CreateCustomerQuery, CustomerListQuery, CustomerSearchQuery};
+use common::import::ImportQuery;
+use common::page::PageQuery;
 use warp::Filter;
 
 pub mod handler;
@@ -7,31 +11,87 @@ pub mod repository;
 pub fn get_routes(
     db_pool: DBPool,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
-    let customer = warp::path!("api" / "customer");
-    let customer_param = warp::path!("api" / "customer" / u32);
-    let customer_unpaid_invoices = warp::path!("api" / "customer" / u32 / "invoice");
-    let customer_contracts = warp::path!("api" / "customer" / u32 / "contract");
+    let customer = warp::path!("customer");
+    let customer_search = warp::path!("customer" / "search");
+    let customer_duplicates = warp::path!("customer" / "duplicates");
+    let customer_bulk = warp::path!("customer" / "bulk");
+    let customer_import = warp::path!("customer" / "import");
+    let customer_param = warp::path!("customer" / u32);
+    let customer_merges = warp::path!("customer" / u32 / "merge");
+    let customer_notes = warp::path!("customer" / u32 / "note");
+    let customer_note_param = warp::path!("customer" / u32 / "note" / u32);
+    let customer_tags = warp::path!("customer" / u32 / "tag");
+    let customer_fields = warp::path!("customer" / u32 / "field");
+    let customer_export = warp::path!("customer" / u32 / "export");
+    let customer_anonymize = warp::path!("customer" / u32 / "anonymize");
+    let customer_unpaid_invoices = warp::path!("customer" / u32 / "invoice");
+    let customer_contracts = warp::path!("customer" / u32 / "contract");
+    let customer_addresses = warp::path!("customer" / u32 / "address");
+    let customer_address_param = warp::path!("customer" / u32 / "address" / u32);
+    let customer_contacts = warp::path!("customer" / u32 / "contact");
+    let customer_contact_param = warp::path!("customer" / u32 / "contact" / u32);
 
     customer
         .and(warp::get())
+        .and(warp::query::<CustomerListQuery>())
+        .and(warp::query::<PageQuery>())
+        .and(export::format())
         .and(with_db(db_pool.clone()))
         .and_then(handler::list_customers_handler)
+        .or(customer_search
+            .and(warp::get())
+            .and(warp::query::<CustomerSearchQuery>())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::search_customers_handler))
+        .or(customer_duplicates
+            .and(warp::get())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::list_duplicate_customers_handler))
         .or(customer_param
             .and(warp::get())
             .and(with_db(db_pool.clone()))
             .and_then(handler::fetch_customer_handler))
         .or(customer
             .and(warp::post())
+            .and(warp::query::<CreateCustomerQuery>())
             .and(warp::body::aggregate())
             .and(with_db(db_pool.clone()))
             .and_then(handler::create_customer_handler))
+        .or(customer_bulk
+            .and(warp::post())
+            .and(warp::query::<BulkQuery>())
+            .and(warp::query::<CreateCustomerQuery>())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::bulk_create_customers_handler))
+        .or(customer_bulk
+            .and(warp::put())
+            .and(warp::query::<BulkQuery>())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::bulk_update_customers_handler))
+        .or(customer_import
+            .and(warp::post())
+            .and(warp::query::<ImportQuery>())
+            .and(warp::query::<CreateCustomerQuery>())
+            .and(import::form())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::import_customers_handler))
         .or(customer_param
             .and(warp::put())
+            .and(etag::if_match())
             .and(warp::body::aggregate())
             .and(with_db(db_pool.clone()))
             .and_then(handler::update_customer_handler))
+        .or(customer_param
+            .and(warp::patch())
+            .and(etag::if_match())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::patch_customer_handler))
         .or(customer_param
             .and(warp::delete())
+            .and(etag::if_match())
             .and(with_db(db_pool.clone()))
             .and_then(handler::delete_customer_handler))
         .or(customer_unpaid_invoices
@@ -42,4 +102,99 @@ pub fn get_routes(
             .and(warp::get())
             .and(with_db(db_pool.clone()))
             .and_then(handler::list_customer_contracts_handler))
+        .or(customer_addresses
+            .and(warp::get())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::list_customer_addresses_handler))
+        .or(customer_addresses
+            .and(warp::post())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::add_customer_address_handler))
+        .or(customer_address_param
+            .and(warp::put())
+            .and(etag::if_match())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::update_customer_address_handler))
+        .or(customer_address_param
+            .and(warp::delete())
+            .and(etag::if_match())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::remove_customer_address_handler))
+        .or(customer_contacts
+            .and(warp::get())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::list_customer_contacts_handler))
+        .or(customer_contacts
+            .and(warp::post())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::add_customer_contact_handler))
+        .or(customer_contact_param
+            .and(warp::put())
+            .and(etag::if_match())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::update_customer_contact_handler))
+        .or(customer_contact_param
+            .and(warp::delete())
+            .and(etag::if_match())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::remove_customer_contact_handler))
+        .or(customer_merges
+            .and(warp::get())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::list_customer_merges_handler))
+        .or(customer_merges
+            .and(warp::post())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::merge_customer_handler))
+        .or(customer_export
+            .and(warp::get())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::export_customer_handler))
+        .or(customer_anonymize
+            .and(warp::post())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::anonymize_customer_handler))
+        .or(customer_notes
+            .and(warp::get())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::list_customer_notes_handler))
+        .or(customer_notes
+            .and(warp::post())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::add_customer_note_handler))
+        .or(customer_note_param
+            .and(warp::put())
+            .and(etag::if_match())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::update_customer_note_handler))
+        .or(customer_note_param
+            .and(warp::delete())
+            .and(etag::if_match())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::remove_customer_note_handler))
+        .or(customer_tags
+            .and(warp::get())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::list_customer_tags_handler))
+        .or(customer_tags
+            .and(warp::put())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::set_customer_tags_handler))
+        .or(customer_fields
+            .and(warp::get())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::list_customer_fields_handler))
+        .or(customer_fields
+            .and(warp::put())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::set_customer_fields_handler))
 }
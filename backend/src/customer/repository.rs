@@ -1,28 +1,240 @@
 use crate::db::contract::row_to_contract;
 use crate::db::customer::{row_to_customer, SELECT_FIELDS, TABLE};
+use crate::db::customer_address::row_to_address;
+use crate::db::customer_contact::row_to_contact;
+use crate::db::customer_merge::row_to_customer_merge;
+use crate::db::customer_note::row_to_note;
 use crate::db::invoice::row_to_invoice;
 use crate::db::{get_db_con, Result};
 use crate::error::application::Error;
-use crate::DBPool;
+use crate::page::{Listing, Page};
+use crate::{DBCon, DBPool};
+use common::addon::ContractAddon;
+use common::address::{Address, AddressRequest};
+use common::contact::{Contact, ContactRequest};
 use common::contract::Contract;
-use common::customer::{Customer, CustomerRequest};
-use common::invoice::Invoice;
-use oracle::sql_type::OracleType;
+use common::credit::Deposit;
+use common::cui::{Cui, VatNumber};
+use common::customer::{Customer, CustomerListQuery, CustomerMatch, CustomerRequest};
+use common::field::Entity;
+use common::invoice::{Invoice, InvoiceLine};
+use common::merge::{CustomerDuplicate, CustomerMerge, DuplicateReason, NAME_SIMILARITY_THRESHOLD};
+use common::note::{Note, NoteRequest};
+use common::page::PageQuery;
+use common::payment::Payment;
+use common::phone::Phone;
+use common::privacy::{Anonymization, ANONYMIZED};
+use oracle::sql_type::{OracleType, ToSql};
+use std::collections::HashMap;
 
-pub async fn fetch(db_pool: &DBPool) -> Result<Vec<Customer>> {
+const LISTING: Listing = Listing {
+    table: TABLE,
+    fields: SELECT_FIELDS,
+    sort_columns: &[
+        ("id", "id"),
+        ("kind", "kind"),
+        ("name", "name"),
+        ("fullname", "fullname"),
+    ],
+};
+
+/// Page of the customers of the kind, having the tag and the custom field value of
+/// the filter, when given.
+pub async fn fetch(
+    db_pool: &DBPool,
+    filter: &CustomerListQuery,
+    page: &PageQuery,
+) -> Result<Page<Customer>> {
+    let con = get_db_con(db_pool).await?;
+
+    filtered(filter, |condition, params| {
+        crate::page::fetch(&con, &LISTING, condition, params, page, row_to_customer)
+    })
+}
+
+/// Passes each customer matching the filter to `each` as it is read, sorted as by the page.
+pub fn export(
+    con: &DBCon,
+    filter: &CustomerListQuery,
+    page: &PageQuery,
+    each: &mut dyn FnMut(Customer) -> Result<()>,
+) -> Result<()> {
+    filtered(filter, |condition, params| {
+        crate::page::for_each(
+            con,
+            &LISTING,
+            condition,
+            params,
+            page,
+            row_to_customer,
+            each,
+        )
+    })
+}
+
+/// Calls `list` with the condition selecting the customers matching the filter and its
+/// parameters.
+fn filtered<R>(
+    filter: &CustomerListQuery,
+    list: impl FnOnce(&str, &[(&str, &dyn ToSql)]) -> R,
+) -> R {
+    let condition = format!(
+        "(:kind IS NULL OR kind = :kind) AND {} AND {}",
+        crate::db::tag::filter_condition(&Entity::Customer),
+        crate::db::custom_field::filter_condition(&Entity::Customer)
+    );
+    let kind: Option<String> = filter.kind.clone().map(String::from);
+
+    list(
+        condition.as_str(),
+        &[
+            ("kind", &kind),
+            ("tag", &filter.tag),
+            ("field", &filter.field),
+            ("value", &filter.value),
+        ],
+    )
+}
+
+/// Relevance of a match on each searchable field, highest first for exact matches.
+const SEARCH_SCORES: [(&str, &str); 8] = [
+    (
+        "id",
+        "CASE WHEN id = :num THEN 100 ELSE 0 END",
+    ),
+    (
+        "name",
+        "CASE WHEN UPPER(name) = :q THEN 100 WHEN UPPER(name) LIKE :q_prefix ESCAPE '\\' THEN 60 \
+        WHEN UPPER(name) LIKE :q_like ESCAPE '\\' THEN 30 ELSE 0 END",
+    ),
+    (
+        "fullname",
+        "CASE WHEN UPPER(fullname) = :q THEN 100 WHEN UPPER(fullname) LIKE :q_prefix ESCAPE '\\' THEN 60 \
+        WHEN UPPER(fullname) LIKE :q_like ESCAPE '\\' THEN 30 ELSE 0 END",
+    ),
+    (
+        "phone",
+        "CASE WHEN phone = :phone THEN 90 WHEN :phone_digits IS NOT NULL AND phone LIKE '%' || :phone_digits || '%' THEN 40 ELSE 0 END",
+    ),
+    (
+        "cnp",
+        "CASE WHEN cnp = :q OR cui = :q OR cui = 'RO' || :q THEN 100 \
+        WHEN cnp LIKE :q_prefix ESCAPE '\\' OR cui LIKE :q_prefix ESCAPE '\\' THEN 50 ELSE 0 END",
+    ),
+    (
+        "address",
+        "CASE WHEN UPPER(address) LIKE :q_like ESCAPE '\\' OR EXISTS (SELECT 1 FROM customer_address a \
+        WHERE a.customer_id = customer.id AND (UPPER(a.street) LIKE :q_like ESCAPE '\\' \
+        OR UPPER(a.city) LIKE :q_like ESCAPE '\\' OR a.postal_code = :q)) THEN 20 ELSE 0 END",
+    ),
+    (
+        "contract",
+        "CASE WHEN EXISTS (SELECT 1 FROM contract c WHERE c.customer_id = customer.id AND c.id = :num) \
+        THEN 80 ELSE 0 END",
+    ),
+    (
+        "invoice",
+        "CASE WHEN EXISTS (SELECT 1 FROM invoice i JOIN contract c ON c.id = i.contract_id \
+        WHERE c.customer_id = customer.id AND i.id = :num) THEN 80 ELSE 0 END",
+    ),
+];
+
+const SEARCH_LIMIT: u32 = 50;
+
+/// Searches customers by partial name, fullname, phone, CNP or CUI, address,
+/// contract id or invoice number, the most relevant first.
+pub async fn search(db_pool: &DBPool, q: &str) -> Result<Vec<CustomerMatch>> {
     let con = get_db_con(db_pool).await?;
-    let query = format!("SELECT {} FROM {}", SELECT_FIELDS, TABLE);
 
-    let rows = con.query(query.as_str(), &[]).map_err(Error::DBQuery)?;
+    let score_columns: Vec<String> = SEARCH_SCORES
+        .iter()
+        .map(|(field, _)| format!("{}_score", field))
+        .collect();
+    let score_expressions: Vec<String> = SEARCH_SCORES
+        .iter()
+        .map(|(field, expression)| format!("{} AS {}_score", expression, field))
+        .collect();
+    let total_score = score_columns.join(" + ");
+
+    let query = format!(
+        "SELECT {}, {} FROM (SELECT {}, {} FROM {} WHERE anonymized_at IS NULL) \
+        WHERE {} > 0 \
+        ORDER BY {} DESC, id FETCH FIRST {} ROWS ONLY",
+        SELECT_FIELDS,
+        score_columns.join(", "),
+        SELECT_FIELDS,
+        score_expressions.join(", "),
+        TABLE,
+        total_score,
+        total_score,
+        SEARCH_LIMIT
+    );
+
+    let q = q.trim().to_uppercase();
+    let escaped = q
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    let q_prefix = format!("{}%", escaped);
+    let q_like = format!("%{}%", escaped);
+    let num: Option<u32> = q.parse().ok();
+    let phone: Option<String> = Phone::parse(&q).ok().map(|phone| phone.to_string());
+    // partial numbers are matched without the trunk prefix, e.g. 0722 as 722
+    let phone_digits: Option<String> = Some(
+        q.chars()
+            .filter(|c| c.is_ascii_digit())
+            .collect::<String>()
+            .trim_start_matches('0')
+            .to_string(),
+    )
+    .filter(|digits| digits.len() >= 3 && !q.chars().any(char::is_alphabetic));
+
+    let rows = con
+        .query_named(
+            query.as_str(),
+            &[
+                ("q", &q),
+                ("q_prefix", &q_prefix),
+                ("q_like", &q_like),
+                ("num", &num),
+                ("phone", &phone),
+                ("phone_digits", &phone_digits),
+            ],
+        )
+        .map_err(Error::DBQuery)?;
+
+    // the scores follow the customer fields
+    let offset = SELECT_FIELDS.split(',').count();
 
     Ok(rows
-        .filter(|r| r.is_ok())
-        .map(|r| row_to_customer(&r.unwrap()))
+        .flatten()
+        .map(|row| {
+            let scores: Vec<(String, u32)> = SEARCH_SCORES
+                .iter()
+                .enumerate()
+                .map(|(index, (field, _))| (field.to_string(), row.get(offset + index).unwrap()))
+                .collect();
+
+            CustomerMatch {
+                customer: row_to_customer(&row),
+                score: scores.iter().map(|(_, score)| score).sum(),
+                matched_fields: scores
+                    .into_iter()
+                    .filter(|(_, score)| *score > 0)
+                    .map(|(field, _)| field)
+                    .collect(),
+            }
+        })
         .collect())
 }
 
 pub async fn fetch_one(db_pool: &DBPool, id: u32) -> Result<Customer> {
     let con = get_db_con(db_pool).await?;
+
+    fetch_customer(&con, id)
+}
+
+pub fn fetch_customer(con: &DBCon, id: u32) -> Result<Customer> {
     let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
 
     let row = con
@@ -34,31 +246,59 @@ pub async fn fetch_one(db_pool: &DBPool, id: u32) -> Result<Customer> {
 
 pub async fn create(db_pool: &DBPool, body: CustomerRequest) -> Result<Customer> {
     let con = get_db_con(db_pool).await?;
+
+    let customer = match create_customer(&con, &body) {
+        Ok(customer) => customer,
+        Err(e) => {
+            con.rollback().map_err(Error::DBQuery)?;
+            return Err(e);
+        }
+    };
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    Ok(customer)
+}
+
+/// Inserts the customer within the transaction of the connection, without committing it.
+pub fn create_customer(con: &DBCon, body: &CustomerRequest) -> Result<Customer> {
     let query = format!(
-        "INSERT INTO {} (name, fullname, address, phone, cnp) \
-        VALUES (:name, :fullname, :address, :phone, :cnp) RETURNING id into :id",
+        "INSERT INTO {} (kind, name, fullname, address, phone, cnp, cui, trade_register_number, \
+        vat_payer, reverse_charge, legal_representative) VALUES (:kind, :name, :fullname, :address, \
+        :phone, :cnp, :cui, :trade_register_number, :vat_payer, :reverse_charge, :legal_representative) \
+        RETURNING id into :id",
         TABLE
     );
 
+    let kind: String = body.kind.clone().into();
+    let cui: Option<String> = body.cui.as_deref().map(normalize_cui);
+    let vat_payer = body.vat_payer as i32;
+    let reverse_charge = body.reverse_charge as i32;
+    let phone = Phone::normalize(&body.phone);
+
     let stmt = con
         .execute_named(
             query.as_str(),
             &[
+                ("kind", &kind),
                 ("name", &body.name),
                 ("fullname", &body.fullname),
                 ("address", &body.address),
-                ("phone", &body.phone),
+                ("phone", &phone),
                 ("cnp", &body.cnp),
+                ("cui", &cui),
+                ("trade_register_number", &body.trade_register_number),
+                ("vat_payer", &vat_payer),
+                ("reverse_charge", &reverse_charge),
+                ("legal_representative", &body.legal_representative),
                 ("id", &OracleType::Number(0, 0)),
             ],
         )
         .map_err(Error::DBQuery)?;
 
-    if let Err(e) = con.commit() {
-        con.rollback().map_err(Error::DBQuery)?;
-        return Err(Error::DBQuery(e));
-    }
-
     let row_id: u32 = stmt.returned_values("id").map_err(Error::DBQuery)?[0];
     let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
 
@@ -69,31 +309,74 @@ pub async fn create(db_pool: &DBPool, body: CustomerRequest) -> Result<Customer>
     Ok(row_to_customer(&row))
 }
 
-pub async fn update(db_pool: &DBPool, id: u32, body: CustomerRequest) -> Result<Customer> {
+pub async fn update(
+    db_pool: &DBPool,
+    id: u32,
+    version: u32,
+    body: CustomerRequest,
+) -> Result<Customer> {
     let con = get_db_con(db_pool).await?;
-    let query = format!("UPDATE {} SET name = :name, fullname = :fullname, address = :address, phone = :phone, cnp = :cnp WHERE id = :id", TABLE);
 
-    con.execute_named(
-        query.as_str(),
-        &[
-            ("name", &body.name),
-            ("fullname", &body.fullname),
-            ("address", &body.address),
-            ("phone", &body.phone),
-            ("cnp", &body.cnp),
-            ("id", &id),
-        ],
-    )
-    .map_err(|e| match e {
-        oracle::Error::NoDataFound => Error::CustomerNotFound(id),
-        _ => Error::DBQuery(e),
-    })?;
+    let customer = match update_customer(&con, id, version, &body) {
+        Ok(customer) => customer,
+        Err(e) => {
+            con.rollback().map_err(Error::DBQuery)?;
+            return Err(e);
+        }
+    };
 
     if let Err(e) = con.commit() {
         con.rollback().map_err(Error::DBQuery)?;
         return Err(Error::DBQuery(e));
     }
 
+    Ok(customer)
+}
+
+/// Updates the customer at the version within the transaction of the connection,
+/// without committing it.
+pub fn update_customer(
+    con: &DBCon,
+    id: u32,
+    version: u32,
+    body: &CustomerRequest,
+) -> Result<Customer> {
+    let query = format!("UPDATE {} SET kind = :kind, name = :name, fullname = :fullname, address = :address, phone = :phone, cnp = :cnp, cui = :cui, trade_register_number = :trade_register_number, vat_payer = :vat_payer, reverse_charge = :reverse_charge, legal_representative = :legal_representative WHERE id = :id AND version = :version", TABLE);
+
+    let kind: String = body.kind.clone().into();
+    let cui: Option<String> = body.cui.as_deref().map(normalize_cui);
+    let vat_payer = body.vat_payer as i32;
+    let reverse_charge = body.reverse_charge as i32;
+    let phone = Phone::normalize(&body.phone);
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("kind", &kind),
+                ("name", &body.name),
+                ("fullname", &body.fullname),
+                ("address", &body.address),
+                ("phone", &phone),
+                ("cnp", &body.cnp),
+                ("cui", &cui),
+                ("trade_register_number", &body.trade_register_number),
+                ("vat_payer", &vat_payer),
+                ("reverse_charge", &reverse_charge),
+                ("legal_representative", &body.legal_representative),
+                ("id", &id),
+                ("version", &version),
+            ],
+        )
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::CustomerNotFound(id),
+            _ => Error::DBQuery(e),
+        })?;
+
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("customer", id));
+    }
+
     let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
 
     let row = con
@@ -103,13 +386,21 @@ pub async fn update(db_pool: &DBPool, id: u32, body: CustomerRequest) -> Result<
     Ok(row_to_customer(&row))
 }
 
-pub async fn delete(db_pool: &DBPool, id: u32) -> Result<()> {
+pub async fn delete(db_pool: &DBPool, id: u32, version: u32) -> Result<()> {
     let con = get_db_con(db_pool).await?;
-    let query = format!("DELETE FROM {} WHERE id = :id", TABLE);
+    let query = format!(
+        "DELETE FROM {} WHERE id = :id AND version = :version",
+        TABLE
+    );
 
-    con.execute_named(query.as_str(), &[("id", &id)])
+    let stmt = con
+        .execute_named(query.as_str(), &[("id", &id), ("version", &version)])
         .map_err(Error::DBQuery)?;
 
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("customer", id));
+    }
+
     if let Err(e) = con.commit() {
         con.rollback().map_err(Error::DBQuery)?;
         return Err(Error::DBQuery(e));
@@ -118,6 +409,291 @@ pub async fn delete(db_pool: &DBPool, id: u32) -> Result<()> {
     Ok(())
 }
 
+/// Condition under which customers `a` and `b` are duplicates, the CUIs being
+/// compared without their VAT prefix.
+const DUPLICATE_CONDITION: &str = "a.cnp = b.cnp \
+    OR REGEXP_REPLACE(a.cui, '^RO') = REGEXP_REPLACE(b.cui, '^RO') \
+    OR (a.phone = b.phone \
+        AND UTL_MATCH.JARO_WINKLER_SIMILARITY(UPPER(a.fullname), UPPER(b.fullname)) >= :threshold)";
+
+const DUPLICATE_REASON: &str = "CASE WHEN a.cnp = b.cnp THEN 'SAME_CNP' \
+    WHEN REGEXP_REPLACE(a.cui, '^RO') = REGEXP_REPLACE(b.cui, '^RO') THEN 'SAME_CUI' \
+    ELSE 'SIMILAR_NAME_PHONE' END";
+
+/// Finds the customers a new customer would duplicate, those sharing its CNP
+/// or CUI first.
+pub async fn find_duplicates(
+    db_pool: &DBPool,
+    body: &CustomerRequest,
+) -> Result<Vec<(Customer, DuplicateReason)>> {
+    let con = get_db_con(db_pool).await?;
+
+    find_customer_duplicates(&con, body)
+}
+
+/// Finds the duplicates of a new customer seen from `con`, which includes the
+/// customers its transaction created so far.
+pub fn find_customer_duplicates(
+    con: &DBCon,
+    body: &CustomerRequest,
+) -> Result<Vec<(Customer, DuplicateReason)>> {
+    let fields: Vec<String> = SELECT_FIELDS
+        .split(',')
+        .map(|field| format!("a.{}", field.trim()))
+        .collect();
+    let query = format!(
+        "SELECT {}, {} AS reason FROM {} a, (SELECT :cnp AS cnp, :cui AS cui, :phone AS phone, \
+        :fullname AS fullname FROM dual) b WHERE {}",
+        fields.join(", "),
+        DUPLICATE_REASON,
+        TABLE,
+        DUPLICATE_CONDITION
+    );
+
+    let cui: Option<String> = body.cui.as_deref().map(normalize_cui);
+    let phone = Phone::normalize(&body.phone);
+
+    let rows = con
+        .query_named(
+            query.as_str(),
+            &[
+                ("cnp", &body.cnp),
+                ("cui", &cui),
+                ("phone", &phone),
+                ("fullname", &body.fullname),
+                ("threshold", &NAME_SIMILARITY_THRESHOLD),
+            ],
+        )
+        .map_err(Error::DBQuery)?;
+
+    // the reason follows the customer fields
+    let offset = fields.len();
+
+    let mut duplicates: Vec<(Customer, DuplicateReason)> = rows
+        .flatten()
+        .map(|row| {
+            let reason: String = row.get(offset).unwrap();
+            (row_to_customer(&row), reason.into())
+        })
+        .collect();
+    duplicates.sort_by_key(|(customer, reason)| (!reason.is_certain(), customer.id));
+
+    Ok(duplicates)
+}
+
+/// Lists every pair of customers likely registered for the same person or company.
+pub async fn fetch_duplicates(db_pool: &DBPool) -> Result<Vec<CustomerDuplicate>> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "SELECT a.id, b.id, {} AS reason FROM {} a JOIN {} b ON a.id < b.id AND ({}) \
+        ORDER BY a.id, b.id",
+        DUPLICATE_REASON, TABLE, TABLE, DUPLICATE_CONDITION
+    );
+
+    let pairs: Vec<(u32, u32, DuplicateReason)> = con
+        .query_named(query.as_str(), &[("threshold", &NAME_SIMILARITY_THRESHOLD)])
+        .map_err(Error::DBQuery)?
+        .flatten()
+        .map(|row| {
+            let reason: String = row.get(2).unwrap();
+            (row.get(0).unwrap(), row.get(1).unwrap(), reason.into())
+        })
+        .collect();
+
+    if pairs.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut ids: Vec<String> = pairs
+        .iter()
+        .flat_map(|(id, duplicate_id, _)| [id.to_string(), duplicate_id.to_string()])
+        .collect();
+    ids.sort();
+    ids.dedup();
+
+    let query = format!(
+        "SELECT {} FROM {} WHERE id IN ({})",
+        SELECT_FIELDS,
+        TABLE,
+        ids.join(", ")
+    );
+
+    let customers: HashMap<u32, Customer> = con
+        .query(query.as_str(), &[])
+        .map_err(Error::DBQuery)?
+        .flatten()
+        .map(|row| row_to_customer(&row))
+        .map(|customer| (customer.id, customer))
+        .collect();
+
+    Ok(pairs
+        .into_iter()
+        .filter_map(|(id, duplicate_id, reason)| {
+            Some(CustomerDuplicate {
+                customer: customers.get(&id)?.clone(),
+                duplicate: customers.get(&duplicate_id)?.clone(),
+                reason,
+            })
+        })
+        .collect())
+}
+
+/// Moves the contracts (along with their invoices and payments), addresses,
+/// contacts, notes, tags, custom field values and merge history of a customer to
+/// another one, removes it and records the merge, all in one transaction.
+pub async fn merge(db_pool: &DBPool, id: u32, source_id: u32) -> Result<CustomerMerge> {
+    use crate::db::customer_merge::{SELECT_FIELDS, TABLE};
+
+    let con = get_db_con(db_pool).await?;
+
+    let merge_id = match merge_into(&con, id, source_id) {
+        Ok(merge_id) => merge_id,
+        Err(e) => {
+            con.rollback().map_err(Error::DBQuery)?;
+            return Err(e);
+        }
+    };
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &merge_id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(row_to_customer_merge(&row))
+}
+
+fn merge_into(con: &DBCon, id: u32, source_id: u32) -> Result<u32> {
+    use crate::db::customer_merge::TABLE as MERGE_TABLE;
+
+    // lock both customers so that nothing is added to the source meanwhile
+    let query = format!("SELECT id FROM {} WHERE id = :id FOR UPDATE", TABLE);
+    con.query_row_named(query.as_str(), &[("id", &id)])
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::CustomerNotFound(id),
+            _ => Error::DBQuery(e),
+        })?;
+
+    let query = format!(
+        "SELECT {} FROM {} WHERE id = :id FOR UPDATE",
+        SELECT_FIELDS, TABLE
+    );
+    let source = con
+        .query_row_named(query.as_str(), &[("id", &source_id)])
+        .map(|row| row_to_customer(&row))
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::CustomerNotFound(source_id),
+            _ => Error::DBQuery(e),
+        })?;
+
+    let row = con
+        .query_row_named(
+            "SELECT (SELECT COUNT(*) FROM contract WHERE customer_id = :source_id), \
+            (SELECT COUNT(*) FROM invoice i JOIN contract c ON c.id = i.contract_id \
+                WHERE c.customer_id = :source_id), \
+            (SELECT COUNT(*) FROM payment p JOIN invoice i ON i.id = p.invoice_id \
+                JOIN contract c ON c.id = i.contract_id WHERE c.customer_id = :source_id) \
+            FROM dual",
+            &[("source_id", &source_id)],
+        )
+        .map_err(Error::DBQuery)?;
+    let contracts_moved: u32 = row.get(0).map_err(Error::DBQuery)?;
+    let invoices_moved: u32 = row.get(1).map_err(Error::DBQuery)?;
+    let payments_moved: u32 = row.get(2).map_err(Error::DBQuery)?;
+
+    for table in [
+        "contract",
+        "customer_address",
+        "customer_contact",
+        "customer_note",
+    ] {
+        let query = format!(
+            "UPDATE {} SET customer_id = :id WHERE customer_id = :source_id",
+            table
+        );
+        con.execute_named(query.as_str(), &[("id", &id), ("source_id", &source_id)])
+            .map_err(Error::DBQuery)?;
+    }
+
+    // the tags and custom field values of the kept customer take precedence
+    let (tag_table, _) = crate::db::tag::assignment_table(&Entity::Customer);
+    let (value_table, _) = crate::db::custom_field::value_table(&Entity::Customer);
+
+    for (table, column) in [(tag_table, "tag_id"), (value_table, "field_id")] {
+        let query = format!(
+            "UPDATE {} SET customer_id = :id WHERE customer_id = :source_id AND {} NOT IN \
+            (SELECT {} FROM {} WHERE customer_id = :id)",
+            table, column, column, table
+        );
+        con.execute_named(query.as_str(), &[("id", &id), ("source_id", &source_id)])
+            .map_err(Error::DBQuery)?;
+    }
+
+    let query = format!(
+        "UPDATE {} SET target_customer_id = :id WHERE target_customer_id = :source_id",
+        MERGE_TABLE
+    );
+    con.execute_named(query.as_str(), &[("id", &id), ("source_id", &source_id)])
+        .map_err(Error::DBQuery)?;
+
+    let query = format!(
+        "INSERT INTO {} (target_customer_id, source_customer_id, source_name, source_fullname, \
+        source_cnp, source_cui, contracts_moved, invoices_moved, payments_moved) VALUES (:id, \
+        :source_id, :source_name, :source_fullname, :source_cnp, :source_cui, :contracts_moved, \
+        :invoices_moved, :payments_moved) RETURNING id INTO :merge_id",
+        MERGE_TABLE
+    );
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("id", &id),
+                ("source_id", &source_id),
+                ("source_name", &source.name),
+                ("source_fullname", &source.fullname),
+                ("source_cnp", &source.cnp),
+                ("source_cui", &source.cui),
+                ("contracts_moved", &contracts_moved),
+                ("invoices_moved", &invoices_moved),
+                ("payments_moved", &payments_moved),
+                ("merge_id", &OracleType::Number(0, 0)),
+            ],
+        )
+        .map_err(Error::DBQuery)?;
+    let merge_id: u32 = stmt.returned_values("merge_id").map_err(Error::DBQuery)?[0];
+
+    let query = format!("DELETE FROM {} WHERE id = :id", TABLE);
+    con.execute_named(query.as_str(), &[("id", &source_id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(merge_id)
+}
+
+pub async fn fetch_merges(db_pool: &DBPool, id: u32) -> Result<Vec<CustomerMerge>> {
+    use crate::db::customer_merge::{SELECT_FIELDS, TABLE};
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "SELECT {} FROM {} WHERE target_customer_id = :id ORDER BY merged_at DESC, id DESC",
+        SELECT_FIELDS, TABLE
+    );
+
+    let rows = con
+        .query_named(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(rows
+        .filter(|r| r.is_ok())
+        .map(|r| row_to_customer_merge(&r.unwrap()))
+        .collect())
+}
+
+#[allow(dead_code)]
 pub async fn fetch_unpaid_invoices(db_pool: &DBPool, id: u32) -> Result<Vec<Invoice>> {
     use crate::db::invoice::SELECT_FIELDS;
 
@@ -177,3 +753,743 @@ pub async fn fetch_contracts(db_pool: &DBPool, id: u32) -> Result<Vec<Contract>>
         .map(|r| row_to_contract(&r.unwrap()))
         .collect())
 }
+
+pub async fn fetch_contract_addons(db_pool: &DBPool, id: u32) -> Result<Vec<ContractAddon>> {
+    use crate::db::contract_addon::{row_to_contract_addon, SELECT_FIELDS, TABLE};
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "SELECT {} FROM {} WHERE contract_id IN (SELECT id FROM contract WHERE customer_id = :id) \
+        ORDER BY contract_id, start_date, id",
+        SELECT_FIELDS, TABLE
+    );
+
+    let rows = con
+        .query_named(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(rows
+        .filter(|r| r.is_ok())
+        .map(|r| row_to_contract_addon(&r.unwrap()))
+        .collect())
+}
+
+pub async fn fetch_deposits(db_pool: &DBPool, id: u32) -> Result<Vec<Deposit>> {
+    use crate::db::contract_deposit::{row_to_deposit, SELECT_FIELDS, TABLE};
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "SELECT {} FROM {} WHERE contract_id IN (SELECT id FROM contract WHERE customer_id = :id)",
+        SELECT_FIELDS, TABLE
+    );
+
+    let rows = con
+        .query_named(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(rows
+        .filter(|r| r.is_ok())
+        .map(|r| row_to_deposit(&r.unwrap()))
+        .collect())
+}
+
+pub async fn fetch_invoices(db_pool: &DBPool, id: u32) -> Result<Vec<Invoice>> {
+    use crate::db::invoice::{SELECT_FIELDS, TABLE};
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "SELECT {} FROM {} WHERE contract_id IN (SELECT id FROM contract WHERE customer_id = :id) \
+        ORDER BY contract_id, issue_date, id",
+        SELECT_FIELDS, TABLE
+    );
+
+    let rows = con
+        .query_named(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(rows
+        .filter(|r| r.is_ok())
+        .map(|r| row_to_invoice(&r.unwrap()))
+        .collect())
+}
+
+pub async fn fetch_invoice_lines(db_pool: &DBPool, id: u32) -> Result<Vec<InvoiceLine>> {
+    use crate::db::invoice_line::{row_to_invoice_line, SELECT_FIELDS, TABLE};
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "SELECT {} FROM {} WHERE invoice_id IN (SELECT i.id FROM invoice i \
+        JOIN contract c ON c.id = i.contract_id WHERE c.customer_id = :id) ORDER BY invoice_id, id",
+        SELECT_FIELDS, TABLE
+    );
+
+    let rows = con
+        .query_named(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(rows
+        .filter(|r| r.is_ok())
+        .map(|r| row_to_invoice_line(&r.unwrap()))
+        .collect())
+}
+
+pub async fn fetch_payments(db_pool: &DBPool, id: u32) -> Result<Vec<Payment>> {
+    use crate::db::payment::{row_to_payment, SELECT_FIELDS, TABLE};
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "SELECT {} FROM {} WHERE invoice_id IN (SELECT i.id FROM invoice i \
+        JOIN contract c ON c.id = i.contract_id WHERE c.customer_id = :id) \
+        ORDER BY invoice_id, payment_date, id",
+        SELECT_FIELDS, TABLE
+    );
+
+    let rows = con
+        .query_named(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(rows
+        .filter(|r| r.is_ok())
+        .map(|r| row_to_payment(&r.unwrap()))
+        .collect())
+}
+
+/// Scrubs the personal data of a customer, its addresses, contacts, notes and
+/// the customers merged into it, leaving its contracts, invoices and payments
+/// untouched. The city and county of the addresses are kept, being needed for
+/// tax reporting without identifying anyone.
+pub async fn anonymize(db_pool: &DBPool, id: u32) -> Result<Anonymization> {
+    let con = get_db_con(db_pool).await?;
+
+    let (addresses_scrubbed, contacts_removed) = match anonymize_in_place(&con, id) {
+        Ok(counts) => counts,
+        Err(e) => {
+            con.rollback().map_err(Error::DBQuery)?;
+            return Err(e);
+        }
+    };
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(Anonymization {
+        customer: row_to_customer(&row),
+        addresses_scrubbed,
+        contacts_removed,
+    })
+}
+
+fn anonymize_in_place(con: &DBCon, id: u32) -> Result<(u32, u32)> {
+    use crate::db::customer_address::TABLE as ADDRESS_TABLE;
+    use crate::db::customer_contact::TABLE as CONTACT_TABLE;
+    use crate::db::customer_merge::TABLE as MERGE_TABLE;
+    use crate::db::customer_note::TABLE as NOTE_TABLE;
+
+    let query = format!(
+        "UPDATE {} SET name = :anonymized, fullname = :anonymized, address = :anonymized, \
+        phone = NULL, cnp = NULL, legal_representative = NULL, anonymized_at = SYSDATE \
+        WHERE id = :id",
+        TABLE
+    );
+    let stmt = con
+        .execute_named(query.as_str(), &[("anonymized", &ANONYMIZED), ("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::CustomerNotFound(id));
+    }
+
+    let query = format!(
+        "UPDATE {} SET street = :anonymized, street_number = '-', postal_code = '000000' \
+        WHERE customer_id = :id",
+        ADDRESS_TABLE
+    );
+    let addresses_scrubbed = con
+        .execute_named(query.as_str(), &[("anonymized", &ANONYMIZED), ("id", &id)])
+        .and_then(|stmt| stmt.row_count())
+        .map_err(Error::DBQuery)?;
+
+    let query = format!("DELETE FROM {} WHERE customer_id = :id", CONTACT_TABLE);
+    let contacts_removed = con
+        .execute_named(query.as_str(), &[("id", &id)])
+        .and_then(|stmt| stmt.row_count())
+        .map_err(Error::DBQuery)?;
+
+    // free text notes and custom field values may hold anything the customer said,
+    // while tags only segment the customer
+    let (value_table, _) = crate::db::custom_field::value_table(&Entity::Customer);
+
+    for table in [NOTE_TABLE, value_table] {
+        let query = format!("DELETE FROM {} WHERE customer_id = :id", table);
+        con.execute_named(query.as_str(), &[("id", &id)])
+            .map_err(Error::DBQuery)?;
+    }
+
+    let query = format!(
+        "UPDATE {} SET source_name = :anonymized, source_fullname = :anonymized, source_cnp = NULL \
+        WHERE target_customer_id = :id",
+        MERGE_TABLE
+    );
+    con.execute_named(query.as_str(), &[("anonymized", &ANONYMIZED), ("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok((addresses_scrubbed as u32, contacts_removed as u32))
+}
+
+pub async fn fetch_addresses(db_pool: &DBPool, id: u32) -> Result<Vec<Address>> {
+    use crate::db::customer_address::{SELECT_FIELDS, TABLE};
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "SELECT {} FROM {} WHERE customer_id = :id ORDER BY address_type, id",
+        SELECT_FIELDS, TABLE
+    );
+
+    let rows = con
+        .query_named(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(rows
+        .filter(|r| r.is_ok())
+        .map(|r| row_to_address(&r.unwrap()))
+        .collect())
+}
+
+pub async fn fetch_address(db_pool: &DBPool, id: u32, address_id: u32) -> Result<Address> {
+    let con = get_db_con(db_pool).await?;
+
+    fetch_customer_address(&con, id, address_id)
+}
+
+pub fn fetch_customer_address(con: &DBCon, id: u32, address_id: u32) -> Result<Address> {
+    use crate::db::customer_address::{SELECT_FIELDS, TABLE};
+
+    let query = format!(
+        "SELECT {} FROM {} WHERE id = :address_id AND customer_id = :id",
+        SELECT_FIELDS, TABLE
+    );
+
+    let row = con
+        .query_row_named(query.as_str(), &[("address_id", &address_id), ("id", &id)])
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::AddressNotFound(address_id),
+            _ => Error::DBQuery(e),
+        })?;
+
+    Ok(row_to_address(&row))
+}
+
+pub async fn add_address(db_pool: &DBPool, id: u32, body: AddressRequest) -> Result<Address> {
+    use crate::db::customer_address::{SELECT_FIELDS, TABLE};
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "INSERT INTO {} (customer_id, address_type, street, street_number, city, county, postal_code) \
+        VALUES (:customer_id, :address_type, :street, :street_number, :city, :county, :postal_code) \
+        RETURNING id INTO :id",
+        TABLE
+    );
+
+    let address_type: String = body.address_type.into();
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("customer_id", &id),
+                ("address_type", &address_type),
+                ("street", &body.street),
+                ("street_number", &body.number),
+                ("city", &body.city),
+                ("county", &body.county),
+                ("postal_code", &body.postal_code),
+                ("id", &OracleType::Number(0, 0)),
+            ],
+        )
+        .map_err(Error::DBQuery)?;
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    let row_id: u32 = stmt.returned_values("id").map_err(Error::DBQuery)?[0];
+    let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &row_id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(row_to_address(&row))
+}
+
+pub async fn update_address(
+    db_pool: &DBPool,
+    id: u32,
+    address_id: u32,
+    version: u32,
+    body: AddressRequest,
+) -> Result<Address> {
+    use crate::db::customer_address::{SELECT_FIELDS, TABLE};
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "UPDATE {} SET address_type = :address_type, street = :street, street_number = :street_number, \
+        city = :city, county = :county, postal_code = :postal_code \
+        WHERE id = :address_id AND customer_id = :id AND version = :version",
+        TABLE
+    );
+
+    let address_type: String = body.address_type.into();
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("address_type", &address_type),
+                ("street", &body.street),
+                ("street_number", &body.number),
+                ("city", &body.city),
+                ("county", &body.county),
+                ("postal_code", &body.postal_code),
+                ("address_id", &address_id),
+                ("version", &version),
+                ("id", &id),
+            ],
+        )
+        .map_err(Error::DBQuery)?;
+
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("address", address_id));
+    }
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    let query = format!(
+        "SELECT {} FROM {} WHERE id = :address_id AND customer_id = :id",
+        SELECT_FIELDS, TABLE
+    );
+
+    let row = con
+        .query_row_named(query.as_str(), &[("address_id", &address_id), ("id", &id)])
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::AddressNotFound(address_id),
+            _ => Error::DBQuery(e),
+        })?;
+
+    Ok(row_to_address(&row))
+}
+
+pub async fn remove_address(
+    db_pool: &DBPool,
+    id: u32,
+    address_id: u32,
+    version: u32,
+) -> Result<()> {
+    use crate::db::customer_address::TABLE;
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "DELETE FROM {} WHERE id = :address_id AND customer_id = :id AND version = :version",
+        TABLE
+    );
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("address_id", &address_id),
+                ("version", &version),
+                ("id", &id),
+            ],
+        )
+        .map_err(Error::DBQuery)?;
+
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("address", address_id));
+    }
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    Ok(())
+}
+
+pub async fn fetch_contacts(db_pool: &DBPool, id: u32) -> Result<Vec<Contact>> {
+    use crate::db::customer_contact::{SELECT_FIELDS, TABLE};
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "SELECT {} FROM {} WHERE customer_id = :id ORDER BY role, id",
+        SELECT_FIELDS, TABLE
+    );
+
+    let rows = con
+        .query_named(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(rows
+        .filter(|r| r.is_ok())
+        .map(|r| row_to_contact(&r.unwrap()))
+        .collect())
+}
+
+pub async fn fetch_contact(db_pool: &DBPool, id: u32, contact_id: u32) -> Result<Contact> {
+    use crate::db::customer_contact::{SELECT_FIELDS, TABLE};
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "SELECT {} FROM {} WHERE id = :contact_id AND customer_id = :id",
+        SELECT_FIELDS, TABLE
+    );
+
+    let row = con
+        .query_row_named(query.as_str(), &[("contact_id", &contact_id), ("id", &id)])
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::ContactNotFound(contact_id),
+            _ => Error::DBQuery(e),
+        })?;
+
+    Ok(row_to_contact(&row))
+}
+
+pub async fn add_contact(db_pool: &DBPool, id: u32, body: ContactRequest) -> Result<Contact> {
+    use crate::db::customer_contact::{SELECT_FIELDS, TABLE};
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "INSERT INTO {} (customer_id, role, name, email, phone) \
+        VALUES (:customer_id, :role, :name, :email, :phone) RETURNING id INTO :id",
+        TABLE
+    );
+
+    let role: String = body.role.into();
+    let phone: Option<String> = body.phone.as_deref().map(Phone::normalize);
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("customer_id", &id),
+                ("role", &role),
+                ("name", &body.name),
+                ("email", &body.email),
+                ("phone", &phone),
+                ("id", &OracleType::Number(0, 0)),
+            ],
+        )
+        .map_err(Error::DBQuery)?;
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    let row_id: u32 = stmt.returned_values("id").map_err(Error::DBQuery)?[0];
+    let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &row_id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(row_to_contact(&row))
+}
+
+pub async fn update_contact(
+    db_pool: &DBPool,
+    id: u32,
+    contact_id: u32,
+    version: u32,
+    body: ContactRequest,
+) -> Result<Contact> {
+    use crate::db::customer_contact::{SELECT_FIELDS, TABLE};
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "UPDATE {} SET role = :role, name = :name, email = :email, phone = :phone \
+        WHERE id = :contact_id AND customer_id = :id AND version = :version",
+        TABLE
+    );
+
+    let role: String = body.role.into();
+    let phone: Option<String> = body.phone.as_deref().map(Phone::normalize);
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("role", &role),
+                ("name", &body.name),
+                ("email", &body.email),
+                ("phone", &phone),
+                ("contact_id", &contact_id),
+                ("version", &version),
+                ("id", &id),
+            ],
+        )
+        .map_err(Error::DBQuery)?;
+
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("contact", contact_id));
+    }
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    let query = format!(
+        "SELECT {} FROM {} WHERE id = :contact_id AND customer_id = :id",
+        SELECT_FIELDS, TABLE
+    );
+
+    let row = con
+        .query_row_named(query.as_str(), &[("contact_id", &contact_id), ("id", &id)])
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::ContactNotFound(contact_id),
+            _ => Error::DBQuery(e),
+        })?;
+
+    Ok(row_to_contact(&row))
+}
+
+pub async fn remove_contact(
+    db_pool: &DBPool,
+    id: u32,
+    contact_id: u32,
+    version: u32,
+) -> Result<()> {
+    use crate::db::customer_contact::TABLE;
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "DELETE FROM {} WHERE id = :contact_id AND customer_id = :id AND version = :version",
+        TABLE
+    );
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("contact_id", &contact_id),
+                ("version", &version),
+                ("id", &id),
+            ],
+        )
+        .map_err(Error::DBQuery)?;
+
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("contact", contact_id));
+    }
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    Ok(())
+}
+
+/// Lists the notes of a customer as a timeline, the pinned ones first.
+pub async fn fetch_notes(db_pool: &DBPool, id: u32) -> Result<Vec<Note>> {
+    use crate::db::customer_note::{SELECT_FIELDS, TABLE};
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "SELECT {} FROM {} WHERE customer_id = :id ORDER BY pinned DESC, created_at DESC, id DESC",
+        SELECT_FIELDS, TABLE
+    );
+
+    let rows = con
+        .query_named(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(rows
+        .filter(|r| r.is_ok())
+        .map(|r| row_to_note(&r.unwrap()))
+        .collect())
+}
+
+pub async fn fetch_note(db_pool: &DBPool, id: u32, note_id: u32) -> Result<Note> {
+    use crate::db::customer_note::{SELECT_FIELDS, TABLE};
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "SELECT {} FROM {} WHERE id = :note_id AND customer_id = :id",
+        SELECT_FIELDS, TABLE
+    );
+
+    let row = con
+        .query_row_named(query.as_str(), &[("note_id", &note_id), ("id", &id)])
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::NoteNotFound(note_id),
+            _ => Error::DBQuery(e),
+        })?;
+
+    Ok(row_to_note(&row))
+}
+
+pub async fn add_note(db_pool: &DBPool, id: u32, body: NoteRequest) -> Result<Note> {
+    use crate::db::customer_note::{SELECT_FIELDS, TABLE};
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "INSERT INTO {} (customer_id, contract_id, invoice_id, kind, category, author, body, pinned) \
+        VALUES (:customer_id, :contract_id, :invoice_id, :kind, :category, :author, :body, :pinned) \
+        RETURNING id INTO :id",
+        TABLE
+    );
+
+    let kind: String = body.kind.into();
+    let category: String = body.category.into();
+    let pinned = body.pinned as i32;
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("customer_id", &id),
+                ("contract_id", &body.contract_id),
+                ("invoice_id", &body.invoice_id),
+                ("kind", &kind),
+                ("category", &category),
+                ("author", &body.author),
+                ("body", &body.body),
+                ("pinned", &pinned),
+                ("id", &OracleType::Number(0, 0)),
+            ],
+        )
+        .map_err(Error::DBQuery)?;
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    let row_id: u32 = stmt.returned_values("id").map_err(Error::DBQuery)?[0];
+    let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &row_id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(row_to_note(&row))
+}
+
+pub async fn update_note(
+    db_pool: &DBPool,
+    id: u32,
+    note_id: u32,
+    version: u32,
+    body: NoteRequest,
+) -> Result<Note> {
+    use crate::db::customer_note::{SELECT_FIELDS, TABLE};
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "UPDATE {} SET contract_id = :contract_id, invoice_id = :invoice_id, kind = :kind, \
+        category = :category, author = :author, body = :body, pinned = :pinned \
+        WHERE id = :note_id AND customer_id = :id AND version = :version",
+        TABLE
+    );
+
+    let kind: String = body.kind.into();
+    let category: String = body.category.into();
+    let pinned = body.pinned as i32;
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("contract_id", &body.contract_id),
+                ("invoice_id", &body.invoice_id),
+                ("kind", &kind),
+                ("category", &category),
+                ("author", &body.author),
+                ("body", &body.body),
+                ("pinned", &pinned),
+                ("note_id", &note_id),
+                ("version", &version),
+                ("id", &id),
+            ],
+        )
+        .map_err(Error::DBQuery)?;
+
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("note", note_id));
+    }
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    let query = format!(
+        "SELECT {} FROM {} WHERE id = :note_id AND customer_id = :id",
+        SELECT_FIELDS, TABLE
+    );
+
+    let row = con
+        .query_row_named(query.as_str(), &[("note_id", &note_id), ("id", &id)])
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::NoteNotFound(note_id),
+            _ => Error::DBQuery(e),
+        })?;
+
+    Ok(row_to_note(&row))
+}
+
+pub async fn remove_note(db_pool: &DBPool, id: u32, note_id: u32, version: u32) -> Result<()> {
+    use crate::db::customer_note::TABLE;
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "DELETE FROM {} WHERE id = :note_id AND customer_id = :id AND version = :version",
+        TABLE
+    );
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[("note_id", &note_id), ("version", &version), ("id", &id)],
+        )
+        .map_err(Error::DBQuery)?;
+
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("note", note_id));
+    }
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    Ok(())
+}
+
+/// Stores CUIs of VAT payers as `RO` followed by the digits, and the VAT numbers of
+/// other member states in capitals, both without separators.
+fn normalize_cui(cui: &str) -> String {
+    if VatNumber::is_foreign(cui) {
+        return VatNumber::parse(cui)
+            .map(|vat_number| vat_number.to_string())
+            .unwrap_or(cui.to_string());
+    }
+
+    Cui::parse(cui)
+        .map(|cui| cui.to_string())
+        .unwrap_or(cui.to_string())
+}
@@ -7,11 +7,16 @@ use std::fs;
 pub type Result<T> = std::result::Result<T, Error>;
 
 const DB_POOL_MAX_OPEN: u32 = 32;
+#[allow(dead_code)]
 const CREATE_TABLES_SQL: &str = "./db-scripts/tables.sql";
+#[allow(dead_code)]
 const SEEDING_DATA_SQL: &str = "./db-scripts/seeding.sql";
+#[allow(dead_code)]
 const CREATE_PROCEDURES_SQL: &str = "./db-scripts/procedures.sql";
+#[allow(dead_code)]
 const CREATE_TRIGGERS_SQL: &str = "./db-scripts/triggers.sql";
 
+#[allow(dead_code)]
 pub async fn init_db(db_pool: &DBPool) -> Result<()> {
     let con = get_db_con(db_pool).await?;
 
@@ -53,27 +58,184 @@ pub fn create_pool() -> std::result::Result<DBPool, oracle::Error> {
 }
 
 pub mod customer {
+    use chrono::{DateTime, Utc};
     use common::customer::Customer;
     use oracle::Row;
 
     pub const TABLE: &str = "customer";
-    pub const SELECT_FIELDS: &str = "id, name, fullname, address, phone, cnp";
+    pub const SELECT_FIELDS: &str = "id, kind, name, fullname, address, phone, cnp, cui, \
+        trade_register_number, vat_payer, legal_representative, anonymized_at, version, \
+        reverse_charge";
 
     pub fn row_to_customer(row: &Row) -> Customer {
         let id: u32 = row.get(0).unwrap();
-        let name: String = row.get(1).unwrap();
-        let fullname: String = row.get(2).unwrap();
-        let address: String = row.get(3).unwrap();
-        let phone: String = row.get(4).unwrap();
-        let cnp: String = row.get(5).unwrap();
+        let kind: String = row.get(1).unwrap();
+        let name: String = row.get(2).unwrap();
+        let fullname: String = row.get(3).unwrap();
+        let address: String = row.get(4).unwrap();
+        let phone: Option<String> = row.get(5).unwrap();
+        let cnp: Option<String> = row.get(6).unwrap();
+        let cui: Option<String> = row.get(7).unwrap();
+        let trade_register_number: Option<String> = row.get(8).unwrap();
+        let vat_payer: i32 = row.get(9).unwrap();
+        let legal_representative: Option<String> = row.get(10).unwrap();
+        let anonymized_at: Option<DateTime<Utc>> = row.get(11).unwrap();
+        let version: u32 = row.get(12).unwrap();
+        let reverse_charge: i32 = row.get(13).unwrap();
 
         Customer {
             id,
+            kind: kind.into(),
             name,
             fullname,
             address,
-            phone,
+            phone: phone.unwrap_or_default(),
             cnp,
+            cui,
+            trade_register_number,
+            vat_payer: vat_payer != 0,
+            reverse_charge: reverse_charge != 0,
+            legal_representative,
+            anonymized_at,
+            version,
+        }
+    }
+}
+
+pub mod customer_merge {
+    use chrono::{DateTime, Utc};
+    use common::merge::CustomerMerge;
+    use oracle::Row;
+
+    pub const TABLE: &str = "customer_merge";
+    pub const SELECT_FIELDS: &str = "id, target_customer_id, source_customer_id, source_name, \
+        source_fullname, source_cnp, source_cui, contracts_moved, invoices_moved, payments_moved, \
+        merged_at";
+
+    pub fn row_to_customer_merge(row: &Row) -> CustomerMerge {
+        let id: u32 = row.get(0).unwrap();
+        let target_customer_id: u32 = row.get(1).unwrap();
+        let source_customer_id: u32 = row.get(2).unwrap();
+        let source_name: String = row.get(3).unwrap();
+        let source_fullname: String = row.get(4).unwrap();
+        let source_cnp: Option<String> = row.get(5).unwrap();
+        let source_cui: Option<String> = row.get(6).unwrap();
+        let contracts_moved: u32 = row.get(7).unwrap();
+        let invoices_moved: u32 = row.get(8).unwrap();
+        let payments_moved: u32 = row.get(9).unwrap();
+        let merged_at: DateTime<Utc> = row.get(10).unwrap();
+
+        CustomerMerge {
+            id,
+            target_customer_id,
+            source_customer_id,
+            source_name,
+            source_fullname,
+            source_cnp,
+            source_cui,
+            contracts_moved,
+            invoices_moved,
+            payments_moved,
+            merged_at,
+        }
+    }
+}
+
+pub mod customer_address {
+    use common::address::Address;
+    use oracle::Row;
+
+    pub const TABLE: &str = "customer_address";
+    pub const SELECT_FIELDS: &str =
+        "id, customer_id, address_type, street, street_number, city, county, postal_code, version";
+
+    pub fn row_to_address(row: &Row) -> Address {
+        let id: u32 = row.get(0).unwrap();
+        let customer_id: u32 = row.get(1).unwrap();
+        let address_type: String = row.get(2).unwrap();
+        let street: String = row.get(3).unwrap();
+        let number: String = row.get(4).unwrap();
+        let city: String = row.get(5).unwrap();
+        let county: String = row.get(6).unwrap();
+        let postal_code: String = row.get(7).unwrap();
+        let version: u32 = row.get(8).unwrap();
+
+        Address {
+            id,
+            customer_id,
+            address_type: address_type.into(),
+            street,
+            number,
+            city,
+            county,
+            postal_code,
+            version,
+        }
+    }
+}
+
+pub mod customer_contact {
+    use common::contact::Contact;
+    use oracle::Row;
+
+    pub const TABLE: &str = "customer_contact";
+    pub const SELECT_FIELDS: &str = "id, customer_id, role, name, email, phone, version";
+
+    pub fn row_to_contact(row: &Row) -> Contact {
+        let id: u32 = row.get(0).unwrap();
+        let customer_id: u32 = row.get(1).unwrap();
+        let role: String = row.get(2).unwrap();
+        let name: String = row.get(3).unwrap();
+        let email: String = row.get(4).unwrap();
+        let phone: Option<String> = row.get(5).unwrap();
+        let version: u32 = row.get(6).unwrap();
+
+        Contact {
+            id,
+            customer_id,
+            role: role.into(),
+            name,
+            email,
+            phone,
+            version,
+        }
+    }
+}
+
+pub mod customer_note {
+    use chrono::{DateTime, Utc};
+    use common::note::Note;
+    use oracle::Row;
+
+    pub const TABLE: &str = "customer_note";
+    pub const SELECT_FIELDS: &str = "id, customer_id, contract_id, invoice_id, kind, category, \
+        author, body, pinned, created_at, version";
+
+    pub fn row_to_note(row: &Row) -> Note {
+        let id: u32 = row.get(0).unwrap();
+        let customer_id: u32 = row.get(1).unwrap();
+        let contract_id: Option<u32> = row.get(2).unwrap();
+        let invoice_id: Option<u32> = row.get(3).unwrap();
+        let kind: String = row.get(4).unwrap();
+        let category: String = row.get(5).unwrap();
+        let author: String = row.get(6).unwrap();
+        let body: String = row.get(7).unwrap();
+        let pinned: i32 = row.get(8).unwrap();
+        let created_at: DateTime<Utc> = row.get(9).unwrap();
+        let version: u32 = row.get(10).unwrap();
+
+        Note {
+            id,
+            customer_id,
+            contract_id,
+            invoice_id,
+            kind: kind.into(),
+            category: category.into(),
+            author,
+            body,
+            pinned: pinned != 0,
+            created_at,
+            version,
         }
     }
 }
@@ -83,7 +245,8 @@ pub mod subscription {
     use oracle::Row;
 
     pub const TABLE: &str = "subscription";
-    pub const SELECT_FIELDS: &str = "id, description, type, traffic, price, extra_traffic_price";
+    pub const SELECT_FIELDS: &str =
+        "id, description, type, traffic, price, extra_traffic_price, version";
 
     pub fn row_to_subscription(row: &Row) -> Subscription {
         let id: u32 = row.get(0).unwrap();
@@ -92,6 +255,7 @@ pub mod subscription {
         let traffic: i32 = row.get(3).unwrap();
         let price: f64 = row.get(4).unwrap();
         let extra_traffic_price: f64 = row.get(5).unwrap();
+        let version: u32 = row.get(6).unwrap();
 
         Subscription {
             id,
@@ -100,6 +264,55 @@ pub mod subscription {
             traffic,
             price,
             extra_traffic_price,
+            version,
+        }
+    }
+}
+
+pub mod addon {
+    use common::addon::Addon;
+    use oracle::Row;
+
+    pub const TABLE: &str = "addon";
+    pub const SELECT_FIELDS: &str = "id, description, price, version";
+
+    pub fn row_to_addon(row: &Row) -> Addon {
+        let id: u32 = row.get(0).unwrap();
+        let description: String = row.get(1).unwrap();
+        let price: f64 = row.get(2).unwrap();
+        let version: u32 = row.get(3).unwrap();
+
+        Addon {
+            id,
+            description,
+            price,
+            version,
+        }
+    }
+}
+
+pub mod bundle {
+    use common::bundle::Bundle;
+    use oracle::Row;
+
+    pub const TABLE: &str = "bundle";
+    pub const SELECT_FIELDS: &str = "id, description, price, version";
+    pub const COMPONENT_TABLE: &str = "bundle_subscription";
+
+    /// The components of the bundle are stored in `COMPONENT_TABLE` and have to be
+    /// fetched separately.
+    pub fn row_to_bundle(row: &Row) -> Bundle {
+        let id: u32 = row.get(0).unwrap();
+        let description: String = row.get(1).unwrap();
+        let price: f64 = row.get(2).unwrap();
+        let version: u32 = row.get(3).unwrap();
+
+        Bundle {
+            id,
+            description,
+            price,
+            subscription_ids: vec![],
+            version,
         }
     }
 }
@@ -110,21 +323,95 @@ pub mod contract {
     use oracle::Row;
 
     pub const TABLE: &str = "contract";
-    pub const SELECT_FIELDS: &str = "id, customer_id, subscription_id, start_date, end_date";
+    pub const SELECT_FIELDS: &str =
+        "id, customer_id, subscription_id, bundle_id, start_date, end_date, \
+        penalty_rate, promotion_id, service_address_id, version";
 
     pub fn row_to_contract(row: &Row) -> Contract {
         let id: u32 = row.get(0).unwrap();
         let customer_id: u32 = row.get(1).unwrap();
-        let subscription_id: u32 = row.get(2).unwrap();
-        let start_date: DateTime<Utc> = row.get(3).unwrap();
-        let end_date: DateTime<Utc> = row.get(4).unwrap();
+        let subscription_id: Option<u32> = row.get(2).unwrap();
+        let bundle_id: Option<u32> = row.get(3).unwrap();
+        let start_date: DateTime<Utc> = row.get(4).unwrap();
+        let end_date: DateTime<Utc> = row.get(5).unwrap();
+        let penalty_rate: f64 = row.get(6).unwrap();
+        let promotion_id: Option<u32> = row.get(7).unwrap();
+        let service_address_id: Option<u32> = row.get(8).unwrap();
+        let version: u32 = row.get(9).unwrap();
 
         Contract {
             id,
             customer_id,
             subscription_id,
+            bundle_id,
+            start_date,
+            end_date,
+            penalty_rate,
+            promotion_id,
+            service_address_id,
+            version,
+        }
+    }
+}
+
+pub mod contract_deposit {
+    use chrono::{DateTime, Utc};
+    use common::credit::Deposit;
+    use oracle::Row;
+
+    pub const TABLE: &str = "contract_deposit";
+    pub const SELECT_FIELDS: &str =
+        "id, contract_id, amount, status, created_at, paid_at, refunded_at, refunded_amount";
+
+    pub fn row_to_deposit(row: &Row) -> Deposit {
+        let id: u32 = row.get(0).unwrap();
+        let contract_id: u32 = row.get(1).unwrap();
+        let amount: f64 = row.get(2).unwrap();
+        let status: String = row.get(3).unwrap();
+        let created_at: DateTime<Utc> = row.get(4).unwrap();
+        let paid_at: Option<DateTime<Utc>> = row.get(5).unwrap();
+        let refunded_at: Option<DateTime<Utc>> = row.get(6).unwrap();
+        let refunded_amount: Option<f64> = row.get(7).unwrap();
+
+        Deposit {
+            id,
+            contract_id,
+            amount,
+            status: status.into(),
+            created_at,
+            paid_at,
+            refunded_at,
+            refunded_amount,
+        }
+    }
+}
+
+pub mod contract_addon {
+    use chrono::{DateTime, Utc};
+    use common::addon::ContractAddon;
+    use oracle::Row;
+
+    pub const TABLE: &str = "contract_addon";
+    pub const SELECT_FIELDS: &str =
+        "id, contract_id, addon_id, start_date, end_date, price, version";
+
+    pub fn row_to_contract_addon(row: &Row) -> ContractAddon {
+        let id: u32 = row.get(0).unwrap();
+        let contract_id: u32 = row.get(1).unwrap();
+        let addon_id: u32 = row.get(2).unwrap();
+        let start_date: DateTime<Utc> = row.get(3).unwrap();
+        let end_date: DateTime<Utc> = row.get(4).unwrap();
+        let price: f64 = row.get(5).unwrap();
+        let version: u32 = row.get(6).unwrap();
+
+        ContractAddon {
+            id,
+            contract_id,
+            addon_id,
             start_date,
             end_date,
+            price,
+            version,
         }
     }
 }
@@ -135,7 +422,12 @@ pub mod invoice {
     use oracle::Row;
 
     pub const TABLE: &str = "invoice";
-    pub const SELECT_FIELDS: &str = "id, contract_id, issue_date, due_date, amount, status";
+    pub const SELECT_FIELDS: &str =
+        "id, contract_id, issue_date, due_date, amount, status, vat_rate, reverse_charge, version";
+
+    /// Amount still to be paid on the invoice aliased `i`.
+    pub const OUTSTANDING_AMOUNT: &str =
+        "i.amount - NVL((SELECT SUM(p.amount) FROM payment p WHERE p.invoice_id = i.id), 0)";
 
     pub fn row_to_invoice(row: &Row) -> Invoice {
         let id: u32 = row.get(0).unwrap();
@@ -144,6 +436,9 @@ pub mod invoice {
         let due_date: DateTime<Utc> = row.get(3).unwrap();
         let amount: f64 = row.get(4).unwrap();
         let status: String = row.get(5).unwrap();
+        let vat_rate: f64 = row.get(6).unwrap();
+        let reverse_charge: i32 = row.get(7).unwrap();
+        let version: u32 = row.get(8).unwrap();
 
         Invoice {
             id,
@@ -152,6 +447,36 @@ pub mod invoice {
             due_date,
             amount,
             status: status.into(),
+            vat_rate,
+            reverse_charge: reverse_charge != 0,
+            version,
+        }
+    }
+}
+
+pub mod invoice_line {
+    use common::invoice::InvoiceLine;
+    use oracle::Row;
+
+    pub const TABLE: &str = "invoice_line";
+    pub const SELECT_FIELDS: &str =
+        "id, invoice_id, line_type, description, amount, source_invoice_id";
+
+    pub fn row_to_invoice_line(row: &Row) -> InvoiceLine {
+        let id: u32 = row.get(0).unwrap();
+        let invoice_id: u32 = row.get(1).unwrap();
+        let line_type: String = row.get(2).unwrap();
+        let description: String = row.get(3).unwrap();
+        let amount: f64 = row.get(4).unwrap();
+        let source_invoice_id: Option<u32> = row.get(5).unwrap();
+
+        InvoiceLine {
+            id,
+            invoice_id,
+            line_type: line_type.into(),
+            description,
+            amount,
+            source_invoice_id,
         }
     }
 }
@@ -162,19 +487,195 @@ pub mod payment {
     use oracle::Row;
 
     pub const TABLE: &str = "payment";
-    pub const SELECT_FIELDS: &str = "id, invoice_id, payment_date, amount";
+    pub const SELECT_FIELDS: &str = "id, invoice_id, payment_date, amount, version";
 
     pub fn row_to_payment(row: &Row) -> Payment {
         let id: u32 = row.get(0).unwrap();
         let invoice_id: u32 = row.get(1).unwrap();
         let payment_date: DateTime<Utc> = row.get(2).unwrap();
         let amount: f64 = row.get(3).unwrap();
+        let version: u32 = row.get(4).unwrap();
 
         Payment {
             id,
             invoice_id,
             payment_date,
             amount,
+            version,
+        }
+    }
+}
+
+pub mod promotion {
+    use chrono::{DateTime, Utc};
+    use common::promotion::Promotion;
+    use oracle::Row;
+
+    pub const TABLE: &str = "promotion";
+    pub const SELECT_FIELDS: &str = "id, code, description, discount_type, discount_value, \
+        duration_cycles, subscription_type, valid_from, valid_until, version";
+
+    pub fn row_to_promotion(row: &Row) -> Promotion {
+        let id: u32 = row.get(0).unwrap();
+        let code: String = row.get(1).unwrap();
+        let description: String = row.get(2).unwrap();
+        let discount_type: String = row.get(3).unwrap();
+        let discount_value: f64 = row.get(4).unwrap();
+        let duration_cycles: u32 = row.get(5).unwrap();
+        let subscription_type: Option<String> = row.get(6).unwrap();
+        let valid_from: DateTime<Utc> = row.get(7).unwrap();
+        let valid_until: DateTime<Utc> = row.get(8).unwrap();
+        let version: u32 = row.get(9).unwrap();
+
+        Promotion {
+            id,
+            code,
+            description,
+            discount_type: discount_type.into(),
+            discount_value,
+            duration_cycles,
+            subscription_type: subscription_type.map(|t| t.into()),
+            valid_from,
+            valid_until,
+            version,
+        }
+    }
+}
+
+pub mod tag {
+    use common::field::Entity;
+    use common::tag::Tag;
+    use oracle::Row;
+
+    pub const TABLE: &str = "tag";
+    pub const SELECT_FIELDS: &str = "id, name, description, version";
+
+    pub fn row_to_tag(row: &Row) -> Tag {
+        let id: u32 = row.get(0).unwrap();
+        let name: String = row.get(1).unwrap();
+        let description: Option<String> = row.get(2).unwrap();
+        let version: u32 = row.get(3).unwrap();
+
+        Tag {
+            id,
+            name,
+            description,
+            version,
+        }
+    }
+
+    /// Table assigning tags to the records of an entity, and its record column.
+    pub fn assignment_table(entity: &Entity) -> (&'static str, &'static str) {
+        match entity {
+            Entity::Customer => ("customer_tag", "customer_id"),
+            Entity::Contract => ("contract_tag", "contract_id"),
+        }
+    }
+
+    /// Condition on the records of `entity` having the tag bound to `:tag`,
+    /// always true when `:tag` is null.
+    pub fn filter_condition(entity: &Entity) -> String {
+        let (table, column) = assignment_table(entity);
+        let record_table: String = entity.clone().into();
+
+        format!(
+            "(:tag IS NULL OR EXISTS (SELECT 1 FROM {} a JOIN {} t ON t.id = a.tag_id \
+            WHERE a.{} = {}.id AND t.name = UPPER(:tag)))",
+            table,
+            TABLE,
+            column,
+            record_table.to_lowercase()
+        )
+    }
+}
+
+pub mod custom_field {
+    use common::field::{CustomField, Entity, OPTIONS_SEPARATOR};
+    use oracle::Row;
+
+    pub const TABLE: &str = "custom_field";
+    // the value tables joined to the fields have a version too
+    pub const SELECT_FIELDS: &str =
+        "id, entity, field_key, label, field_type, enum_options, custom_field.version";
+
+    pub fn row_to_custom_field(row: &Row) -> CustomField {
+        let id: u32 = row.get(0).unwrap();
+        let entity: String = row.get(1).unwrap();
+        let key: String = row.get(2).unwrap();
+        let label: String = row.get(3).unwrap();
+        let field_type: String = row.get(4).unwrap();
+        let options: Option<String> = row.get(5).unwrap();
+        let version: u32 = row.get(6).unwrap();
+
+        CustomField {
+            id,
+            entity: entity.into(),
+            key,
+            label,
+            field_type: field_type.into(),
+            options: options
+                .map(|options| {
+                    options
+                        .split(OPTIONS_SEPARATOR)
+                        .map(|option| option.to_string())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            version,
+        }
+    }
+
+    /// Table storing the custom field values of the records of an entity, and
+    /// its record column.
+    pub fn value_table(entity: &Entity) -> (&'static str, &'static str) {
+        match entity {
+            Entity::Customer => ("customer_field_value", "customer_id"),
+            Entity::Contract => ("contract_field_value", "contract_id"),
+        }
+    }
+
+    /// Condition on the records of `entity` having a value for the field keyed
+    /// `:field`, equal to `:value` unless it is null. Always true when `:field`
+    /// is null.
+    pub fn filter_condition(entity: &Entity) -> String {
+        let (table, column) = value_table(entity);
+        let record_table: String = entity.clone().into();
+
+        format!(
+            "(:field IS NULL OR EXISTS (SELECT 1 FROM {} v JOIN {} f ON f.id = v.field_id \
+            WHERE v.{} = {}.id AND f.field_key = :field AND (:value IS NULL OR v.field_value = :value)))",
+            table,
+            TABLE,
+            column,
+            record_table.to_lowercase()
+        )
+    }
+}
+
+pub mod user {
+    use chrono::{DateTime, Utc};
+    use common::user::User;
+    use oracle::Row;
+
+    pub const TABLE: &str = "app_user";
+    pub const SESSION_TABLE: &str = "user_session";
+    pub const SELECT_FIELDS: &str = "id, username, role, active, created_at, version";
+
+    pub fn row_to_user(row: &Row) -> User {
+        let id: u32 = row.get(0).unwrap();
+        let username: String = row.get(1).unwrap();
+        let role: String = row.get(2).unwrap();
+        let active: i32 = row.get(3).unwrap();
+        let created_at: DateTime<Utc> = row.get(4).unwrap();
+        let version: u32 = row.get(5).unwrap();
+
+        User {
+            id,
+            username,
+            role: role.into(),
+            active: active != 0,
+            created_at,
+            version,
         }
     }
 }
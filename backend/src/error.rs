@@ -1,11 +1,16 @@
-use serde::Serialize;
+use crate::constraint;
+use common::error::{ErrorCode, FieldError, Problem, PROBLEM_JSON};
 use std::convert::Infallible;
 use std::error::Error;
+use uuid::Uuid;
 use validator::{ValidationErrors, ValidationErrorsKind};
+use warp::http::header::{HeaderValue, CONTENT_TYPE, WWW_AUTHENTICATE};
 use warp::{http::StatusCode, Rejection, Reply};
 
 pub mod application {
     use chrono::{DateTime, Utc};
+    use common::field::CustomFieldError;
+    use common::merge::DuplicateReason;
     use r2d2_oracle::r2d2;
     use thiserror::Error;
     use validator::ValidationErrors;
@@ -16,26 +21,118 @@ pub mod application {
         DBPool(r2d2::Error),
         #[error("error executing DB query: {0}")]
         DBQuery(#[from] oracle::Error),
+        #[allow(dead_code)]
         #[error("error creating table: {0}")]
         DBInit(oracle::Error),
         #[error("error reading file: {0}")]
         ReadFile(#[from] std::io::Error),
         #[error("customer {0} not found")]
         CustomerNotFound(u32),
+        #[error("address {0} not found")]
+        AddressNotFound(u32),
+        #[error("contact {0} not found")]
+        ContactNotFound(u32),
+        #[error("note {0} not found")]
+        NoteNotFound(u32),
+        #[error("customer {0} already exists ({1})")]
+        DuplicateCustomer(u32, DuplicateReason),
+        #[error("customer {0} is anonymized")]
+        CustomerAnonymized(u32),
+        #[error("customer {0} cannot be merged into itself")]
+        SelfMerge(u32),
         #[error("contract {0} not found")]
         ContractNotFound(u32),
         #[error("invoice {0} not found")]
         InvoiceNotFound(u32),
+        #[allow(dead_code)]
         #[error("payment {0} not found")]
         PaymentNotFound(u32),
         #[error("subscription {0} not found")]
         SubscriptionNotFound(u32),
+        #[error("add-on {0} not found")]
+        AddonNotFound(u32),
+        #[error("bundle {0} not found")]
+        BundleNotFound(u32),
+        #[error("promotion {0} not found")]
+        PromotionNotFound(u32),
+        #[error("coupon {0} not found")]
+        CouponNotFound(String),
+        #[error("coupon {0} is not applicable: {1}")]
+        CouponNotApplicable(String, String),
+        #[error("customer {0} failed the credit check: {1}")]
+        CreditCheckFailed(u32, String),
+        #[error("customer {0} requires a security deposit of {1:.2}: {2}")]
+        DepositRequired(u32, f64, String),
+        #[error("contract {0} has no security deposit")]
+        DepositNotFound(u32),
+        #[error("security deposit of contract {0} is not pending")]
+        DepositNotPending(u32),
+        #[error("security deposit of contract {0} cannot be refunded: {1}")]
+        DepositNotRefundable(u32, String),
+        #[error("tag {0} not found")]
+        TagNotFound(u32),
+        #[error("tag {0} already exists")]
+        DuplicateTag(String),
+        #[error("tag {0} does not exist")]
+        UnknownTag(String),
+        #[error("custom field {0} not found")]
+        CustomFieldNotFound(u32),
+        #[error("custom field {0} already exists")]
+        DuplicateCustomField(String),
+        #[error("custom field {0} does not exist")]
+        UnknownCustomField(String),
+        #[error("invalid custom field {0}: {1}")]
+        InvalidCustomField(String, CustomFieldError),
+        #[error("option {1} of custom field {0} is in use")]
+        CustomFieldOptionInUse(String, String),
         #[error(
             "invoice (issue_date: {1}, due_date: {2}) not in contract (id: {0}) availability period"
         )]
         InvoiceNotInContractAvailabilityPeriod(u32, DateTime<Utc>, DateTime<Utc>),
+        #[error(
+            "add-on (start_date: {1}, end_date: {2}) not in contract (id: {0}) availability period"
+        )]
+        AddonNotInContractAvailabilityPeriod(u32, DateTime<Utc>, DateTime<Utc>),
+        #[error("address {0} is not a service installation address of customer {1}")]
+        NotAServiceAddress(u32, u32),
+        #[error("contract {0} does not belong to customer {1}")]
+        ContractNotOfCustomer(u32, u32),
+        #[error("invoice {0} does not belong to customer {1} or to the noted contract")]
+        InvoiceNotOfCustomer(u32, u32),
         #[error("payment date ({0}) must be later than invoice (id: {1}) issue date")]
         PaymentBeforeInvoiceIssueDate(DateTime<Utc>, u32),
+        #[error("cannot sort by {0}")]
+        InvalidSort(String),
+        #[error("If-Match header missing")]
+        MissingIfMatch,
+        #[error("{0} {1} has changed since it was fetched")]
+        VersionConflict(&'static str, u32),
+        #[error("invalid idempotency key {0}")]
+        InvalidIdempotencyKey(String),
+        #[error("idempotency key {0} was used for a different request")]
+        IdempotencyKeyReused(String),
+        #[error("request with idempotency key {0} is still being processed")]
+        IdempotencyKeyInProgress(String),
+        #[error("bulk request of {0} items is too large")]
+        TooManyBulkItems(usize),
+        #[error("invalid import: {0}")]
+        InvalidImport(String),
+        #[error("export failed: {0}")]
+        ExportFailed(String),
+        #[error("missing or invalid session token")]
+        Unauthenticated,
+        #[error("invalid credentials for user {0}")]
+        InvalidCredentials(String),
+        #[error("user {0} is not an administrator")]
+        AdminRequired(u32),
+        #[error("user {0} not found")]
+        UserNotFound(u32),
+        #[error("user {0} already exists")]
+        DuplicateUser(String),
+        #[error("user {0} cannot delete, deactivate or demote their own account")]
+        OwnAccount(u32),
+        #[error("error hashing password: {0}")]
+        PasswordHash(String),
         #[error("JSON path error: {0}")]
         JSONPath(String),
         #[error("validation error: {0}")]
@@ -43,28 +140,69 @@ pub mod application {
     }
 }
 
-#[derive(Serialize)]
-struct ErrorResponse {
-    message: String,
-    errors: Option<Vec<FieldError>>,
-}
+/// Name of the header holding the correlation id of a failed request.
+pub const CORRELATION_ID: &str = "x-correlation-id";
 
-#[derive(Serialize)]
-struct FieldError {
-    field: String,
-    field_errors: Vec<String>,
-}
+/// Status code, error code and message of the response to a rejection, along with
+/// the errors of the fields that failed validation.
+pub(crate) type Description = (StatusCode, ErrorCode, String, Option<Vec<FieldError>>);
 
 impl warp::reject::Reject for crate::error::application::Error {}
 
+/// Replies with the problem details of the rejection, under a new correlation id
+/// logged along with its cause.
 pub async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
-    let (code, message, errors) = if err.is_not_found() {
-        (StatusCode::NOT_FOUND, "Not Found".to_string(), None)
+    let (status, code, detail, errors) = describe(&err);
+    let correlation_id = correlation_id();
+    log::info!(
+        "replied {} {} to request {}",
+        status.as_u16(),
+        code,
+        correlation_id
+    );
+
+    let problem = Problem {
+        problem_type: "about:blank".to_string(),
+        title: status.canonical_reason().unwrap_or_default().to_string(),
+        status: status.as_u16(),
+        detail,
+        code,
+        correlation_id: correlation_id.clone(),
+        errors,
+    };
+    let reply = warp::reply::with_header(warp::reply::json(&problem), CONTENT_TYPE, PROBLEM_JSON);
+    let reply = warp::reply::with_header(reply, CORRELATION_ID, correlation_id);
+    let mut response = warp::reply::with_status(reply, status).into_response();
+    if status == StatusCode::UNAUTHORIZED {
+        response
+            .headers_mut()
+            .insert(WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+    }
+
+    Ok(response)
+}
+
+/// Random identifier of a failed request, a version 4 UUID.
+fn correlation_id() -> String {
+    Uuid::new_v4().to_string()
+}
+
+/// Status code, error code and message of the response to the rejection, along
+/// with the errors of the fields that failed validation.
+pub(crate) fn describe(err: &Rejection) -> Description {
+    if err.is_not_found() {
+        (
+            StatusCode::NOT_FOUND,
+            ErrorCode::NotFound,
+            "Not Found".to_string(),
+            None,
+        )
     } else if let Some(body_err) = err.find::<warp::filters::body::BodyDeserializeError>() {
         log::warn!("invalid body: {}", body_err);
 
         (
             StatusCode::BAD_REQUEST,
+            ErrorCode::InvalidBody,
             body_err
                 .source()
                 .map(|cause| cause.to_string())
@@ -75,7 +213,12 @@ pub async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible>
         match e {
             application::Error::JSONPath(e) => {
                 log::warn!("error parsing JSON: {}", e);
-                (StatusCode::BAD_REQUEST, e.to_string(), None)
+                (
+                    StatusCode::BAD_REQUEST,
+                    ErrorCode::InvalidBody,
+                    e.to_string(),
+                    None,
+                )
             }
             application::Error::Validation(val_errs) => {
                 let errors: Vec<FieldError> = val_errs
@@ -107,53 +250,90 @@ pub async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible>
 
                 (
                     StatusCode::BAD_REQUEST,
+                    ErrorCode::ValidationFailed,
                     "field errors".to_string(),
                     Some(errors),
                 )
             }
             application::Error::DBQuery(e) => {
                 log::error!("error executing query: {:?}", e);
-
-                match e {
-                    oracle::Error::NoDataFound => {
-                        (StatusCode::NOT_FOUND, "Not Found".to_string(), None)
-                    }
-                    oracle::Error::OciError(e) => match e.code() {
-                        20001 => (
-                            StatusCode::BAD_REQUEST,
-                            "The invoice is already paid!".to_string(),
-                            None,
-                        ),
-                        20000 => (
-                            StatusCode::BAD_REQUEST,
-                            "You cannot pay more than the total amount of the invoice!".to_string(),
-                            None,
-                        ),
-                        _ => (
-                            StatusCode::BAD_REQUEST,
-                            "Could not execute request".to_string(),
-                            None,
-                        ),
-                    },
-                    _ => (
-                        StatusCode::BAD_REQUEST,
-                        "Could not execute request".to_string(),
-                        None,
-                    ),
-                }
+                describe_db_error(e)
             }
             application::Error::CustomerNotFound(id) => {
                 log::warn!("customer not found: {}", id);
                 (
                     StatusCode::NOT_FOUND,
+                    ErrorCode::CustomerNotFound,
                     format!("Customer {} not found", id),
                     None,
                 )
             }
+            application::Error::AddressNotFound(id) => {
+                log::warn!("address not found: {}", id);
+                (
+                    StatusCode::NOT_FOUND,
+                    ErrorCode::AddressNotFound,
+                    format!("Address {} not found", id),
+                    None,
+                )
+            }
+            application::Error::ContactNotFound(id) => {
+                log::warn!("contact not found: {}", id);
+                (
+                    StatusCode::NOT_FOUND,
+                    ErrorCode::ContactNotFound,
+                    format!("Contact {} not found", id),
+                    None,
+                )
+            }
+            application::Error::DuplicateCustomer(id, reason) => {
+                log::warn!("customer {} already exists ({})", id, reason);
+                (
+                    StatusCode::CONFLICT,
+                    ErrorCode::DuplicateCustomer,
+                    if reason.is_certain() {
+                        format!("Customer {} already exists ({})", id, reason)
+                    } else {
+                        format!(
+                            "Customer {} may already exist ({}), use force=true to create it anyway",
+                            id, reason
+                        )
+                    },
+                    None,
+                )
+            }
+            application::Error::CustomerAnonymized(id) => {
+                log::warn!("customer {} is anonymized", id);
+                (
+                    StatusCode::CONFLICT,
+                    ErrorCode::CustomerAnonymized,
+                    format!("Customer {} is anonymized", id),
+                    None,
+                )
+            }
+            application::Error::SelfMerge(id) => {
+                log::warn!("customer {} cannot be merged into itself", id);
+                (
+                    StatusCode::BAD_REQUEST,
+                    ErrorCode::SelfMerge,
+                    format!("Customer {} cannot be merged into itself", id),
+                    None,
+                )
+            }
+            application::Error::NoteNotFound(id) => {
+                log::warn!("note not found: {}", id);
+                (
+                    StatusCode::NOT_FOUND,
+                    ErrorCode::NoteNotFound,
+                    format!("Note {} not found", id),
+                    None,
+                )
+            }
             application::Error::ContractNotFound(id) => {
                 log::warn!("contract not found: {}", id);
                 (
                     StatusCode::NOT_FOUND,
+                    ErrorCode::ContractNotFound,
                     format!("Contract {} not found", id),
                     None,
                 )
@@ -162,6 +342,7 @@ pub async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible>
                 log::warn!("invoice not found: {}", id);
                 (
                     StatusCode::NOT_FOUND,
+                    ErrorCode::InvoiceNotFound,
                     format!("Invoice {} not found", id),
                     None,
                 )
@@ -170,6 +351,7 @@ pub async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible>
                 log::warn!("payment not found: {}", id);
                 (
                     StatusCode::NOT_FOUND,
+                    ErrorCode::PaymentNotFound,
                     format!("Payment {} not found", id),
                     None,
                 )
@@ -178,10 +360,191 @@ pub async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible>
                 log::warn!("subscription not found: {}", id);
                 (
                     StatusCode::NOT_FOUND,
+                    ErrorCode::SubscriptionNotFound,
                     format!("Subscription {} not found", id),
                     None,
                 )
             }
+            application::Error::AddonNotFound(id) => {
+                log::warn!("add-on not found: {}", id);
+                (
+                    StatusCode::NOT_FOUND,
+                    ErrorCode::AddonNotFound,
+                    format!("Add-on {} not found", id),
+                    None,
+                )
+            }
+            application::Error::BundleNotFound(id) => {
+                log::warn!("bundle not found: {}", id);
+                (
+                    StatusCode::NOT_FOUND,
+                    ErrorCode::BundleNotFound,
+                    format!("Bundle {} not found", id),
+                    None,
+                )
+            }
+            application::Error::PromotionNotFound(id) => {
+                log::warn!("promotion not found: {}", id);
+                (
+                    StatusCode::NOT_FOUND,
+                    ErrorCode::PromotionNotFound,
+                    format!("Promotion {} not found", id),
+                    None,
+                )
+            }
+            application::Error::CouponNotFound(code) => {
+                log::warn!("coupon not found: {}", code);
+                (
+                    StatusCode::NOT_FOUND,
+                    ErrorCode::CouponNotFound,
+                    format!("Coupon {} not found", code),
+                    None,
+                )
+            }
+            application::Error::CouponNotApplicable(code, reason) => {
+                log::warn!("coupon {} is not applicable: {}", code, reason);
+                (
+                    StatusCode::BAD_REQUEST,
+                    ErrorCode::CouponNotApplicable,
+                    format!("Coupon {} is not applicable: {}", code, reason),
+                    None,
+                )
+            }
+            application::Error::CreditCheckFailed(id, reasons) => {
+                log::warn!("customer {} failed the credit check: {}", id, reasons);
+                (
+                    StatusCode::FORBIDDEN,
+                    ErrorCode::CreditCheckFailed,
+                    format!("Customer {} failed the credit check: {}", id, reasons),
+                    None,
+                )
+            }
+            application::Error::DepositRequired(id, amount, reasons) => {
+                log::warn!(
+                    "customer {} requires a security deposit of {:.2}: {}",
+                    id,
+                    amount,
+                    reasons
+                );
+                (
+                    StatusCode::PAYMENT_REQUIRED,
+                    ErrorCode::DepositRequired,
+                    format!(
+                        "Customer {} requires a security deposit of {:.2}: {}",
+                        id, amount, reasons
+                    ),
+                    None,
+                )
+            }
+            application::Error::DepositNotFound(id) => {
+                log::warn!("security deposit not found for contract {}", id);
+                (
+                    StatusCode::NOT_FOUND,
+                    ErrorCode::DepositNotFound,
+                    format!("Contract {} has no security deposit", id),
+                    None,
+                )
+            }
+            application::Error::DepositNotPending(id) => {
+                log::warn!("security deposit of contract {} is not pending", id);
+                (
+                    StatusCode::CONFLICT,
+                    ErrorCode::DepositNotPending,
+                    format!("Security deposit of contract {} is not pending", id),
+                    None,
+                )
+            }
+            application::Error::DepositNotRefundable(id, reason) => {
+                log::warn!(
+                    "security deposit of contract {} cannot be refunded: {}",
+                    id,
+                    reason
+                );
+                (
+                    StatusCode::CONFLICT,
+                    ErrorCode::DepositNotRefundable,
+                    format!(
+                        "Security deposit of contract {} cannot be refunded: {}",
+                        id, reason
+                    ),
+                    None,
+                )
+            }
+            application::Error::TagNotFound(id) => {
+                log::warn!("tag not found: {}", id);
+                (
+                    StatusCode::NOT_FOUND,
+                    ErrorCode::TagNotFound,
+                    format!("Tag {} not found", id),
+                    None,
+                )
+            }
+            application::Error::DuplicateTag(name) => {
+                log::warn!("tag {} already exists", name);
+                (
+                    StatusCode::CONFLICT,
+                    ErrorCode::DuplicateTag,
+                    format!("Tag {} already exists", name),
+                    None,
+                )
+            }
+            application::Error::UnknownTag(name) => {
+                log::warn!("tag {} does not exist", name);
+                (
+                    StatusCode::BAD_REQUEST,
+                    ErrorCode::UnknownTag,
+                    format!("Tag {} does not exist", name),
+                    None,
+                )
+            }
+            application::Error::CustomFieldNotFound(id) => {
+                log::warn!("custom field not found: {}", id);
+                (
+                    StatusCode::NOT_FOUND,
+                    ErrorCode::CustomFieldNotFound,
+                    format!("Custom field {} not found", id),
+                    None,
+                )
+            }
+            application::Error::DuplicateCustomField(key) => {
+                log::warn!("custom field {} already exists", key);
+                (
+                    StatusCode::CONFLICT,
+                    ErrorCode::DuplicateCustomField,
+                    format!("Custom field {} already exists", key),
+                    None,
+                )
+            }
+            application::Error::UnknownCustomField(key) => {
+                log::warn!("custom field {} does not exist", key);
+                (
+                    StatusCode::BAD_REQUEST,
+                    ErrorCode::UnknownCustomField,
+                    format!("Custom field {} does not exist", key),
+                    None,
+                )
+            }
+            application::Error::InvalidCustomField(key, e) => {
+                log::warn!("invalid custom field {}: {}", key, e);
+                (
+                    StatusCode::BAD_REQUEST,
+                    ErrorCode::InvalidCustomField,
+                    format!("Invalid custom field {}: {}", key, e),
+                    None,
+                )
+            }
+            application::Error::CustomFieldOptionInUse(key, option) => {
+                log::warn!("option {} of custom field {} is in use", option, key);
+                (
+                    StatusCode::CONFLICT,
+                    ErrorCode::CustomFieldOptionInUse,
+                    format!(
+                        "Option {} of custom field {} is still set on some records",
+                        option, key
+                    ),
+                    None,
+                )
+            }
             application::Error::InvoiceNotInContractAvailabilityPeriod(
                 id,
                 issue_date,
@@ -192,7 +555,7 @@ pub async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible>
                     issue_date, due_date, id
                 );
                 (
-                    StatusCode::BAD_REQUEST,
+                    StatusCode::BAD_REQUEST, ErrorCode::OutsideContractPeriod,
                     format!(
                         "Invoice (issue_date: {}, due_date: {}) not in contract (id: {}) availability period",
                         issue_date, due_date, id
@@ -200,6 +563,68 @@ pub async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible>
                     None,
                 )
             }
+            application::Error::AddonNotInContractAvailabilityPeriod(id, start_date, end_date) => {
+                log::warn!(
+                    "add-on (start_date: {}, end_date: {}) not in contract (id: {}) availability period",
+                    start_date, end_date, id
+                );
+                (
+                    StatusCode::BAD_REQUEST, ErrorCode::OutsideContractPeriod,
+                    format!(
+                        "Add-on (start_date: {}, end_date: {}) not in contract (id: {}) availability period",
+                        start_date, end_date, id
+                    ),
+                    None,
+                )
+            }
+            application::Error::NotAServiceAddress(address_id, customer_id) => {
+                log::warn!(
+                    "address {} is not a service installation address of customer {}",
+                    address_id,
+                    customer_id
+                );
+                (
+                    StatusCode::BAD_REQUEST,
+                    ErrorCode::NotAServiceAddress,
+                    format!(
+                        "Address {} is not a service installation address of customer {}",
+                        address_id, customer_id
+                    ),
+                    None,
+                )
+            }
+            application::Error::ContractNotOfCustomer(contract_id, customer_id) => {
+                log::warn!(
+                    "contract {} does not belong to customer {}",
+                    contract_id,
+                    customer_id
+                );
+                (
+                    StatusCode::BAD_REQUEST,
+                    ErrorCode::ContractNotOfCustomer,
+                    format!(
+                        "Contract {} does not belong to customer {}",
+                        contract_id, customer_id
+                    ),
+                    None,
+                )
+            }
+            application::Error::InvoiceNotOfCustomer(invoice_id, customer_id) => {
+                log::warn!(
+                    "invoice {} does not belong to customer {} or to the noted contract",
+                    invoice_id,
+                    customer_id
+                );
+                (
+                    StatusCode::BAD_REQUEST,
+                    ErrorCode::InvoiceNotOfCustomer,
+                    format!(
+                        "Invoice {} does not belong to customer {} or to the noted contract",
+                        invoice_id, customer_id
+                    ),
+                    None,
+                )
+            }
             application::Error::PaymentBeforeInvoiceIssueDate(payment_date, invoice_id) => {
                 log::warn!(
                     "payment date ({}) must be later than invoice (id: {}) issue date",
@@ -208,6 +633,7 @@ pub async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible>
                 );
                 (
                     StatusCode::BAD_REQUEST,
+                    ErrorCode::PaymentBeforeInvoiceIssueDate,
                     format!(
                         "Payment date ({}) must be later than invoice (id: {}) issue date",
                         payment_date, invoice_id
@@ -215,10 +641,171 @@ pub async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible>
                     None,
                 )
             }
+            application::Error::InvalidSort(field) => {
+                log::warn!("cannot sort by {}", field);
+                (
+                    StatusCode::BAD_REQUEST,
+                    ErrorCode::InvalidSort,
+                    format!("Cannot sort by {}", field),
+                    None,
+                )
+            }
+            application::Error::MissingIfMatch => {
+                log::warn!("If-Match header missing");
+                (
+                    StatusCode::PRECONDITION_REQUIRED,
+                    ErrorCode::IfMatchRequired,
+                    "The If-Match header with the ETag of the record is required".to_string(),
+                    None,
+                )
+            }
+            application::Error::VersionConflict(entity, id) => {
+                log::warn!("{} {} has changed since it was fetched", entity, id);
+                (
+                    StatusCode::PRECONDITION_FAILED,
+                    ErrorCode::VersionConflict,
+                    format!(
+                        "The {} has been changed by someone else since it was fetched",
+                        entity
+                    ),
+                    None,
+                )
+            }
+            application::Error::InvalidIdempotencyKey(key) => {
+                log::warn!("invalid idempotency key {}", key);
+                (
+                    StatusCode::BAD_REQUEST,
+                    ErrorCode::InvalidIdempotencyKey,
+                    "The Idempotency-Key header must have between 1 and 255 characters".to_string(),
+                    None,
+                )
+            }
+            application::Error::IdempotencyKeyReused(key) => {
+                log::warn!("idempotency key {} was used for a different request", key);
+                (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    ErrorCode::IdempotencyKeyReused,
+                    format!(
+                        "The idempotency key {} was already used for a different request",
+                        key
+                    ),
+                    None,
+                )
+            }
+            application::Error::IdempotencyKeyInProgress(key) => {
+                log::warn!(
+                    "request with idempotency key {} is still being processed",
+                    key
+                );
+                (
+                    StatusCode::CONFLICT,
+                    ErrorCode::IdempotencyKeyInProgress,
+                    format!(
+                        "The request with the idempotency key {} is still being processed",
+                        key
+                    ),
+                    None,
+                )
+            }
+            application::Error::TooManyBulkItems(count) => {
+                log::warn!("bulk request of {} items is too large", count);
+                (
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    ErrorCode::TooManyBulkItems,
+                    format!(
+                        "A bulk request may contain at most {} items, not {}",
+                        crate::bulk::MAX_ITEMS,
+                        count
+                    ),
+                    None,
+                )
+            }
+            application::Error::InvalidImport(reason) => {
+                log::warn!("invalid import: {}", reason);
+                (
+                    StatusCode::BAD_REQUEST,
+                    ErrorCode::InvalidImport,
+                    format!("Invalid import: {}", reason),
+                    None,
+                )
+            }
+            application::Error::Unauthenticated => {
+                log::warn!("missing or invalid session token");
+                (
+                    StatusCode::UNAUTHORIZED,
+                    ErrorCode::Unauthenticated,
+                    "Sign in to use the API".to_string(),
+                    None,
+                )
+            }
+            application::Error::InvalidCredentials(username) => {
+                log::warn!("invalid credentials for user {}", username);
+                (
+                    StatusCode::UNAUTHORIZED,
+                    ErrorCode::InvalidCredentials,
+                    "Invalid username or password".to_string(),
+                    None,
+                )
+            }
+            application::Error::AdminRequired(id) => {
+                log::warn!("user {} is not an administrator", id);
+                (
+                    StatusCode::FORBIDDEN,
+                    ErrorCode::AdminRequired,
+                    "Only administrators can manage users".to_string(),
+                    None,
+                )
+            }
+            application::Error::UserNotFound(id) => {
+                log::warn!("user not found: {}", id);
+                (
+                    StatusCode::NOT_FOUND,
+                    ErrorCode::UserNotFound,
+                    format!("User {} not found", id),
+                    None,
+                )
+            }
+            application::Error::DuplicateUser(username) => {
+                log::warn!("user {} already exists", username);
+                (
+                    StatusCode::CONFLICT,
+                    ErrorCode::DuplicateUser,
+                    format!("User {} already exists", username),
+                    None,
+                )
+            }
+            application::Error::OwnAccount(id) => {
+                log::warn!("user {} tried to remove their own access", id);
+                (
+                    StatusCode::CONFLICT,
+                    ErrorCode::OwnAccount,
+                    "You cannot delete, deactivate or demote your own account".to_string(),
+                    None,
+                )
+            }
+            application::Error::PasswordHash(reason) => {
+                log::error!("error hashing password: {}", reason);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    ErrorCode::InternalError,
+                    "Internal Server Error".to_string(),
+                    None,
+                )
+            }
+            application::Error::ExportFailed(reason) => {
+                log::error!("export failed: {}", reason);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    ErrorCode::InternalError,
+                    "Internal Server Error".to_string(),
+                    None,
+                )
+            }
             _ => {
                 log::error!("unhandled application error: {:?}", err);
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
+                    ErrorCode::InternalError,
                     "Internal Server Error".to_string(),
                     None,
                 )
@@ -227,6 +814,7 @@ pub async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible>
     } else if err.find::<warp::reject::MethodNotAllowed>().is_some() {
         (
             StatusCode::METHOD_NOT_ALLOWED,
+            ErrorCode::MethodNotAllowed,
             "Method Not Allowed".to_string(),
             None,
         )
@@ -234,14 +822,101 @@ pub async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible>
         log::error!("unhandled error: {:?}", err);
         (
             StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCode::InternalError,
             "Internal Server Error".to_string(),
             None,
         )
-    };
+    }
+}
 
-    let json = warp::reply::json(&ErrorResponse { message, errors });
+/// Description of a failed query, telling the violated constraints of `tables.sql`
+/// and the errors raised by the triggers by their code.
+fn describe_db_error(e: &oracle::Error) -> Description {
+    let db_error = match e {
+        oracle::Error::NoDataFound => {
+            return (
+                StatusCode::NOT_FOUND,
+                ErrorCode::NotFound,
+                "Not Found".to_string(),
+                None,
+            )
+        }
+        oracle::Error::OciError(db_error) => db_error,
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                ErrorCode::DatabaseError,
+                "Could not execute request".to_string(),
+                None,
+            )
+        }
+    };
+    let violated = constraint::violated(db_error).unwrap_or_default();
+    let field_error = |field: &str, error: &str| {
+        Some(vec![FieldError {
+            field: field.to_string(),
+            field_errors: vec![format!("{}: {}", error, violated)],
+        }])
+    };
 
-    Ok(warp::reply::with_status(json, code))
+    match db_error.code() {
+        constraint::INVOICE_ALREADY_PAID => (
+            StatusCode::BAD_REQUEST,
+            ErrorCode::InvoiceAlreadyPaid,
+            "The invoice is already paid!".to_string(),
+            None,
+        ),
+        constraint::OVERPAYMENT => (
+            StatusCode::BAD_REQUEST,
+            ErrorCode::Overpayment,
+            "You cannot pay more than the total amount of the invoice!".to_string(),
+            None,
+        ),
+        constraint::UNIQUE_VIOLATION => (
+            StatusCode::CONFLICT,
+            ErrorCode::UniqueViolation,
+            match constraint::field(&violated) {
+                Some(field) => format!("A record with the same {} already exists", field),
+                None => "The record already exists".to_string(),
+            },
+            constraint::field(&violated).and_then(|field| field_error(field, "unique")),
+        ),
+        constraint::CHECK_VIOLATION => (
+            StatusCode::BAD_REQUEST,
+            ErrorCode::CheckViolation,
+            match constraint::field(&violated) {
+                Some(field) => format!("Invalid {}", field),
+                None => "The record is invalid".to_string(),
+            },
+            constraint::field(&violated).and_then(|field| field_error(field, "check")),
+        ),
+        constraint::PARENT_KEY_NOT_FOUND => match constraint::foreign_key(&violated) {
+            Some((field, code)) => (
+                StatusCode::BAD_REQUEST,
+                code,
+                format!("The record referenced by {} does not exist", field),
+                field_error(field, "foreign_key"),
+            ),
+            None => (
+                StatusCode::BAD_REQUEST,
+                ErrorCode::FkConflict,
+                "A referenced record does not exist".to_string(),
+                None,
+            ),
+        },
+        constraint::CHILD_RECORD_FOUND => (
+            StatusCode::CONFLICT,
+            ErrorCode::FkConflict,
+            "The record is still referenced by other records".to_string(),
+            None,
+        ),
+        _ => (
+            StatusCode::BAD_REQUEST,
+            ErrorCode::DatabaseError,
+            "Could not execute request".to_string(),
+            None,
+        ),
+    }
 }
 
 fn validation_errs_to_str_vec(ve: &ValidationErrors) -> Vec<String> {
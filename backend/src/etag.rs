@@ -0,0 +1,40 @@
+use crate::error::application::Error;
+use common::etag;
+use warp::http::header::{ETAG, IF_MATCH};
+use warp::{reject, Filter, Rejection, Reply};
+
+/// `If-Match` header of a request changing a record, which is only applied if
+/// the record has not changed since the client fetched it.
+#[derive(Clone, Debug)]
+pub struct IfMatch(String);
+
+impl IfMatch {
+    pub fn matches(&self, version: u32) -> bool {
+        etag::matches(&self.0, version)
+    }
+
+    /// Fails with a conflict on the `entity` with the `id` if it has changed.
+    pub fn check(&self, entity: &'static str, id: u32, version: u32) -> Result<(), Error> {
+        if self.matches(version) {
+            Ok(())
+        } else {
+            Err(Error::VersionConflict(entity, id))
+        }
+    }
+}
+
+/// Requires the `If-Match` header, rejecting requests without it.
+pub fn if_match() -> impl Filter<Extract = (IfMatch,), Error = Rejection> + Clone {
+    warp::header::optional::<String>(IF_MATCH.as_str()).and_then(
+        |value: Option<String>| async move {
+            value
+                .map(IfMatch)
+                .ok_or_else(|| reject::custom(Error::MissingIfMatch))
+        },
+    )
+}
+
+/// Tags the reply with the version of the record it represents.
+pub fn reply(reply: impl Reply, version: u32) -> impl Reply {
+    warp::reply::with_header(reply, ETAG, etag::of(version))
+}
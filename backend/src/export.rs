@@ -0,0 +1,331 @@
+use crate::db::{self, get_db_con};
+use crate::error::application::Error;
+use crate::{DBCon, DBPool, Result};
+use chrono::{DateTime, NaiveDate};
+use common::export::{ExportFormat, ExportQuery};
+use futures_util::{stream, StreamExt};
+use rust_xlsxwriter::{Format, Workbook, Worksheet, XlsxError};
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+use std::io::{self, Seek, Write};
+use tokio::sync::mpsc::{self, Sender};
+use warp::http::header::{HeaderValue, CONTENT_DISPOSITION, CONTENT_TYPE};
+use warp::hyper::body::{Body, Bytes};
+use warp::reply::Response;
+use warp::{reject, Filter, Rejection};
+
+/// Bytes of an export buffered before they are sent to the client.
+const CHUNK_SIZE: usize = 64 * 1024;
+/// Chunks an export may be read from the database ahead of the client.
+const CHUNKS_AHEAD: usize = 4;
+
+/// Lists that are exported, by their path.
+const LISTS: [&str; 5] = ["customer", "subscription", "contract", "invoice", "payment"];
+
+type Chunk = db::Result<Bytes>;
+
+/// Whether a GET request of the path, below `/api/v1`, downloads a file: an export
+/// of a list or the personal data of a customer.
+pub fn is_download(path: &str, format: Option<ExportFormat>) -> bool {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    match segments.as_slice() {
+        [list] => LISTS.contains(list) && format.is_some_and(|f| f != ExportFormat::Json),
+        ["customer", id, "export"] => id.parse::<u32>().is_ok(),
+        _ => false,
+    }
+}
+
+/// Format of a list: the `format` query parameter or else the first export format
+/// of the `Accept` header, JSON by default.
+pub fn format() -> impl Filter<Extract = (ExportFormat,), Error = Rejection> + Clone {
+    warp::query::<ExportQuery>()
+        .and(warp::header::optional::<String>("accept"))
+        .map(|query: ExportQuery, accept: Option<String>| {
+            query
+                .format
+                .or_else(|| accept.as_deref().and_then(ExportFormat::from_accept))
+                .unwrap_or_default()
+        })
+}
+
+/// Column of an export, holding a field of the records.
+struct Column {
+    name: String,
+    /// Format of the field, `date` or `date-time` being written as dates to XLSX.
+    format: Option<String>,
+}
+
+/// Replies with the file `name` of the records passed by `export` to its callback,
+/// which reads them from the database on a blocking thread while they are being
+/// streamed, a few chunks ahead of the client.
+pub async fn reply<T, F>(
+    db_pool: &DBPool,
+    name: &str,
+    format: ExportFormat,
+    export: F,
+) -> Result<Response>
+where
+    T: Serialize + JsonSchema,
+    F: FnOnce(&DBCon, &mut dyn FnMut(T) -> db::Result<()>) -> db::Result<()> + Send + 'static,
+{
+    log::info!("Exporting {} as {}", name, format.extension());
+
+    let con = get_db_con(db_pool).await.map_err(reject::custom)?;
+    let columns = columns::<T>();
+    let (sender, mut receiver) = mpsc::channel::<Chunk>(CHUNKS_AHEAD);
+
+    tokio::task::spawn_blocking(move || {
+        let mut out = ChunkWriter {
+            sender: sender.clone(),
+            buffer: Vec::with_capacity(CHUNK_SIZE),
+        };
+
+        let result = write(&con, format, &columns, &mut out, export)
+            .and_then(|_| out.flush().map_err(|e| Error::ExportFailed(e.to_string())));
+        if let Err(e) = result {
+            // the client may be gone already
+            let _ = sender.blocking_send(Err(e));
+        }
+    });
+
+    // nothing is sent before the query of the export runs, so that its errors are
+    // replied as such rather than cutting the file short
+    let first = match receiver.recv().await {
+        Some(Err(e)) => return Err(reject::custom(e)),
+        first => first,
+    };
+    let exported = name.to_string();
+    let chunks = stream::iter(first)
+        .chain(stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|chunk| (chunk, receiver))
+        }))
+        .map(move |chunk| {
+            chunk.map_err(|e| {
+                log::error!("error exporting {}: {}", exported, e);
+                io::Error::other(e.to_string())
+            })
+        });
+
+    let mut response = Response::new(Body::wrap_stream(chunks));
+    let headers = response.headers_mut();
+    headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static(format.content_type()),
+    );
+    if let Ok(disposition) = HeaderValue::from_str(&format!(
+        "attachment; filename=\"{}.{}\"",
+        name,
+        format.extension()
+    )) {
+        headers.insert(CONTENT_DISPOSITION, disposition);
+    }
+
+    Ok(response)
+}
+
+/// Columns of the records of type `T`, in the order of its fields.
+fn columns<T: JsonSchema>() -> Vec<Column> {
+    let root = SchemaGenerator::default().into_root_schema_for::<T>();
+
+    root.schema
+        .object
+        .map(|object| object.properties)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, schema)| Column {
+            name,
+            format: match schema {
+                Schema::Object(schema) => schema.format,
+                Schema::Bool(_) => None,
+            },
+        })
+        .collect()
+}
+
+fn write<T, F>(
+    con: &DBCon,
+    format: ExportFormat,
+    columns: &[Column],
+    out: &mut ChunkWriter,
+    export: F,
+) -> db::Result<()>
+where
+    T: Serialize,
+    F: FnOnce(&DBCon, &mut dyn FnMut(T) -> db::Result<()>) -> db::Result<()>,
+{
+    let failed = |e: &dyn std::fmt::Display| Error::ExportFailed(e.to_string());
+
+    match format {
+        // JSON lists are replied in pages rather than exported
+        ExportFormat::Json | ExportFormat::Ndjson => export(con, &mut |record| {
+            serde_json::to_writer(&mut *out, &record).map_err(|e| failed(&e))?;
+            out.write_all(b"\n").map_err(|e| failed(&e))
+        }),
+        ExportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(&mut *out);
+            writer
+                .write_record(columns.iter().map(|column| &column.name))
+                .map_err(|e| failed(&e))?;
+
+            export(con, &mut |record| {
+                let record = fields(record)?;
+                writer
+                    .write_record(columns.iter().map(|column| text(record.get(&column.name))))
+                    .map_err(|e| failed(&e))
+            })?;
+
+            writer.flush().map_err(|e| failed(&e))
+        }
+        ExportFormat::Xlsx => {
+            let mut workbook = Workbook::new();
+            let worksheet = workbook.add_worksheet_with_constant_memory();
+            let bold = Format::new().set_bold();
+            for (index, column) in columns.iter().enumerate() {
+                worksheet
+                    .write_string_with_format(0, index as u16, &column.name, &bold)
+                    .map_err(|e| failed(&e))?;
+            }
+            worksheet.set_freeze_panes(1, 0).map_err(|e| failed(&e))?;
+
+            let mut row = 0;
+            export(con, &mut |record| {
+                let record = fields(record)?;
+                row += 1;
+                write_row(worksheet, row, columns, &record).map_err(|e| failed(&e))
+            })?;
+
+            // the sheet is kept in temporary files rather than in memory, and so is
+            // the workbook, which can only be zipped to a seekable file
+            let mut file = tempfile::tempfile().map_err(|e| failed(&e))?;
+            workbook.save_to_writer(&mut file).map_err(|e| failed(&e))?;
+            file.rewind().map_err(|e| failed(&e))?;
+            io::copy(&mut file, out).map_err(|e| failed(&e))?;
+
+            Ok(())
+        }
+    }
+}
+
+fn fields<T: Serialize>(record: T) -> db::Result<Map<String, Value>> {
+    match serde_json::to_value(record) {
+        Ok(Value::Object(fields)) => Ok(fields),
+        Ok(_) => Ok(Map::new()),
+        Err(e) => Err(Error::ExportFailed(e.to_string())),
+    }
+}
+
+fn text(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(text)) => escape_formula(text).into_owned(),
+        Some(value) => value.to_string(),
+    }
+}
+
+/// Quotes the text of a cell that a spreadsheet would otherwise read as a formula.
+fn escape_formula(text: &str) -> Cow<'_, str> {
+    if text.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        Cow::Owned(format!("'{}", text))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+fn write_row(
+    worksheet: &mut Worksheet,
+    row: u32,
+    columns: &[Column],
+    record: &Map<String, Value>,
+) -> std::result::Result<(), XlsxError> {
+    let date = Format::new().set_num_format("yyyy-mm-dd");
+    let date_time = Format::new().set_num_format("yyyy-mm-dd hh:mm:ss");
+
+    for (index, column) in columns.iter().enumerate() {
+        let col = index as u16;
+
+        match record.get(&column.name) {
+            None | Some(Value::Null) => {}
+            Some(Value::Bool(value)) => {
+                worksheet.write_boolean(row, col, *value)?;
+            }
+            Some(Value::Number(value)) => {
+                worksheet.write_number(row, col, value.as_f64().unwrap_or_default())?;
+            }
+            Some(Value::String(value)) => match column.format.as_deref() {
+                Some("date-time") => match DateTime::parse_from_rfc3339(value) {
+                    Ok(value) => {
+                        worksheet.write_datetime_with_format(
+                            row,
+                            col,
+                            value.naive_utc(),
+                            &date_time,
+                        )?;
+                    }
+                    Err(_) => {
+                        worksheet.write_string(row, col, escape_formula(value))?;
+                    }
+                },
+                Some("date") => match value.parse::<NaiveDate>() {
+                    Ok(value) => {
+                        worksheet.write_datetime_with_format(row, col, value, &date)?;
+                    }
+                    Err(_) => {
+                        worksheet.write_string(row, col, escape_formula(value))?;
+                    }
+                },
+                _ => {
+                    worksheet.write_string(row, col, escape_formula(value))?;
+                }
+            },
+            Some(value) => {
+                worksheet.write_string(row, col, value.to_string())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Sends what is written to it in chunks to the client streaming the export,
+/// failing once the client is gone.
+struct ChunkWriter {
+    sender: Sender<Chunk>,
+    buffer: Vec<u8>,
+}
+
+impl ChunkWriter {
+    fn send(&mut self) -> io::Result<()> {
+        let chunk = Bytes::from(std::mem::replace(
+            &mut self.buffer,
+            Vec::with_capacity(CHUNK_SIZE),
+        ));
+
+        self.sender
+            .blocking_send(Ok(chunk))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "the client is gone"))
+    }
+}
+
+impl Write for ChunkWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        if self.buffer.len() >= CHUNK_SIZE {
+            self.send()?;
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+
+        self.send()
+    }
+}
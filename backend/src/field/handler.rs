@@ -0,0 +1,184 @@
+use crate::error::application::Error;
+use crate::etag::{self, IfMatch};
+use crate::field::repository;
+use crate::{DBPool, Result};
+use common::field::{
+    CustomField, CustomFieldQuery, CustomFieldRequest, CustomFieldResponse, CustomFieldValues,
+    Entity, FieldType, UpdateCustomFieldRequest,
+};
+use validator::Validate;
+use warp::reply::json;
+use warp::{reject, Buf, Reply};
+
+pub async fn list_custom_fields_handler(
+    query: CustomFieldQuery,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Listing custom fields");
+
+    let fields = repository::fetch(&db_pool, query.entity)
+        .await
+        .map_err(reject::custom)?;
+    Ok(json::<Vec<_>>(
+        &fields.into_iter().map(CustomFieldResponse::from).collect(),
+    ))
+}
+
+pub async fn fetch_custom_field_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Fetching custom field with id {}", id);
+
+    let field = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    let version = field.version;
+    Ok(etag::reply(
+        json(&CustomFieldResponse::from(field)),
+        version,
+    ))
+}
+
+pub async fn create_custom_field_handler(buf: impl Buf, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Creating a new custom field");
+
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: CustomFieldRequest = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    body.validate()
+        .map_err(|e| reject::custom(Error::Validation(e)))?;
+
+    let created_field = repository::create(&db_pool, body)
+        .await
+        .map_err(reject::custom)?;
+
+    let response = json(&CustomFieldResponse::from(created_field));
+
+    Ok(warp::reply::with_status(
+        response,
+        warp::http::StatusCode::CREATED,
+    ))
+}
+
+pub async fn update_custom_field_handler(
+    id: u32,
+    if_match: IfMatch,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Updating custom field with id {}", id);
+
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: UpdateCustomFieldRequest = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    body.validate()
+        .map_err(|e| reject::custom(Error::Validation(e)))?;
+
+    let field = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    if_match
+        .check("custom field", id, field.version)
+        .map_err(reject::custom)?;
+
+    CustomField::check_options(&field.field_type, &body.options)
+        .map_err(|e| reject::custom(Error::InvalidCustomField(field.key.clone(), e)))?;
+
+    // options still in use cannot be removed
+    if field.field_type == FieldType::Enum {
+        let used_values = repository::fetch_used_values(&db_pool, &field)
+            .await
+            .map_err(reject::custom)?;
+
+        if let Some(value) = used_values
+            .into_iter()
+            .find(|value| !body.options.contains(value))
+        {
+            return Err(reject::custom(Error::CustomFieldOptionInUse(
+                field.key, value,
+            )));
+        }
+    }
+
+    let field = repository::update(&db_pool, id, field.version, body)
+        .await
+        .map_err(reject::custom)?;
+    let version = field.version;
+    Ok(etag::reply(
+        json(&CustomFieldResponse::from(field)),
+        version,
+    ))
+}
+
+/// Deleting a custom field removes its values from every customer or contract.
+pub async fn delete_custom_field_handler(
+    id: u32,
+    if_match: IfMatch,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Deleting custom field with id {}", id);
+
+    let field = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    if_match
+        .check("custom field", id, field.version)
+        .map_err(reject::custom)?;
+
+    repository::delete(&db_pool, id, field.version)
+        .await
+        .map_err(reject::custom)?;
+    Ok(warp::http::StatusCode::NO_CONTENT)
+}
+
+/// Checks the custom field values of a customer or contract against the fields
+/// of the entity, returning the ids of the fields along with the values to store.
+/// Empty values are left out, clearing the field.
+pub async fn parse_values(
+    db_pool: &DBPool,
+    entity: Entity,
+    values: CustomFieldValues,
+) -> Result<Vec<(u32, String)>> {
+    let fields = repository::fetch(db_pool, Some(entity))
+        .await
+        .map_err(reject::custom)?;
+
+    values
+        .into_iter()
+        .filter(|(_, value)| !value.trim().is_empty())
+        .map(|(key, value)| {
+            let field = fields
+                .iter()
+                .find(|field| field.key == key)
+                .ok_or_else(|| reject::custom(Error::UnknownCustomField(key.clone())))?;
+
+            field
+                .parse_value(&value)
+                .map(|value| (field.id, value))
+                .map_err(|e| reject::custom(Error::InvalidCustomField(key, e)))
+        })
+        .collect()
+}
+
+/// Brings the value a list is filtered on to the form it is stored in for the
+/// `field` of the entity, e.g. `5.0` to `5` for a number field.
+pub async fn parse_filter(
+    db_pool: &DBPool,
+    entity: Entity,
+    field: &Option<String>,
+    value: Option<String>,
+) -> Result<Option<String>> {
+    match (field, value) {
+        (Some(key), Some(value)) => {
+            let field = repository::fetch_by_key(db_pool, entity, key)
+                .await
+                .map_err(reject::custom)?;
+
+            field
+                .parse_value(&value)
+                .map(Some)
+                .map_err(|e| reject::custom(Error::InvalidCustomField(field.key, e)))
+        }
+        (_, value) => Ok(value),
+    }
+}
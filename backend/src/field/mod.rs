@@ -0,0 +1,39 @@
+use crate::{etag, with_db, DBPool};
+use common::field::CustomFieldQuery;
+use warp::Filter;
+
+pub mod handler;
+pub mod repository;
+
+pub fn get_routes(
+    db_pool: DBPool,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let field = warp::path!("field");
+    let field_param = warp::path!("field" / u32);
+
+    field
+        .and(warp::get())
+        .and(warp::query::<CustomFieldQuery>())
+        .and(with_db(db_pool.clone()))
+        .and_then(handler::list_custom_fields_handler)
+        .or(field_param
+            .and(warp::get())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::fetch_custom_field_handler))
+        .or(field
+            .and(warp::post())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::create_custom_field_handler))
+        .or(field_param
+            .and(warp::put())
+            .and(etag::if_match())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::update_custom_field_handler))
+        .or(field_param
+            .and(warp::delete())
+            .and(etag::if_match())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::delete_custom_field_handler))
+}
@@ -0,0 +1,296 @@
+use crate::constraint::UNIQUE_VIOLATION;
+use crate::db::custom_field::{row_to_custom_field, value_table, SELECT_FIELDS, TABLE};
+use crate::db::{get_db_con, Result};
+use crate::error::application::Error;
+use crate::{DBCon, DBPool};
+use common::field::{
+    CustomField, CustomFieldRequest, CustomFieldValue, Entity, UpdateCustomFieldRequest,
+    OPTIONS_SEPARATOR,
+};
+use oracle::sql_type::OracleType;
+
+/// Options of an enum field as they are stored, `None` for other fields.
+fn join_options(options: &[String]) -> Option<String> {
+    Some(options.join(&OPTIONS_SEPARATOR.to_string())).filter(|options| !options.is_empty())
+}
+
+pub async fn fetch(db_pool: &DBPool, entity: Option<Entity>) -> Result<Vec<CustomField>> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "SELECT {} FROM {} WHERE :entity IS NULL OR entity = :entity ORDER BY entity, label",
+        SELECT_FIELDS, TABLE
+    );
+
+    let entity: Option<String> = entity.map(String::from);
+
+    let rows = con
+        .query_named(query.as_str(), &[("entity", &entity)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(rows
+        .filter(|r| r.is_ok())
+        .map(|r| row_to_custom_field(&r.unwrap()))
+        .collect())
+}
+
+pub async fn fetch_one(db_pool: &DBPool, id: u32) -> Result<CustomField> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &id)])
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::CustomFieldNotFound(id),
+            _ => Error::DBQuery(e),
+        })?;
+
+    Ok(row_to_custom_field(&row))
+}
+
+pub async fn fetch_by_key(db_pool: &DBPool, entity: Entity, key: &str) -> Result<CustomField> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "SELECT {} FROM {} WHERE entity = :entity AND field_key = :key",
+        SELECT_FIELDS, TABLE
+    );
+
+    let entity: String = entity.into();
+
+    let row = con
+        .query_row_named(query.as_str(), &[("entity", &entity), ("key", &key)])
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::UnknownCustomField(key.to_string()),
+            _ => Error::DBQuery(e),
+        })?;
+
+    Ok(row_to_custom_field(&row))
+}
+
+pub async fn create(db_pool: &DBPool, body: CustomFieldRequest) -> Result<CustomField> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "INSERT INTO {} (entity, field_key, label, field_type, enum_options) \
+        VALUES (:entity, :key, :label, :field_type, :options) RETURNING id INTO :id",
+        TABLE
+    );
+
+    let entity: String = body.entity.into();
+    let field_type: String = body.field_type.into();
+    let options = join_options(&body.options);
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("entity", &entity),
+                ("key", &body.key),
+                ("label", &body.label),
+                ("field_type", &field_type),
+                ("options", &options),
+                ("id", &OracleType::Number(0, 0)),
+            ],
+        )
+        .map_err(|e| match e {
+            oracle::Error::OciError(ref db_error) if db_error.code() == UNIQUE_VIOLATION => {
+                Error::DuplicateCustomField(body.key.clone())
+            }
+            _ => Error::DBQuery(e),
+        })?;
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    let row_id: u32 = stmt.returned_values("id").map_err(Error::DBQuery)?[0];
+    let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &row_id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(row_to_custom_field(&row))
+}
+
+pub async fn update(
+    db_pool: &DBPool,
+    id: u32,
+    version: u32,
+    body: UpdateCustomFieldRequest,
+) -> Result<CustomField> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "UPDATE {} SET label = :label, enum_options = :options \
+        WHERE id = :id AND version = :version",
+        TABLE
+    );
+
+    let options = join_options(&body.options);
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("id", &id),
+                ("version", &version),
+                ("label", &body.label),
+                ("options", &options),
+            ],
+        )
+        .map_err(Error::DBQuery)?;
+
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("custom field", id));
+    }
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &id)])
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::CustomFieldNotFound(id),
+            _ => Error::DBQuery(e),
+        })?;
+
+    Ok(row_to_custom_field(&row))
+}
+
+pub async fn delete(db_pool: &DBPool, id: u32, version: u32) -> Result<()> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "DELETE FROM {} WHERE id = :id AND version = :version",
+        TABLE
+    );
+
+    let stmt = con
+        .execute_named(query.as_str(), &[("id", &id), ("version", &version)])
+        .map_err(Error::DBQuery)?;
+
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("custom field", id));
+    }
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    Ok(())
+}
+
+/// Distinct values stored for a field across the customers or contracts.
+pub async fn fetch_used_values(db_pool: &DBPool, field: &CustomField) -> Result<Vec<String>> {
+    let con = get_db_con(db_pool).await?;
+    let (value_table, _) = value_table(&field.entity);
+    let query = format!(
+        "SELECT DISTINCT field_value FROM {} WHERE field_id = :id",
+        value_table
+    );
+
+    let rows = con
+        .query_named(query.as_str(), &[("id", &field.id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(rows
+        .filter(|r| r.is_ok())
+        .map(|r| r.unwrap().get(0).unwrap())
+        .collect())
+}
+
+/// Custom field values of the customer or contract `id`.
+pub async fn fetch_values(
+    db_pool: &DBPool,
+    entity: &Entity,
+    id: u32,
+) -> Result<Vec<CustomFieldValue>> {
+    let con = get_db_con(db_pool).await?;
+    let (value_table, column) = value_table(entity);
+    let query = format!(
+        "SELECT {}, field_value FROM {} JOIN {} ON field_id = id WHERE {} = :id ORDER BY label",
+        SELECT_FIELDS, TABLE, value_table, column
+    );
+
+    let rows = con
+        .query_named(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    // the value follows the field definition
+    let offset = SELECT_FIELDS.split(',').count();
+
+    Ok(rows
+        .flatten()
+        .map(|row| CustomFieldValue {
+            field: row_to_custom_field(&row),
+            value: row.get(offset).unwrap(),
+        })
+        .collect())
+}
+
+/// Replaces the custom field values of the customer or contract `id`, given by
+/// field id, in one transaction.
+pub async fn set_values(
+    db_pool: &DBPool,
+    entity: &Entity,
+    id: u32,
+    values: &[(u32, String)],
+) -> Result<Vec<CustomFieldValue>> {
+    let con = get_db_con(db_pool).await?;
+
+    if let Err(e) = set_field_values(&con, entity, id, values) {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(e);
+    }
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    let (value_table, column) = value_table(entity);
+    let query = format!(
+        "SELECT {}, field_value FROM {} JOIN {} ON field_id = id WHERE {} = :id ORDER BY label",
+        SELECT_FIELDS, TABLE, value_table, column
+    );
+
+    let rows = con
+        .query_named(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    let offset = SELECT_FIELDS.split(',').count();
+
+    Ok(rows
+        .flatten()
+        .map(|row| CustomFieldValue {
+            field: row_to_custom_field(&row),
+            value: row.get(offset).unwrap(),
+        })
+        .collect())
+}
+
+fn set_field_values(con: &DBCon, entity: &Entity, id: u32, values: &[(u32, String)]) -> Result<()> {
+    let (value_table, column) = value_table(entity);
+
+    let query = format!("DELETE FROM {} WHERE {} = :id", value_table, column);
+    con.execute_named(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    let query = format!(
+        "INSERT INTO {} ({}, field_id, field_value) VALUES (:id, :field_id, :value)",
+        value_table, column
+    );
+
+    for (field_id, value) in values {
+        con.execute_named(
+            query.as_str(),
+            &[("id", &id), ("field_id", field_id), ("value", value)],
+        )
+        .map_err(Error::DBQuery)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,322 @@
+use crate::auth;
+use crate::config::CONFIG;
+use crate::constraint::UNIQUE_VIOLATION;
+use crate::db::{get_db_con, Result};
+use crate::error::{self, application::Error};
+use crate::{with_db, DBPool};
+use sha2::{Digest, Sha256};
+use std::convert::Infallible;
+use warp::http::header::{HeaderValue, CONTENT_TYPE};
+use warp::http::{HeaderMap, Method, Request, StatusCode};
+use warp::hyper::body::{self, Bytes};
+use warp::hyper::service::Service;
+use warp::path::FullPath;
+use warp::reply::Response;
+use warp::{reject, Filter, Rejection, Reply};
+
+pub const IDEMPOTENCY_KEY: &str = "idempotency-key";
+/// Set on the responses replayed for a retried request instead of executing it again.
+pub const IDEMPOTENT_REPLAYED: &str = "idempotent-replayed";
+
+const TABLE: &str = "idempotent_request";
+const MAX_KEY_LENGTH: usize = 255;
+/// Path of the requests served without recording their responses, which carry
+/// session tokens.
+const LOGIN_PATH: &str = "/auth/login";
+
+/// Response of the first request sent with an idempotency key.
+struct StoredResponse {
+    status: u16,
+    content_type: Option<String>,
+    body: String,
+}
+
+impl StoredResponse {
+    fn into_response(self) -> Response {
+        let mut response = Response::new(self.body.into());
+        *response.status_mut() = StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK);
+
+        let headers = response.headers_mut();
+        if let Some(value) = self
+            .content_type
+            .and_then(|content_type| HeaderValue::from_str(&content_type).ok())
+        {
+            headers.insert(CONTENT_TYPE, value);
+        }
+        headers.insert(IDEMPOTENT_REPLAYED, HeaderValue::from_static("true"));
+
+        response
+    }
+}
+
+/// Serves the POST requests carrying an `Idempotency-Key` header at most once: the
+/// response to the first request with a key is stored and replayed to the retries
+/// of the same request, while reusing the key for a different request fails. Keys
+/// belong to the signed in user sending them, so that only its sender gets the
+/// replay, and the requests of no user are served without being recorded.
+pub fn idempotent<F, R>(
+    db_pool: DBPool,
+    routes: F,
+) -> impl Filter<Extract = (Response,), Error = Infallible> + Clone
+where
+    F: Filter<Extract = (R,), Error = Infallible> + Clone + Send + Sync + 'static,
+    R: Reply,
+{
+    let service = warp::service(routes.clone());
+    let query = warp::query::raw().or(warp::any().map(String::new)).unify();
+
+    warp::post()
+        .and(warp::header::<String>(IDEMPOTENCY_KEY))
+        .and(warp::path::full())
+        .and(query)
+        .and(warp::header::headers_cloned())
+        .and(auth::token())
+        .and(warp::body::bytes())
+        .and(with_db(db_pool))
+        .and_then(
+            move |key: String,
+                  path: FullPath,
+                  query: String,
+                  headers: HeaderMap,
+                  token: Option<String>,
+                  body: Bytes,
+                  db_pool: DBPool| {
+                let mut service = service.clone();
+                async move {
+                    let uri = if query.is_empty() {
+                        path.as_str().to_string()
+                    } else {
+                        format!("{}?{}", path.as_str(), query)
+                    };
+                    let hash = request_hash(&uri, &body);
+
+                    // the sessions opened by signing in are not to be stored
+                    let user_id = match token {
+                        Some(token) if !path.as_str().ends_with(LOGIN_PATH) => {
+                            auth::repository::authenticate(&db_pool, &token)
+                                .await
+                                .ok()
+                                .map(|user| user.id)
+                        }
+                        _ => None,
+                    };
+                    let claimed = match user_id {
+                        Some(user_id) => claim(&db_pool, user_id, &key, &hash).await,
+                        None => Ok(None),
+                    };
+
+                    let response = match claimed {
+                        Ok(Some(stored)) => {
+                            log::info!("Replaying the response to request {}", key);
+                            stored.into_response()
+                        }
+                        Ok(None) => {
+                            let mut request = Request::new(body.into());
+                            *request.method_mut() = Method::POST;
+                            *request.uri_mut() = uri.parse().unwrap_or_default();
+                            *request.headers_mut() = headers;
+
+                            let response = match service.call(request).await {
+                                Ok(response) => response,
+                                Err(infallible) => match infallible {},
+                            };
+                            match user_id {
+                                Some(user_id) => store(&db_pool, user_id, &key, response).await,
+                                None => response,
+                            }
+                        }
+                        Err(e) => rejection_response(e).await,
+                    };
+
+                    Ok::<_, Rejection>(response)
+                }
+            },
+        )
+        .or(routes.map(|reply: R| reply.into_response()))
+        .unify()
+}
+
+/// Fingerprint of a request, to tell the retries of a request from other requests
+/// reusing its key.
+fn request_hash(uri: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(uri.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body);
+
+    format!("{:x}", hasher.finalize())
+}
+
+async fn rejection_response(e: Error) -> Response {
+    match error::handle_rejection(reject::custom(e)).await {
+        Ok(reply) => reply.into_response(),
+        Err(infallible) => match infallible {},
+    }
+}
+
+/// Records the response to the request with the key of the user, unless it is a
+/// server error or the request was not authenticated, after which the key is
+/// released for the request to be retried.
+async fn store(db_pool: &DBPool, user_id: u32, key: &str, response: Response) -> Response {
+    let (parts, response_body) = response.into_parts();
+    let response_body = match body::to_bytes(response_body).await {
+        Ok(response_body) => response_body,
+        Err(e) => {
+            log::error!("error reading the response to request {}: {}", key, e);
+            let _ = release(db_pool, user_id, key).await;
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let result = if parts.status.is_server_error() || parts.status == StatusCode::UNAUTHORIZED {
+        release(db_pool, user_id, key).await
+    } else {
+        let content_type = parts
+            .headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok());
+        save(
+            db_pool,
+            user_id,
+            key,
+            parts.status.as_u16(),
+            content_type,
+            &String::from_utf8_lossy(&response_body),
+        )
+        .await
+    };
+    if let Err(e) = result {
+        log::error!("error storing the response to request {}: {}", key, e);
+    }
+
+    Response::from_parts(parts, response_body.into())
+}
+
+/// Claims the key of the user for the request with the hash, returning the stored
+/// response if the request was already served.
+async fn claim(
+    db_pool: &DBPool,
+    user_id: u32,
+    key: &str,
+    hash: &str,
+) -> Result<Option<StoredResponse>> {
+    if key.is_empty() || key.chars().count() > MAX_KEY_LENGTH {
+        return Err(Error::InvalidIdempotencyKey(key.to_string()));
+    }
+
+    let con = get_db_con(db_pool).await?;
+    // the keys expire, so that a request left unanswered by a crash can be retried
+    let query = format!(
+        "DELETE FROM {} WHERE created_at < SYSTIMESTAMP - NUMTODSINTERVAL(:hours, 'HOUR')",
+        TABLE
+    );
+    con.execute_named(query.as_str(), &[("hours", &CONFIG.idempotency_key_hours)])
+        .map_err(Error::DBQuery)?;
+
+    let query = format!(
+        "INSERT INTO {} (user_id, idempotency_key, request_hash) VALUES (:user_id, :key, :hash)",
+        TABLE
+    );
+
+    match con.execute_named(
+        query.as_str(),
+        &[("user_id", &user_id), ("key", &key), ("hash", &hash)],
+    ) {
+        Ok(_) => {
+            if let Err(e) = con.commit() {
+                con.rollback().map_err(Error::DBQuery)?;
+                return Err(Error::DBQuery(e));
+            }
+
+            Ok(None)
+        }
+        Err(oracle::Error::OciError(ref db_error)) if db_error.code() == UNIQUE_VIOLATION => {
+            con.commit().map_err(Error::DBQuery)?;
+
+            let query = format!(
+                "SELECT request_hash, status, content_type, body FROM {} \
+                WHERE user_id = :user_id AND idempotency_key = :key",
+                TABLE
+            );
+            let row = con
+                .query_row_named(query.as_str(), &[("user_id", &user_id), ("key", &key)])
+                .map_err(Error::DBQuery)?;
+
+            let request_hash: String = row.get("request_hash").map_err(Error::DBQuery)?;
+            let status: Option<u16> = row.get("status").map_err(Error::DBQuery)?;
+
+            if request_hash != hash {
+                return Err(Error::IdempotencyKeyReused(key.to_string()));
+            }
+
+            match status {
+                Some(status) => Ok(Some(StoredResponse {
+                    status,
+                    content_type: row.get("content_type").map_err(Error::DBQuery)?,
+                    body: row
+                        .get::<_, Option<String>>("body")
+                        .map_err(Error::DBQuery)?
+                        .unwrap_or_default(),
+                })),
+                None => Err(Error::IdempotencyKeyInProgress(key.to_string())),
+            }
+        }
+        Err(e) => {
+            con.rollback().map_err(Error::DBQuery)?;
+            Err(Error::DBQuery(e))
+        }
+    }
+}
+
+async fn save(
+    db_pool: &DBPool,
+    user_id: u32,
+    key: &str,
+    status: u16,
+    content_type: Option<&str>,
+    body: &str,
+) -> Result<()> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "UPDATE {} SET status = :status, content_type = :content_type, body = :body \
+        WHERE user_id = :user_id AND idempotency_key = :key",
+        TABLE
+    );
+
+    con.execute_named(
+        query.as_str(),
+        &[
+            ("user_id", &user_id),
+            ("key", &key),
+            ("status", &status),
+            ("content_type", &content_type),
+            ("body", &body),
+        ],
+    )
+    .map_err(Error::DBQuery)?;
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    Ok(())
+}
+
+async fn release(db_pool: &DBPool, user_id: u32, key: &str) -> Result<()> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "DELETE FROM {} WHERE user_id = :user_id AND idempotency_key = :key",
+        TABLE
+    );
+
+    con.execute_named(query.as_str(), &[("user_id", &user_id), ("key", &key)])
+        .map_err(Error::DBQuery)?;
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,414 @@
+use crate::bulk::{Bulk, MAX_ITEMS};
+use crate::error::application::Error;
+use crate::Result;
+use calamine::{open_workbook_from_rs, Data, Reader, Xlsx, XlsxError};
+use chrono::{DateTime, NaiveDate, NaiveTime};
+use common::error::FieldError;
+use common::import::{ImportResponse, ImportRowResult, FILE_PART, MAPPING_PART};
+use futures_util::TryStreamExt;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, Schema, SingleOrVec};
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read};
+use warp::multipart::{FormData, Part};
+use warp::reply::json;
+use warp::{reject, Buf, Filter, Rejection, Reply};
+
+/// Largest file that can be imported.
+const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+const XLSX_CONTENT_TYPE: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+/// Multipart form of an import, holding the file and its column mapping.
+pub fn form() -> impl Filter<Extract = (FormData,), Error = Rejection> + Clone {
+    warp::multipart::form().max_length(MAX_FILE_SIZE)
+}
+
+/// Type of the values of a field, which the cells of its column are converted to.
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Integer,
+    Number,
+    Boolean,
+    DateTime,
+    Text,
+}
+
+struct Field {
+    name: String,
+    kind: Kind,
+    required: bool,
+}
+
+struct Row {
+    line: usize,
+    values: BTreeMap<String, String>,
+    item: std::result::Result<Value, Vec<FieldError>>,
+}
+
+/// Rows of an imported file, converted to the bodies of the requests creating
+/// their records.
+pub struct Import {
+    columns: Vec<String>,
+    fields: Vec<String>,
+    mapping: BTreeMap<String, String>,
+    rows: Vec<Row>,
+}
+
+impl Import {
+    /// Reads the file of the form and converts its rows to requests of type `T`,
+    /// mapping its columns to the fields of `T` as by the mapping of the form or,
+    /// without one, by name.
+    pub async fn read<T: JsonSchema>(form: FormData) -> Result<Self> {
+        let parts: Vec<Part> = form
+            .try_collect()
+            .await
+            .map_err(|e| reject::custom(Error::InvalidImport(e.to_string())))?;
+
+        let mut file = None;
+        let mut mapping = None;
+        for part in parts {
+            match part.name() {
+                FILE_PART => {
+                    let xlsx = part
+                        .filename()
+                        .map(|name| name.to_lowercase().ends_with(".xlsx"))
+                        .unwrap_or_default()
+                        || part.content_type() == Some(XLSX_CONTENT_TYPE);
+                    file = Some((xlsx, read_part(part).await?));
+                }
+                MAPPING_PART => {
+                    let bytes = read_part(part).await?;
+                    let parsed: BTreeMap<String, String> =
+                        serde_json::from_slice(&bytes).map_err(|e| {
+                            reject::custom(Error::InvalidImport(format!("mapping: {}", e)))
+                        })?;
+                    mapping = Some(parsed);
+                }
+                _ => {}
+            }
+        }
+
+        let (xlsx, bytes) = file.ok_or_else(|| {
+            reject::custom(Error::InvalidImport(format!("no {} part", FILE_PART)))
+        })?;
+        let table = if xlsx {
+            parse_xlsx(bytes)
+        } else {
+            parse_csv(&bytes)
+        }
+        .map_err(|e| reject::custom(Error::InvalidImport(e)))?;
+
+        let mut table = table
+            .into_iter()
+            .enumerate()
+            .filter(|(_, row)| row.iter().any(|cell| !cell.is_empty()));
+        let columns: Vec<String> = table.next().map(|(_, header)| header).unwrap_or_default();
+        let table: Vec<(usize, Vec<String>)> = table.collect();
+
+        if table.len() > MAX_ITEMS {
+            return Err(reject::custom(Error::TooManyBulkItems(table.len())));
+        }
+
+        let fields = fields::<T>();
+        let mapping = match mapping {
+            Some(mapping) if !mapping.is_empty() => mapping,
+            _ => columns
+                .iter()
+                .filter_map(|column| {
+                    let name = column.trim().to_lowercase();
+                    fields
+                        .iter()
+                        .find(|field| field.name == name)
+                        .map(|field| (column.clone(), field.name.clone()))
+                })
+                .collect(),
+        };
+        check_mapping(&columns, &fields, &mapping)?;
+
+        let rows = table
+            .into_iter()
+            .map(|(index, row)| convert(index + 1, &row, &columns, &fields, &mapping))
+            .collect();
+
+        Ok(Import {
+            columns,
+            fields: fields.into_iter().map(|field| field.name).collect(),
+            mapping,
+            rows,
+        })
+    }
+
+    /// Requests of the rows, or the errors of the cells that could not be converted.
+    pub fn items(&self) -> Vec<std::result::Result<Value, Vec<FieldError>>> {
+        self.rows.iter().map(|row| row.item.clone()).collect()
+    }
+
+    /// Finishes the import of the rows, recorded by the bulk request in order, and
+    /// replies with the outcome of each row. The rows are checked and written
+    /// through the bulk request, within its transaction.
+    pub fn reply<T: Serialize>(self, bulk: Bulk<T>) -> Result<impl Reply> {
+        let (code, report) = bulk.report()?;
+
+        let rows = self
+            .rows
+            .into_iter()
+            .zip(report.items)
+            .map(|(row, item)| ImportRowResult {
+                line: row.line,
+                values: row.values,
+                status: item.status,
+                record: item.record,
+                code: item.code,
+                error_code: item.error_code,
+                message: item.message,
+                errors: item.errors,
+            })
+            .collect();
+
+        let response = ImportResponse {
+            committed: report.committed,
+            columns: self.columns,
+            fields: self.fields,
+            mapping: self.mapping,
+            succeeded: report.succeeded,
+            failed: report.failed,
+            rows,
+        };
+
+        Ok(warp::reply::with_status(json(&response), code))
+    }
+}
+
+async fn read_part(part: Part) -> Result<Vec<u8>> {
+    part.stream()
+        .try_fold(Vec::new(), |mut bytes, buf| async move {
+            // reading from a slice into a vector cannot fail
+            let _ = buf.reader().read_to_end(&mut bytes);
+            Ok(bytes)
+        })
+        .await
+        .map_err(|e| reject::custom(Error::InvalidImport(e.to_string())))
+}
+
+fn parse_csv(bytes: &[u8]) -> std::result::Result<Vec<Vec<String>>, String> {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    // spreadsheets using the decimal comma export their CSV files with semicolons
+    let header = bytes.split(|&b| b == b'\n').next().unwrap_or_default();
+    let semicolons = header.iter().filter(|&&b| b == b';').count();
+    let commas = header.iter().filter(|&&b| b == b',').count();
+    let delimiter = if semicolons > commas { b';' } else { b',' };
+
+    csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .delimiter(delimiter)
+        .from_reader(bytes)
+        .records()
+        .map(|record| {
+            record
+                .map(|record| record.iter().map(String::from).collect())
+                .map_err(|e| e.to_string())
+        })
+        .collect()
+}
+
+/// Reads the first sheet of the workbook.
+fn parse_xlsx(bytes: Vec<u8>) -> std::result::Result<Vec<Vec<String>>, String> {
+    let mut workbook: Xlsx<_> =
+        open_workbook_from_rs(Cursor::new(bytes)).map_err(|e: XlsxError| e.to_string())?;
+    let range = workbook
+        .worksheet_range_at(0)
+        .ok_or_else(|| "the workbook has no sheets".to_string())?
+        .map_err(|e| e.to_string())?;
+
+    Ok(range
+        .rows()
+        .map(|row| row.iter().map(cell).collect())
+        .collect())
+}
+
+fn cell(data: &Data) -> String {
+    match data {
+        Data::DateTime(date_time) => date_time
+            .as_datetime()
+            .map(|date_time| {
+                if date_time.time() == NaiveTime::MIN {
+                    date_time.date().to_string()
+                } else {
+                    date_time.and_utc().to_rfc3339()
+                }
+            })
+            .unwrap_or_default(),
+        Data::Empty | Data::Error(_) => String::new(),
+        data => data.to_string().trim().to_string(),
+    }
+}
+
+/// Fields of the requests of type `T`, as described by its schema.
+fn fields<T: JsonSchema>() -> Vec<Field> {
+    let root = SchemaGenerator::default().into_root_schema_for::<T>();
+    let object = root.schema.object.unwrap_or_default();
+
+    object
+        .properties
+        .iter()
+        .map(|(name, schema)| Field {
+            name: name.clone(),
+            kind: kind(schema),
+            required: object.required.contains(name),
+        })
+        .collect()
+}
+
+fn kind(schema: &Schema) -> Kind {
+    let Schema::Object(schema) = schema else {
+        return Kind::Text;
+    };
+    let types = match &schema.instance_type {
+        Some(SingleOrVec::Single(instance_type)) => vec![**instance_type],
+        Some(SingleOrVec::Vec(instance_types)) => instance_types.clone(),
+        None => vec![],
+    };
+
+    if types.contains(&InstanceType::Integer) {
+        Kind::Integer
+    } else if types.contains(&InstanceType::Number) {
+        Kind::Number
+    } else if types.contains(&InstanceType::Boolean) {
+        Kind::Boolean
+    } else if schema.format.as_deref() == Some("date-time") {
+        Kind::DateTime
+    } else {
+        Kind::Text
+    }
+}
+
+/// Fails unless the mapping maps existing columns to distinct fields, including
+/// all the required ones.
+fn check_mapping(
+    columns: &[String],
+    fields: &[Field],
+    mapping: &BTreeMap<String, String>,
+) -> Result<()> {
+    let mut mapped: Vec<&String> = vec![];
+
+    for (column, field) in mapping {
+        if !columns.contains(column) {
+            return Err(reject::custom(Error::InvalidImport(format!(
+                "the file has no column {}",
+                column
+            ))));
+        }
+        if !fields.iter().any(|f| &f.name == field) {
+            return Err(reject::custom(Error::InvalidImport(format!(
+                "column {} is mapped to the unknown field {}",
+                column, field
+            ))));
+        }
+        if mapped.contains(&field) {
+            return Err(reject::custom(Error::InvalidImport(format!(
+                "more than one column is mapped to the field {}",
+                field
+            ))));
+        }
+        mapped.push(field);
+    }
+
+    if let Some(field) = fields
+        .iter()
+        .find(|field| field.required && !mapped.contains(&&field.name))
+    {
+        return Err(reject::custom(Error::InvalidImport(format!(
+            "no column is mapped to the required field {}",
+            field.name
+        ))));
+    }
+
+    Ok(())
+}
+
+fn convert(
+    line: usize,
+    row: &[String],
+    columns: &[String],
+    fields: &[Field],
+    mapping: &BTreeMap<String, String>,
+) -> Row {
+    let mut values = BTreeMap::new();
+    let mut item = Map::new();
+    let mut errors = vec![];
+
+    for (index, column) in columns.iter().enumerate() {
+        let Some(field) = mapping
+            .get(column)
+            .and_then(|name| fields.iter().find(|field| &field.name == name))
+        else {
+            continue;
+        };
+        let value = row.get(index).map(|value| value.trim()).unwrap_or_default();
+        values.insert(field.name.clone(), value.to_string());
+
+        if value.is_empty() {
+            if field.required {
+                errors.push(FieldError {
+                    field: field.name.clone(),
+                    field_errors: vec!["is required".to_string()],
+                });
+            }
+            continue;
+        }
+
+        match convert_value(field.kind, value) {
+            Ok(value) => {
+                item.insert(field.name.clone(), value);
+            }
+            Err(error) => errors.push(FieldError {
+                field: field.name.clone(),
+                field_errors: vec![error.to_string()],
+            }),
+        }
+    }
+
+    Row {
+        line,
+        values,
+        item: if errors.is_empty() {
+            Ok(Value::Object(item))
+        } else {
+            Err(errors)
+        },
+    }
+}
+
+fn convert_value(kind: Kind, value: &str) -> std::result::Result<Value, &'static str> {
+    match kind {
+        Kind::Integer => value
+            .parse::<i64>()
+            .map(Value::from)
+            .map_err(|_| "should be an integer"),
+        Kind::Number => value
+            .replace(',', ".")
+            .parse::<f64>()
+            .map(Value::from)
+            .map_err(|_| "should be a number"),
+        Kind::Boolean => match value.to_lowercase().as_str() {
+            "true" | "yes" | "da" | "1" => Ok(Value::Bool(true)),
+            "false" | "no" | "nu" | "0" => Ok(Value::Bool(false)),
+            _ => Err("should be true or false"),
+        },
+        Kind::DateTime => DateTime::parse_from_rfc3339(value)
+            .map(|date_time| date_time.to_rfc3339())
+            .or_else(|_| {
+                NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                    .or_else(|_| NaiveDate::parse_from_str(value, "%d.%m.%Y"))
+                    .map(|date| date.and_time(NaiveTime::MIN).and_utc().to_rfc3339())
+            })
+            .map(Value::String)
+            .map_err(|_| "should be a date, as YYYY-MM-DD"),
+        Kind::Text => Ok(Value::String(value.to_string())),
+    }
+}
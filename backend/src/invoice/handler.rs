@@ -1,19 +1,50 @@
+use crate::config::CONFIG;
 use crate::error::application::Error;
+use crate::etag::{self, IfMatch};
 use crate::invoice::repository;
-use crate::{contract, DBPool, Result};
-use common::invoice::{CreateInvoiceRequest, InvoiceResponse};
+use crate::{
+    addon, bundle, contract, customer, export, page, promotion, subscription, DBPool, Result,
+};
+use chrono::{DateTime, Utc};
+use common::export::ExportFormat;
+use common::invoice::{
+    self, CreateInvoiceRequest, Invoice, InvoiceLineResponse, InvoiceLineType, InvoiceListQuery,
+    InvoiceResponse, NewInvoiceLine,
+};
+use common::page::PageQuery;
 use common::payment::PaymentResponse;
+use common::penalty::{self, PenaltyQuery, PenaltyResponse};
 use validator::Validate;
 use warp::reply::json;
 use warp::{reject, Buf, Reply};
 
-pub async fn list_invoices_handler(db_pool: DBPool) -> Result<impl Reply> {
+pub async fn list_invoices_handler(
+    query: InvoiceListQuery,
+    page: PageQuery,
+    format: ExportFormat,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
     log::info!("Listing invoices");
 
-    let invoices = repository::fetch(&db_pool).await.map_err(reject::custom)?;
-    Ok(json::<Vec<_>>(
-        &invoices.into_iter().map(InvoiceResponse::from).collect(),
-    ))
+    if format != ExportFormat::Json {
+        return export::reply(&db_pool, "invoices", format, move |con, each| {
+            repository::export(con, &query, &page, &mut |invoice| {
+                each(InvoiceResponse::from(invoice))
+            })
+        })
+        .await;
+    }
+
+    let invoices = repository::fetch(&db_pool, &query, &page)
+        .await
+        .map_err(reject::custom)?;
+    Ok(page::reply(
+        "/api/v1/invoice",
+        &query,
+        &page,
+        invoices.map(InvoiceResponse::from),
+    )
+    .into_response())
 }
 
 pub async fn fetch_invoice_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
@@ -22,7 +53,8 @@ pub async fn fetch_invoice_handler(id: u32, db_pool: DBPool) -> Result<impl Repl
     let invoice = repository::fetch_one(&db_pool, id)
         .await
         .map_err(reject::custom)?;
-    Ok(json(&InvoiceResponse::from(invoice)))
+    let version = invoice.version;
+    Ok(etag::reply(json(&InvoiceResponse::from(invoice)), version))
 }
 
 pub async fn fetch_payments(id: u32, db_pool: DBPool) -> Result<impl Reply> {
@@ -36,6 +68,36 @@ pub async fn fetch_payments(id: u32, db_pool: DBPool) -> Result<impl Reply> {
     ))
 }
 
+pub async fn fetch_lines(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Fetching lines for invoice with id {}", id);
+
+    let lines = repository::fetch_lines(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    Ok(json::<Vec<_>>(
+        &lines.into_iter().map(InvoiceLineResponse::from).collect(),
+    ))
+}
+
+pub async fn fetch_penalty(id: u32, query: PenaltyQuery, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Computing late payment penalty for invoice with id {}", id);
+
+    let invoice = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+
+    let as_of = query
+        .as_of
+        .map(|date| date.and_hms_opt(0, 0, 0).unwrap().and_utc())
+        .unwrap_or_else(Utc::now);
+
+    Ok(json(
+        &compute_penalty(&db_pool, invoice, as_of)
+            .await
+            .map_err(reject::custom)?,
+    ))
+}
+
 pub async fn create_invoice_handler(buf: impl Buf, db_pool: DBPool) -> Result<impl Reply> {
     log::info!("Creating a new invoice");
 
@@ -75,7 +137,123 @@ pub async fn create_invoice_handler(buf: impl Buf, db_pool: DBPool) -> Result<im
         ));
     }
 
-    let created_invoice = repository::create(&db_pool, body)
+    let previous_invoices = contract::repository::fetch_invoices(&db_pool, contract.id)
+        .await
+        .map_err(reject::custom)?;
+
+    // a bundle is billed as one line per component, sharing the invoiced amount
+    let mut lines = if let Some(bundle_id) = contract.bundle_id {
+        let bundle = bundle::repository::fetch_one(&db_pool, bundle_id)
+            .await
+            .map_err(reject::custom)?;
+        let components = bundle::repository::fetch_subscriptions(&db_pool, bundle_id)
+            .await
+            .map_err(reject::custom)?;
+        let prices: Vec<f64> = components.iter().map(|s| s.price).collect();
+
+        components
+            .into_iter()
+            .zip(common::bundle::allocate(body.amount, &prices))
+            .map(|(subscription, amount)| NewInvoiceLine {
+                line_type: InvoiceLineType::BundleComponent,
+                description: format!("{}: {}", bundle.description, subscription.description),
+                amount,
+                source_invoice_id: None,
+            })
+            .collect()
+    } else {
+        let subscription = subscription::repository::fetch_one(
+            &db_pool,
+            contract.subscription_id.unwrap_or_default(),
+        )
+        .await
+        .map_err(reject::custom)?;
+
+        vec![NewInvoiceLine {
+            line_type: InvoiceLineType::Subscription,
+            description: subscription.description,
+            amount: body.amount,
+            source_invoice_id: None,
+        }]
+    };
+
+    // add-ons are billed at their contract price, outside of the promotion
+    let contract_addons = contract::repository::fetch_addons(&db_pool, contract.id)
+        .await
+        .map_err(reject::custom)?;
+
+    for contract_addon in contract_addons
+        .into_iter()
+        .filter(|a| a.is_billed_between(body.issue_date, body.due_date))
+    {
+        let addon = addon::repository::fetch_one(&db_pool, contract_addon.addon_id)
+            .await
+            .map_err(reject::custom)?;
+
+        lines.push(NewInvoiceLine {
+            line_type: InvoiceLineType::Addon,
+            description: addon.description,
+            amount: contract_addon.price,
+            source_invoice_id: None,
+        });
+    }
+
+    // each invoice of the contract bills one cycle of its promotion, if any
+    if let Some(promotion_id) = contract.promotion_id {
+        let promotion = promotion::repository::fetch_one(&db_pool, promotion_id)
+            .await
+            .map_err(reject::custom)?;
+        let cycle = previous_invoices.len() as u32;
+        let discount = promotion.discount_for(body.amount, cycle);
+
+        if discount > 0.0 {
+            lines.push(NewInvoiceLine {
+                line_type: InvoiceLineType::Discount,
+                description: format!(
+                    "{} ({}, cycle {} of {})",
+                    promotion.description,
+                    promotion.code,
+                    cycle + 1,
+                    promotion.duration_cycles
+                ),
+                amount: -discount,
+                source_invoice_id: None,
+            });
+        }
+    }
+
+    if body.bill_penalties {
+        for overdue_invoice in previous_invoices {
+            let summary = compute_penalty(&db_pool, overdue_invoice, body.issue_date)
+                .await
+                .map_err(reject::custom)?;
+
+            if summary.unbilled_penalty > 0.0 {
+                lines.push(NewInvoiceLine {
+                    line_type: InvoiceLineType::Penalty,
+                    description: format!(
+                        "Late payment penalty for invoice {} ({} days overdue)",
+                        summary.invoice_id, summary.days_overdue
+                    ),
+                    amount: summary.unbilled_penalty,
+                    source_invoice_id: Some(summary.invoice_id),
+                });
+            }
+        }
+    }
+
+    // VAT payers of other EU member states account for the VAT themselves under reverse charge
+    let customer = customer::repository::fetch_one(&db_pool, contract.customer_id)
+        .await
+        .map_err(reject::custom)?;
+    let reverse_charge = customer.is_reverse_charge();
+    let vat_rate = if reverse_charge { 0.0 } else { CONFIG.vat_rate };
+
+    if let Some(vat_line) = invoice::vat_line(&lines, vat_rate) {
+        lines.push(vat_line);
+    }
+
+    let created_invoice = repository::create(&db_pool, body, lines, vat_rate, reverse_charge)
         .await
         .map_err(reject::custom)?;
 
@@ -87,11 +265,53 @@ pub async fn create_invoice_handler(buf: impl Buf, db_pool: DBPool) -> Result<im
     ))
 }
 
-pub async fn delete_invoice_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+pub async fn delete_invoice_handler(
+    id: u32,
+    if_match: IfMatch,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
     log::info!("Deleting invoice with id {}", id);
 
-    repository::delete(&db_pool, id)
+    let invoice = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    if_match
+        .check("invoice", id, invoice.version)
+        .map_err(reject::custom)?;
+
+    repository::delete(&db_pool, id, invoice.version)
         .await
         .map_err(reject::custom)?;
     Ok(warp::http::StatusCode::NO_CONTENT)
 }
+
+async fn compute_penalty(
+    db_pool: &DBPool,
+    invoice: Invoice,
+    as_of: DateTime<Utc>,
+) -> std::result::Result<PenaltyResponse, Error> {
+    let contract = contract::repository::fetch_one(db_pool, invoice.contract_id).await?;
+    let payments = repository::fetch_payments(db_pool, invoice.id).await?;
+    let lines = repository::fetch_lines(db_pool, invoice.id).await?;
+    let billed_penalty = repository::fetch_billed_penalty(db_pool, invoice.id).await?;
+
+    let outstanding_amount = penalty::outstanding_amount(&invoice, &payments, as_of);
+    let accrued_penalty =
+        penalty::accrued_penalty(&invoice, &lines, &payments, contract.penalty_rate, as_of);
+    let days_overdue = if outstanding_amount > 0.0 && as_of > invoice.due_date {
+        (as_of - invoice.due_date).num_days()
+    } else {
+        0
+    };
+
+    Ok(PenaltyResponse {
+        invoice_id: invoice.id,
+        as_of,
+        penalty_rate: contract.penalty_rate,
+        days_overdue,
+        outstanding_amount,
+        accrued_penalty,
+        billed_penalty,
+        unbilled_penalty: penalty::round_to_cents((accrued_penalty - billed_penalty).max(0.0)),
+    })
+}
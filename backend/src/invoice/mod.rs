@@ -1,4 +1,7 @@
-use crate::{with_db, DBPool};
+use crate::{etag, export, with_db, DBPool};
+use common::invoice::InvoiceListQuery;
+use common::page::PageQuery;
+use common::penalty::PenaltyQuery;
 use warp::Filter;
 
 pub mod handler;
@@ -7,12 +10,17 @@ pub mod repository;
 pub fn get_routes(
     db_pool: DBPool,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
-    let invoice = warp::path!("api" / "invoice");
-    let invoice_param = warp::path!("api" / "invoice" / u32);
-    let invoice_payments = warp::path!("api" / "invoice" / u32 / "payment");
+    let invoice = warp::path!("invoice");
+    let invoice_param = warp::path!("invoice" / u32);
+    let invoice_payments = warp::path!("invoice" / u32 / "payment");
+    let invoice_lines = warp::path!("invoice" / u32 / "line");
+    let invoice_penalty = warp::path!("invoice" / u32 / "penalty");
 
     invoice
         .and(warp::get())
+        .and(warp::query::<InvoiceListQuery>())
+        .and(warp::query::<PageQuery>())
+        .and(export::format())
         .and(with_db(db_pool.clone()))
         .and_then(handler::list_invoices_handler)
         .or(invoice_param
@@ -26,10 +34,20 @@ pub fn get_routes(
             .and_then(handler::create_invoice_handler))
         .or(invoice_param
             .and(warp::delete())
+            .and(etag::if_match())
             .and(with_db(db_pool.clone()))
             .and_then(handler::delete_invoice_handler))
         .or(invoice_payments
             .and(warp::get())
             .and(with_db(db_pool.clone()))
             .and_then(handler::fetch_payments))
+        .or(invoice_lines
+            .and(warp::get())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::fetch_lines))
+        .or(invoice_penalty
+            .and(warp::get())
+            .and(warp::query::<PenaltyQuery>())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::fetch_penalty))
 }
@@ -1,22 +1,78 @@
 use crate::db::invoice::{row_to_invoice, SELECT_FIELDS, TABLE};
+use crate::db::invoice_line::row_to_invoice_line;
 use crate::db::payment::row_to_payment;
 use crate::db::{get_db_con, Result};
 use crate::error::application::Error;
-use crate::DBPool;
-use common::invoice::{CreateInvoiceRequest, Invoice};
+use crate::page::{date_range, Listing, Page};
+use crate::{DBCon, DBPool};
+use common::invoice::{
+    CreateInvoiceRequest, Invoice, InvoiceLine, InvoiceLineType, InvoiceListQuery, InvoiceStatus,
+    NewInvoiceLine,
+};
+use common::page::PageQuery;
 use common::payment::Payment;
-use oracle::sql_type::OracleType;
-
-pub async fn fetch(db_pool: &DBPool) -> Result<Vec<Invoice>> {
+use oracle::sql_type::{OracleType, ToSql};
+
+const LISTING: Listing = Listing {
+    table: TABLE,
+    fields: SELECT_FIELDS,
+    sort_columns: &[
+        ("id", "id"),
+        ("contract_id", "contract_id"),
+        ("issue_date", "issue_date"),
+        ("due_date", "due_date"),
+        ("amount", "amount"),
+        ("status", "status"),
+    ],
+};
+
+/// Page of the invoices having the status, of the contract and issued in the date
+/// range of the filter, when given.
+pub async fn fetch(
+    db_pool: &DBPool,
+    filter: &InvoiceListQuery,
+    page: &PageQuery,
+) -> Result<Page<Invoice>> {
     let con = get_db_con(db_pool).await?;
-    let query = format!("SELECT {} FROM {}", SELECT_FIELDS, TABLE);
 
-    let rows = con.query(query.as_str(), &[]).map_err(Error::DBQuery)?;
+    filtered(filter, |condition, params| {
+        crate::page::fetch(&con, &LISTING, condition, params, page, row_to_invoice)
+    })
+}
 
-    Ok(rows
-        .filter(|r| r.is_ok())
-        .map(|r| row_to_invoice(&r.unwrap()))
-        .collect())
+/// Passes each invoice matching the filter to `each` as it is read, sorted as by the page.
+pub fn export(
+    con: &DBCon,
+    filter: &InvoiceListQuery,
+    page: &PageQuery,
+    each: &mut dyn FnMut(Invoice) -> Result<()>,
+) -> Result<()> {
+    filtered(filter, |condition, params| {
+        crate::page::for_each(con, &LISTING, condition, params, page, row_to_invoice, each)
+    })
+}
+
+/// Calls `list` with the condition selecting the invoices matching the filter and its
+/// parameters.
+fn filtered<R>(
+    filter: &InvoiceListQuery,
+    list: impl FnOnce(&str, &[(&str, &dyn ToSql)]) -> R,
+) -> R {
+    let status: Option<String> = filter.status.clone().map(String::from);
+    let (date_from, date_to) = date_range(filter.from, filter.to);
+
+    list(
+        "(:status IS NULL OR status = :status) \
+        AND (:contract_id IS NULL OR contract_id = :contract_id) \
+        AND (:date_from IS NULL OR issue_date >= :date_from) \
+        AND (:date_to IS NULL OR issue_date < :date_to)",
+        &[
+            ("status", &status),
+            ("contract_id", &filter.contract_id),
+            ("date_from", &date_from),
+            ("date_to", &date_to),
+        ],
+    )
 }
 
 pub async fn fetch_one(db_pool: &DBPool, id: u32) -> Result<Invoice> {
@@ -30,14 +86,33 @@ pub async fn fetch_one(db_pool: &DBPool, id: u32) -> Result<Invoice> {
     Ok(row_to_invoice(&row))
 }
 
-pub async fn create(db_pool: &DBPool, body: CreateInvoiceRequest) -> Result<Invoice> {
+pub async fn create(
+    db_pool: &DBPool,
+    body: CreateInvoiceRequest,
+    lines: Vec<NewInvoiceLine>,
+    vat_rate: f64,
+    reverse_charge: bool,
+) -> Result<Invoice> {
+    use crate::db::invoice_line::TABLE as LINE_TABLE;
+
     let con = get_db_con(db_pool).await?;
     let query = format!(
-        "INSERT INTO {} (contract_id, issue_date, due_date, amount) \
-        VALUES (:contract_id, :issue_date, :due_date, :amount) RETURNING id into :id",
+        "INSERT INTO {} (contract_id, issue_date, due_date, amount, status, vat_rate, reverse_charge) \
+        VALUES (:contract_id, :issue_date, :due_date, :amount, :status, :vat_rate, :reverse_charge) \
+        RETURNING id into :id",
         TABLE
     );
 
+    // a fully discounted invoice has nothing left to be paid
+    let amount: f64 = lines.iter().map(|line| line.amount).sum();
+    let status: String = if amount > 0.0 {
+        InvoiceStatus::Unpaid
+    } else {
+        InvoiceStatus::Paid
+    }
+    .into();
+    let reverse_charge = reverse_charge as i32;
+
     let stmt = con
         .execute_named(
             query.as_str(),
@@ -45,18 +120,45 @@ pub async fn create(db_pool: &DBPool, body: CreateInvoiceRequest) -> Result<Invo
                 ("contract_id", &body.contract_id),
                 ("issue_date", &body.issue_date),
                 ("due_date", &body.due_date),
-                ("amount", &body.amount),
+                ("amount", &amount),
+                ("status", &status),
+                ("vat_rate", &vat_rate),
+                ("reverse_charge", &reverse_charge),
                 ("id", &OracleType::Number(0, 0)),
             ],
         )
         .map_err(Error::DBQuery)?;
 
+    let row_id: u32 = stmt.returned_values("id").map_err(Error::DBQuery)?[0];
+    let query = format!(
+        "INSERT INTO {} (invoice_id, line_type, description, amount, source_invoice_id) \
+        VALUES (:invoice_id, :line_type, :description, :amount, :source_invoice_id)",
+        LINE_TABLE
+    );
+
+    for line in lines {
+        let line_type: String = line.line_type.into();
+
+        if let Err(e) = con.execute_named(
+            query.as_str(),
+            &[
+                ("invoice_id", &row_id),
+                ("line_type", &line_type),
+                ("description", &line.description),
+                ("amount", &line.amount),
+                ("source_invoice_id", &line.source_invoice_id),
+            ],
+        ) {
+            con.rollback().map_err(Error::DBQuery)?;
+            return Err(Error::DBQuery(e));
+        }
+    }
+
     if let Err(e) = con.commit() {
         con.rollback().map_err(Error::DBQuery)?;
         return Err(Error::DBQuery(e));
     }
 
-    let row_id: u32 = stmt.returned_values("id").map_err(Error::DBQuery)?[0];
     let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
 
     let row = con
@@ -66,13 +168,21 @@ pub async fn create(db_pool: &DBPool, body: CreateInvoiceRequest) -> Result<Invo
     Ok(row_to_invoice(&row))
 }
 
-pub async fn delete(db_pool: &DBPool, id: u32) -> Result<()> {
+pub async fn delete(db_pool: &DBPool, id: u32, version: u32) -> Result<()> {
     let con = get_db_con(db_pool).await?;
-    let query = format!("DELETE FROM {} WHERE id = :id", TABLE);
+    let query = format!(
+        "DELETE FROM {} WHERE id = :id AND version = :version",
+        TABLE
+    );
 
-    con.execute_named(query.as_str(), &[("id", &id)])
+    let stmt = con
+        .execute_named(query.as_str(), &[("id", &id), ("version", &version)])
         .map_err(Error::DBQuery)?;
 
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("invoice", id));
+    }
+
     if let Err(e) = con.commit() {
         con.rollback().map_err(Error::DBQuery)?;
         return Err(Error::DBQuery(e));
@@ -96,3 +206,39 @@ pub async fn fetch_payments(db_pool: &DBPool, id: u32) -> Result<Vec<Payment>> {
         .map(|r| row_to_payment(&r.unwrap()))
         .collect())
 }
+
+pub async fn fetch_lines(db_pool: &DBPool, id: u32) -> Result<Vec<InvoiceLine>> {
+    use crate::db::invoice_line::{SELECT_FIELDS, TABLE};
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "SELECT {} FROM {} WHERE invoice_id = :id ORDER BY id",
+        SELECT_FIELDS, TABLE
+    );
+
+    let rows = con
+        .query_named(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(rows
+        .filter(|r| r.is_ok())
+        .map(|r| row_to_invoice_line(&r.unwrap()))
+        .collect())
+}
+
+pub async fn fetch_billed_penalty(db_pool: &DBPool, id: u32) -> Result<f64> {
+    use crate::db::invoice_line::TABLE;
+
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "SELECT NVL(SUM(amount), 0) FROM {} WHERE source_invoice_id = :id AND line_type = :line_type",
+        TABLE
+    );
+    let line_type: String = InvoiceLineType::Penalty.into();
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &id), ("line_type", &line_type)])
+        .map_err(Error::DBQuery)?;
+
+    row.get(0).map_err(Error::DBQuery)
+}
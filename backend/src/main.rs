@@ -1,3 +1,7 @@
+// the routes of all the resources chained with `or` nest deeper than the default limit
+#![recursion_limit = "256"]
+
+use config::CONFIG;
 use r2d2_oracle::{r2d2, OracleConnectionManager};
 use std::convert::Infallible;
 use warp::{
@@ -5,14 +9,32 @@ use warp::{
     Filter, Rejection,
 };
 
+mod addon;
+mod auth;
+mod bulk;
+mod bundle;
 mod config;
+mod constraint;
 mod contract;
+mod credit;
 mod customer;
 mod db;
 mod error;
+mod etag;
+mod export;
+mod field;
+mod idempotency;
+mod import;
 mod invoice;
+mod openapi;
+mod page;
+mod patch;
 mod payment;
+mod promotion;
 mod subscription;
+mod tag;
+mod user;
+mod version;
 
 type Result<T> = std::result::Result<T, Rejection>;
 type DBCon = r2d2::PooledConnection<OracleConnectionManager>;
@@ -28,33 +50,82 @@ async fn main() {
     //     .await
     //     .expect("database can be initialized");
 
+    if let Err(e) = auth::repository::bootstrap(&db_pool).await {
+        log::error!("error creating the administrator: {}", e);
+    }
+
     let customer_routes = customer::get_routes(db_pool.clone());
     let subscription_routes = subscription::get_routes(db_pool.clone());
     let contract_routes = contract::get_routes(db_pool.clone());
     let invoice_routes = invoice::get_routes(db_pool.clone());
     let payment_routes = payment::get_routes(db_pool.clone());
+    let promotion_routes = promotion::get_routes(db_pool.clone());
+    let bundle_routes = bundle::get_routes(db_pool.clone());
+    let addon_routes = addon::get_routes(db_pool.clone());
+    let tag_routes = tag::get_routes(db_pool.clone());
+    let field_routes = field::get_routes(db_pool.clone());
+    let credit_routes = credit::get_routes(db_pool.clone());
+    let openapi_routes = openapi::get_routes();
+    let auth_routes = auth::get_routes(db_pool.clone());
+    let user_routes = user::get_routes(db_pool.clone());
 
-    let routes = customer_routes
+    // all the resources are reserved to signed in users, the users to administrators
+    let resources = customer_routes
         .or(subscription_routes)
         .or(contract_routes)
         .or(invoice_routes)
         .or(payment_routes)
-        .recover(error::handle_rejection)
-        .with(
-            warp::cors()
-                .allow_credentials(true)
-                .allow_methods(&[
-                    Method::OPTIONS,
-                    Method::GET,
-                    Method::POST,
-                    Method::DELETE,
-                    Method::PUT,
-                ])
-                .allow_headers(vec![header::CONTENT_TYPE, header::ACCEPT])
-                .expose_headers(vec![header::LINK])
-                .max_age(300)
-                .allow_any_origin(),
-        );
+        .or(promotion_routes)
+        .or(bundle_routes)
+        .or(addon_routes)
+        .or(tag_routes)
+        .or(field_routes)
+        .or(credit_routes);
+    let api = auth_routes
+        .or(openapi_routes)
+        .or(user_routes)
+        .or(auth::signed_in(db_pool.clone()).and(resources));
+
+    // the unversioned paths are an alias of the current version
+    let v1 = warp::path("v1")
+        .and(api.clone())
+        .map(|reply| CONFIG.v1_deprecation().announce(reply));
+    let unversioned = api.map(|reply| CONFIG.unversioned_deprecation().announce(reply));
+
+    let routes = warp::path("api")
+        .and(v1.or(unversioned))
+        .recover(error::handle_rejection);
+
+    let routes = idempotency::idempotent(db_pool.clone(), routes).with(
+        warp::cors()
+            .allow_methods(&[
+                Method::OPTIONS,
+                Method::GET,
+                Method::POST,
+                Method::DELETE,
+                Method::PUT,
+                Method::PATCH,
+            ])
+            .allow_headers(vec![
+                header::CONTENT_TYPE,
+                header::ACCEPT,
+                header::AUTHORIZATION,
+                header::IF_MATCH,
+                header::HeaderName::from_static(idempotency::IDEMPOTENCY_KEY),
+            ])
+            .expose_headers(vec![
+                header::CONTENT_DISPOSITION,
+                header::ETAG,
+                header::LINK,
+                header::HeaderName::from_static(error::CORRELATION_ID),
+                header::HeaderName::from_static(page::TOTAL_COUNT),
+                header::HeaderName::from_static(version::DEPRECATION),
+                header::HeaderName::from_static(version::SUNSET),
+                header::HeaderName::from_static(idempotency::IDEMPOTENT_REPLAYED),
+            ])
+            .max_age(300)
+            .allow_origins(CONFIG.frontend_origins.iter().map(String::as_str)),
+    );
 
     log::info!("Listening on port :{}", 8000);
 
@@ -0,0 +1,54 @@
+use crate::openapi::spec;
+use crate::Result;
+use lazy_static::lazy_static;
+use serde_json::Value;
+use warp::reply::{html, json};
+use warp::Reply;
+
+const SWAGGER_UI_HTML: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="utf-8" />
+    <title>ISP Manager API</title>
+    <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css" />
+</head>
+<body>
+    <div id="swagger-ui"></div>
+    <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+    <script>
+        window.ui = SwaggerUIBundle({ url: "/api/v1/openapi.json", dom_id: "#swagger-ui" });
+    </script>
+</body>
+</html>
+"##;
+
+const REDOC_HTML: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="utf-8" />
+    <title>ISP Manager API</title>
+</head>
+<body>
+    <redoc spec-url="/api/v1/openapi.json"></redoc>
+    <script src="https://unpkg.com/redoc@2/bundles/redoc.standalone.js"></script>
+</body>
+</html>
+"##;
+
+lazy_static! {
+    static ref DOCUMENT: Value = spec::document();
+}
+
+pub async fn openapi_handler() -> Result<impl Reply> {
+    log::info!("Fetching the OpenAPI document");
+
+    Ok(json(&*DOCUMENT))
+}
+
+pub async fn swagger_ui_handler() -> Result<impl Reply> {
+    Ok(html(SWAGGER_UI_HTML))
+}
+
+pub async fn redoc_handler() -> Result<impl Reply> {
+    Ok(html(REDOC_HTML))
+}
@@ -0,0 +1,16 @@
+use warp::Filter;
+
+pub mod handler;
+pub mod spec;
+
+pub fn get_routes() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let openapi = warp::path!("openapi.json");
+    let docs = warp::path!("docs");
+    let redoc = warp::path!("redoc");
+
+    openapi
+        .and(warp::get())
+        .and_then(handler::openapi_handler)
+        .or(docs.and(warp::get()).and_then(handler::swagger_ui_handler))
+        .or(redoc.and(warp::get()).and_then(handler::redoc_handler))
+}
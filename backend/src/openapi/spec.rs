@@ -0,0 +1,1130 @@
+use crate::patch::MERGE_PATCH_JSON;
+use common::addon::{AddonRequest, AddonResponse, ContractAddonRequest, ContractAddonResponse};
+use common::address::{AddressRequest, AddressResponse};
+use common::bulk::{BulkQuery, BulkResponse, BulkUpdate};
+use common::bundle::{BundleRequest, BundleResponse};
+use common::contact::{ContactRequest, ContactResponse};
+use common::contract::{
+    ContractListQuery, ContractResponse, CreateContractQuery, CreateContractRequest,
+    UpdateContractRequest,
+};
+use common::credit::{CreditAssessmentResponse, CreditCheckQuery, DepositResponse};
+use common::customer::{
+    CreateCustomerQuery, CustomerListQuery, CustomerRequest, CustomerResponse, CustomerSearchQuery,
+    CustomerSearchResponse,
+};
+use common::error::{Problem, PROBLEM_JSON};
+use common::export::{ExportFormat, ExportQuery};
+use common::field::{
+    CustomFieldQuery, CustomFieldRequest, CustomFieldResponse, CustomFieldValueResponse,
+    CustomFieldValues, UpdateCustomFieldRequest,
+};
+use common::import::{ImportQuery, ImportResponse, FILE_PART, MAPPING_PART};
+use common::invoice::{
+    CreateInvoiceRequest, InvoiceLineResponse, InvoiceListQuery, InvoiceResponse,
+};
+use common::merge::{CustomerDuplicateResponse, CustomerMergeRequest, CustomerMergeResponse};
+use common::note::{NoteRequest, NoteResponse};
+use common::page::PageQuery;
+use common::payment::{CreatePaymentRequest, PaymentListQuery, PaymentResponse};
+use common::penalty::{PenaltyQuery, PenaltyResponse};
+use common::privacy::{AnonymizationResponse, CustomerExport};
+use common::promotion::{PromotionRequest, PromotionResponse};
+use common::subscription::{SubscriptionListQuery, SubscriptionRequest, SubscriptionResponse};
+use common::tag::{TagAssignmentRequest, TagRequest, TagResponse};
+use common::user::{
+    CreateUserRequest, LoginRequest, LoginResponse, UpdateUserRequest, UserResponse,
+};
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::JsonSchema;
+use serde_json::{json, Map, Value};
+
+/// OpenAPI 3 document being assembled, the schemas of the `common` types it refers
+/// to being collected under `components`.
+struct Spec {
+    generator: SchemaGenerator,
+    paths: Map<String, Value>,
+}
+
+/// Operation of the document, added to it once fully described.
+struct Operation<'a> {
+    spec: &'a mut Spec,
+    method: &'static str,
+    path: &'static str,
+    summary: &'static str,
+    parameters: Vec<Value>,
+    request_body: Option<Value>,
+    responses: Map<String, Value>,
+    public: bool,
+}
+
+impl Spec {
+    fn new() -> Self {
+        Spec {
+            generator: SchemaGenerator::new(SchemaSettings::openapi3()),
+            paths: Map::new(),
+        }
+    }
+
+    fn operation(
+        &mut self,
+        method: &'static str,
+        path: &'static str,
+        summary: &'static str,
+    ) -> Operation<'_> {
+        Operation {
+            spec: self,
+            method,
+            path,
+            summary,
+            parameters: vec![],
+            request_body: None,
+            responses: Map::new(),
+            public: false,
+        }
+    }
+
+    fn get(&mut self, path: &'static str, summary: &'static str) -> Operation<'_> {
+        self.operation("get", path, summary)
+    }
+
+    fn post(&mut self, path: &'static str, summary: &'static str) -> Operation<'_> {
+        self.operation("post", path, summary).idempotent()
+    }
+
+    fn put(&mut self, path: &'static str, summary: &'static str) -> Operation<'_> {
+        self.operation("put", path, summary)
+    }
+
+    fn patch(&mut self, path: &'static str, summary: &'static str) -> Operation<'_> {
+        self.operation("patch", path, summary)
+    }
+
+    fn delete(&mut self, path: &'static str, summary: &'static str) -> Operation<'_> {
+        self.operation("delete", path, summary)
+    }
+
+    fn schema<T: JsonSchema>(&mut self) -> Value {
+        serde_json::to_value(self.generator.subschema_for::<T>()).unwrap_or_default()
+    }
+
+    fn into_document(mut self) -> Value {
+        let error = self.schema::<Problem>();
+        for operations in self.paths.values_mut() {
+            for operation in operations
+                .as_object_mut()
+                .into_iter()
+                .flat_map(|o| o.values_mut())
+            {
+                operation["responses"]["default"] = json!({
+                    "description": "Error, with the problem details of RFC 7807",
+                    "content": { PROBLEM_JSON: { "schema": error } },
+                });
+            }
+        }
+
+        json!({
+            "openapi": "3.0.3",
+            "info": {
+                "title": "ISP Manager API",
+                "version": env!("CARGO_PKG_VERSION"),
+                "description": "Every path is also served without the `/v1` prefix, as an alias of \
+                    the v1 API. Deprecated versions announce it with the `Deprecation` and `Sunset` \
+                    headers of their responses. Requests are authenticated by the session token \
+                    returned by `/auth/login`, sent as `Authorization: Bearer <token>` or, for \
+                    downloads, as the `access_token` query parameter.",
+            },
+            "paths": self.paths,
+            "security": [{ "bearer": [] }],
+            "components": {
+                "schemas": self.generator.definitions(),
+                "securitySchemes": {
+                    "bearer": { "type": "http", "scheme": "bearer" },
+                },
+            },
+        })
+    }
+}
+
+impl<'a> Operation<'a> {
+    /// Query parameters of the fields of `T`.
+    fn query<T: JsonSchema>(mut self) -> Self {
+        let root = self.spec.generator.root_schema_for::<T>();
+
+        if let Some(object) = root.schema.object {
+            for (name, schema) in object.properties {
+                self.parameters.push(json!({
+                    "name": name,
+                    "in": "query",
+                    "required": object.required.contains(&name),
+                    "schema": schema,
+                }));
+            }
+        }
+
+        self
+    }
+
+    /// Pagination parameters and headers of a list endpoint.
+    fn paged(self) -> Self {
+        self.query::<PageQuery>()
+    }
+
+    /// Export of every record of a list endpoint, negotiated by the `format` query
+    /// parameter or the `Accept` header instead of the JSON pages.
+    fn exported(mut self) -> Self {
+        if let Some(content) = self
+            .responses
+            .get_mut("200")
+            .and_then(|response| response["content"].as_object_mut())
+        {
+            for format in ExportFormat::ALL {
+                if format != ExportFormat::Json {
+                    content.insert(
+                        format.content_type().to_string(),
+                        json!({ "schema": { "type": "string", "format": "binary" } }),
+                    );
+                }
+            }
+        }
+
+        self.query::<ExportQuery>()
+    }
+
+    fn body<T: JsonSchema>(mut self) -> Self {
+        let schema = self.spec.schema::<T>();
+        self.request_body = Some(json!({
+            "required": true,
+            "content": { "application/json": { "schema": schema } },
+        }));
+        self
+    }
+
+    /// JSON Merge Patch of `T`: any subset of its fields, `null` clearing optional ones.
+    fn merge_patch<T: JsonSchema>(mut self) -> Self {
+        let schema = self.spec.schema::<T>();
+        self.request_body = Some(json!({
+            "required": true,
+            "content": { MERGE_PATCH_JSON: { "schema": schema } },
+        }));
+        self
+    }
+
+    fn returns<T: JsonSchema>(mut self, status: u16) -> Self {
+        let schema = self.spec.schema::<T>();
+        let mut response = json!({
+            "description": "Success",
+            "content": { "application/json": { "schema": schema } },
+        });
+
+        if self.parameters.iter().any(|p| p["name"] == "per_page") {
+            response["headers"] = json!({
+                "X-Total-Count": {
+                    "description": "Number of records matching the filters",
+                    "schema": { "type": "integer" },
+                },
+                "Link": {
+                    "description": "RFC 5988 links to the first, previous, next and last pages",
+                    "schema": { "type": "string" },
+                },
+            });
+        }
+
+        self.responses.insert(status.to_string(), response);
+        self
+    }
+
+    fn returns_html(mut self) -> Self {
+        self.responses.insert(
+            "200".to_string(),
+            json!({
+                "description": "Success",
+                "content": { "text/html": { "schema": { "type": "string" } } },
+            }),
+        );
+        self
+    }
+
+    /// `ETag` header of the version of the record, on the success responses.
+    fn tagged(mut self) -> Self {
+        for (_, response) in self
+            .responses
+            .iter_mut()
+            .filter(|(s, _)| s.starts_with('2'))
+        {
+            response["headers"]["ETag"] = json!({
+                "description": "Version of the record, to send back in the If-Match header",
+                "schema": { "type": "string" },
+            });
+        }
+        self
+    }
+
+    /// `If-Match` header the version of the changed record must match.
+    fn conditional(mut self) -> Self {
+        self.parameters.push(json!({
+            "name": "If-Match",
+            "in": "header",
+            "required": true,
+            "description": "ETag of the record as fetched, or `*` to overwrite any changes",
+            "schema": { "type": "string" },
+        }));
+        self.responses.insert(
+            "412".to_string(),
+            json!({ "description": "The record has changed since it was fetched" }),
+        );
+        self.responses.insert(
+            "428".to_string(),
+            json!({ "description": "The If-Match header is missing" }),
+        );
+        self
+    }
+
+    /// `Idempotency-Key` header, accepted by every POST request.
+    fn idempotent(mut self) -> Self {
+        self.parameters.push(json!({
+            "name": "Idempotency-Key",
+            "in": "header",
+            "required": false,
+            "description": "Unique key of the request, whose response is replayed to its retries \
+                instead of executing it again",
+            "schema": { "type": "string", "minLength": 1, "maxLength": 255 },
+        }));
+        self.responses.insert(
+            "422".to_string(),
+            json!({ "description": "The Idempotency-Key was already used for a different request" }),
+        );
+        self
+    }
+
+    /// Array of the items of a bulk request, answered by the outcome of each item.
+    fn bulk<T: JsonSchema, R: JsonSchema>(mut self, applied: u16) -> Self {
+        let report = self.spec.schema::<BulkResponse<R>>();
+        let response = |description: &str| {
+            json!({
+                "description": description,
+                "content": { "application/json": { "schema": report } },
+            })
+        };
+
+        self.responses
+            .insert(applied.to_string(), response("Every item was applied"));
+        self.responses.insert(
+            "207".to_string(),
+            response("Only some items were applied, in best-effort mode"),
+        );
+        self.responses.insert(
+            "422".to_string(),
+            response("No item was applied since some failed, in transactional mode"),
+        );
+        self.query::<BulkQuery>().body::<Vec<T>>()
+    }
+
+    /// Multipart form of a file whose rows are imported as requests of type `T`,
+    /// answered by the outcome of each row.
+    fn import<T: JsonSchema, R: JsonSchema>(mut self) -> Self {
+        let fields = self.spec.schema::<T>();
+        let report = self.spec.schema::<ImportResponse<R>>();
+        let response = |description: &str| {
+            json!({
+                "description": description,
+                "content": { "application/json": { "schema": report } },
+            })
+        };
+
+        self.request_body = Some(json!({
+            "required": true,
+            "content": {
+                "multipart/form-data": {
+                    "schema": {
+                        "type": "object",
+                        "required": [FILE_PART],
+                        "properties": {
+                            FILE_PART: {
+                                "type": "string",
+                                "format": "binary",
+                                "description": "CSV or XLSX file, whose first row names its columns",
+                            },
+                            MAPPING_PART: {
+                                "type": "object",
+                                "additionalProperties": { "type": "string" },
+                                "description": "Field of each column, the columns being matched to the fields by name otherwise",
+                            },
+                        },
+                    },
+                },
+            },
+            "description": format!(
+                "Rows of the file, mapped to the fields of {}",
+                fields["$ref"].as_str().unwrap_or_default()
+            ),
+        }));
+        self.responses.insert(
+            "200".to_string(),
+            response("Every row is valid, or was imported without creating any record"),
+        );
+        self.responses
+            .insert("201".to_string(), response("Every row was imported"));
+        self.responses.insert(
+            "207".to_string(),
+            response("Only some rows were imported, in best-effort mode"),
+        );
+        self.responses.insert(
+            "422".to_string(),
+            response("Some rows failed, so that no row was imported in transactional mode"),
+        );
+        self.query::<ImportQuery>()
+    }
+
+    /// Served without authentication.
+    fn public(mut self) -> Self {
+        self.public = true;
+        self
+    }
+
+    fn no_content(mut self) -> Self {
+        self.responses
+            .insert("204".to_string(), json!({ "description": "No Content" }));
+        self
+    }
+
+    fn add(self) {
+        // `/api/v1/customer/{id}/note/{note_id}` is tagged `customer`
+        let tag = self.path.split('/').nth(3).unwrap_or_default();
+        let path_parameters = self
+            .path
+            .split('/')
+            .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+            .map(|name| {
+                json!({
+                    "name": name,
+                    "in": "path",
+                    "required": true,
+                    "schema": { "type": "integer", "format": "uint32", "minimum": 0 },
+                })
+            });
+
+        let mut operation = json!({
+            "tags": [tag],
+            "summary": self.summary,
+            "parameters": path_parameters.chain(self.parameters).collect::<Vec<_>>(),
+            "responses": self.responses,
+        });
+        if let Some(request_body) = self.request_body {
+            operation["requestBody"] = request_body;
+        }
+        if self.public {
+            operation["security"] = json!([]);
+        }
+
+        let operations = self
+            .spec
+            .paths
+            .entry(self.path)
+            .or_insert_with(|| json!({}));
+        operations[self.method] = operation;
+    }
+}
+
+/// OpenAPI document describing every route of the API.
+pub fn document() -> Value {
+    let mut spec = Spec::new();
+
+    spec.get("/api/v1/openapi.json", "OpenAPI document of the API")
+        .returns::<Value>(200)
+        .public()
+        .add();
+    spec.get("/api/v1/docs", "Swagger UI of the API")
+        .returns_html()
+        .public()
+        .add();
+    spec.get("/api/v1/redoc", "Redoc documentation of the API")
+        .returns_html()
+        .public()
+        .add();
+
+    auth(&mut spec);
+    user(&mut spec);
+
+    customer(&mut spec);
+    subscription(&mut spec);
+    contract(&mut spec);
+    invoice(&mut spec);
+    payment(&mut spec);
+    catalog(&mut spec);
+    segmentation(&mut spec);
+    credit(&mut spec);
+
+    spec.into_document()
+}
+
+fn customer(spec: &mut Spec) {
+    spec.get("/api/v1/customer", "List customers")
+        .query::<CustomerListQuery>()
+        .paged()
+        .returns::<Vec<CustomerResponse>>(200)
+        .exported()
+        .add();
+    spec.post("/api/v1/customer", "Create a customer")
+        .query::<CreateCustomerQuery>()
+        .body::<CustomerRequest>()
+        .returns::<CustomerResponse>(201)
+        .add();
+    spec.post("/api/v1/customer/bulk", "Create customers in bulk")
+        .query::<CreateCustomerQuery>()
+        .bulk::<CustomerRequest, CustomerResponse>(201)
+        .add();
+    spec.put("/api/v1/customer/bulk", "Update customers in bulk")
+        .bulk::<BulkUpdate<CustomerRequest>, CustomerResponse>(200)
+        .add();
+    spec.post(
+        "/api/v1/customer/import",
+        "Import customers from a CSV or XLSX file",
+    )
+    .query::<CreateCustomerQuery>()
+    .import::<CustomerRequest, CustomerResponse>()
+    .add();
+    spec.get("/api/v1/customer/search", "Search customers")
+        .query::<CustomerSearchQuery>()
+        .returns::<Vec<CustomerSearchResponse>>(200)
+        .add();
+    spec.get(
+        "/api/v1/customer/duplicates",
+        "List likely duplicate customers",
+    )
+    .returns::<Vec<CustomerDuplicateResponse>>(200)
+    .add();
+    spec.get("/api/v1/customer/{id}", "Fetch a customer")
+        .returns::<CustomerResponse>(200)
+        .tagged()
+        .add();
+    spec.put("/api/v1/customer/{id}", "Update a customer")
+        .body::<CustomerRequest>()
+        .returns::<CustomerResponse>(200)
+        .tagged()
+        .conditional()
+        .add();
+    spec.patch("/api/v1/customer/{id}", "Update some fields of a customer")
+        .merge_patch::<CustomerRequest>()
+        .returns::<CustomerResponse>(200)
+        .tagged()
+        .conditional()
+        .add();
+    spec.delete("/api/v1/customer/{id}", "Delete a customer")
+        .no_content()
+        .conditional()
+        .add();
+    spec.get(
+        "/api/v1/customer/{id}/invoice",
+        "List the unpaid invoices of a customer",
+    )
+    .returns::<Vec<InvoiceResponse>>(200)
+    .add();
+    spec.get(
+        "/api/v1/customer/{id}/contract",
+        "List the contracts of a customer",
+    )
+    .returns::<Vec<ContractResponse>>(200)
+    .add();
+    spec.get(
+        "/api/v1/customer/{id}/address",
+        "List the addresses of a customer",
+    )
+    .returns::<Vec<AddressResponse>>(200)
+    .add();
+    spec.post(
+        "/api/v1/customer/{id}/address",
+        "Add an address to a customer",
+    )
+    .body::<AddressRequest>()
+    .returns::<AddressResponse>(201)
+    .add();
+    spec.put(
+        "/api/v1/customer/{id}/address/{address_id}",
+        "Update an address of a customer",
+    )
+    .body::<AddressRequest>()
+    .returns::<AddressResponse>(200)
+    .tagged()
+    .conditional()
+    .add();
+    spec.delete(
+        "/api/v1/customer/{id}/address/{address_id}",
+        "Remove an address of a customer",
+    )
+    .no_content()
+    .conditional()
+    .add();
+    spec.get(
+        "/api/v1/customer/{id}/contact",
+        "List the contacts of a customer",
+    )
+    .returns::<Vec<ContactResponse>>(200)
+    .add();
+    spec.post(
+        "/api/v1/customer/{id}/contact",
+        "Add a contact to a customer",
+    )
+    .body::<ContactRequest>()
+    .returns::<ContactResponse>(201)
+    .add();
+    spec.put(
+        "/api/v1/customer/{id}/contact/{contact_id}",
+        "Update a contact of a customer",
+    )
+    .body::<ContactRequest>()
+    .returns::<ContactResponse>(200)
+    .tagged()
+    .conditional()
+    .add();
+    spec.delete(
+        "/api/v1/customer/{id}/contact/{contact_id}",
+        "Remove a contact of a customer",
+    )
+    .no_content()
+    .conditional()
+    .add();
+    spec.get(
+        "/api/v1/customer/{id}/merge",
+        "List the customers merged into a customer",
+    )
+    .returns::<Vec<CustomerMergeResponse>>(200)
+    .add();
+    spec.post(
+        "/api/v1/customer/{id}/merge",
+        "Merge a customer into another",
+    )
+    .body::<CustomerMergeRequest>()
+    .returns::<CustomerMergeResponse>(201)
+    .add();
+    spec.get(
+        "/api/v1/customer/{id}/export",
+        "Export the personal data of a customer",
+    )
+    .returns::<CustomerExport>(200)
+    .add();
+    spec.post("/api/v1/customer/{id}/anonymize", "Anonymize a customer")
+        .returns::<AnonymizationResponse>(200)
+        .add();
+    spec.get("/api/v1/customer/{id}/note", "List the notes of a customer")
+        .returns::<Vec<NoteResponse>>(200)
+        .add();
+    spec.post("/api/v1/customer/{id}/note", "Add a note to a customer")
+        .body::<NoteRequest>()
+        .returns::<NoteResponse>(201)
+        .add();
+    spec.put(
+        "/api/v1/customer/{id}/note/{note_id}",
+        "Update a note of a customer",
+    )
+    .body::<NoteRequest>()
+    .returns::<NoteResponse>(200)
+    .tagged()
+    .conditional()
+    .add();
+    spec.delete(
+        "/api/v1/customer/{id}/note/{note_id}",
+        "Remove a note of a customer",
+    )
+    .no_content()
+    .conditional()
+    .add();
+    spec.get("/api/v1/customer/{id}/tag", "List the tags of a customer")
+        .returns::<Vec<TagResponse>>(200)
+        .add();
+    spec.put(
+        "/api/v1/customer/{id}/tag",
+        "Replace the tags of a customer",
+    )
+    .body::<TagAssignmentRequest>()
+    .returns::<Vec<TagResponse>>(200)
+    .add();
+    spec.get(
+        "/api/v1/customer/{id}/field",
+        "List the custom field values of a customer",
+    )
+    .returns::<Vec<CustomFieldValueResponse>>(200)
+    .add();
+    spec.put(
+        "/api/v1/customer/{id}/field",
+        "Replace the custom field values of a customer",
+    )
+    .body::<CustomFieldValues>()
+    .returns::<Vec<CustomFieldValueResponse>>(200)
+    .add();
+}
+
+fn subscription(spec: &mut Spec) {
+    spec.get("/api/v1/subscription", "List subscriptions")
+        .query::<SubscriptionListQuery>()
+        .paged()
+        .returns::<Vec<SubscriptionResponse>>(200)
+        .exported()
+        .add();
+    spec.post("/api/v1/subscription", "Create a subscription")
+        .body::<SubscriptionRequest>()
+        .returns::<SubscriptionResponse>(201)
+        .add();
+    spec.post(
+        "/api/v1/subscription/import",
+        "Import subscriptions from a CSV or XLSX file",
+    )
+    .import::<SubscriptionRequest, SubscriptionResponse>()
+    .add();
+    spec.get("/api/v1/subscription/{id}", "Fetch a subscription")
+        .returns::<SubscriptionResponse>(200)
+        .tagged()
+        .add();
+    spec.put("/api/v1/subscription/{id}", "Update a subscription")
+        .body::<SubscriptionRequest>()
+        .returns::<SubscriptionResponse>(200)
+        .tagged()
+        .conditional()
+        .add();
+    spec.patch(
+        "/api/v1/subscription/{id}",
+        "Update some fields of a subscription",
+    )
+    .merge_patch::<SubscriptionRequest>()
+    .returns::<SubscriptionResponse>(200)
+    .tagged()
+    .conditional()
+    .add();
+    spec.delete("/api/v1/subscription/{id}", "Delete a subscription")
+        .no_content()
+        .conditional()
+        .add();
+}
+
+fn contract(spec: &mut Spec) {
+    spec.get("/api/v1/contract", "List contracts")
+        .query::<ContractListQuery>()
+        .paged()
+        .returns::<Vec<ContractResponse>>(200)
+        .exported()
+        .add();
+    spec.post("/api/v1/contract", "Create a contract")
+        .query::<CreateContractQuery>()
+        .body::<CreateContractRequest>()
+        .returns::<ContractResponse>(201)
+        .add();
+    spec.post("/api/v1/contract/bulk", "Create contracts in bulk")
+        .query::<CreateContractQuery>()
+        .bulk::<CreateContractRequest, ContractResponse>(201)
+        .add();
+    spec.put("/api/v1/contract/bulk", "Update contracts in bulk")
+        .bulk::<BulkUpdate<UpdateContractRequest>, ContractResponse>(200)
+        .add();
+    spec.post(
+        "/api/v1/contract/import",
+        "Import contracts from a CSV or XLSX file",
+    )
+    .query::<CreateContractQuery>()
+    .import::<CreateContractRequest, ContractResponse>()
+    .add();
+    spec.get("/api/v1/contract/{id}", "Fetch a contract")
+        .returns::<ContractResponse>(200)
+        .tagged()
+        .add();
+    spec.put("/api/v1/contract/{id}", "Update a contract")
+        .body::<UpdateContractRequest>()
+        .returns::<ContractResponse>(200)
+        .tagged()
+        .conditional()
+        .add();
+    spec.patch("/api/v1/contract/{id}", "Update some fields of a contract")
+        .merge_patch::<UpdateContractRequest>()
+        .returns::<ContractResponse>(200)
+        .tagged()
+        .conditional()
+        .add();
+    spec.delete("/api/v1/contract/{id}", "Delete a contract")
+        .no_content()
+        .conditional()
+        .add();
+    spec.get(
+        "/api/v1/contract/{id}/invoice",
+        "List the invoices of a contract",
+    )
+    .returns::<Vec<InvoiceResponse>>(200)
+    .add();
+    spec.get(
+        "/api/v1/contract/{id}/addon",
+        "List the add-ons of a contract",
+    )
+    .returns::<Vec<ContractAddonResponse>>(200)
+    .add();
+    spec.post(
+        "/api/v1/contract/{id}/addon",
+        "Attach an add-on to a contract",
+    )
+    .body::<ContractAddonRequest>()
+    .returns::<ContractAddonResponse>(201)
+    .add();
+    spec.delete(
+        "/api/v1/contract/{id}/addon/{contract_addon_id}",
+        "Detach an add-on from a contract",
+    )
+    .no_content()
+    .conditional()
+    .add();
+    spec.get("/api/v1/contract/{id}/tag", "List the tags of a contract")
+        .returns::<Vec<TagResponse>>(200)
+        .add();
+    spec.put(
+        "/api/v1/contract/{id}/tag",
+        "Replace the tags of a contract",
+    )
+    .body::<TagAssignmentRequest>()
+    .returns::<Vec<TagResponse>>(200)
+    .add();
+    spec.get(
+        "/api/v1/contract/{id}/field",
+        "List the custom field values of a contract",
+    )
+    .returns::<Vec<CustomFieldValueResponse>>(200)
+    .add();
+    spec.put(
+        "/api/v1/contract/{id}/field",
+        "Replace the custom field values of a contract",
+    )
+    .body::<CustomFieldValues>()
+    .returns::<Vec<CustomFieldValueResponse>>(200)
+    .add();
+}
+
+fn invoice(spec: &mut Spec) {
+    spec.get("/api/v1/invoice", "List invoices")
+        .query::<InvoiceListQuery>()
+        .paged()
+        .returns::<Vec<InvoiceResponse>>(200)
+        .exported()
+        .add();
+    spec.post("/api/v1/invoice", "Issue an invoice")
+        .body::<CreateInvoiceRequest>()
+        .returns::<InvoiceResponse>(201)
+        .add();
+    spec.get("/api/v1/invoice/{id}", "Fetch an invoice")
+        .returns::<InvoiceResponse>(200)
+        .tagged()
+        .add();
+    spec.delete("/api/v1/invoice/{id}", "Delete an invoice")
+        .no_content()
+        .conditional()
+        .add();
+    spec.get(
+        "/api/v1/invoice/{id}/payment",
+        "List the payments of an invoice",
+    )
+    .returns::<Vec<PaymentResponse>>(200)
+    .add();
+    spec.get("/api/v1/invoice/{id}/line", "List the lines of an invoice")
+        .returns::<Vec<InvoiceLineResponse>>(200)
+        .add();
+    spec.get(
+        "/api/v1/invoice/{id}/penalty",
+        "Compute the late payment penalty of an invoice",
+    )
+    .query::<PenaltyQuery>()
+    .returns::<PenaltyResponse>(200)
+    .add();
+}
+
+fn payment(spec: &mut Spec) {
+    spec.get("/api/v1/payment", "List payments")
+        .query::<PaymentListQuery>()
+        .paged()
+        .returns::<Vec<PaymentResponse>>(200)
+        .exported()
+        .add();
+    spec.post("/api/v1/payment", "Record a payment")
+        .body::<CreatePaymentRequest>()
+        .returns::<PaymentResponse>(201)
+        .add();
+    spec.post("/api/v1/payment/bulk", "Record payments in bulk")
+        .bulk::<CreatePaymentRequest, PaymentResponse>(201)
+        .add();
+    spec.get("/api/v1/payment/{id}", "Fetch a payment")
+        .returns::<PaymentResponse>(200)
+        .tagged()
+        .add();
+}
+
+/// Promotions, bundles and add-ons sold along with the subscriptions.
+fn catalog(spec: &mut Spec) {
+    spec.get("/api/v1/promotion", "List promotions")
+        .returns::<Vec<PromotionResponse>>(200)
+        .add();
+    spec.post("/api/v1/promotion", "Create a promotion")
+        .body::<PromotionRequest>()
+        .returns::<PromotionResponse>(201)
+        .add();
+    spec.get("/api/v1/promotion/{id}", "Fetch a promotion")
+        .returns::<PromotionResponse>(200)
+        .tagged()
+        .add();
+    spec.put("/api/v1/promotion/{id}", "Update a promotion")
+        .body::<PromotionRequest>()
+        .returns::<PromotionResponse>(200)
+        .tagged()
+        .conditional()
+        .add();
+    spec.delete("/api/v1/promotion/{id}", "Delete a promotion")
+        .no_content()
+        .conditional()
+        .add();
+
+    spec.get("/api/v1/bundle", "List bundles")
+        .returns::<Vec<BundleResponse>>(200)
+        .add();
+    spec.post("/api/v1/bundle", "Create a bundle")
+        .body::<BundleRequest>()
+        .returns::<BundleResponse>(201)
+        .add();
+    spec.get("/api/v1/bundle/{id}", "Fetch a bundle")
+        .returns::<BundleResponse>(200)
+        .tagged()
+        .add();
+    spec.put("/api/v1/bundle/{id}", "Update a bundle")
+        .body::<BundleRequest>()
+        .returns::<BundleResponse>(200)
+        .tagged()
+        .conditional()
+        .add();
+    spec.delete("/api/v1/bundle/{id}", "Delete a bundle")
+        .no_content()
+        .conditional()
+        .add();
+    spec.get(
+        "/api/v1/bundle/{id}/subscription",
+        "List the subscriptions of a bundle",
+    )
+    .returns::<Vec<SubscriptionResponse>>(200)
+    .add();
+
+    spec.get("/api/v1/addon", "List add-ons")
+        .returns::<Vec<AddonResponse>>(200)
+        .add();
+    spec.post("/api/v1/addon", "Create an add-on")
+        .body::<AddonRequest>()
+        .returns::<AddonResponse>(201)
+        .add();
+    spec.get("/api/v1/addon/{id}", "Fetch an add-on")
+        .returns::<AddonResponse>(200)
+        .tagged()
+        .add();
+    spec.put("/api/v1/addon/{id}", "Update an add-on")
+        .body::<AddonRequest>()
+        .returns::<AddonResponse>(200)
+        .tagged()
+        .conditional()
+        .add();
+    spec.delete("/api/v1/addon/{id}", "Delete an add-on")
+        .no_content()
+        .conditional()
+        .add();
+}
+
+/// Tags and custom fields of customers and contracts.
+fn segmentation(spec: &mut Spec) {
+    spec.get("/api/v1/tag", "List tags")
+        .returns::<Vec<TagResponse>>(200)
+        .add();
+    spec.post("/api/v1/tag", "Create a tag")
+        .body::<TagRequest>()
+        .returns::<TagResponse>(201)
+        .add();
+    spec.get("/api/v1/tag/{id}", "Fetch a tag")
+        .returns::<TagResponse>(200)
+        .tagged()
+        .add();
+    spec.put("/api/v1/tag/{id}", "Update a tag")
+        .body::<TagRequest>()
+        .returns::<TagResponse>(200)
+        .tagged()
+        .conditional()
+        .add();
+    spec.delete("/api/v1/tag/{id}", "Delete a tag")
+        .no_content()
+        .conditional()
+        .add();
+
+    spec.get("/api/v1/field", "List custom fields")
+        .query::<CustomFieldQuery>()
+        .returns::<Vec<CustomFieldResponse>>(200)
+        .add();
+    spec.post("/api/v1/field", "Create a custom field")
+        .body::<CustomFieldRequest>()
+        .returns::<CustomFieldResponse>(201)
+        .add();
+    spec.get("/api/v1/field/{id}", "Fetch a custom field")
+        .returns::<CustomFieldResponse>(200)
+        .tagged()
+        .add();
+    spec.put("/api/v1/field/{id}", "Update a custom field")
+        .body::<UpdateCustomFieldRequest>()
+        .returns::<CustomFieldResponse>(200)
+        .tagged()
+        .conditional()
+        .add();
+    spec.delete("/api/v1/field/{id}", "Delete a custom field")
+        .no_content()
+        .conditional()
+        .add();
+}
+
+/// Sessions of the users signing in.
+fn auth(spec: &mut Spec) {
+    // the responses opening sessions are never stored for idempotency
+    spec.operation("post", "/api/v1/auth/login", "Sign in, opening a session")
+        .body::<LoginRequest>()
+        .returns::<LoginResponse>(200)
+        .public()
+        .add();
+    spec.post("/api/v1/auth/logout", "Sign out, closing the session")
+        .no_content()
+        .add();
+    spec.get("/api/v1/auth/me", "Fetch the signed in user")
+        .returns::<UserResponse>(200)
+        .add();
+}
+
+/// Users of the API, managed by administrators.
+fn user(spec: &mut Spec) {
+    spec.get("/api/v1/user", "List users")
+        .returns::<Vec<UserResponse>>(200)
+        .add();
+    spec.post("/api/v1/user", "Create a user")
+        .body::<CreateUserRequest>()
+        .returns::<UserResponse>(201)
+        .add();
+    spec.get("/api/v1/user/{id}", "Fetch a user")
+        .returns::<UserResponse>(200)
+        .tagged()
+        .add();
+    spec.put("/api/v1/user/{id}", "Update a user")
+        .body::<UpdateUserRequest>()
+        .returns::<UserResponse>(200)
+        .tagged()
+        .conditional()
+        .add();
+    spec.delete("/api/v1/user/{id}", "Delete a user")
+        .no_content()
+        .conditional()
+        .add();
+}
+
+/// Credit checks of customers and security deposits of contracts.
+fn credit(spec: &mut Spec) {
+    spec.get(
+        "/api/v1/customer/{id}/credit",
+        "Run the credit check of a customer",
+    )
+    .query::<CreditCheckQuery>()
+    .returns::<CreditAssessmentResponse>(200)
+    .add();
+    spec.get(
+        "/api/v1/contract/{id}/deposit",
+        "Fetch the security deposit of a contract",
+    )
+    .returns::<DepositResponse>(200)
+    .add();
+    spec.post(
+        "/api/v1/contract/{id}/deposit/pay",
+        "Mark the security deposit of a contract paid",
+    )
+    .returns::<DepositResponse>(200)
+    .add();
+    spec.post(
+        "/api/v1/contract/{id}/deposit/refund",
+        "Refund the security deposit of a contract",
+    )
+    .returns::<DepositResponse>(200)
+    .add();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::document;
+    use regex::Regex;
+    use std::collections::BTreeSet;
+    use std::fs;
+
+    /// Method and v1 path of every route declared by the `get_routes` of the
+    /// resources, path parameters being written `{}`.
+    fn declared_routes() -> BTreeSet<(String, String)> {
+        let path_re = Regex::new(r"let (\w+) = warp::path!\((.*?)\);").unwrap();
+        let route_re =
+            Regex::new(r"(\w+)\s*\.and\(warp::(get|post|put|delete|patch)\(\)\)").unwrap();
+        let mut routes = BTreeSet::new();
+
+        let src = concat!(env!("CARGO_MANIFEST_DIR"), "/src");
+        for entry in fs::read_dir(src).unwrap() {
+            let Ok(source) = fs::read_to_string(entry.unwrap().path().join("mod.rs")) else {
+                continue;
+            };
+
+            let paths: Vec<(String, String)> = path_re
+                .captures_iter(&source)
+                .map(|c| {
+                    let path = c[2]
+                        .split(" / ")
+                        .map(|segment| match segment.trim_matches('"') {
+                            "u32" => "{}",
+                            segment => segment,
+                        })
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    (c[1].to_string(), format!("/api/v1/{}", path))
+                })
+                .collect();
+
+            for c in route_re.captures_iter(&source) {
+                let (_, path) = paths
+                    .iter()
+                    .find(|(name, _)| *name == c[1])
+                    .unwrap_or_else(|| panic!("path {} is not declared", &c[1]));
+                routes.insert((c[2].to_string(), path.clone()));
+            }
+        }
+
+        routes
+    }
+
+    fn described_routes() -> BTreeSet<(String, String)> {
+        let param_re = Regex::new(r"\{\w+\}").unwrap();
+        let document = document();
+
+        document["paths"]
+            .as_object()
+            .unwrap()
+            .iter()
+            .flat_map(|(path, operations)| {
+                let path = param_re.replace_all(path, "{}").to_string();
+                operations
+                    .as_object()
+                    .unwrap()
+                    .keys()
+                    .map(move |method| (method.clone(), path.clone()))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn every_route_is_described() {
+        let declared = declared_routes();
+        let described = described_routes();
+
+        assert!(!declared.is_empty());
+        assert_eq!(
+            declared.difference(&described).collect::<Vec<_>>(),
+            Vec::<&(String, String)>::new(),
+            "routes missing from the OpenAPI document"
+        );
+        assert_eq!(
+            described.difference(&declared).collect::<Vec<_>>(),
+            Vec::<&(String, String)>::new(),
+            "OpenAPI operations without a route"
+        );
+    }
+}
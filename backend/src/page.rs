@@ -0,0 +1,208 @@
+use crate::db::Result;
+use crate::error::application::Error;
+use crate::DBCon;
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use common::page::PageQuery;
+use oracle::sql_type::ToSql;
+use oracle::Row;
+use serde::Serialize;
+use validator::Validate;
+use warp::http::header::LINK;
+use warp::Reply;
+
+pub const TOTAL_COUNT: &str = "x-total-count";
+
+/// Table a list endpoint pages through, along with the query parameters it can be
+/// sorted by and the columns they sort on.
+pub struct Listing {
+    pub table: &'static str,
+    pub fields: &'static str,
+    pub sort_columns: &'static [(&'static str, &'static str)],
+}
+
+/// Records of one page of a list, out of the `total` matching its filters.
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: u32,
+    /// Id of the last record, the cursor of the next page.
+    pub last_id: Option<u32>,
+}
+
+impl<T> Page<T> {
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            total: self.total,
+            last_id: self.last_id,
+        }
+    }
+}
+
+/// Bounds of a date range with an inclusive `to`, as compared against timestamps.
+pub fn date_range(
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+    let day_start = |date: NaiveDate| date.and_hms_opt(0, 0, 0).unwrap().and_utc();
+
+    (
+        from.map(day_start),
+        to.and_then(|to| to.checked_add_days(Days::new(1)))
+            .map(day_start),
+    )
+}
+
+/// Selects the page of the records of the listing matching `condition`, which
+/// may only refer to the named `params`.
+pub fn fetch<T>(
+    con: &DBCon,
+    listing: &Listing,
+    condition: &str,
+    params: &[(&str, &dyn ToSql)],
+    page: &PageQuery,
+    row_to: fn(&Row) -> T,
+) -> Result<Page<T>> {
+    let (order_by, descending) = order_by(listing, page)?;
+    let cursor = match page.after {
+        Some(after) if descending => format!("id < {}", after),
+        Some(after) => format!("id > {}", after),
+        None => "1 = 1".to_string(),
+    };
+
+    let query = format!("SELECT COUNT(*) FROM {} WHERE {}", listing.table, condition);
+    let total: u32 = con
+        .query_row_named(query.as_str(), params)
+        .and_then(|row| row.get(0))
+        .map_err(Error::DBQuery)?;
+
+    let query = format!(
+        "SELECT {} FROM {} WHERE {} AND {} ORDER BY {} OFFSET {} ROWS FETCH NEXT {} ROWS ONLY",
+        listing.fields,
+        listing.table,
+        condition,
+        cursor,
+        order_by,
+        page.offset(),
+        page.per_page()
+    );
+
+    let rows: Vec<Row> = con
+        .query_named(query.as_str(), params)
+        .map_err(Error::DBQuery)?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(Page {
+        last_id: rows.last().and_then(|row| row.get("id").ok()),
+        items: rows.iter().map(row_to).collect(),
+        total,
+    })
+}
+
+/// Passes every record of the listing matching `condition` to `each` as it is
+/// read, sorted as the pages would be, to export the whole list.
+pub fn for_each<T>(
+    con: &DBCon,
+    listing: &Listing,
+    condition: &str,
+    params: &[(&str, &dyn ToSql)],
+    page: &PageQuery,
+    row_to: fn(&Row) -> T,
+    each: &mut dyn FnMut(T) -> Result<()>,
+) -> Result<()> {
+    let (order_by, _) = order_by(listing, page)?;
+    let query = format!(
+        "SELECT {} FROM {} WHERE {} ORDER BY {}",
+        listing.fields, listing.table, condition, order_by
+    );
+
+    for row in con
+        .query_named(query.as_str(), params)
+        .map_err(Error::DBQuery)?
+    {
+        let row = row.map_err(Error::DBQuery)?;
+        each(row_to(&row))?;
+    }
+
+    Ok(())
+}
+
+/// `ORDER BY` clause of the sort of the page, along with whether it is descending.
+fn order_by(listing: &Listing, page: &PageQuery) -> Result<(String, bool)> {
+    page.validate().map_err(Error::Validation)?;
+
+    let (field, descending) = page.sort().unwrap_or(("id", false));
+    let column = listing
+        .sort_columns
+        .iter()
+        .find(|(name, _)| *name == field)
+        .map(|(_, column)| *column)
+        .ok_or_else(|| Error::InvalidSort(field.to_string()))?;
+
+    let direction = if descending { "DESC" } else { "ASC" };
+    // ties are broken by id so that consecutive pages never overlap
+    let order_by = if column == "id" {
+        format!("id {}", direction)
+    } else {
+        format!("{} {}, id {}", column, direction, direction)
+    };
+
+    Ok((order_by, descending))
+}
+
+/// Replies with the records of the page, their total count and the RFC 5988 links
+/// to the neighbouring pages of the list at `path`, keeping its filters.
+pub fn reply<T: Serialize, F: Serialize>(
+    path: &str,
+    filter: &F,
+    query: &PageQuery,
+    page: Page<T>,
+) -> impl Reply {
+    let filter = serde_urlencoded::to_string(filter).unwrap_or_default();
+    let mut params = vec![("per_page", query.per_page().to_string())];
+    if let Some(sort) = &query.sort {
+        params.push(("sort", sort.clone()));
+    }
+
+    let link = |rel: &str, position: Option<(&'static str, u32)>| {
+        let mut params = params.clone();
+        if let Some((name, value)) = position {
+            params.push((name, value.to_string()));
+        }
+
+        let params = serde_urlencoded::to_string(&params).unwrap_or_default();
+        let query_string = [filter.as_str(), params.as_str()]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("&");
+
+        format!("<{}?{}>; rel=\"{}\"", path, query_string, rel)
+    };
+
+    let mut links = vec![];
+    if query.after.is_some() {
+        links.push(link("first", None));
+        if page.items.len() as u32 == query.per_page() {
+            if let Some(last_id) = page.last_id {
+                links.push(link("next", Some(("after", last_id))));
+            }
+        }
+    } else {
+        let current = query.page();
+        let last = query.last_page(page.total);
+
+        links.push(link("first", Some(("page", 1))));
+        if current > 1 {
+            links.push(link("prev", Some(("page", (current - 1).min(last)))));
+        }
+        if current < last {
+            links.push(link("next", Some(("page", current + 1))));
+        }
+        links.push(link("last", Some(("page", last))));
+    }
+
+    let reply = warp::reply::json(&page.items);
+    let reply = warp::reply::with_header(reply, TOTAL_COUNT, page.total.to_string());
+    warp::reply::with_header(reply, LINK, links.join(", "))
+}
@@ -0,0 +1,51 @@
+use crate::error::application::Error;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use validator::Validate;
+use warp::Buf;
+
+/// Media type of JSON Merge Patch (RFC 7386) request bodies.
+pub const MERGE_PATCH_JSON: &str = "application/merge-patch+json";
+
+/// Applies the JSON merge patch in `buf` to the `current` state of a resource,
+/// validating only the fields the patch supplies: rules already broken by the
+/// stored record are not reported against a patch that leaves them alone.
+pub fn apply<T>(current: &T, buf: impl Buf) -> Result<T, Error>
+where
+    T: Serialize + DeserializeOwned + Validate,
+{
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let patch: Value = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| Error::JSONPath(e.to_string()))?;
+
+    let Value::Object(fields) = &patch else {
+        return Err(Error::JSONPath(
+            "a merge patch must be a JSON object".to_string(),
+        ));
+    };
+
+    let mut merged = serde_json::to_value(current).map_err(|e| Error::JSONPath(e.to_string()))?;
+    common::patch::apply(&mut merged, &patch);
+    let patched: T =
+        serde_path_to_error::deserialize(merged).map_err(|e| Error::JSONPath(e.to_string()))?;
+
+    let Err(mut errors) = patched.validate() else {
+        return Ok(patched);
+    };
+
+    let existing = current.validate().err().unwrap_or_default();
+    errors.errors_mut().retain(|field, kind| {
+        if *field != "__all__" {
+            return fields.contains_key(*field);
+        }
+
+        existing.errors().get(field) != Some(kind)
+    });
+
+    if errors.is_empty() {
+        Ok(patched)
+    } else {
+        Err(Error::Validation(errors))
+    }
+}
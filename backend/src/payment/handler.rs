@@ -1,18 +1,42 @@
+use crate::bulk::{self, Bulk};
 use crate::error::application::Error;
 use crate::payment::repository;
-use crate::{invoice, DBPool, Result};
-use common::payment::{CreatePaymentRequest, PaymentResponse};
+use crate::{etag, export, invoice, page, DBPool, Result};
+use common::bulk::{BulkItemStatus, BulkQuery};
+use common::export::ExportFormat;
+use common::page::PageQuery;
+use common::payment::{CreatePaymentRequest, PaymentListQuery, PaymentResponse};
 use validator::Validate;
 use warp::reply::json;
 use warp::{reject, Buf, Reply};
 
-pub async fn list_payments_handler(db_pool: DBPool) -> Result<impl Reply> {
+pub async fn list_payments_handler(
+    query: PaymentListQuery,
+    page: PageQuery,
+    format: ExportFormat,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
     log::info!("Listing payments");
 
-    let payments = repository::fetch(&db_pool).await.map_err(reject::custom)?;
-    Ok(json::<Vec<_>>(
-        &payments.into_iter().map(PaymentResponse::from).collect(),
-    ))
+    if format != ExportFormat::Json {
+        return export::reply(&db_pool, "payments", format, move |con, each| {
+            repository::export(con, &query, &page, &mut |payment| {
+                each(PaymentResponse::from(payment))
+            })
+        })
+        .await;
+    }
+
+    let payments = repository::fetch(&db_pool, &query, &page)
+        .await
+        .map_err(reject::custom)?;
+    Ok(page::reply(
+        "/api/v1/payment",
+        &query,
+        &page,
+        payments.map(PaymentResponse::from),
+    )
+    .into_response())
 }
 
 pub async fn fetch_payment_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
@@ -21,7 +45,8 @@ pub async fn fetch_payment_handler(id: u32, db_pool: DBPool) -> Result<impl Repl
     let payment = repository::fetch_one(&db_pool, id)
         .await
         .map_err(reject::custom)?;
-    Ok(json(&PaymentResponse::from(payment)))
+    let version = payment.version;
+    Ok(etag::reply(json(&PaymentResponse::from(payment)), version))
 }
 
 pub async fn create_payment_handler(buf: impl Buf, db_pool: DBPool) -> Result<impl Reply> {
@@ -34,8 +59,50 @@ pub async fn create_payment_handler(buf: impl Buf, db_pool: DBPool) -> Result<im
     body.validate()
         .map_err(|e| reject::custom(Error::Validation(e)))?;
 
+    check_invoice(&db_pool, &body).await?;
+
+    let created_payment = repository::create(&db_pool, body)
+        .await
+        .map_err(reject::custom)?;
+
+    let response = json(&PaymentResponse::from(created_payment));
+
+    Ok(warp::reply::with_status(
+        response,
+        warp::http::StatusCode::CREATED,
+    ))
+}
+
+pub async fn bulk_create_payments_handler(
+    query: BulkQuery,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Creating payments in bulk");
+
+    let items = bulk::items(buf)?;
+    let mut bulk = Bulk::begin(&db_pool, query.mode, BulkItemStatus::Created).await?;
+
+    for item in items {
+        let result: Result<PaymentResponse> = async {
+            let body: CreatePaymentRequest = bulk::parse(item)?;
+            check_invoice(&db_pool, &body).await?;
+
+            let payment = bulk.write(|con| repository::create_payment(con, &body))?;
+            Ok(PaymentResponse::from(payment))
+        }
+        .await;
+
+        bulk.record(result);
+    }
+
+    bulk.finish()
+}
+
+/// Checks that the paid invoice exists and was issued by the payment date.
+async fn check_invoice(db_pool: &DBPool, body: &CreatePaymentRequest) -> Result<()> {
     // check if invoice exists
-    let invoice = invoice::repository::fetch_one(&db_pool, body.invoice_id).await;
+    let invoice = invoice::repository::fetch_one(db_pool, body.invoice_id).await;
 
     if invoice.is_err() {
         return Err(reject::custom(Error::InvoiceNotFound(body.invoice_id)));
@@ -51,14 +118,5 @@ pub async fn create_payment_handler(buf: impl Buf, db_pool: DBPool) -> Result<im
         )));
     }
 
-    let created_payment = repository::create(&db_pool, body)
-        .await
-        .map_err(reject::custom)?;
-
-    let response = json(&PaymentResponse::from(created_payment));
-
-    Ok(warp::reply::with_status(
-        response,
-        warp::http::StatusCode::CREATED,
-    ))
+    Ok(())
 }
@@ -1,4 +1,7 @@
-use crate::{with_db, DBPool};
+use crate::{export, with_db, DBPool};
+use common::bulk::BulkQuery;
+use common::page::PageQuery;
+use common::payment::PaymentListQuery;
 use warp::Filter;
 
 pub mod handler;
@@ -7,11 +10,15 @@ pub mod repository;
 pub fn get_routes(
     db_pool: DBPool,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
-    let payment = warp::path!("api" / "payment");
-    let payment_param = warp::path!("api" / "payment" / u32);
+    let payment = warp::path!("payment");
+    let payment_param = warp::path!("payment" / u32);
+    let payment_bulk = warp::path!("payment" / "bulk");
 
     payment
         .and(warp::get())
+        .and(warp::query::<PaymentListQuery>())
+        .and(warp::query::<PageQuery>())
+        .and(export::format())
         .and(with_db(db_pool.clone()))
         .and_then(handler::list_payments_handler)
         .or(payment_param
@@ -23,4 +30,10 @@ pub fn get_routes(
             .and(warp::body::aggregate())
             .and(with_db(db_pool.clone()))
             .and_then(handler::create_payment_handler))
+        .or(payment_bulk
+            .and(warp::post())
+            .and(warp::query::<BulkQuery>())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::bulk_create_payments_handler))
 }
@@ -1,20 +1,66 @@
 use crate::db::payment::{row_to_payment, SELECT_FIELDS, TABLE};
 use crate::db::{get_db_con, Result};
 use crate::error::application::Error;
-use crate::DBPool;
-use common::payment::{CreatePaymentRequest, Payment};
-use oracle::sql_type::OracleType;
+use crate::page::{date_range, Listing, Page};
+use crate::{DBCon, DBPool};
+use common::page::PageQuery;
+use common::payment::{CreatePaymentRequest, Payment, PaymentListQuery};
+use oracle::sql_type::{OracleType, ToSql};
 
-pub async fn fetch(db_pool: &DBPool) -> Result<Vec<Payment>> {
+const LISTING: Listing = Listing {
+    table: TABLE,
+    fields: SELECT_FIELDS,
+    sort_columns: &[
+        ("id", "id"),
+        ("invoice_id", "invoice_id"),
+        ("payment_date", "payment_date"),
+        ("amount", "amount"),
+    ],
+};
+
+/// Page of the payments of the invoice and in the date range of the filter, when given.
+pub async fn fetch(
+    db_pool: &DBPool,
+    filter: &PaymentListQuery,
+    page: &PageQuery,
+) -> Result<Page<Payment>> {
     let con = get_db_con(db_pool).await?;
-    let query = format!("SELECT {} FROM {}", SELECT_FIELDS, TABLE);
 
-    let rows = con.query(query.as_str(), &[]).map_err(Error::DBQuery)?;
+    filtered(filter, |condition, params| {
+        crate::page::fetch(&con, &LISTING, condition, params, page, row_to_payment)
+    })
+}
+
+/// Passes each payment matching the filter to `each` as it is read, sorted as by the page.
+pub fn export(
+    con: &DBCon,
+    filter: &PaymentListQuery,
+    page: &PageQuery,
+    each: &mut dyn FnMut(Payment) -> Result<()>,
+) -> Result<()> {
+    filtered(filter, |condition, params| {
+        crate::page::for_each(con, &LISTING, condition, params, page, row_to_payment, each)
+    })
+}
+
+/// Calls `list` with the condition selecting the payments matching the filter and its
+/// parameters.
+fn filtered<R>(
+    filter: &PaymentListQuery,
+    list: impl FnOnce(&str, &[(&str, &dyn ToSql)]) -> R,
+) -> R {
+    let (date_from, date_to) = date_range(filter.from, filter.to);
 
-    Ok(rows
-        .filter(|r| r.is_ok())
-        .map(|r| row_to_payment(&r.unwrap()))
-        .collect())
+    list(
+        "(:invoice_id IS NULL OR invoice_id = :invoice_id) \
+        AND (:date_from IS NULL OR payment_date >= :date_from) \
+        AND (:date_to IS NULL OR payment_date < :date_to)",
+        &[
+            ("invoice_id", &filter.invoice_id),
+            ("date_from", &date_from),
+            ("date_to", &date_to),
+        ],
+    )
 }
 
 pub async fn fetch_one(db_pool: &DBPool, id: u32) -> Result<Payment> {
@@ -30,6 +76,26 @@ pub async fn fetch_one(db_pool: &DBPool, id: u32) -> Result<Payment> {
 
 pub async fn create(db_pool: &DBPool, body: CreatePaymentRequest) -> Result<Payment> {
     let con = get_db_con(db_pool).await?;
+
+    let payment = match create_payment(&con, &body) {
+        Ok(payment) => payment,
+        Err(e) => {
+            con.rollback().map_err(Error::DBQuery)?;
+            return Err(e);
+        }
+    };
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    Ok(payment)
+}
+
+/// Inserts the payment within the transaction of the connection, without committing
+/// it, the triggers of its invoice seeing the payments inserted before it.
+pub fn create_payment(con: &DBCon, body: &CreatePaymentRequest) -> Result<Payment> {
     let query = format!(
         "INSERT INTO {} (invoice_id, payment_date, amount) \
         VALUES (:invoice_id, :payment_date, :amount) RETURNING id INTO :id",
@@ -48,11 +114,6 @@ pub async fn create(db_pool: &DBPool, body: CreatePaymentRequest) -> Result<Paym
         )
         .map_err(Error::DBQuery)?;
 
-    if let Err(e) = con.commit() {
-        con.rollback().map_err(Error::DBQuery)?;
-        return Err(Error::DBQuery(e));
-    }
-
     let row_id: u32 = stmt.returned_values("id").map_err(Error::DBQuery)?[0];
     let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
 
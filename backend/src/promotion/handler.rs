@@ -0,0 +1,107 @@
+use crate::error::application::Error;
+use crate::etag::{self, IfMatch};
+use crate::promotion::repository;
+use crate::{DBPool, Result};
+use common::promotion::{PromotionRequest, PromotionResponse};
+use validator::Validate;
+use warp::reply::json;
+use warp::{reject, Buf, Reply};
+
+pub async fn list_promotions_handler(db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Listing promotions");
+
+    let promotions = repository::fetch(&db_pool).await.map_err(reject::custom)?;
+    Ok(json::<Vec<_>>(
+        &promotions
+            .into_iter()
+            .map(PromotionResponse::from)
+            .collect(),
+    ))
+}
+
+pub async fn fetch_promotion_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Fetching promotion with id {}", id);
+
+    let promotion = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    let version = promotion.version;
+    Ok(etag::reply(
+        json(&PromotionResponse::from(promotion)),
+        version,
+    ))
+}
+
+pub async fn create_promotion_handler(buf: impl Buf, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Creating a new promotion");
+
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: PromotionRequest = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    body.validate()
+        .map_err(|e| reject::custom(Error::Validation(e)))?;
+
+    let created_promotion = repository::create(&db_pool, body)
+        .await
+        .map_err(reject::custom)?;
+
+    let response = json(&PromotionResponse::from(created_promotion));
+
+    Ok(warp::reply::with_status(
+        response,
+        warp::http::StatusCode::CREATED,
+    ))
+}
+
+pub async fn update_promotion_handler(
+    id: u32,
+    if_match: IfMatch,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Updating promotion with id {}", id);
+
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: PromotionRequest = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    body.validate()
+        .map_err(|e| reject::custom(Error::Validation(e)))?;
+
+    let promotion = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    if_match
+        .check("promotion", id, promotion.version)
+        .map_err(reject::custom)?;
+
+    let promotion = repository::update(&db_pool, id, promotion.version, body)
+        .await
+        .map_err(reject::custom)?;
+    let version = promotion.version;
+    Ok(etag::reply(
+        json(&PromotionResponse::from(promotion)),
+        version,
+    ))
+}
+
+pub async fn delete_promotion_handler(
+    id: u32,
+    if_match: IfMatch,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Deleting promotion with id {}", id);
+
+    let promotion = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    if_match
+        .check("promotion", id, promotion.version)
+        .map_err(reject::custom)?;
+
+    repository::delete(&db_pool, id, promotion.version)
+        .await
+        .map_err(reject::custom)?;
+    Ok(warp::http::StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,37 @@
+use crate::{etag, with_db, DBPool};
+use warp::Filter;
+
+pub mod handler;
+pub mod repository;
+
+pub fn get_routes(
+    db_pool: DBPool,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let promotion = warp::path!("promotion");
+    let promotion_param = warp::path!("promotion" / u32);
+
+    promotion
+        .and(warp::get())
+        .and(with_db(db_pool.clone()))
+        .and_then(handler::list_promotions_handler)
+        .or(promotion_param
+            .and(warp::get())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::fetch_promotion_handler))
+        .or(promotion
+            .and(warp::post())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::create_promotion_handler))
+        .or(promotion_param
+            .and(warp::put())
+            .and(etag::if_match())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::update_promotion_handler))
+        .or(promotion_param
+            .and(warp::delete())
+            .and(etag::if_match())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::delete_promotion_handler))
+}
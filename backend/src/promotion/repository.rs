@@ -0,0 +1,164 @@
+use crate::db::promotion::{row_to_promotion, SELECT_FIELDS, TABLE};
+use crate::db::{get_db_con, Result};
+use crate::error::application::Error;
+use crate::{DBCon, DBPool};
+use common::promotion::{Promotion, PromotionRequest};
+use oracle::sql_type::OracleType;
+
+pub async fn fetch(db_pool: &DBPool) -> Result<Vec<Promotion>> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!("SELECT {} FROM {}", SELECT_FIELDS, TABLE);
+
+    let rows = con.query(query.as_str(), &[]).map_err(Error::DBQuery)?;
+
+    Ok(rows
+        .filter(|r| r.is_ok())
+        .map(|r| row_to_promotion(&r.unwrap()))
+        .collect())
+}
+
+pub async fn fetch_one(db_pool: &DBPool, id: u32) -> Result<Promotion> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &id)])
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::PromotionNotFound(id),
+            _ => Error::DBQuery(e),
+        })?;
+
+    Ok(row_to_promotion(&row))
+}
+
+pub fn fetch_promotion_by_code(con: &DBCon, code: &str) -> Result<Promotion> {
+    let query = format!(
+        "SELECT {} FROM {} WHERE code = UPPER(:code)",
+        SELECT_FIELDS, TABLE
+    );
+
+    let row = con
+        .query_row_named(query.as_str(), &[("code", &code)])
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::CouponNotFound(code.to_string()),
+            _ => Error::DBQuery(e),
+        })?;
+
+    Ok(row_to_promotion(&row))
+}
+
+pub async fn create(db_pool: &DBPool, body: PromotionRequest) -> Result<Promotion> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "INSERT INTO {} (code, description, discount_type, discount_value, duration_cycles, \
+        subscription_type, valid_from, valid_until) \
+        VALUES (:code, :description, :discount_type, :discount_value, :duration_cycles, \
+        :subscription_type, :valid_from, :valid_until) RETURNING id INTO :id",
+        TABLE
+    );
+
+    let discount_type: String = body.discount_type.into();
+    let subscription_type: Option<String> = body.subscription_type.map(String::from);
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("code", &body.code),
+                ("description", &body.description),
+                ("discount_type", &discount_type),
+                ("discount_value", &body.discount_value),
+                ("duration_cycles", &body.duration_cycles),
+                ("subscription_type", &subscription_type),
+                ("valid_from", &body.valid_from),
+                ("valid_until", &body.valid_until),
+                ("id", &OracleType::Number(0, 0)),
+            ],
+        )
+        .map_err(Error::DBQuery)?;
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    let row_id: u32 = stmt.returned_values("id").map_err(Error::DBQuery)?[0];
+    let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &row_id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(row_to_promotion(&row))
+}
+
+pub async fn update(
+    db_pool: &DBPool,
+    id: u32,
+    version: u32,
+    body: PromotionRequest,
+) -> Result<Promotion> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!("UPDATE {} SET code = :code, description = :description, discount_type = :discount_type, discount_value = :discount_value, duration_cycles = :duration_cycles, subscription_type = :subscription_type, valid_from = :valid_from, valid_until = :valid_until WHERE id = :id AND version = :version", TABLE);
+
+    let discount_type: String = body.discount_type.into();
+    let subscription_type: Option<String> = body.subscription_type.map(String::from);
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("id", &id),
+                ("version", &version),
+                ("code", &body.code),
+                ("description", &body.description),
+                ("discount_type", &discount_type),
+                ("discount_value", &body.discount_value),
+                ("duration_cycles", &body.duration_cycles),
+                ("subscription_type", &subscription_type),
+                ("valid_from", &body.valid_from),
+                ("valid_until", &body.valid_until),
+            ],
+        )
+        .map_err(Error::DBQuery)?;
+
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("promotion", id));
+    }
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(row_to_promotion(&row))
+}
+
+pub async fn delete(db_pool: &DBPool, id: u32, version: u32) -> Result<()> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "DELETE FROM {} WHERE id = :id AND version = :version",
+        TABLE
+    );
+
+    let stmt = con
+        .execute_named(query.as_str(), &[("id", &id), ("version", &version)])
+        .map_err(Error::DBQuery)?;
+
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("promotion", id));
+    }
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    Ok(())
+}
@@ -1,21 +1,46 @@
+use crate::bulk::{self, Bulk};
 use crate::error::application::Error;
+use crate::etag::{self, IfMatch};
+use crate::import::Import;
 use crate::subscription::repository;
-use crate::{DBPool, Result};
-use common::subscription::{SubscriptionRequest, SubscriptionResponse};
+use crate::{export, page, patch, DBPool, Result};
+use common::bulk::BulkItemStatus;
+use common::export::ExportFormat;
+use common::import::ImportQuery;
+use common::page::PageQuery;
+use common::subscription::{SubscriptionListQuery, SubscriptionRequest, SubscriptionResponse};
 use validator::Validate;
+use warp::multipart::FormData;
 use warp::reply::json;
 use warp::{reject, Buf, Reply};
 
-pub async fn list_subscriptions_handler(db_pool: DBPool) -> Result<impl Reply> {
+pub async fn list_subscriptions_handler(
+    query: SubscriptionListQuery,
+    page: PageQuery,
+    format: ExportFormat,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
     log::info!("Listing subscriptions");
 
-    let subscriptions = repository::fetch(&db_pool).await.map_err(reject::custom)?;
-    Ok(json::<Vec<_>>(
-        &subscriptions
-            .into_iter()
-            .map(SubscriptionResponse::from)
-            .collect(),
-    ))
+    if format != ExportFormat::Json {
+        return export::reply(&db_pool, "subscriptions", format, move |con, each| {
+            repository::export(con, &query, &page, &mut |subscription| {
+                each(SubscriptionResponse::from(subscription))
+            })
+        })
+        .await;
+    }
+
+    let subscriptions = repository::fetch(&db_pool, &query, &page)
+        .await
+        .map_err(reject::custom)?;
+    Ok(page::reply(
+        "/api/v1/subscription",
+        &query,
+        &page,
+        subscriptions.map(SubscriptionResponse::from),
+    )
+    .into_response())
 }
 
 pub async fn fetch_subscription_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
@@ -24,7 +49,11 @@ pub async fn fetch_subscription_handler(id: u32, db_pool: DBPool) -> Result<impl
     let subscription = repository::fetch_one(&db_pool, id)
         .await
         .map_err(reject::custom)?;
-    Ok(json(&SubscriptionResponse::from(subscription)))
+    let version = subscription.version;
+    Ok(etag::reply(
+        json(&SubscriptionResponse::from(subscription)),
+        version,
+    ))
 }
 
 pub async fn create_subscription_handler(buf: impl Buf, db_pool: DBPool) -> Result<impl Reply> {
@@ -49,8 +78,38 @@ pub async fn create_subscription_handler(buf: impl Buf, db_pool: DBPool) -> Resu
     ))
 }
 
+pub async fn import_subscriptions_handler(
+    query: ImportQuery,
+    form: FormData,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Importing subscriptions");
+
+    let import = Import::read::<SubscriptionRequest>(form).await?;
+    let mut bulk = Bulk::begin(&db_pool, query.mode, BulkItemStatus::Created)
+        .await?
+        .dry_run(!query.commit);
+
+    for item in import.items() {
+        match item {
+            Ok(item) => {
+                let result: Result<SubscriptionResponse> = bulk::parse(item).and_then(|body| {
+                    let subscription =
+                        bulk.write(|con| repository::create_subscription(con, &body))?;
+                    Ok(SubscriptionResponse::from(subscription))
+                });
+                bulk.record(result);
+            }
+            Err(errors) => bulk.record_errors(errors),
+        }
+    }
+
+    import.reply(bulk)
+}
+
 pub async fn update_subscription_handler(
     id: u32,
+    if_match: IfMatch,
     buf: impl Buf,
     db_pool: DBPool,
 ) -> Result<impl Reply> {
@@ -63,17 +122,67 @@ pub async fn update_subscription_handler(
     body.validate()
         .map_err(|e| reject::custom(Error::Validation(e)))?;
 
-    Ok(json(&SubscriptionResponse::from(
-        repository::update(&db_pool, id, body)
-            .await
-            .map_err(reject::custom)?,
-    )))
+    let subscription = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(|_| reject::custom(Error::SubscriptionNotFound(id)))?;
+    if_match
+        .check("subscription", id, subscription.version)
+        .map_err(reject::custom)?;
+
+    let subscription = repository::update(&db_pool, id, subscription.version, body)
+        .await
+        .map_err(reject::custom)?;
+    let version = subscription.version;
+    Ok(etag::reply(
+        json(&SubscriptionResponse::from(subscription)),
+        version,
+    ))
+}
+
+pub async fn patch_subscription_handler(
+    id: u32,
+    if_match: IfMatch,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Patching subscription with id {}", id);
+
+    let subscription = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(|_| reject::custom(Error::SubscriptionNotFound(id)))?;
+    if_match
+        .check("subscription", id, subscription.version)
+        .map_err(reject::custom)?;
+
+    let version = subscription.version;
+    let body =
+        patch::apply(&SubscriptionRequest::from(subscription), buf).map_err(reject::custom)?;
+
+    let subscription = repository::update(&db_pool, id, version, body)
+        .await
+        .map_err(reject::custom)?;
+    let version = subscription.version;
+    Ok(etag::reply(
+        json(&SubscriptionResponse::from(subscription)),
+        version,
+    ))
 }
 
-pub async fn delete_subscription_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+pub async fn delete_subscription_handler(
+    id: u32,
+    if_match: IfMatch,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
     log::info!("Deleting subscription with id {}", id);
 
-    repository::delete(&db_pool, id)
+    let subscription = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(|_| reject::custom(Error::SubscriptionNotFound(id)))?;
+    if_match
+        .check("subscription", id, subscription.version)
+        .map_err(reject::custom)?;
+
+    repository::delete(&db_pool, id, subscription.version)
         .await
         .map_err(reject::custom)?;
     Ok(warp::http::StatusCode::NO_CONTENT)
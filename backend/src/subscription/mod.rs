@@ -1,4 +1,7 @@
-use crate::{with_db, DBPool};
+use crate::{etag, export, import, with_db, DBPool};
+use common::import::ImportQuery;
+use common::page::PageQuery;
+use common::subscription::SubscriptionListQuery;
 use warp::Filter;
 
 pub mod handler;
@@ -7,11 +10,15 @@ pub mod repository;
 pub fn get_routes(
     db_pool: DBPool,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
-    let subscription = warp::path!("api" / "subscription");
-    let subscription_param = warp::path!("api" / "subscription" / u32);
+    let subscription = warp::path!("subscription");
+    let subscription_import = warp::path!("subscription" / "import");
+    let subscription_param = warp::path!("subscription" / u32);
 
     subscription
         .and(warp::get())
+        .and(warp::query::<SubscriptionListQuery>())
+        .and(warp::query::<PageQuery>())
+        .and(export::format())
         .and(with_db(db_pool.clone()))
         .and_then(handler::list_subscriptions_handler)
         .or(subscription_param
@@ -23,13 +30,27 @@ pub fn get_routes(
             .and(warp::body::aggregate())
             .and(with_db(db_pool.clone()))
             .and_then(handler::create_subscription_handler))
+        .or(subscription_import
+            .and(warp::post())
+            .and(warp::query::<ImportQuery>())
+            .and(import::form())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::import_subscriptions_handler))
         .or(subscription_param
             .and(warp::put())
+            .and(etag::if_match())
             .and(warp::body::aggregate())
             .and(with_db(db_pool.clone()))
             .and_then(handler::update_subscription_handler))
+        .or(subscription_param
+            .and(warp::patch())
+            .and(etag::if_match())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::patch_subscription_handler))
         .or(subscription_param
             .and(warp::delete())
+            .and(etag::if_match())
             .and(with_db(db_pool.clone()))
             .and_then(handler::delete_subscription_handler))
 }
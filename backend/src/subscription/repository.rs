@@ -1,24 +1,84 @@
 use crate::db::subscription::{row_to_subscription, SELECT_FIELDS, TABLE};
 use crate::db::{get_db_con, Result};
 use crate::error::application::Error;
-use crate::DBPool;
-use common::subscription::{Subscription, SubscriptionRequest};
-use oracle::sql_type::OracleType;
-
-pub async fn fetch(db_pool: &DBPool) -> Result<Vec<Subscription>> {
+use crate::page::{Listing, Page};
+use crate::{DBCon, DBPool};
+use common::page::PageQuery;
+use common::subscription::{Subscription, SubscriptionListQuery, SubscriptionRequest};
+use oracle::sql_type::{OracleType, ToSql};
+
+const LISTING: Listing = Listing {
+    table: TABLE,
+    fields: SELECT_FIELDS,
+    sort_columns: &[
+        ("id", "id"),
+        ("description", "description"),
+        ("type", "type"),
+        ("traffic", "traffic"),
+        ("price", "price"),
+    ],
+};
+
+/// Page of the subscriptions of the type and in the price range of the filter, when given.
+pub async fn fetch(
+    db_pool: &DBPool,
+    filter: &SubscriptionListQuery,
+    page: &PageQuery,
+) -> Result<Page<Subscription>> {
     let con = get_db_con(db_pool).await?;
-    let query = format!("SELECT {} FROM {}", SELECT_FIELDS, TABLE);
 
-    let rows = con.query(query.as_str(), &[]).map_err(Error::DBQuery)?;
+    filtered(filter, |condition, params| {
+        crate::page::fetch(&con, &LISTING, condition, params, page, row_to_subscription)
+    })
+}
+
+/// Passes each subscription matching the filter to `each` as it is read, sorted as by the page.
+pub fn export(
+    con: &DBCon,
+    filter: &SubscriptionListQuery,
+    page: &PageQuery,
+    each: &mut dyn FnMut(Subscription) -> Result<()>,
+) -> Result<()> {
+    filtered(filter, |condition, params| {
+        crate::page::for_each(
+            con,
+            &LISTING,
+            condition,
+            params,
+            page,
+            row_to_subscription,
+            each,
+        )
+    })
+}
 
-    Ok(rows
-        .filter(|r| r.is_ok())
-        .map(|r| row_to_subscription(&r.unwrap()))
-        .collect())
+/// Calls `list` with the condition selecting the subscriptions matching the filter and its
+/// parameters.
+fn filtered<R>(
+    filter: &SubscriptionListQuery,
+    list: impl FnOnce(&str, &[(&str, &dyn ToSql)]) -> R,
+) -> R {
+    let subscription_type: Option<String> = filter.subscription_type.clone().map(String::from);
+
+    list(
+        "(:subscription_type IS NULL OR type = :subscription_type) \
+        AND (:min_price IS NULL OR price >= :min_price) \
+        AND (:max_price IS NULL OR price <= :max_price)",
+        &[
+            ("subscription_type", &subscription_type),
+            ("min_price", &filter.min_price),
+            ("max_price", &filter.max_price),
+        ],
+    )
 }
 
 pub async fn fetch_one(db_pool: &DBPool, id: u32) -> Result<Subscription> {
     let con = get_db_con(db_pool).await?;
+
+    fetch_subscription(&con, id)
+}
+
+pub fn fetch_subscription(con: &DBCon, id: u32) -> Result<Subscription> {
     let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
 
     let row = con
@@ -30,13 +90,32 @@ pub async fn fetch_one(db_pool: &DBPool, id: u32) -> Result<Subscription> {
 
 pub async fn create(db_pool: &DBPool, body: SubscriptionRequest) -> Result<Subscription> {
     let con = get_db_con(db_pool).await?;
+
+    let subscription = match create_subscription(&con, &body) {
+        Ok(subscription) => subscription,
+        Err(e) => {
+            con.rollback().map_err(Error::DBQuery)?;
+            return Err(e);
+        }
+    };
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    Ok(subscription)
+}
+
+/// Inserts the subscription within the transaction of the connection, without committing it.
+pub fn create_subscription(con: &DBCon, body: &SubscriptionRequest) -> Result<Subscription> {
     let query = format!(
         "INSERT INTO {} (description, type, traffic, price, extra_traffic_price) \
         VALUES (:description, :type, :traffic, :price, :extra_traffic_price) RETURNING id INTO :id",
         TABLE
     );
 
-    let subscription_type: String = body.subscription_type.into();
+    let subscription_type: String = body.subscription_type.clone().into();
 
     let stmt = con
         .execute_named(
@@ -52,11 +131,6 @@ pub async fn create(db_pool: &DBPool, body: SubscriptionRequest) -> Result<Subsc
         )
         .map_err(Error::DBQuery)?;
 
-    if let Err(e) = con.commit() {
-        con.rollback().map_err(Error::DBQuery)?;
-        return Err(Error::DBQuery(e));
-    }
-
     let row_id: u32 = stmt.returned_values("id").map_err(Error::DBQuery)?[0];
     let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
 
@@ -67,27 +141,38 @@ pub async fn create(db_pool: &DBPool, body: SubscriptionRequest) -> Result<Subsc
     Ok(row_to_subscription(&row))
 }
 
-pub async fn update(db_pool: &DBPool, id: u32, body: SubscriptionRequest) -> Result<Subscription> {
+pub async fn update(
+    db_pool: &DBPool,
+    id: u32,
+    version: u32,
+    body: SubscriptionRequest,
+) -> Result<Subscription> {
     let con = get_db_con(db_pool).await?;
-    let query = format!("UPDATE {} SET description = :description, type = :type, traffic = :traffic, price = :price, extra_traffic_price = :extra_traffic_price WHERE id = :id", TABLE);
+    let query = format!("UPDATE {} SET description = :description, type = :type, traffic = :traffic, price = :price, extra_traffic_price = :extra_traffic_price WHERE id = :id AND version = :version", TABLE);
 
     let subscription_type: String = body.subscription_type.into();
 
-    con.execute_named(
-        query.as_str(),
-        &[
-            ("id", &id),
-            ("description", &body.description),
-            ("type", &subscription_type),
-            ("traffic", &body.traffic),
-            ("price", &body.price),
-            ("extra_traffic_price", &body.extra_traffic_price),
-        ],
-    )
-    .map_err(|e| match e {
-        oracle::Error::NoDataFound => Error::SubscriptionNotFound(id),
-        _ => Error::DBQuery(e),
-    })?;
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("id", &id),
+                ("version", &version),
+                ("description", &body.description),
+                ("type", &subscription_type),
+                ("traffic", &body.traffic),
+                ("price", &body.price),
+                ("extra_traffic_price", &body.extra_traffic_price),
+            ],
+        )
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::SubscriptionNotFound(id),
+            _ => Error::DBQuery(e),
+        })?;
+
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("subscription", id));
+    }
 
     if let Err(e) = con.commit() {
         con.rollback().map_err(Error::DBQuery)?;
@@ -103,13 +188,21 @@ pub async fn update(db_pool: &DBPool, id: u32, body: SubscriptionRequest) -> Res
     Ok(row_to_subscription(&row))
 }
 
-pub async fn delete(db_pool: &DBPool, id: u32) -> Result<()> {
+pub async fn delete(db_pool: &DBPool, id: u32, version: u32) -> Result<()> {
     let con = get_db_con(db_pool).await?;
-    let query = format!("DELETE FROM {} WHERE id = :id", TABLE);
+    let query = format!(
+        "DELETE FROM {} WHERE id = :id AND version = :version",
+        TABLE
+    );
 
-    con.execute_named(query.as_str(), &[("id", &id)])
+    let stmt = con
+        .execute_named(query.as_str(), &[("id", &id), ("version", &version)])
         .map_err(Error::DBQuery)?;
 
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("subscription", id));
+    }
+
     if let Err(e) = con.commit() {
         con.rollback().map_err(Error::DBQuery)?;
         return Err(Error::DBQuery(e));
@@ -0,0 +1,94 @@
+use crate::error::application::Error;
+use crate::etag::{self, IfMatch};
+use crate::tag::repository;
+use crate::{DBPool, Result};
+use common::tag::{TagRequest, TagResponse};
+use validator::Validate;
+use warp::reply::json;
+use warp::{reject, Buf, Reply};
+
+pub async fn list_tags_handler(db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Listing tags");
+
+    let tags = repository::fetch(&db_pool).await.map_err(reject::custom)?;
+    Ok(json::<Vec<_>>(
+        &tags.into_iter().map(TagResponse::from).collect(),
+    ))
+}
+
+pub async fn fetch_tag_handler(id: u32, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Fetching tag with id {}", id);
+
+    let tag = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    let version = tag.version;
+    Ok(etag::reply(json(&TagResponse::from(tag)), version))
+}
+
+pub async fn create_tag_handler(buf: impl Buf, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Creating a new tag");
+
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: TagRequest = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    body.validate()
+        .map_err(|e| reject::custom(Error::Validation(e)))?;
+
+    let created_tag = repository::create(&db_pool, body)
+        .await
+        .map_err(reject::custom)?;
+
+    let response = json(&TagResponse::from(created_tag));
+
+    Ok(warp::reply::with_status(
+        response,
+        warp::http::StatusCode::CREATED,
+    ))
+}
+
+pub async fn update_tag_handler(
+    id: u32,
+    if_match: IfMatch,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("Updating tag with id {}", id);
+
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: TagRequest = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    body.validate()
+        .map_err(|e| reject::custom(Error::Validation(e)))?;
+
+    let tag = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    if_match
+        .check("tag", id, tag.version)
+        .map_err(reject::custom)?;
+
+    let tag = repository::update(&db_pool, id, tag.version, body)
+        .await
+        .map_err(reject::custom)?;
+    let version = tag.version;
+    Ok(etag::reply(json(&TagResponse::from(tag)), version))
+}
+
+pub async fn delete_tag_handler(id: u32, if_match: IfMatch, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Deleting tag with id {}", id);
+
+    let tag = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    if_match
+        .check("tag", id, tag.version)
+        .map_err(reject::custom)?;
+
+    repository::delete(&db_pool, id, tag.version)
+        .await
+        .map_err(reject::custom)?;
+    Ok(warp::http::StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,36 @@
+use crate::{etag, with_db, DBPool};
+use warp::Filter;
+
+pub mod handler;
+pub mod repository;
+
+pub fn get_routes(
+    db_pool: DBPool,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let tag = warp::path!("tag");
+    let tag_param = warp::path!("tag" / u32);
+
+    tag.and(warp::get())
+        .and(with_db(db_pool.clone()))
+        .and_then(handler::list_tags_handler)
+        .or(tag_param
+            .and(warp::get())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::fetch_tag_handler))
+        .or(tag
+            .and(warp::post())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::create_tag_handler))
+        .or(tag_param
+            .and(warp::put())
+            .and(etag::if_match())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::update_tag_handler))
+        .or(tag_param
+            .and(warp::delete())
+            .and(etag::if_match())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::delete_tag_handler))
+}
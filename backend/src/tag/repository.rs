@@ -0,0 +1,234 @@
+use crate::constraint::UNIQUE_VIOLATION;
+use crate::db::tag::{assignment_table, row_to_tag, SELECT_FIELDS, TABLE};
+use crate::db::{get_db_con, Result};
+use crate::error::application::Error;
+use crate::{DBCon, DBPool};
+use common::field::Entity;
+use common::tag::{Tag, TagRequest};
+use oracle::sql_type::OracleType;
+
+pub async fn fetch(db_pool: &DBPool) -> Result<Vec<Tag>> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!("SELECT {} FROM {} ORDER BY name", SELECT_FIELDS, TABLE);
+
+    let rows = con.query(query.as_str(), &[]).map_err(Error::DBQuery)?;
+
+    Ok(rows
+        .filter(|r| r.is_ok())
+        .map(|r| row_to_tag(&r.unwrap()))
+        .collect())
+}
+
+pub async fn fetch_one(db_pool: &DBPool, id: u32) -> Result<Tag> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &id)])
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::TagNotFound(id),
+            _ => Error::DBQuery(e),
+        })?;
+
+    Ok(row_to_tag(&row))
+}
+
+pub async fn create(db_pool: &DBPool, body: TagRequest) -> Result<Tag> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "INSERT INTO {} (name, description) VALUES (:name, :description) RETURNING id INTO :id",
+        TABLE
+    );
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("name", &body.name),
+                ("description", &body.description),
+                ("id", &OracleType::Number(0, 0)),
+            ],
+        )
+        .map_err(|e| match e {
+            oracle::Error::OciError(ref db_error) if db_error.code() == UNIQUE_VIOLATION => {
+                Error::DuplicateTag(body.name.clone())
+            }
+            _ => Error::DBQuery(e),
+        })?;
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    let row_id: u32 = stmt.returned_values("id").map_err(Error::DBQuery)?[0];
+    let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &row_id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(row_to_tag(&row))
+}
+
+pub async fn update(db_pool: &DBPool, id: u32, version: u32, body: TagRequest) -> Result<Tag> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "UPDATE {} SET name = :name, description = :description \
+        WHERE id = :id AND version = :version",
+        TABLE
+    );
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("id", &id),
+                ("version", &version),
+                ("name", &body.name),
+                ("description", &body.description),
+            ],
+        )
+        .map_err(|e| match e {
+            oracle::Error::OciError(ref db_error) if db_error.code() == UNIQUE_VIOLATION => {
+                Error::DuplicateTag(body.name.clone())
+            }
+            _ => Error::DBQuery(e),
+        })?;
+
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("tag", id));
+    }
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &id)])
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::TagNotFound(id),
+            _ => Error::DBQuery(e),
+        })?;
+
+    Ok(row_to_tag(&row))
+}
+
+/// Deleting a tag removes it from the customers and contracts it was assigned to.
+pub async fn delete(db_pool: &DBPool, id: u32, version: u32) -> Result<()> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "DELETE FROM {} WHERE id = :id AND version = :version",
+        TABLE
+    );
+
+    let stmt = con
+        .execute_named(query.as_str(), &[("id", &id), ("version", &version)])
+        .map_err(Error::DBQuery)?;
+
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("tag", id));
+    }
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    Ok(())
+}
+
+/// Tags assigned to the customer or contract `id`.
+pub async fn fetch_assigned(db_pool: &DBPool, entity: &Entity, id: u32) -> Result<Vec<Tag>> {
+    let con = get_db_con(db_pool).await?;
+
+    fetch_assigned_tags(&con, entity, id)
+}
+
+pub fn fetch_assigned_tags(con: &DBCon, entity: &Entity, id: u32) -> Result<Vec<Tag>> {
+    let (assignment_table, column) = assignment_table(entity);
+    let query = format!(
+        "SELECT {} FROM {} WHERE id IN (SELECT tag_id FROM {} WHERE {} = :id) ORDER BY name",
+        SELECT_FIELDS, TABLE, assignment_table, column
+    );
+
+    let rows = con
+        .query_named(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(rows
+        .filter(|r| r.is_ok())
+        .map(|r| row_to_tag(&r.unwrap()))
+        .collect())
+}
+
+/// Replaces the tags of the customer or contract `id` with the ones named, in
+/// one transaction.
+pub async fn assign(
+    db_pool: &DBPool,
+    entity: &Entity,
+    id: u32,
+    names: &[String],
+) -> Result<Vec<Tag>> {
+    let con = get_db_con(db_pool).await?;
+
+    if let Err(e) = assign_tags(&con, entity, id, names) {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(e);
+    }
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    let (assignment_table, column) = assignment_table(entity);
+    let query = format!(
+        "SELECT {} FROM {} WHERE id IN (SELECT tag_id FROM {} WHERE {} = :id) ORDER BY name",
+        SELECT_FIELDS, TABLE, assignment_table, column
+    );
+
+    let rows = con
+        .query_named(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    Ok(rows
+        .filter(|r| r.is_ok())
+        .map(|r| row_to_tag(&r.unwrap()))
+        .collect())
+}
+
+fn assign_tags(con: &DBCon, entity: &Entity, id: u32, names: &[String]) -> Result<()> {
+    let (assignment_table, column) = assignment_table(entity);
+
+    let query = format!("DELETE FROM {} WHERE {} = :id", assignment_table, column);
+    con.execute_named(query.as_str(), &[("id", &id)])
+        .map_err(Error::DBQuery)?;
+
+    let mut names: Vec<String> = names
+        .iter()
+        .map(|name| name.trim().to_uppercase())
+        .collect();
+    names.sort();
+    names.dedup();
+
+    let query = format!(
+        "INSERT INTO {} ({}, tag_id) SELECT :id, id FROM {} WHERE name = :name",
+        assignment_table, column, TABLE
+    );
+
+    for name in names {
+        let stmt = con
+            .execute_named(query.as_str(), &[("id", &id), ("name", &name)])
+            .map_err(Error::DBQuery)?;
+
+        if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+            return Err(Error::UnknownTag(name));
+        }
+    }
+
+    Ok(())
+}
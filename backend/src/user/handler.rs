@@ -0,0 +1,113 @@
+use crate::error::application::Error;
+use crate::etag::{self, IfMatch};
+use crate::user::repository;
+use crate::{DBPool, Result};
+use common::user::{CreateUserRequest, Role, UpdateUserRequest, User, UserResponse};
+use validator::Validate;
+use warp::reply::json;
+use warp::{reject, Buf, Reply};
+
+pub async fn list_users_handler(_admin: User, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Listing users");
+
+    let users = repository::fetch(&db_pool).await.map_err(reject::custom)?;
+    Ok(json::<Vec<_>>(
+        &users.into_iter().map(UserResponse::from).collect(),
+    ))
+}
+
+pub async fn fetch_user_handler(id: u32, _admin: User, db_pool: DBPool) -> Result<impl Reply> {
+    log::info!("Fetching user with id {}", id);
+
+    let user = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    let version = user.version;
+    Ok(etag::reply(json(&UserResponse::from(user)), version))
+}
+
+pub async fn create_user_handler(
+    admin: User,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("User {} is creating a new user", admin.username);
+
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: CreateUserRequest = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    body.validate()
+        .map_err(|e| reject::custom(Error::Validation(e)))?;
+
+    let created_user = repository::create(&db_pool, body)
+        .await
+        .map_err(reject::custom)?;
+
+    let response = json(&UserResponse::from(created_user));
+
+    Ok(warp::reply::with_status(
+        response,
+        warp::http::StatusCode::CREATED,
+    ))
+}
+
+pub async fn update_user_handler(
+    id: u32,
+    admin: User,
+    if_match: IfMatch,
+    buf: impl Buf,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("User {} is updating user with id {}", admin.username, id);
+
+    let deserialized = &mut serde_json::Deserializer::from_reader(buf.reader());
+    let body: UpdateUserRequest = serde_path_to_error::deserialize(deserialized)
+        .map_err(|e| reject::custom(Error::JSONPath(e.to_string())))?;
+
+    body.validate()
+        .map_err(|e| reject::custom(Error::Validation(e)))?;
+
+    // an administrator locking themselves out could leave no one to manage the users
+    if id == admin.id && (!body.active || body.role != Role::Admin) {
+        return Err(reject::custom(Error::OwnAccount(id)));
+    }
+
+    let user = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    if_match
+        .check("user", id, user.version)
+        .map_err(reject::custom)?;
+
+    let user = repository::update(&db_pool, id, user.version, body)
+        .await
+        .map_err(reject::custom)?;
+    let version = user.version;
+    Ok(etag::reply(json(&UserResponse::from(user)), version))
+}
+
+pub async fn delete_user_handler(
+    id: u32,
+    admin: User,
+    if_match: IfMatch,
+    db_pool: DBPool,
+) -> Result<impl Reply> {
+    log::info!("User {} is deleting user with id {}", admin.username, id);
+
+    if id == admin.id {
+        return Err(reject::custom(Error::OwnAccount(id)));
+    }
+
+    let user = repository::fetch_one(&db_pool, id)
+        .await
+        .map_err(reject::custom)?;
+    if_match
+        .check("user", id, user.version)
+        .map_err(reject::custom)?;
+
+    repository::delete(&db_pool, id, user.version)
+        .await
+        .map_err(reject::custom)?;
+    Ok(warp::http::StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,43 @@
+use crate::auth;
+use crate::{etag, with_db, DBPool};
+use warp::Filter;
+
+pub mod handler;
+pub mod repository;
+
+/// Routes managing the users, reserved to administrators.
+pub fn get_routes(
+    db_pool: DBPool,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let user = warp::path!("user");
+    let user_param = warp::path!("user" / u32);
+
+    user.and(warp::get())
+        .and(auth::admin(db_pool.clone()))
+        .and(with_db(db_pool.clone()))
+        .and_then(handler::list_users_handler)
+        .or(user_param
+            .and(warp::get())
+            .and(auth::admin(db_pool.clone()))
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::fetch_user_handler))
+        .or(user
+            .and(warp::post())
+            .and(auth::admin(db_pool.clone()))
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::create_user_handler))
+        .or(user_param
+            .and(warp::put())
+            .and(auth::admin(db_pool.clone()))
+            .and(etag::if_match())
+            .and(warp::body::aggregate())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::update_user_handler))
+        .or(user_param
+            .and(warp::delete())
+            .and(auth::admin(db_pool.clone()))
+            .and(etag::if_match())
+            .and(with_db(db_pool.clone()))
+            .and_then(handler::delete_user_handler))
+}
@@ -0,0 +1,152 @@
+use crate::auth::password;
+use crate::constraint::UNIQUE_VIOLATION;
+use crate::db::user::{row_to_user, SELECT_FIELDS, SESSION_TABLE, TABLE};
+use crate::db::{get_db_con, Result};
+use crate::error::application::Error;
+use crate::{DBCon, DBPool};
+use common::user::{CreateUserRequest, UpdateUserRequest, User};
+use oracle::sql_type::OracleType;
+
+pub async fn fetch(db_pool: &DBPool) -> Result<Vec<User>> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!("SELECT {} FROM {} ORDER BY username", SELECT_FIELDS, TABLE);
+
+    let rows = con.query(query.as_str(), &[]).map_err(Error::DBQuery)?;
+
+    Ok(rows
+        .filter(|r| r.is_ok())
+        .map(|r| row_to_user(&r.unwrap()))
+        .collect())
+}
+
+pub async fn fetch_one(db_pool: &DBPool, id: u32) -> Result<User> {
+    let con = get_db_con(db_pool).await?;
+
+    fetch_user(&con, id)
+}
+
+pub async fn create(db_pool: &DBPool, body: CreateUserRequest) -> Result<User> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "INSERT INTO {} (username, password_hash, role) VALUES (:username, :password_hash, :role) \
+        RETURNING id INTO :id",
+        TABLE
+    );
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("username", &body.username),
+                ("password_hash", &password::hash(&body.password)?),
+                ("role", &String::from(body.role)),
+                ("id", &OracleType::Number(0, 0)),
+            ],
+        )
+        .map_err(|e| match e {
+            oracle::Error::OciError(ref db_error) if db_error.code() == UNIQUE_VIOLATION => {
+                Error::DuplicateUser(body.username.clone())
+            }
+            _ => Error::DBQuery(e),
+        })?;
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    let row_id: u32 = stmt.returned_values("id").map_err(Error::DBQuery)?[0];
+
+    fetch_user(&con, row_id)
+}
+
+/// Deactivating a user or changing their password signs them out everywhere.
+pub async fn update(
+    db_pool: &DBPool,
+    id: u32,
+    version: u32,
+    body: UpdateUserRequest,
+) -> Result<User> {
+    let con = get_db_con(db_pool).await?;
+
+    if let Err(e) = update_user(&con, id, version, &body) {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(e);
+    }
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    fetch_user(&con, id)
+}
+
+/// Deleting a user closes their sessions.
+pub async fn delete(db_pool: &DBPool, id: u32, version: u32) -> Result<()> {
+    let con = get_db_con(db_pool).await?;
+    let query = format!(
+        "DELETE FROM {} WHERE id = :id AND version = :version",
+        TABLE
+    );
+
+    let stmt = con
+        .execute_named(query.as_str(), &[("id", &id), ("version", &version)])
+        .map_err(Error::DBQuery)?;
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("user", id));
+    }
+
+    if let Err(e) = con.commit() {
+        con.rollback().map_err(Error::DBQuery)?;
+        return Err(Error::DBQuery(e));
+    }
+
+    Ok(())
+}
+
+fn fetch_user(con: &DBCon, id: u32) -> Result<User> {
+    let query = format!("SELECT {} FROM {} WHERE id = :id", SELECT_FIELDS, TABLE);
+
+    let row = con
+        .query_row_named(query.as_str(), &[("id", &id)])
+        .map_err(|e| match e {
+            oracle::Error::NoDataFound => Error::UserNotFound(id),
+            _ => Error::DBQuery(e),
+        })?;
+
+    Ok(row_to_user(&row))
+}
+
+fn update_user(con: &DBCon, id: u32, version: u32, body: &UpdateUserRequest) -> Result<()> {
+    let password_hash = body.password.as_deref().map(password::hash).transpose()?;
+    let query = format!(
+        "UPDATE {} SET role = :role, active = :active, \
+        password_hash = NVL(:password_hash, password_hash) WHERE id = :id AND version = :version",
+        TABLE
+    );
+
+    let stmt = con
+        .execute_named(
+            query.as_str(),
+            &[
+                ("id", &id),
+                ("version", &version),
+                ("role", &String::from(body.role)),
+                ("active", &i32::from(body.active)),
+                ("password_hash", &password_hash),
+            ],
+        )
+        .map_err(Error::DBQuery)?;
+    if stmt.row_count().map_err(Error::DBQuery)? == 0 {
+        return Err(Error::VersionConflict("user", id));
+    }
+
+    if !body.active || password_hash.is_some() {
+        let query = format!("DELETE FROM {} WHERE user_id = :id", SESSION_TABLE);
+        con.execute_named(query.as_str(), &[("id", &id)])
+            .map_err(Error::DBQuery)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use chrono::NaiveDate;
+use warp::http::header::{HeaderValue, LINK};
+use warp::reply::Response;
+use warp::Reply;
+
+pub const DEPRECATION: &str = "deprecation";
+pub const SUNSET: &str = "sunset";
+
+/// Deprecation of a version of the API, announced on each of its responses by the
+/// `Deprecation` (RFC 9745) and `Sunset` (RFC 8594) headers.
+#[derive(Clone, Debug, Default)]
+pub struct Deprecation {
+    pub deprecated_on: Option<NaiveDate>,
+    /// Day after which the version may stop being served.
+    pub sunset_on: Option<NaiveDate>,
+    /// Path prefix of the version replacing the deprecated one, if any.
+    pub successor: Option<&'static str>,
+}
+
+impl Deprecation {
+    /// Adds the deprecation headers to the response of a route of the version.
+    pub fn announce(&self, reply: impl Reply) -> Response {
+        let mut response = reply.into_response();
+        let headers = response.headers_mut();
+
+        if let Some(deprecated_on) = self.deprecated_on {
+            let timestamp = deprecated_on
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc()
+                .timestamp();
+            if let Ok(value) = HeaderValue::from_str(&format!("@{}", timestamp)) {
+                headers.insert(DEPRECATION, value);
+            }
+
+            if let Some(successor) = self.successor {
+                let link = format!("<{}>; rel=\"successor-version\"", successor);
+                if let Ok(value) = HeaderValue::from_str(&link) {
+                    headers.append(LINK, value);
+                }
+            }
+        }
+
+        if let Some(sunset_on) = self.sunset_on {
+            let sunset = sunset_on
+                .and_hms_opt(23, 59, 59)
+                .unwrap()
+                .format("%a, %d %b %Y %H:%M:%S GMT");
+            if let Ok(value) = HeaderValue::from_str(&sunset.to_string()) {
+                headers.insert(SUNSET, value);
+            }
+        }
+
+        response
+    }
+}
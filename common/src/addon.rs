@@ -0,0 +1,99 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct Addon {
+    pub id: u32,
+    pub description: String,
+    pub price: f64,
+    pub version: u32,
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct AddonRequest {
+    #[validate(length(min = 3, max = 100))]
+    pub description: String,
+    #[validate(range(min = 0.0))]
+    pub price: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct AddonResponse {
+    pub id: u32,
+    pub description: String,
+    pub price: f64,
+    pub version: u32,
+}
+
+impl From<Addon> for AddonResponse {
+    fn from(addon: Addon) -> Self {
+        AddonResponse {
+            id: addon.id,
+            description: addon.description,
+            price: addon.price,
+            version: addon.version,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct ContractAddon {
+    pub id: u32,
+    pub contract_id: u32,
+    pub addon_id: u32,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    pub price: f64,
+    pub version: u32,
+}
+
+impl ContractAddon {
+    /// An add-on is billed on every invoice whose period overlaps its own.
+    pub fn is_billed_between(&self, issue_date: DateTime<Utc>, due_date: DateTime<Utc>) -> bool {
+        self.start_date <= due_date && self.end_date >= issue_date
+    }
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+#[validate(schema(function = "crate::validation_config::validate_contract_addon_request"))]
+pub struct ContractAddonRequest {
+    pub addon_id: u32,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    /// Defaults to the catalog price of the add-on.
+    #[serde(default)]
+    #[validate(range(min = 0.0))]
+    pub price: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct ContractAddonResponse {
+    pub id: u32,
+    pub contract_id: u32,
+    pub addon_id: u32,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    pub price: f64,
+    pub version: u32,
+}
+
+impl From<ContractAddon> for ContractAddonResponse {
+    fn from(contract_addon: ContractAddon) -> Self {
+        ContractAddonResponse {
+            id: contract_addon.id,
+            contract_id: contract_addon.contract_id,
+            addon_id: contract_addon.addon_id,
+            start_date: contract_addon.start_date,
+            end_date: contract_addon.end_date,
+            price: contract_addon.price,
+            version: contract_addon.version,
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use crate::validation_config::RE_POSTAL_CODE;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub enum AddressType {
+    #[default]
+    #[serde(rename = "BILLING")]
+    Billing,
+    #[serde(rename = "SERVICE")]
+    Service,
+    #[serde(rename = "CORRESPONDENCE")]
+    Correspondence,
+}
+
+impl From<AddressType> for String {
+    fn from(address_type: AddressType) -> Self {
+        match address_type {
+            AddressType::Billing => "BILLING".to_string(),
+            AddressType::Service => "SERVICE".to_string(),
+            AddressType::Correspondence => "CORRESPONDENCE".to_string(),
+        }
+    }
+}
+
+impl From<String> for AddressType {
+    fn from(address_type: String) -> Self {
+        match address_type.as_str() {
+            "BILLING" => AddressType::Billing,
+            "SERVICE" => AddressType::Service,
+            "CORRESPONDENCE" => AddressType::Correspondence,
+            _ => AddressType::Billing,
+        }
+    }
+}
+
+impl fmt::Display for AddressType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AddressType::Billing => write!(f, "BILLING"),
+            AddressType::Service => write!(f, "SERVICE INSTALLATION"),
+            AddressType::Correspondence => write!(f, "CORRESPONDENCE"),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct Address {
+    pub id: u32,
+    pub customer_id: u32,
+    pub address_type: AddressType,
+    pub street: String,
+    pub number: String,
+    pub city: String,
+    pub county: String,
+    pub postal_code: String,
+    pub version: u32,
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct AddressRequest {
+    pub address_type: AddressType,
+    #[validate(length(min = 3, max = 100))]
+    pub street: String,
+    #[validate(length(min = 1, max = 20))]
+    pub number: String,
+    #[validate(length(min = 2, max = 50))]
+    pub city: String,
+    #[validate(length(min = 2, max = 50))]
+    pub county: String,
+    #[validate(regex = "RE_POSTAL_CODE")]
+    pub postal_code: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct AddressResponse {
+    pub id: u32,
+    pub customer_id: u32,
+    pub address_type: AddressType,
+    pub street: String,
+    pub number: String,
+    pub city: String,
+    pub county: String,
+    pub postal_code: String,
+    pub version: u32,
+}
+
+impl From<Address> for AddressResponse {
+    fn from(address: Address) -> Self {
+        AddressResponse {
+            id: address.id,
+            customer_id: address.customer_id,
+            address_type: address.address_type,
+            street: address.street,
+            number: address.number,
+            city: address.city,
+            county: address.county,
+            postal_code: address.postal_code,
+            version: address.version,
+        }
+    }
+}
+
+impl fmt::Display for AddressResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}, {}, {} {}",
+            self.street, self.number, self.city, self.county, self.postal_code
+        )
+    }
+}
@@ -0,0 +1,78 @@
+use crate::error::{ErrorCode, FieldError};
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationErrors};
+
+/// How the items of a bulk request are applied.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum BulkMode {
+    /// Either all the items are applied or, if any of them fails, none is.
+    #[default]
+    Transactional,
+    /// The valid items are applied even if others fail.
+    BestEffort,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct BulkQuery {
+    #[serde(default)]
+    pub mode: BulkMode,
+}
+
+/// Item of a bulk update, changing the record with the `id` if it is still at the
+/// `version` it was fetched at.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct BulkUpdate<T> {
+    pub id: u32,
+    pub version: u32,
+    #[serde(flatten)]
+    pub request: T,
+}
+
+impl<T: Validate> Validate for BulkUpdate<T> {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        self.request.validate()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum BulkItemStatus {
+    Created,
+    Updated,
+    Failed,
+    /// Valid, but not saved since the request was a dry run.
+    Valid,
+    /// Valid, but undone because another item of the transactional request failed.
+    RolledBack,
+}
+
+/// Outcome of an item of a bulk request, at the same `index` as in the request.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct BulkItemResult<T> {
+    pub index: usize,
+    pub status: BulkItemStatus,
+    /// Record as created or updated by the item.
+    pub record: Option<T>,
+    /// Status code the item would have failed with as a request of its own.
+    pub code: Option<u16>,
+    pub error_code: Option<ErrorCode>,
+    pub message: Option<String>,
+    pub errors: Option<Vec<FieldError>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct BulkResponse<T> {
+    pub mode: BulkMode,
+    /// Whether the changes of the succeeded items were saved.
+    pub committed: bool,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub items: Vec<BulkItemResult<T>>,
+}
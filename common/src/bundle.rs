@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct Bundle {
+    pub id: u32,
+    pub description: String,
+    pub price: f64,
+    pub subscription_ids: Vec<u32>,
+    pub version: u32,
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct BundleRequest {
+    #[validate(length(min = 3, max = 100))]
+    pub description: String,
+    #[validate(range(min = 0.0))]
+    pub price: f64,
+    #[validate(
+        length(min = 2),
+        custom(function = "crate::validation_config::validate_bundle_subscription_ids")
+    )]
+    pub subscription_ids: Vec<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct BundleResponse {
+    pub id: u32,
+    pub description: String,
+    pub price: f64,
+    pub subscription_ids: Vec<u32>,
+    pub version: u32,
+}
+
+impl From<Bundle> for BundleResponse {
+    fn from(bundle: Bundle) -> Self {
+        BundleResponse {
+            id: bundle.id,
+            description: bundle.description,
+            price: bundle.price,
+            subscription_ids: bundle.subscription_ids,
+            version: bundle.version,
+        }
+    }
+}
+
+/// Splits `amount` between the components of a bundle proportionally to their
+/// standalone `prices`, so that the shares add up to `amount` to the cent.
+pub fn allocate(amount: f64, prices: &[f64]) -> Vec<f64> {
+    let total: f64 = prices.iter().sum();
+    let mut shares: Vec<f64> = prices
+        .iter()
+        .map(|price| {
+            if total > 0.0 {
+                crate::penalty::round_to_cents(amount * price / total)
+            } else {
+                crate::penalty::round_to_cents(amount / prices.len() as f64)
+            }
+        })
+        .collect();
+
+    let rounding = crate::penalty::round_to_cents(amount - shares.iter().sum::<f64>());
+    if let Some(last) = shares.last_mut() {
+        *last = crate::penalty::round_to_cents(*last + rounding);
+    }
+
+    shares
+}
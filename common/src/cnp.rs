@@ -0,0 +1,148 @@
+use chrono::{NaiveDate, Utc};
+use std::fmt;
+
+const CONTROL_WEIGHTS: [u32; 12] = [2, 7, 9, 1, 4, 6, 3, 5, 8, 2, 7, 9];
+
+/// Data embedded in a Romanian personal numeric code (CNP), laid out as
+/// `S YY MM DD JJ NNN C`: sex and century, birth date, county, serial number
+/// and control digit.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Cnp {
+    pub birth_date: NaiveDate,
+    pub county_code: u32,
+    pub county: &'static str,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum CnpError {
+    Format,
+    SexDigit,
+    BirthDate,
+    County,
+    Serial,
+    ControlDigit,
+}
+
+impl fmt::Display for CnpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CnpError::Format => write!(f, "CNP should have exactly 13 digits"),
+            CnpError::SexDigit => write!(f, "CNP has an invalid sex digit"),
+            CnpError::BirthDate => write!(f, "CNP has an invalid birth date"),
+            CnpError::County => write!(f, "CNP has an invalid county code"),
+            CnpError::Serial => write!(f, "CNP has an invalid serial number"),
+            CnpError::ControlDigit => write!(f, "CNP has an invalid control digit"),
+        }
+    }
+}
+
+impl Cnp {
+    pub fn parse(cnp: &str) -> Result<Cnp, CnpError> {
+        if cnp.len() != 13 || !cnp.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(CnpError::Format);
+        }
+
+        let digits: Vec<u32> = cnp.bytes().map(|b| (b - b'0') as u32).collect();
+        let number = |from: usize, to: usize| digits[from..to].iter().fold(0, |n, d| n * 10 + d);
+
+        // 7 and 8 are residents and 9 foreigners, all of them conventionally born in the 1900s
+        let century = match digits[0] {
+            1 | 2 | 7 | 8 | 9 => 1900,
+            3 | 4 => 1800,
+            5 | 6 => 2000,
+            _ => return Err(CnpError::SexDigit),
+        };
+
+        let birth_date =
+            NaiveDate::from_ymd_opt((century + number(1, 3)) as i32, number(3, 5), number(5, 7))
+                .filter(|date| *date <= Utc::now().date_naive())
+                .ok_or(CnpError::BirthDate)?;
+
+        let county_code = number(7, 9);
+        let county = county_name(county_code).ok_or(CnpError::County)?;
+
+        if number(9, 12) == 0 {
+            return Err(CnpError::Serial);
+        }
+
+        let control = match digits
+            .iter()
+            .zip(CONTROL_WEIGHTS.iter())
+            .map(|(digit, weight)| digit * weight)
+            .sum::<u32>()
+            % 11
+        {
+            10 => 1,
+            control => control,
+        };
+
+        if control != digits[12] {
+            return Err(CnpError::ControlDigit);
+        }
+
+        Ok(Cnp {
+            birth_date,
+            county_code,
+            county,
+        })
+    }
+}
+
+fn county_name(code: u32) -> Option<&'static str> {
+    let name = match code {
+        1 => "Alba",
+        2 => "Arad",
+        3 => "Argeș",
+        4 => "Bacău",
+        5 => "Bihor",
+        6 => "Bistrița-Năsăud",
+        7 => "Botoșani",
+        8 => "Brașov",
+        9 => "Brăila",
+        10 => "Buzău",
+        11 => "Caraș-Severin",
+        12 => "Cluj",
+        13 => "Constanța",
+        14 => "Covasna",
+        15 => "Dâmbovița",
+        16 => "Dolj",
+        17 => "Galați",
+        18 => "Gorj",
+        19 => "Harghita",
+        20 => "Hunedoara",
+        21 => "Ialomița",
+        22 => "Iași",
+        23 => "Ilfov",
+        24 => "Maramureș",
+        25 => "Mehedinți",
+        26 => "Mureș",
+        27 => "Neamț",
+        28 => "Olt",
+        29 => "Prahova",
+        30 => "Satu Mare",
+        31 => "Sălaj",
+        32 => "Sibiu",
+        33 => "Suceava",
+        34 => "Teleorman",
+        35 => "Timiș",
+        36 => "Tulcea",
+        37 => "Vaslui",
+        38 => "Vâlcea",
+        39 => "Vrancea",
+        40 => "București",
+        41 => "București - Sector 1",
+        42 => "București - Sector 2",
+        43 => "București - Sector 3",
+        44 => "București - Sector 4",
+        45 => "București - Sector 5",
+        46 => "București - Sector 6",
+        47 => "București - Sector 7",
+        48 => "București - Sector 8",
+        51 => "Călărași",
+        52 => "Giurgiu",
+        70 => "Any county",
+        _ => return None,
+    };
+
+    Some(name)
+}
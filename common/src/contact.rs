@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub enum ContactRole {
+    #[default]
+    #[serde(rename = "PRIMARY")]
+    Primary,
+    #[serde(rename = "BILLING")]
+    Billing,
+    #[serde(rename = "TECHNICAL")]
+    Technical,
+}
+
+impl From<ContactRole> for String {
+    fn from(role: ContactRole) -> Self {
+        match role {
+            ContactRole::Primary => "PRIMARY".to_string(),
+            ContactRole::Billing => "BILLING".to_string(),
+            ContactRole::Technical => "TECHNICAL".to_string(),
+        }
+    }
+}
+
+impl From<String> for ContactRole {
+    fn from(role: String) -> Self {
+        match role.as_str() {
+            "PRIMARY" => ContactRole::Primary,
+            "BILLING" => ContactRole::Billing,
+            "TECHNICAL" => ContactRole::Technical,
+            _ => ContactRole::Primary,
+        }
+    }
+}
+
+impl fmt::Display for ContactRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContactRole::Primary => write!(f, "PRIMARY"),
+            ContactRole::Billing => write!(f, "BILLING"),
+            ContactRole::Technical => write!(f, "TECHNICAL"),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct Contact {
+    pub id: u32,
+    pub customer_id: u32,
+    pub role: ContactRole,
+    pub name: String,
+    pub email: String,
+    pub phone: Option<String>,
+    pub version: u32,
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct ContactRequest {
+    pub role: ContactRole,
+    #[validate(length(min = 3, max = 100))]
+    pub name: String,
+    #[validate(email, length(max = 100))]
+    pub email: String,
+    #[serde(default)]
+    #[validate(custom = "crate::validation_config::validate_phone")]
+    pub phone: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct ContactResponse {
+    pub id: u32,
+    pub customer_id: u32,
+    pub role: ContactRole,
+    pub name: String,
+    pub email: String,
+    pub phone: Option<String>,
+    pub version: u32,
+}
+
+impl From<Contact> for ContactResponse {
+    fn from(contact: Contact) -> Self {
+        ContactResponse {
+            id: contact.id,
+            customer_id: contact.customer_id,
+            role: contact.role,
+            name: contact.name,
+            email: contact.email,
+            phone: contact.phone,
+            version: contact.version,
+        }
+    }
+}
@@ -1,39 +1,116 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
 #[derive(Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 pub struct Contract {
     pub id: u32,
     pub customer_id: u32,
-    pub subscription_id: u32,
+    /// Exactly one of `subscription_id` and `bundle_id` is set.
+    pub subscription_id: Option<u32>,
+    pub bundle_id: Option<u32>,
     pub start_date: DateTime<Utc>,
     pub end_date: DateTime<Utc>,
+    pub penalty_rate: f64,
+    pub promotion_id: Option<u32>,
+    /// Service installation address of the customer where the line is installed.
+    pub service_address_id: Option<u32>,
+    /// Incremented on each update, the entity tag of the contract.
+    pub version: u32,
 }
 
 #[derive(Serialize, Deserialize, Validate, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 #[validate(schema(function = "crate::validation_config::validate_create_contract_request"))]
 pub struct CreateContractRequest {
     pub customer_id: u32,
-    pub subscription_id: u32,
+    #[serde(default)]
+    pub subscription_id: Option<u32>,
+    #[serde(default)]
+    pub bundle_id: Option<u32>,
     pub start_date: DateTime<Utc>,
     pub end_date: DateTime<Utc>,
+    #[serde(default)]
+    #[validate(range(min = 0.0, max = 100.0))]
+    pub penalty_rate: f64,
+    #[serde(default)]
+    pub coupon_code: Option<String>,
+    #[serde(default)]
+    pub service_address_id: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct CreateContractQuery {
+    /// Creates the contract even if the credit check of the customer requires a
+    /// security deposit, recording the deposit as pending.
+    #[serde(default)]
+    pub accept_deposit: bool,
 }
 
 #[derive(Serialize, Deserialize, Validate, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 #[validate(schema(function = "crate::validation_config::validate_update_contract_request"))]
 pub struct UpdateContractRequest {
     pub start_date: DateTime<Utc>,
     pub end_date: DateTime<Utc>,
+    /// Kept as stored when not given.
+    #[serde(default)]
+    #[validate(range(min = 0.0, max = 100.0))]
+    pub penalty_rate: Option<f64>,
+    #[serde(default)]
+    pub service_address_id: Option<u32>,
+}
+
+impl From<Contract> for UpdateContractRequest {
+    fn from(contract: Contract) -> Self {
+        UpdateContractRequest {
+            start_date: contract.start_date,
+            end_date: contract.end_date,
+            penalty_rate: Some(contract.penalty_rate),
+            service_address_id: contract.service_address_id,
+        }
+    }
+}
+
+/// Filters the listed contracts by customer, by offering, by start date (`from`
+/// and `to` inclusive), by tag name and by custom field value, `value` applying to
+/// the field with the `field` key.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct ContractListQuery {
+    #[serde(default)]
+    pub customer_id: Option<u32>,
+    #[serde(default)]
+    pub subscription_id: Option<u32>,
+    #[serde(default)]
+    pub bundle_id: Option<u32>,
+    #[serde(default)]
+    pub from: Option<NaiveDate>,
+    #[serde(default)]
+    pub to: Option<NaiveDate>,
+    #[serde(default)]
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub field: Option<String>,
+    #[serde(default)]
+    pub value: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 pub struct ContractResponse {
     pub id: u32,
     pub customer_id: u32,
-    pub subscription_id: u32,
+    pub subscription_id: Option<u32>,
+    pub bundle_id: Option<u32>,
     pub start_date: DateTime<Utc>,
     pub end_date: DateTime<Utc>,
+    pub penalty_rate: f64,
+    pub promotion_id: Option<u32>,
+    pub service_address_id: Option<u32>,
+    pub version: u32,
 }
 
 impl From<Contract> for ContractResponse {
@@ -42,8 +119,13 @@ impl From<Contract> for ContractResponse {
             id: contract.id,
             customer_id: contract.customer_id,
             subscription_id: contract.subscription_id,
+            bundle_id: contract.bundle_id,
             start_date: contract.start_date,
             end_date: contract.end_date,
+            penalty_rate: contract.penalty_rate,
+            promotion_id: contract.promotion_id,
+            service_address_id: contract.service_address_id,
+            version: contract.version,
         }
     }
 }
@@ -0,0 +1,228 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Thresholds of the risk assessment run before a contract is created. A
+/// threshold of 0 disables the rule.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CreditRules {
+    /// Overdue invoices from which new contracts are refused.
+    pub block_overdue_invoices: u32,
+    /// Outstanding balance from which new contracts are refused.
+    pub block_balance: f64,
+    /// Overdue invoices from which new contracts require a security deposit.
+    pub deposit_overdue_invoices: u32,
+    /// Outstanding balance from which new contracts require a security deposit.
+    pub deposit_balance: f64,
+    /// Tags of the customers whose new contracts require a security deposit.
+    pub deposit_tags: Vec<String>,
+    /// Security deposit, in months of the price of the subscription or bundle.
+    pub deposit_months: f64,
+}
+
+/// Unpaid invoices of a customer across all its contracts.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct CreditExposure {
+    /// Unpaid invoices past their due date.
+    pub overdue_invoices: u32,
+    /// Amount still to be paid on all the unpaid invoices.
+    pub outstanding_balance: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub enum CreditDecision {
+    #[serde(rename = "APPROVED")]
+    Approved,
+    #[serde(rename = "DEPOSIT_REQUIRED")]
+    DepositRequired,
+    #[serde(rename = "BLOCKED")]
+    Blocked,
+}
+
+impl fmt::Display for CreditDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CreditDecision::Approved => write!(f, "APPROVED"),
+            CreditDecision::DepositRequired => write!(f, "DEPOSIT_REQUIRED"),
+            CreditDecision::Blocked => write!(f, "BLOCKED"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct CreditCheckQuery {
+    /// Offering the security deposit is computed for, if any.
+    #[serde(default)]
+    pub subscription_id: Option<u32>,
+    #[serde(default)]
+    pub bundle_id: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct CreditAssessmentResponse {
+    pub customer_id: u32,
+    pub overdue_invoices: u32,
+    pub outstanding_balance: f64,
+    pub decision: CreditDecision,
+    /// Security deposit required for a contract with the given monthly price.
+    pub required_deposit: f64,
+    /// Rules that led to the decision.
+    pub reasons: Vec<String>,
+}
+
+/// Applies the rules to the unpaid invoices and the tags of a customer about to
+/// sign a contract with the given monthly price.
+pub fn assess(
+    customer_id: u32,
+    exposure: &CreditExposure,
+    tags: &[String],
+    monthly_price: f64,
+    rules: &CreditRules,
+) -> CreditAssessmentResponse {
+    let mut blocking = Vec::new();
+    let mut deposit = Vec::new();
+
+    if rules.block_overdue_invoices > 0 && exposure.overdue_invoices >= rules.block_overdue_invoices
+    {
+        blocking.push(format!(
+            "{} overdue invoices (limit {})",
+            exposure.overdue_invoices, rules.block_overdue_invoices
+        ));
+    }
+
+    if rules.block_balance > 0.0 && exposure.outstanding_balance >= rules.block_balance {
+        blocking.push(format!(
+            "outstanding balance of {:.2} (limit {:.2})",
+            exposure.outstanding_balance, rules.block_balance
+        ));
+    }
+
+    if rules.deposit_overdue_invoices > 0
+        && exposure.overdue_invoices >= rules.deposit_overdue_invoices
+    {
+        deposit.push(format!("{} overdue invoices", exposure.overdue_invoices));
+    }
+
+    if rules.deposit_balance > 0.0 && exposure.outstanding_balance >= rules.deposit_balance {
+        deposit.push(format!(
+            "outstanding balance of {:.2}",
+            exposure.outstanding_balance
+        ));
+    }
+
+    for tag in tags.iter().filter(|tag| rules.deposit_tags.contains(tag)) {
+        deposit.push(format!("tagged {}", tag));
+    }
+
+    let (decision, reasons) = if !blocking.is_empty() {
+        (CreditDecision::Blocked, blocking)
+    } else if !deposit.is_empty() {
+        (CreditDecision::DepositRequired, deposit)
+    } else {
+        (CreditDecision::Approved, vec![])
+    };
+
+    let required_deposit = if decision == CreditDecision::DepositRequired {
+        (monthly_price * rules.deposit_months * 100.0).round() / 100.0
+    } else {
+        0.0
+    };
+
+    CreditAssessmentResponse {
+        customer_id,
+        overdue_invoices: exposure.overdue_invoices,
+        outstanding_balance: exposure.outstanding_balance,
+        decision,
+        required_deposit,
+        reasons,
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub enum DepositStatus {
+    /// Required when the contract was created, not paid yet.
+    #[serde(rename = "PENDING")]
+    Pending,
+    #[serde(rename = "HELD")]
+    Held,
+    #[serde(rename = "REFUNDED")]
+    Refunded,
+}
+
+impl From<DepositStatus> for String {
+    fn from(status: DepositStatus) -> Self {
+        match status {
+            DepositStatus::Pending => "PENDING".to_string(),
+            DepositStatus::Held => "HELD".to_string(),
+            DepositStatus::Refunded => "REFUNDED".to_string(),
+        }
+    }
+}
+
+impl From<String> for DepositStatus {
+    fn from(status: String) -> Self {
+        match status.as_str() {
+            "PENDING" => DepositStatus::Pending,
+            "HELD" => DepositStatus::Held,
+            "REFUNDED" => DepositStatus::Refunded,
+            _ => DepositStatus::Pending,
+        }
+    }
+}
+
+impl fmt::Display for DepositStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DepositStatus::Pending => write!(f, "PENDING"),
+            DepositStatus::Held => write!(f, "HELD"),
+            DepositStatus::Refunded => write!(f, "REFUNDED"),
+        }
+    }
+}
+
+/// Security deposit of a contract, refunded at the end of the contract less the
+/// balance its invoices still have.
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct Deposit {
+    pub id: u32,
+    pub contract_id: u32,
+    pub amount: f64,
+    pub status: DepositStatus,
+    pub created_at: DateTime<Utc>,
+    pub paid_at: Option<DateTime<Utc>>,
+    pub refunded_at: Option<DateTime<Utc>>,
+    pub refunded_amount: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct DepositResponse {
+    pub id: u32,
+    pub contract_id: u32,
+    pub amount: f64,
+    pub status: DepositStatus,
+    pub created_at: DateTime<Utc>,
+    pub paid_at: Option<DateTime<Utc>>,
+    pub refunded_at: Option<DateTime<Utc>>,
+    pub refunded_amount: Option<f64>,
+}
+
+impl From<Deposit> for DepositResponse {
+    fn from(deposit: Deposit) -> Self {
+        DepositResponse {
+            id: deposit.id,
+            contract_id: deposit.contract_id,
+            amount: deposit.amount,
+            status: deposit.status,
+            created_at: deposit.created_at,
+            paid_at: deposit.paid_at,
+            refunded_at: deposit.refunded_at,
+            refunded_amount: deposit.refunded_amount,
+        }
+    }
+}
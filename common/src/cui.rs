@@ -0,0 +1,149 @@
+use std::fmt;
+
+const CONTROL_KEY: [u32; 9] = [7, 5, 3, 2, 1, 7, 5, 3, 2];
+
+/// Romanian fiscal identification code (CUI/CIF) of a legal entity: 2 to 10 digits,
+/// the last one being a control digit, optionally prefixed by `RO` for VAT payers.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Cui {
+    pub number: String,
+    pub vat_prefix: bool,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum CuiError {
+    Format,
+    ControlDigit,
+}
+
+impl fmt::Display for CuiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CuiError::Format => write!(f, "CUI should have between 2 and 10 digits"),
+            CuiError::ControlDigit => write!(f, "CUI has an invalid control digit"),
+        }
+    }
+}
+
+impl Cui {
+    pub fn parse(cui: &str) -> Result<Cui, CuiError> {
+        let cui = cui.trim().to_uppercase();
+        let (number, vat_prefix) = match cui.strip_prefix("RO") {
+            Some(number) => (number.trim_start(), true),
+            None => (cui.as_str(), false),
+        };
+
+        if !(2..=10).contains(&number.len()) || !number.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(CuiError::Format);
+        }
+
+        let digits: Vec<u32> = number.bytes().map(|b| (b - b'0') as u32).collect();
+        let (control_digit, body) = digits.split_last().unwrap();
+
+        // the digits are right-aligned against the control key
+        let sum: u32 = body
+            .iter()
+            .rev()
+            .zip(CONTROL_KEY.iter().rev())
+            .map(|(digit, weight)| digit * weight)
+            .sum();
+
+        let control = match sum * 10 % 11 {
+            10 => 0,
+            control => control,
+        };
+
+        if control != *control_digit {
+            return Err(CuiError::ControlDigit);
+        }
+
+        Ok(Cui {
+            number: number.to_string(),
+            vat_prefix,
+        })
+    }
+}
+
+impl fmt::Display for Cui {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.vat_prefix {
+            write!(f, "RO{}", self.number)
+        } else {
+            write!(f, "{}", self.number)
+        }
+    }
+}
+
+/// VAT identification number of a business registered in another EU member state:
+/// the two-letter country code followed by 2 to 12 letters and digits, with the
+/// separators removed, e.g. `DE123456789`. Only its format is checked, the rules
+/// of each state differing.
+#[derive(Clone, PartialEq, Debug)]
+pub struct VatNumber {
+    pub country: String,
+    pub number: String,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum VatNumberError {
+    Format,
+    Romanian,
+}
+
+impl fmt::Display for VatNumberError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VatNumberError::Format => write!(
+                f,
+                "VAT number should be a country code followed by 2 to 12 letters and digits"
+            ),
+            VatNumberError::Romanian => {
+                write!(f, "Romanian businesses should be identified by their CUI")
+            }
+        }
+    }
+}
+
+impl VatNumber {
+    pub fn parse(vat_number: &str) -> Result<VatNumber, VatNumberError> {
+        let vat_number: String = vat_number
+            .chars()
+            .filter(|c| !matches!(c, ' ' | '-' | '.'))
+            .collect::<String>()
+            .to_uppercase();
+
+        if !VatNumber::is_foreign(&vat_number) {
+            return Err(if vat_number.starts_with("RO") {
+                VatNumberError::Romanian
+            } else {
+                VatNumberError::Format
+            });
+        }
+
+        let (country, number) = vat_number.split_at(2);
+        if !(2..=12).contains(&number.len()) || !number.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            return Err(VatNumberError::Format);
+        }
+
+        Ok(VatNumber {
+            country: country.to_string(),
+            number: number.to_string(),
+        })
+    }
+
+    /// Whether the code starts with the country code of a state other than Romania,
+    /// telling a VAT number of another member state from a CUI.
+    pub fn is_foreign(code: &str) -> bool {
+        let code = code.trim().to_uppercase();
+
+        code.len() >= 2
+            && code.as_bytes()[..2].iter().all(u8::is_ascii_alphabetic)
+            && !code.starts_with("RO")
+    }
+}
+
+impl fmt::Display for VatNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.country, self.number)
+    }
+}
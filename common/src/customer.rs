@@ -1,50 +1,238 @@
-use crate::validation_config::RE_CNP;
+use crate::cnp::Cnp;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use validator::Validate;
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub enum CustomerKind {
+    #[default]
+    #[serde(rename = "INDIVIDUAL")]
+    Individual,
+    #[serde(rename = "BUSINESS")]
+    Business,
+}
+
+impl From<CustomerKind> for String {
+    fn from(kind: CustomerKind) -> Self {
+        match kind {
+            CustomerKind::Individual => "INDIVIDUAL".to_string(),
+            CustomerKind::Business => "BUSINESS".to_string(),
+        }
+    }
+}
+
+impl From<String> for CustomerKind {
+    fn from(kind: String) -> Self {
+        match kind.as_str() {
+            "INDIVIDUAL" => CustomerKind::Individual,
+            "BUSINESS" => CustomerKind::Business,
+            _ => CustomerKind::Individual,
+        }
+    }
+}
+
+impl fmt::Display for CustomerKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CustomerKind::Individual => write!(f, "INDIVIDUAL"),
+            CustomerKind::Business => write!(f, "BUSINESS"),
+        }
+    }
+}
+
 #[derive(Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 pub struct Customer {
     pub id: u32,
+    pub kind: CustomerKind,
     pub name: String,
     pub fullname: String,
     pub address: String,
     pub phone: String,
-    pub cnp: String,
+    /// Set for individuals only.
+    pub cnp: Option<String>,
+    /// Set for businesses only: their CUI or, if reverse charged, their EU VAT number.
+    /// The trade register number and legal representative are set for the Romanian
+    /// businesses, and may be for the others.
+    pub cui: Option<String>,
+    pub trade_register_number: Option<String>,
+    pub vat_payer: bool,
+    /// Set for VAT payers established in another EU member state, which account
+    /// for the VAT on the services they receive themselves.
+    pub reverse_charge: bool,
+    pub legal_representative: Option<String>,
+    /// Set once the personal data of the customer has been scrubbed, the phone
+    /// being empty from then on.
+    pub anonymized_at: Option<DateTime<Utc>>,
+    /// Incremented on each update, the entity tag of the customer.
+    pub version: u32,
+}
+
+impl Customer {
+    /// VAT on services supplied to a business registered for VAT in another EU
+    /// member state is accounted for by the customer, so it is not charged on its
+    /// invoices. Romanian VAT payers are charged VAT as everyone else.
+    pub fn is_reverse_charge(&self) -> bool {
+        self.kind == CustomerKind::Business && self.vat_payer && self.reverse_charge
+    }
 }
 
 #[derive(Serialize, Deserialize, Validate, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+#[validate(schema(function = "crate::validation_config::validate_customer_request"))]
 pub struct CustomerRequest {
+    #[serde(default)]
+    pub kind: CustomerKind,
     #[validate(length(min = 3, max = 20))]
     pub name: String,
     #[validate(length(min = 3, max = 50))]
     pub fullname: String,
     #[validate(length(min = 3, max = 100))]
     pub address: String,
-    #[validate(phone)]
+    #[validate(custom = "crate::validation_config::validate_phone")]
     pub phone: String,
-    #[validate(regex = "RE_CNP")]
-    pub cnp: String,
+    #[serde(default)]
+    #[validate(custom = "crate::validation_config::validate_cnp")]
+    pub cnp: Option<String>,
+    #[serde(default)]
+    /// CUI or, for businesses invoiced with reverse charge, their EU VAT number.
+    #[validate(custom = "crate::validation_config::validate_cui")]
+    pub cui: Option<String>,
+    /// Checked against the Romanian format unless the business is reverse charged.
+    #[serde(default)]
+    #[validate(length(min = 1, max = 20))]
+    pub trade_register_number: Option<String>,
+    #[serde(default)]
+    pub vat_payer: bool,
+    /// Set for VAT payers established in another EU member state.
+    #[serde(default)]
+    pub reverse_charge: bool,
+    #[serde(default)]
+    #[validate(length(min = 3, max = 100))]
+    pub legal_representative: Option<String>,
+}
+
+/// Current state of the customer as a request, which a merge patch is applied to.
+impl From<Customer> for CustomerRequest {
+    fn from(customer: Customer) -> Self {
+        CustomerRequest {
+            kind: customer.kind,
+            name: customer.name,
+            fullname: customer.fullname,
+            address: customer.address,
+            phone: customer.phone,
+            cnp: customer.cnp,
+            cui: customer.cui,
+            trade_register_number: customer.trade_register_number,
+            vat_payer: customer.vat_payer,
+            reverse_charge: customer.reverse_charge,
+            legal_representative: customer.legal_representative,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct CreateCustomerQuery {
+    /// Creates the customer even if a customer with a similar name and the same
+    /// phone number exists. Customers with the same CNP or CUI are always rejected.
+    #[serde(default)]
+    pub force: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 pub struct CustomerResponse {
     pub id: u32,
+    pub kind: CustomerKind,
     pub name: String,
     pub fullname: String,
     pub address: String,
     pub phone: String,
-    pub cnp: String,
+    pub cnp: Option<String>,
+    /// Derived from the CNP, missing if the stored CNP is not valid.
+    pub birth_date: Option<NaiveDate>,
+    pub county: Option<String>,
+    pub cui: Option<String>,
+    pub trade_register_number: Option<String>,
+    pub vat_payer: bool,
+    pub reverse_charge: bool,
+    pub legal_representative: Option<String>,
+    pub anonymized_at: Option<DateTime<Utc>>,
+    pub version: u32,
 }
 
 impl From<Customer> for CustomerResponse {
     fn from(customer: Customer) -> Self {
+        let cnp = customer.cnp.as_deref().and_then(|cnp| Cnp::parse(cnp).ok());
+
         CustomerResponse {
             id: customer.id,
+            kind: customer.kind,
             name: customer.name,
             fullname: customer.fullname,
             address: customer.address,
             phone: customer.phone,
             cnp: customer.cnp,
+            birth_date: cnp.as_ref().map(|cnp| cnp.birth_date),
+            county: cnp.map(|cnp| cnp.county.to_string()),
+            cui: customer.cui,
+            trade_register_number: customer.trade_register_number,
+            vat_payer: customer.vat_payer,
+            reverse_charge: customer.reverse_charge,
+            legal_representative: customer.legal_representative,
+            anonymized_at: customer.anonymized_at,
+            version: customer.version,
+        }
+    }
+}
+
+/// Filters the listed customers by kind, by tag name and by custom field value,
+/// `value` applying to the field with the `field` key.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct CustomerListQuery {
+    #[serde(default)]
+    pub kind: Option<CustomerKind>,
+    #[serde(default)]
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub field: Option<String>,
+    #[serde(default)]
+    pub value: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct CustomerSearchQuery {
+    #[validate(length(min = 2, max = 100))]
+    pub q: String,
+}
+
+/// A customer matching a search, along with its relevance and the fields that matched.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CustomerMatch {
+    pub customer: Customer,
+    pub score: u32,
+    pub matched_fields: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct CustomerSearchResponse {
+    pub score: u32,
+    pub matched_fields: Vec<String>,
+    pub customer: CustomerResponse,
+}
+
+impl From<CustomerMatch> for CustomerSearchResponse {
+    fn from(customer_match: CustomerMatch) -> Self {
+        CustomerSearchResponse {
+            score: customer_match.score,
+            matched_fields: customer_match.matched_fields,
+            customer: CustomerResponse::from(customer_match.customer),
         }
     }
 }
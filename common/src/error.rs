@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Media type of the problem details of a failed request (RFC 7807).
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// Errors of a field of a request failing validation.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct FieldError {
+    pub field: String,
+    pub field_errors: Vec<String>,
+}
+
+/// Stable code of the error a request failed with, which clients branch on rather
+/// than on its message.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    NotFound,
+    MethodNotAllowed,
+    InvalidBody,
+    ValidationFailed,
+    InternalError,
+    DatabaseError,
+    /// A unique constraint of the database was violated.
+    UniqueViolation,
+    /// The record is still referenced by other records.
+    FkConflict,
+    /// A check constraint of the database was violated.
+    CheckViolation,
+    InvoiceAlreadyPaid,
+    /// The payment exceeds what is left to pay of the invoice.
+    Overpayment,
+    CustomerNotFound,
+    AddressNotFound,
+    ContactNotFound,
+    NoteNotFound,
+    ContractNotFound,
+    InvoiceNotFound,
+    PaymentNotFound,
+    SubscriptionNotFound,
+    AddonNotFound,
+    BundleNotFound,
+    PromotionNotFound,
+    CouponNotFound,
+    TagNotFound,
+    CustomFieldNotFound,
+    DepositNotFound,
+    DuplicateCustomer,
+    CustomerAnonymized,
+    SelfMerge,
+    CouponNotApplicable,
+    CreditCheckFailed,
+    DepositRequired,
+    DepositNotPending,
+    DepositNotRefundable,
+    DuplicateTag,
+    UnknownTag,
+    DuplicateCustomField,
+    UnknownCustomField,
+    InvalidCustomField,
+    CustomFieldOptionInUse,
+    /// The dates of an invoice or add-on are outside those of its contract.
+    OutsideContractPeriod,
+    NotAServiceAddress,
+    ContractNotOfCustomer,
+    InvoiceNotOfCustomer,
+    PaymentBeforeInvoiceIssueDate,
+    InvalidSort,
+    IfMatchRequired,
+    VersionConflict,
+    InvalidIdempotencyKey,
+    IdempotencyKeyReused,
+    IdempotencyKeyInProgress,
+    TooManyBulkItems,
+    InvalidImport,
+    /// The request carries no session token, or an expired or revoked one.
+    Unauthenticated,
+    InvalidCredentials,
+    /// The request is reserved to administrators.
+    AdminRequired,
+    UserNotFound,
+    DuplicateUser,
+    /// Administrators cannot delete, deactivate or demote their own account.
+    OwnAccount,
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::String(code)) => f.write_str(&code),
+            _ => write!(f, "{:?}", self),
+        }
+    }
+}
+
+/// Problem details of a failed request (RFC 7807), served as `application/problem+json`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct Problem {
+    /// Always `about:blank`, the problem being told by its `code`.
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    /// Reason phrase of the status code.
+    pub title: String,
+    pub status: u16,
+    /// Explanation of this occurrence of the problem, in English.
+    pub detail: String,
+    pub code: ErrorCode,
+    /// Identifier of the occurrence, logged by the backend along with its cause and
+    /// sent in the `X-Correlation-Id` header.
+    pub correlation_id: String,
+    /// Errors of the fields that failed validation.
+    pub errors: Option<Vec<FieldError>>,
+}
@@ -0,0 +1,19 @@
+/// `If-Match` value matching any version of a record, overwriting it whatever its
+/// changes since it was fetched.
+pub const ANY: &str = "*";
+
+/// Strong entity tag of the version of a record.
+pub fn of(version: u32) -> String {
+    format!("\"{}\"", version)
+}
+
+/// Whether the `If-Match` value, a list of entity tags or `*`, matches the
+/// record at `version`. Weak tags never match.
+pub fn matches(if_match: &str, version: u32) -> bool {
+    let tag = of(version);
+
+    if_match
+        .split(',')
+        .map(str::trim)
+        .any(|candidate| candidate == ANY || candidate == tag)
+}
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+/// Format a list is returned in, either JSON pages or an export of every record
+/// matching its filters.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    Csv,
+    Xlsx,
+    /// Newline-delimited JSON, a record per line.
+    Ndjson,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 4] = [
+        ExportFormat::Json,
+        ExportFormat::Csv,
+        ExportFormat::Xlsx,
+        ExportFormat::Ndjson,
+    ];
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "application/json",
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Xlsx => {
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            }
+            ExportFormat::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Xlsx => "xlsx",
+            ExportFormat::Ndjson => "ndjson",
+        }
+    }
+
+    /// Format of the first media type of an `Accept` header that names one.
+    pub fn from_accept(accept: &str) -> Option<ExportFormat> {
+        accept
+            .split(',')
+            .filter_map(|media_type| media_type.split(';').next())
+            .map(|media_type| media_type.trim().to_lowercase())
+            .find_map(|media_type| {
+                ExportFormat::ALL
+                    .into_iter()
+                    .find(|format| format.content_type() == media_type)
+            })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct ExportQuery {
+    /// Overrides the format negotiated by the `Accept` header.
+    #[serde(default)]
+    pub format: Option<ExportFormat>,
+}
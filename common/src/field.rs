@@ -0,0 +1,316 @@
+use crate::validation_config::RE_FIELD_KEY;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use validator::Validate;
+
+/// Separator of the options of an enum field, as they are stored.
+pub const OPTIONS_SEPARATOR: char = ',';
+
+/// Format of the values of date fields.
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+pub const MAX_VALUE_LENGTH: usize = 500;
+pub const MAX_OPTIONS: usize = 50;
+
+/// Kind of record tags and custom fields are attached to.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub enum Entity {
+    #[default]
+    #[serde(rename = "CUSTOMER")]
+    Customer,
+    #[serde(rename = "CONTRACT")]
+    Contract,
+}
+
+impl From<Entity> for String {
+    fn from(entity: Entity) -> Self {
+        match entity {
+            Entity::Customer => "CUSTOMER".to_string(),
+            Entity::Contract => "CONTRACT".to_string(),
+        }
+    }
+}
+
+impl From<String> for Entity {
+    fn from(entity: String) -> Self {
+        match entity.as_str() {
+            "CUSTOMER" => Entity::Customer,
+            "CONTRACT" => Entity::Contract,
+            _ => Entity::Customer,
+        }
+    }
+}
+
+impl fmt::Display for Entity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Entity::Customer => write!(f, "CUSTOMER"),
+            Entity::Contract => write!(f, "CONTRACT"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub enum FieldType {
+    #[default]
+    #[serde(rename = "STRING")]
+    String,
+    #[serde(rename = "NUMBER")]
+    Number,
+    #[serde(rename = "DATE")]
+    Date,
+    #[serde(rename = "ENUM")]
+    Enum,
+}
+
+impl From<FieldType> for String {
+    fn from(field_type: FieldType) -> Self {
+        match field_type {
+            FieldType::String => "STRING".to_string(),
+            FieldType::Number => "NUMBER".to_string(),
+            FieldType::Date => "DATE".to_string(),
+            FieldType::Enum => "ENUM".to_string(),
+        }
+    }
+}
+
+impl From<String> for FieldType {
+    fn from(field_type: String) -> Self {
+        match field_type.as_str() {
+            "STRING" => FieldType::String,
+            "NUMBER" => FieldType::Number,
+            "DATE" => FieldType::Date,
+            "ENUM" => FieldType::Enum,
+            _ => FieldType::String,
+        }
+    }
+}
+
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldType::String => write!(f, "STRING"),
+            FieldType::Number => write!(f, "NUMBER"),
+            FieldType::Date => write!(f, "DATE"),
+            FieldType::Enum => write!(f, "ENUM"),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum CustomFieldError {
+    TooLong,
+    Number,
+    Date,
+    NotAnOption(Vec<String>),
+    MissingOptions,
+    UnexpectedOptions,
+    InvalidOption(String),
+}
+
+impl fmt::Display for CustomFieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CustomFieldError::TooLong => {
+                write!(
+                    f,
+                    "Value should have at most {} characters",
+                    MAX_VALUE_LENGTH
+                )
+            }
+            CustomFieldError::Number => write!(f, "Value should be a number"),
+            CustomFieldError::Date => write!(f, "Value should be a date formatted as YYYY-MM-DD"),
+            CustomFieldError::NotAnOption(options) => {
+                write!(f, "Value should be one of {}", options.join(", "))
+            }
+            CustomFieldError::MissingOptions => write!(
+                f,
+                "Enum fields should have between 1 and {} options",
+                MAX_OPTIONS
+            ),
+            CustomFieldError::UnexpectedOptions => {
+                write!(f, "Only enum fields should have options")
+            }
+            CustomFieldError::InvalidOption(option) => write!(
+                f,
+                "Option {:?} should be unique, not empty and without commas",
+                option
+            ),
+        }
+    }
+}
+
+/// Definition of an attribute stored for customers or contracts besides their
+/// fixed fields, identified by its `key`.
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct CustomField {
+    pub id: u32,
+    pub entity: Entity,
+    pub key: String,
+    pub label: String,
+    pub field_type: FieldType,
+    /// Allowed values, set for enum fields only.
+    pub options: Vec<String>,
+    pub version: u32,
+}
+
+impl CustomField {
+    /// Checks a value against the type of the field, returning it the way it is
+    /// stored and compared: numbers without trailing zeros and dates as YYYY-MM-DD.
+    pub fn parse_value(&self, value: &str) -> Result<String, CustomFieldError> {
+        let value = value.trim();
+
+        match self.field_type {
+            FieldType::String => {
+                if value.chars().count() > MAX_VALUE_LENGTH {
+                    return Err(CustomFieldError::TooLong);
+                }
+
+                Ok(value.to_string())
+            }
+            FieldType::Number => value
+                .parse::<f64>()
+                .ok()
+                .filter(|number| number.is_finite())
+                .map(|number| number.to_string())
+                .ok_or(CustomFieldError::Number),
+            FieldType::Date => NaiveDate::parse_from_str(value, DATE_FORMAT)
+                .map(|date| date.format(DATE_FORMAT).to_string())
+                .map_err(|_| CustomFieldError::Date),
+            FieldType::Enum => self
+                .options
+                .iter()
+                .find(|option| option.as_str() == value)
+                .cloned()
+                .ok_or_else(|| CustomFieldError::NotAnOption(self.options.clone())),
+        }
+    }
+
+    /// Enum fields need a set of distinct options, which other fields cannot have.
+    pub fn check_options(
+        field_type: &FieldType,
+        options: &[String],
+    ) -> Result<(), CustomFieldError> {
+        if *field_type != FieldType::Enum {
+            return if options.is_empty() {
+                Ok(())
+            } else {
+                Err(CustomFieldError::UnexpectedOptions)
+            };
+        }
+
+        if options.is_empty() || options.len() > MAX_OPTIONS {
+            return Err(CustomFieldError::MissingOptions);
+        }
+
+        for (index, option) in options.iter().enumerate() {
+            if option.trim().is_empty()
+                || option.trim() != option
+                || option.chars().count() > 50
+                || option.contains(OPTIONS_SEPARATOR)
+                || options[..index].contains(option)
+            {
+                return Err(CustomFieldError::InvalidOption(option.clone()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+#[validate(schema(function = "crate::validation_config::validate_custom_field_request"))]
+pub struct CustomFieldRequest {
+    #[serde(default)]
+    pub entity: Entity,
+    #[validate(regex = "RE_FIELD_KEY")]
+    pub key: String,
+    #[validate(length(min = 2, max = 50))]
+    pub label: String,
+    #[serde(default)]
+    pub field_type: FieldType,
+    #[serde(default)]
+    pub options: Vec<String>,
+}
+
+/// The entity, key and type of a field cannot change once values are stored for it.
+#[derive(Serialize, Deserialize, Validate, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct UpdateCustomFieldRequest {
+    #[validate(length(min = 2, max = 50))]
+    pub label: String,
+    #[serde(default)]
+    pub options: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct CustomFieldResponse {
+    pub id: u32,
+    pub entity: Entity,
+    pub key: String,
+    pub label: String,
+    pub field_type: FieldType,
+    pub options: Vec<String>,
+    pub version: u32,
+}
+
+impl From<CustomField> for CustomFieldResponse {
+    fn from(field: CustomField) -> Self {
+        CustomFieldResponse {
+            id: field.id,
+            entity: field.entity,
+            key: field.key,
+            label: field.label,
+            field_type: field.field_type,
+            options: field.options,
+            version: field.version,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct CustomFieldQuery {
+    /// Lists the fields of one entity only.
+    #[serde(default)]
+    pub entity: Option<Entity>,
+}
+
+/// Values of the custom fields of a customer or contract by field key, given as
+/// strings whatever the field type. Replaces all the values of the record, empty
+/// values clearing the field.
+pub type CustomFieldValues = BTreeMap<String, String>;
+
+/// Value of a custom field of a customer or contract.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CustomFieldValue {
+    pub field: CustomField,
+    pub value: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct CustomFieldValueResponse {
+    pub key: String,
+    pub label: String,
+    pub field_type: FieldType,
+    pub value: String,
+}
+
+impl From<CustomFieldValue> for CustomFieldValueResponse {
+    fn from(value: CustomFieldValue) -> Self {
+        CustomFieldValueResponse {
+            key: value.field.key,
+            label: value.field.label,
+            field_type: value.field.field_type,
+            value: value.value,
+        }
+    }
+}
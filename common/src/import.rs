@@ -0,0 +1,55 @@
+use crate::bulk::{BulkItemStatus, BulkMode};
+use crate::error::{ErrorCode, FieldError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Name of the multipart part of an import holding the file, CSV or XLSX.
+pub const FILE_PART: &str = "file";
+/// Name of the multipart part of an import holding its column mapping, a JSON
+/// object of the fields of the request by column of the file.
+pub const MAPPING_PART: &str = "mapping";
+
+/// Options of an import, which only validates the rows of the file, as a dry run,
+/// unless it is `commit`ted.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct ImportQuery {
+    #[serde(default)]
+    pub commit: bool,
+    #[serde(default)]
+    pub mode: BulkMode,
+}
+
+/// Outcome of the import of a row of the file.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct ImportRowResult<T> {
+    /// Line of the row in the file, its header being line 1.
+    pub line: usize,
+    /// Values of the mapped columns of the row, by field of the request.
+    pub values: BTreeMap<String, String>,
+    pub status: BulkItemStatus,
+    /// Record as created by the row.
+    pub record: Option<T>,
+    /// Status code the row would have failed with as a request of its own.
+    pub code: Option<u16>,
+    pub error_code: Option<ErrorCode>,
+    pub message: Option<String>,
+    pub errors: Option<Vec<FieldError>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct ImportResponse<T> {
+    /// Whether the records of the succeeded rows were saved.
+    pub committed: bool,
+    /// Columns of the header of the file.
+    pub columns: Vec<String>,
+    /// Fields of the request the columns can be mapped to.
+    pub fields: Vec<String>,
+    /// Field each column was mapped to.
+    pub mapping: BTreeMap<String, String>,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub rows: Vec<ImportRowResult<T>>,
+}
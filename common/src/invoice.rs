@@ -1,9 +1,10 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use validator::Validate;
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 pub enum InvoiceStatus {
     #[serde(rename = "PAID")]
     Paid,
@@ -40,6 +41,7 @@ impl fmt::Display for InvoiceStatus {
 }
 
 #[derive(Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 pub struct Invoice {
     pub id: u32,
     pub contract_id: u32,
@@ -47,9 +49,14 @@ pub struct Invoice {
     pub due_date: DateTime<Utc>,
     pub amount: f64,
     pub status: InvoiceStatus,
+    /// VAT percentage charged on the invoice, zero under reverse charge.
+    pub vat_rate: f64,
+    pub reverse_charge: bool,
+    pub version: u32,
 }
 
 #[derive(Serialize, Deserialize, Validate, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 #[validate(schema(function = "crate::validation_config::validate_create_invoice_request"))]
 pub struct CreateInvoiceRequest {
     pub contract_id: u32,
@@ -57,9 +64,27 @@ pub struct CreateInvoiceRequest {
     pub due_date: DateTime<Utc>,
     #[validate(range(min = 0.0))]
     pub amount: f64,
+    #[serde(default)]
+    pub bill_penalties: bool,
+}
+
+/// Filters the listed invoices by status, by contract and by issue date (`from`
+/// and `to` inclusive).
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct InvoiceListQuery {
+    #[serde(default)]
+    pub status: Option<InvoiceStatus>,
+    #[serde(default)]
+    pub contract_id: Option<u32>,
+    #[serde(default)]
+    pub from: Option<NaiveDate>,
+    #[serde(default)]
+    pub to: Option<NaiveDate>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 pub struct InvoiceResponse {
     pub id: u32,
     pub contract_id: u32,
@@ -67,6 +92,9 @@ pub struct InvoiceResponse {
     pub due_date: DateTime<Utc>,
     pub amount: f64,
     pub status: InvoiceStatus,
+    pub vat_rate: f64,
+    pub reverse_charge: bool,
+    pub version: u32,
 }
 
 impl From<Invoice> for InvoiceResponse {
@@ -78,6 +106,139 @@ impl From<Invoice> for InvoiceResponse {
             due_date: invoice.due_date,
             amount: invoice.amount,
             status: invoice.status,
+            vat_rate: invoice.vat_rate,
+            reverse_charge: invoice.reverse_charge,
+            version: invoice.version,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub enum InvoiceLineType {
+    #[serde(rename = "SUBSCRIPTION")]
+    Subscription,
+    #[serde(rename = "PENALTY")]
+    Penalty,
+    #[serde(rename = "DISCOUNT")]
+    Discount,
+    #[serde(rename = "BUNDLE_COMPONENT")]
+    BundleComponent,
+    #[serde(rename = "ADDON")]
+    Addon,
+    #[serde(rename = "VAT")]
+    Vat,
+}
+
+impl From<InvoiceLineType> for String {
+    fn from(line_type: InvoiceLineType) -> Self {
+        match line_type {
+            InvoiceLineType::Subscription => "SUBSCRIPTION".to_string(),
+            InvoiceLineType::Penalty => "PENALTY".to_string(),
+            InvoiceLineType::Discount => "DISCOUNT".to_string(),
+            InvoiceLineType::BundleComponent => "BUNDLE_COMPONENT".to_string(),
+            InvoiceLineType::Addon => "ADDON".to_string(),
+            InvoiceLineType::Vat => "VAT".to_string(),
+        }
+    }
+}
+
+impl From<String> for InvoiceLineType {
+    fn from(line_type: String) -> Self {
+        match line_type.as_str() {
+            "SUBSCRIPTION" => InvoiceLineType::Subscription,
+            "PENALTY" => InvoiceLineType::Penalty,
+            "DISCOUNT" => InvoiceLineType::Discount,
+            "BUNDLE_COMPONENT" => InvoiceLineType::BundleComponent,
+            "ADDON" => InvoiceLineType::Addon,
+            "VAT" => InvoiceLineType::Vat,
+            _ => InvoiceLineType::Subscription,
+        }
+    }
+}
+
+impl fmt::Display for InvoiceLineType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvoiceLineType::Subscription => write!(f, "SUBSCRIPTION"),
+            InvoiceLineType::Penalty => write!(f, "PENALTY"),
+            InvoiceLineType::Discount => write!(f, "DISCOUNT"),
+            InvoiceLineType::BundleComponent => write!(f, "BUNDLE_COMPONENT"),
+            InvoiceLineType::Addon => write!(f, "ADDON"),
+            InvoiceLineType::Vat => write!(f, "VAT"),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct InvoiceLine {
+    pub id: u32,
+    pub invoice_id: u32,
+    pub line_type: InvoiceLineType,
+    pub description: String,
+    pub amount: f64,
+    /// The overdue invoice a penalty line was computed for.
+    pub source_invoice_id: Option<u32>,
+}
+
+/// A line to be billed on an invoice that has not been persisted yet.
+#[derive(Clone, PartialEq, Debug)]
+pub struct NewInvoiceLine {
+    pub line_type: InvoiceLineType,
+    pub description: String,
+    pub amount: f64,
+    pub source_invoice_id: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct InvoiceLineResponse {
+    pub id: u32,
+    pub invoice_id: u32,
+    pub line_type: InvoiceLineType,
+    pub description: String,
+    pub amount: f64,
+    pub source_invoice_id: Option<u32>,
+}
+
+impl From<InvoiceLine> for InvoiceLineResponse {
+    fn from(line: InvoiceLine) -> Self {
+        InvoiceLineResponse {
+            id: line.id,
+            invoice_id: line.invoice_id,
+            line_type: line.line_type,
+            description: line.description,
+            amount: line.amount,
+            source_invoice_id: line.source_invoice_id,
         }
     }
 }
+
+/// VAT line charged at `vat_rate` on the taxable lines of an invoice. Late payment
+/// penalties are damages rather than a supply of services, so they are not taxed.
+pub fn vat_line(lines: &[NewInvoiceLine], vat_rate: f64) -> Option<NewInvoiceLine> {
+    let taxable_amount: f64 = lines
+        .iter()
+        .filter(|line| {
+            !matches!(
+                line.line_type,
+                InvoiceLineType::Penalty | InvoiceLineType::Vat
+            )
+        })
+        .map(|line| line.amount)
+        .sum();
+
+    let amount = crate::penalty::round_to_cents(taxable_amount * vat_rate / 100.0);
+
+    if amount <= 0.0 {
+        return None;
+    }
+
+    Some(NewInvoiceLine {
+        line_type: InvoiceLineType::Vat,
+        description: format!("VAT {}%", vat_rate),
+        amount,
+        source_invoice_id: None,
+    })
+}
@@ -1,29 +1,181 @@
+pub mod addon;
+pub mod address;
+pub mod bulk;
+pub mod bundle;
+pub mod cnp;
+pub mod contact;
 pub mod contract;
+pub mod credit;
+pub mod cui;
 pub mod customer;
+pub mod error;
+pub mod etag;
+pub mod export;
+pub mod field;
+pub mod import;
 pub mod invoice;
+pub mod merge;
+pub mod note;
+pub mod page;
+pub mod patch;
 pub mod payment;
+pub mod penalty;
+pub mod phone;
+pub mod privacy;
+pub mod promotion;
 pub mod subscription;
+pub mod tag;
+pub mod user;
 
 pub(crate) mod validation_config {
-    use chrono::Utc;
+    use chrono::{DateTime, Utc};
     use lazy_static::lazy_static;
     use regex::Regex;
     use validator::ValidationError;
 
     lazy_static! {
-        pub static ref RE_CNP: Regex = Regex::new(r"^\d{13}$").unwrap();
+        pub static ref RE_COUPON_CODE: Regex = Regex::new(r"^[A-Z0-9_-]{3,30}$").unwrap();
+        pub static ref RE_FIELD_KEY: Regex = Regex::new(r"^[a-z][a-z0-9_]{1,29}$").unwrap();
+        pub static ref RE_TAG_NAME: Regex = Regex::new(r"^[A-Z][A-Z0-9_]{1,29}$").unwrap();
+        pub static ref RE_POSTAL_CODE: Regex = Regex::new(r"^\d{6}$").unwrap();
+        pub static ref RE_USERNAME: Regex = Regex::new(r"^[a-z][a-z0-9_.-]{2,49}$").unwrap();
+        pub static ref RE_TRADE_REGISTER: Regex =
+            Regex::new(r"^[JFC](0[1-9]|[1-4][0-9]|5[0-2])/\d{1,6}/(19|20)\d{2}$").unwrap();
+    }
+
+    fn today() -> DateTime<Utc> {
+        Utc::now()
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            .and_utc()
+    }
+
+    pub fn validate_cnp(cnp: &str) -> Result<(), ValidationError> {
+        let mut error = ValidationError::new("cnp");
+
+        crate::cnp::Cnp::parse(cnp).map(|_| ()).map_err(|e| {
+            error.message = Some(e.to_string().into());
+            error
+        })
+    }
+
+    pub fn validate_phone(phone: &str) -> Result<(), ValidationError> {
+        let mut error = ValidationError::new("phone");
+
+        crate::phone::Phone::parse(phone).map(|_| ()).map_err(|e| {
+            error.message = Some(e.to_string().into());
+            error
+        })
+    }
+
+    /// Accepts a CUI or, for the businesses of other EU member states, a VAT number.
+    pub fn validate_cui(cui: &str) -> Result<(), ValidationError> {
+        let mut error = ValidationError::new("cui");
+
+        let result = if crate::cui::VatNumber::is_foreign(cui) {
+            crate::cui::VatNumber::parse(cui)
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        } else {
+            crate::cui::Cui::parse(cui)
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        };
+
+        result.map_err(|e| {
+            error.message = Some(e.into());
+            error
+        })
+    }
+
+    pub fn validate_customer_request(
+        customer: &crate::customer::CustomerRequest,
+    ) -> Result<(), ValidationError> {
+        match customer.kind {
+            crate::customer::CustomerKind::Individual => {
+                if customer.cnp.is_none() {
+                    return Err(ValidationError::new("Individuals should have a CNP"));
+                }
+
+                if customer.cui.is_some()
+                    || customer.trade_register_number.is_some()
+                    || customer.legal_representative.is_some()
+                    || customer.vat_payer
+                    || customer.reverse_charge
+                {
+                    return Err(ValidationError::new(
+                        "Individuals should not have legal entity data",
+                    ));
+                }
+            }
+            crate::customer::CustomerKind::Business => {
+                if customer.cnp.is_some() {
+                    return Err(ValidationError::new("Businesses should not have a CNP"));
+                }
+
+                let foreign = customer
+                    .cui
+                    .as_deref()
+                    .is_some_and(crate::cui::VatNumber::is_foreign);
+
+                // businesses of other member states are identified by their VAT number
+                // alone, having no Romanian trade register number
+                if customer.reverse_charge {
+                    if !customer.vat_payer {
+                        return Err(ValidationError::new(
+                            "Only VAT payers can be invoiced with reverse charge",
+                        ));
+                    }
+
+                    if !foreign {
+                        return Err(ValidationError::new(
+                            "Businesses invoiced with reverse charge should have the VAT number of another EU member state",
+                        ));
+                    }
+
+                    return Ok(());
+                }
+
+                if foreign {
+                    return Err(ValidationError::new(
+                        "Only businesses invoiced with reverse charge can have a foreign VAT number",
+                    ));
+                }
+
+                if customer.cui.is_none()
+                    || customer.legal_representative.is_none()
+                    || !customer
+                        .trade_register_number
+                        .as_deref()
+                        .is_some_and(|number| RE_TRADE_REGISTER.is_match(number))
+                {
+                    return Err(ValidationError::new(
+                        "Businesses should have a CUI, a trade register number such as J40/1234/2020 and a legal representative",
+                    ));
+                }
+            }
+        }
+
+        Ok(())
     }
 
     pub fn validate_create_contract_request(
         contract: &crate::contract::CreateContractRequest,
     ) -> Result<(), ValidationError> {
-        if contract.start_date < Utc::now().date().and_hms(0, 0, 0) {
+        if contract.subscription_id.is_some() == contract.bundle_id.is_some() {
+            return Err(ValidationError::new(
+                "Contract should reference either a subscription or a bundle",
+            ));
+        }
+
+        if contract.start_date < today() {
             return Err(ValidationError::new(
                 "Start date should be later than or equal to today",
             ));
         }
 
-        if contract.end_date < Utc::now().date().and_hms(0, 0, 0) {
+        if contract.end_date < today() {
             return Err(ValidationError::new(
                 "End date should be later than or equal to today",
             ));
@@ -41,13 +193,13 @@ pub(crate) mod validation_config {
     pub fn validate_update_contract_request(
         contract: &crate::contract::UpdateContractRequest,
     ) -> Result<(), ValidationError> {
-        if contract.start_date < Utc::now().date().and_hms(0, 0, 0) {
+        if contract.start_date < today() {
             return Err(ValidationError::new(
                 "Start date should be later than or equal to today",
             ));
         }
 
-        if contract.end_date < Utc::now().date().and_hms(0, 0, 0) {
+        if contract.end_date < today() {
             return Err(ValidationError::new(
                 "End date should be later than or equal to today",
             ));
@@ -65,13 +217,13 @@ pub(crate) mod validation_config {
     pub fn validate_create_invoice_request(
         invoice: &crate::invoice::CreateInvoiceRequest,
     ) -> Result<(), ValidationError> {
-        if invoice.issue_date < Utc::now().date().and_hms(0, 0, 0) {
+        if invoice.issue_date < today() {
             return Err(ValidationError::new(
                 "Issue date should be later than or equal to today",
             ));
         }
 
-        if invoice.due_date < Utc::now().date().and_hms(0, 0, 0) {
+        if invoice.due_date < today() {
             return Err(ValidationError::new(
                 "Due date should be later than or equal to today",
             ));
@@ -86,10 +238,8 @@ pub(crate) mod validation_config {
         Ok(())
     }
 
-    pub fn validate_payment_date(
-        payment_date: &chrono::DateTime<Utc>,
-    ) -> Result<(), ValidationError> {
-        if payment_date < &Utc::now().date().and_hms(0, 0, 0) {
+    pub fn validate_payment_date(payment_date: &DateTime<Utc>) -> Result<(), ValidationError> {
+        if *payment_date < today() {
             return Err(ValidationError::new(
                 "Payment date should be later than or equal to today",
             ));
@@ -97,4 +247,82 @@ pub(crate) mod validation_config {
 
         Ok(())
     }
+
+    pub fn validate_contract_addon_request(
+        contract_addon: &crate::addon::ContractAddonRequest,
+    ) -> Result<(), ValidationError> {
+        if contract_addon.start_date > contract_addon.end_date {
+            return Err(ValidationError::new(
+                "Start date should be earlier than end date",
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_promotion_request(
+        promotion: &crate::promotion::PromotionRequest,
+    ) -> Result<(), ValidationError> {
+        if promotion.discount_type == crate::promotion::DiscountType::Percentage
+            && promotion.discount_value > 100.0
+        {
+            return Err(ValidationError::new(
+                "Percentage discount should be at most 100",
+            ));
+        }
+
+        if promotion.valid_from > promotion.valid_until {
+            return Err(ValidationError::new(
+                "Valid from date should be earlier than valid until date",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// A bundle combines at least two different subscriptions.
+    pub fn validate_bundle_subscription_ids(
+        subscription_ids: &[u32],
+    ) -> Result<(), ValidationError> {
+        let distinct: std::collections::HashSet<&u32> = subscription_ids.iter().collect();
+
+        if distinct.len() < 2 {
+            let mut error = ValidationError::new("subscription_ids");
+            error.message = Some("should hold at least two different subscriptions".into());
+            return Err(error);
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_custom_field_request(
+        field: &crate::field::CustomFieldRequest,
+    ) -> Result<(), ValidationError> {
+        crate::field::CustomField::check_options(&field.field_type, &field.options).map_err(|e| {
+            let mut error = ValidationError::new("options");
+            error.message = Some(e.to_string().into());
+            error
+        })
+    }
+
+    pub fn validate_page_query(page: &crate::page::PageQuery) -> Result<(), ValidationError> {
+        if page.after.is_none() {
+            return Ok(());
+        }
+
+        if page.page.is_some() {
+            let mut error = ValidationError::new("after");
+            error.message = Some("cannot be combined with page".into());
+            return Err(error);
+        }
+
+        match page.sort() {
+            Some(("id", _)) | None => Ok(()),
+            Some(_) => {
+                let mut error = ValidationError::new("after");
+                error.message = Some("can only be combined with sorting by id".into());
+                Err(error)
+            }
+        }
+    }
 }
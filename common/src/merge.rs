@@ -0,0 +1,143 @@
+use crate::customer::{Customer, CustomerResponse};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Minimum Jaro-Winkler similarity (0-100) of two fullnames for customers with
+/// the same phone number to be reported as duplicates.
+pub const NAME_SIMILARITY_THRESHOLD: u32 = 85;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub enum DuplicateReason {
+    #[serde(rename = "SAME_CNP")]
+    SameCnp,
+    #[serde(rename = "SAME_CUI")]
+    SameCui,
+    #[serde(rename = "SIMILAR_NAME_PHONE")]
+    SimilarNamePhone,
+}
+
+impl From<DuplicateReason> for String {
+    fn from(reason: DuplicateReason) -> Self {
+        match reason {
+            DuplicateReason::SameCnp => "SAME_CNP".to_string(),
+            DuplicateReason::SameCui => "SAME_CUI".to_string(),
+            DuplicateReason::SimilarNamePhone => "SIMILAR_NAME_PHONE".to_string(),
+        }
+    }
+}
+
+impl From<String> for DuplicateReason {
+    fn from(reason: String) -> Self {
+        match reason.as_str() {
+            "SAME_CNP" => DuplicateReason::SameCnp,
+            "SAME_CUI" => DuplicateReason::SameCui,
+            _ => DuplicateReason::SimilarNamePhone,
+        }
+    }
+}
+
+impl fmt::Display for DuplicateReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DuplicateReason::SameCnp => write!(f, "same CNP"),
+            DuplicateReason::SameCui => write!(f, "same CUI"),
+            DuplicateReason::SimilarNamePhone => write!(f, "similar name and same phone"),
+        }
+    }
+}
+
+impl DuplicateReason {
+    /// Customers sharing a national identifier are the same person or company,
+    /// while similar names with the same phone may be relatives or namesakes.
+    pub fn is_certain(&self) -> bool {
+        matches!(self, DuplicateReason::SameCnp | DuplicateReason::SameCui)
+    }
+}
+
+/// Two customers likely registered for the same person or company, the
+/// duplicate being the one registered last.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CustomerDuplicate {
+    pub customer: Customer,
+    pub duplicate: Customer,
+    pub reason: DuplicateReason,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct CustomerDuplicateResponse {
+    pub customer: CustomerResponse,
+    pub duplicate: CustomerResponse,
+    pub reason: DuplicateReason,
+}
+
+impl From<CustomerDuplicate> for CustomerDuplicateResponse {
+    fn from(duplicate: CustomerDuplicate) -> Self {
+        CustomerDuplicateResponse {
+            customer: CustomerResponse::from(duplicate.customer),
+            duplicate: CustomerResponse::from(duplicate.duplicate),
+            reason: duplicate.reason,
+        }
+    }
+}
+
+/// Record of a customer merged into another one, keeping the identity of the
+/// removed customer.
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct CustomerMerge {
+    pub id: u32,
+    pub target_customer_id: u32,
+    pub source_customer_id: u32,
+    pub source_name: String,
+    pub source_fullname: String,
+    pub source_cnp: Option<String>,
+    pub source_cui: Option<String>,
+    pub contracts_moved: u32,
+    pub invoices_moved: u32,
+    pub payments_moved: u32,
+    pub merged_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct CustomerMergeRequest {
+    /// Customer merged into the one in the path and then removed.
+    pub source_customer_id: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct CustomerMergeResponse {
+    pub id: u32,
+    pub target_customer_id: u32,
+    pub source_customer_id: u32,
+    pub source_name: String,
+    pub source_fullname: String,
+    pub source_cnp: Option<String>,
+    pub source_cui: Option<String>,
+    pub contracts_moved: u32,
+    pub invoices_moved: u32,
+    pub payments_moved: u32,
+    pub merged_at: DateTime<Utc>,
+}
+
+impl From<CustomerMerge> for CustomerMergeResponse {
+    fn from(merge: CustomerMerge) -> Self {
+        CustomerMergeResponse {
+            id: merge.id,
+            target_customer_id: merge.target_customer_id,
+            source_customer_id: merge.source_customer_id,
+            source_name: merge.source_name,
+            source_fullname: merge.source_fullname,
+            source_cnp: merge.source_cnp,
+            source_cui: merge.source_cui,
+            contracts_moved: merge.contracts_moved,
+            invoices_moved: merge.invoices_moved,
+            payments_moved: merge.payments_moved,
+            merged_at: merge.merged_at,
+        }
+    }
+}
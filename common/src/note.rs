@@ -0,0 +1,177 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use validator::Validate;
+
+/// What a note records: a plain note or an interaction with the customer.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub enum NoteKind {
+    #[default]
+    #[serde(rename = "NOTE")]
+    Note,
+    #[serde(rename = "CALL")]
+    Call,
+    #[serde(rename = "EMAIL")]
+    Email,
+    #[serde(rename = "VISIT")]
+    Visit,
+}
+
+impl From<NoteKind> for String {
+    fn from(kind: NoteKind) -> Self {
+        match kind {
+            NoteKind::Note => "NOTE".to_string(),
+            NoteKind::Call => "CALL".to_string(),
+            NoteKind::Email => "EMAIL".to_string(),
+            NoteKind::Visit => "VISIT".to_string(),
+        }
+    }
+}
+
+impl From<String> for NoteKind {
+    fn from(kind: String) -> Self {
+        match kind.as_str() {
+            "NOTE" => NoteKind::Note,
+            "CALL" => NoteKind::Call,
+            "EMAIL" => NoteKind::Email,
+            "VISIT" => NoteKind::Visit,
+            _ => NoteKind::Note,
+        }
+    }
+}
+
+impl fmt::Display for NoteKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NoteKind::Note => write!(f, "NOTE"),
+            NoteKind::Call => write!(f, "CALL"),
+            NoteKind::Email => write!(f, "EMAIL"),
+            NoteKind::Visit => write!(f, "VISIT"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub enum NoteCategory {
+    #[default]
+    #[serde(rename = "GENERAL")]
+    General,
+    #[serde(rename = "BILLING")]
+    Billing,
+    #[serde(rename = "TECHNICAL")]
+    Technical,
+    #[serde(rename = "SALES")]
+    Sales,
+    #[serde(rename = "COMPLAINT")]
+    Complaint,
+}
+
+impl From<NoteCategory> for String {
+    fn from(category: NoteCategory) -> Self {
+        match category {
+            NoteCategory::General => "GENERAL".to_string(),
+            NoteCategory::Billing => "BILLING".to_string(),
+            NoteCategory::Technical => "TECHNICAL".to_string(),
+            NoteCategory::Sales => "SALES".to_string(),
+            NoteCategory::Complaint => "COMPLAINT".to_string(),
+        }
+    }
+}
+
+impl From<String> for NoteCategory {
+    fn from(category: String) -> Self {
+        match category.as_str() {
+            "GENERAL" => NoteCategory::General,
+            "BILLING" => NoteCategory::Billing,
+            "TECHNICAL" => NoteCategory::Technical,
+            "SALES" => NoteCategory::Sales,
+            "COMPLAINT" => NoteCategory::Complaint,
+            _ => NoteCategory::General,
+        }
+    }
+}
+
+impl fmt::Display for NoteCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NoteCategory::General => write!(f, "GENERAL"),
+            NoteCategory::Billing => write!(f, "BILLING"),
+            NoteCategory::Technical => write!(f, "TECHNICAL"),
+            NoteCategory::Sales => write!(f, "SALES"),
+            NoteCategory::Complaint => write!(f, "COMPLAINT"),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct Note {
+    pub id: u32,
+    pub customer_id: u32,
+    /// Contract the note is about, if any.
+    pub contract_id: Option<u32>,
+    /// Invoice the note is about, if any.
+    pub invoice_id: Option<u32>,
+    pub kind: NoteKind,
+    pub category: NoteCategory,
+    pub author: String,
+    pub body: String,
+    pub pinned: bool,
+    pub created_at: DateTime<Utc>,
+    pub version: u32,
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct NoteRequest {
+    #[serde(default)]
+    pub kind: NoteKind,
+    #[serde(default)]
+    pub category: NoteCategory,
+    #[serde(default)]
+    pub contract_id: Option<u32>,
+    #[serde(default)]
+    pub invoice_id: Option<u32>,
+    #[validate(length(min = 2, max = 100))]
+    pub author: String,
+    #[validate(length(min = 1, max = 2000))]
+    pub body: String,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct NoteResponse {
+    pub id: u32,
+    pub customer_id: u32,
+    pub contract_id: Option<u32>,
+    pub invoice_id: Option<u32>,
+    pub kind: NoteKind,
+    pub category: NoteCategory,
+    pub author: String,
+    pub body: String,
+    pub pinned: bool,
+    pub created_at: DateTime<Utc>,
+    pub version: u32,
+}
+
+impl From<Note> for NoteResponse {
+    fn from(note: Note) -> Self {
+        NoteResponse {
+            id: note.id,
+            customer_id: note.customer_id,
+            contract_id: note.contract_id,
+            invoice_id: note.invoice_id,
+            kind: note.kind,
+            category: note.category,
+            author: note.author,
+            body: note.body,
+            pinned: note.pinned,
+            created_at: note.created_at,
+            version: note.version,
+        }
+    }
+}
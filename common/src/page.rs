@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+pub const DEFAULT_PER_PAGE: u32 = 50;
+pub const MAX_PER_PAGE: u32 = 1000;
+
+/// Page of a list, either numbered or following the record with the `after` id
+/// (cursor pagination), sorted ascending by `sort` or descending by `-sort`.
+#[derive(Serialize, Deserialize, Validate, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+#[validate(schema(function = "crate::validation_config::validate_page_query"))]
+pub struct PageQuery {
+    #[serde(default)]
+    #[validate(range(min = 1))]
+    pub page: Option<u32>,
+    #[serde(default)]
+    #[validate(range(min = 1, max = 1000))]
+    pub per_page: Option<u32>,
+    #[serde(default)]
+    pub after: Option<u32>,
+    #[serde(default)]
+    pub sort: Option<String>,
+}
+
+impl PageQuery {
+    pub fn page(&self) -> u32 {
+        self.page.unwrap_or(1)
+    }
+
+    pub fn per_page(&self) -> u32 {
+        self.per_page.unwrap_or(DEFAULT_PER_PAGE)
+    }
+
+    /// Rows skipped before the page, always 0 for cursor pagination. Computed in
+    /// `u64`, as pages far past the end of a list would overflow a `u32`.
+    pub fn offset(&self) -> u64 {
+        u64::from(self.page().saturating_sub(1)).saturating_mul(u64::from(self.per_page()))
+    }
+
+    /// Sorted field along with whether it is sorted descending.
+    pub fn sort(&self) -> Option<(&str, bool)> {
+        self.sort
+            .as_deref()
+            .filter(|sort| !sort.is_empty())
+            .map(|sort| match sort.strip_prefix('-') {
+                Some(field) => (field, true),
+                None => (sort, false),
+            })
+    }
+
+    /// Number of the last page of a list with `total` records.
+    pub fn last_page(&self, total: u32) -> u32 {
+        total.div_ceil(self.per_page()).max(1)
+    }
+}
@@ -0,0 +1,52 @@
+use serde_json::{Map, Value};
+
+/// Applies a JSON Merge Patch (RFC 7386) to `target`: the members of an object
+/// patch replace those of the target, recursively, and `null` members remove them.
+pub fn apply(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                apply(target.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+/// JSON Merge Patch turning `original` into `modified`, an empty object when they
+/// are equal.
+pub fn diff(original: &Value, modified: &Value) -> Value {
+    let (Value::Object(original), Value::Object(modified)) = (original, modified) else {
+        return modified.clone();
+    };
+
+    let mut patch = Map::new();
+
+    for key in original.keys().filter(|key| !modified.contains_key(*key)) {
+        patch.insert(key.clone(), Value::Null);
+    }
+
+    for (key, value) in modified {
+        match original.get(key) {
+            Some(original) if original == value => {}
+            Some(original) if original.is_object() && value.is_object() => {
+                patch.insert(key.clone(), diff(original, value));
+            }
+            _ => {
+                patch.insert(key.clone(), value.clone());
+            }
+        }
+    }
+
+    Value::Object(patch)
+}
@@ -1,16 +1,19 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
 #[derive(Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 pub struct Payment {
     pub id: u32,
     pub invoice_id: u32,
     pub payment_date: DateTime<Utc>,
     pub amount: f64,
+    pub version: u32,
 }
 
 #[derive(Serialize, Deserialize, Validate, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 pub struct CreatePaymentRequest {
     pub invoice_id: u32,
     #[validate(custom(
@@ -22,12 +25,27 @@ pub struct CreatePaymentRequest {
     pub amount: f64,
 }
 
+/// Filters the listed payments by invoice and by payment date (`from` and `to`
+/// inclusive).
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct PaymentListQuery {
+    #[serde(default)]
+    pub invoice_id: Option<u32>,
+    #[serde(default)]
+    pub from: Option<NaiveDate>,
+    #[serde(default)]
+    pub to: Option<NaiveDate>,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 pub struct PaymentResponse {
     pub id: u32,
     pub invoice_id: u32,
     pub payment_date: DateTime<Utc>,
     pub amount: f64,
+    pub version: u32,
 }
 
 impl From<Payment> for PaymentResponse {
@@ -37,6 +55,7 @@ impl From<Payment> for PaymentResponse {
             invoice_id: payment.invoice_id,
             payment_date: payment.payment_date,
             amount: payment.amount,
+            version: payment.version,
         }
     }
 }
@@ -0,0 +1,92 @@
+use crate::invoice::{Invoice, InvoiceLine, InvoiceLineType};
+use crate::payment::Payment;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct PenaltyQuery {
+    pub as_of: Option<NaiveDate>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct PenaltyResponse {
+    pub invoice_id: u32,
+    pub as_of: DateTime<Utc>,
+    pub penalty_rate: f64,
+    pub days_overdue: i64,
+    pub outstanding_amount: f64,
+    pub accrued_penalty: f64,
+    pub billed_penalty: f64,
+    pub unbilled_penalty: f64,
+}
+
+/// Amount of the invoice still unpaid at `as_of`.
+pub fn outstanding_amount(invoice: &Invoice, payments: &[Payment], as_of: DateTime<Utc>) -> f64 {
+    let paid: f64 = payments
+        .iter()
+        .filter(|p| p.payment_date <= as_of)
+        .map(|p| p.amount)
+        .sum();
+
+    (invoice.amount - paid).max(0.0)
+}
+
+/// Amount of the invoice that penalties accrue on: its lines, VAT included, but
+/// not the penalties of other invoices billed on it.
+pub fn penalty_base(lines: &[InvoiceLine]) -> f64 {
+    lines
+        .iter()
+        .filter(|line| line.line_type != InvoiceLineType::Penalty)
+        .map(|line| line.amount)
+        .sum()
+}
+
+/// Penalty accrued by an invoice from its due date until `as_of`.
+///
+/// `rate` is a daily percentage applied to the part of the [`penalty_base`] that
+/// is still unpaid on each day, so partial payments made after the due date stop
+/// the penalty from accruing on the paid part. As required by Romanian law, the
+/// total penalty cannot exceed the amount it was computed for.
+pub fn accrued_penalty(
+    invoice: &Invoice,
+    lines: &[InvoiceLine],
+    payments: &[Payment],
+    rate: f64,
+    as_of: DateTime<Utc>,
+) -> f64 {
+    if as_of <= invoice.due_date || rate <= 0.0 {
+        return 0.0;
+    }
+
+    let mut late_payments: Vec<&Payment> = payments
+        .iter()
+        .filter(|p| p.payment_date > invoice.due_date && p.payment_date <= as_of)
+        .collect();
+    late_payments.sort_by_key(|p| p.payment_date);
+
+    let base = penalty_base(lines);
+    let paid: f64 = payments
+        .iter()
+        .filter(|p| p.payment_date <= invoice.due_date)
+        .map(|p| p.amount)
+        .sum();
+
+    let mut balance = (base - paid).max(0.0);
+    let mut since = invoice.due_date;
+    let mut penalty = 0.0;
+
+    for payment in late_payments {
+        penalty += balance * rate / 100.0 * (payment.payment_date - since).num_days() as f64;
+        balance = (balance - payment.amount).max(0.0);
+        since = payment.payment_date;
+    }
+    penalty += balance * rate / 100.0 * (as_of - since).num_days() as f64;
+
+    round_to_cents(penalty.min(base))
+}
+
+pub fn round_to_cents(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
@@ -0,0 +1,94 @@
+use std::fmt;
+
+/// Country calling code assumed for numbers written in the national format.
+pub const DEFAULT_COUNTRY_CODE: &str = "40";
+
+const SEPARATORS: [char; 5] = [' ', '-', '.', '(', ')'];
+
+/// Phone number in the E.164 format: a `+`, the country calling code and the
+/// subscriber number, at most 15 digits in total.
+///
+/// Accepts international numbers prefixed by `+` or `00` and Romanian national
+/// numbers starting with the `0` trunk prefix, with spaces, dashes, dots and
+/// parentheses as separators.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Phone {
+    pub digits: String,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum PhoneError {
+    Format,
+    Length,
+    NationalNumber,
+}
+
+impl fmt::Display for PhoneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PhoneError::Format => write!(
+                f,
+                "Phone number should start with +, 00 or 0 and contain only digits"
+            ),
+            PhoneError::Length => write!(f, "Phone number should have between 8 and 15 digits"),
+            PhoneError::NationalNumber => write!(
+                f,
+                "Romanian phone numbers should have 9 digits after the 0 or +40 prefix"
+            ),
+        }
+    }
+}
+
+impl Phone {
+    pub fn parse(phone: &str) -> Result<Phone, PhoneError> {
+        let phone: String = phone
+            .trim()
+            .chars()
+            .filter(|c| !SEPARATORS.contains(c))
+            .collect();
+
+        let digits = if let Some(international) = phone.strip_prefix('+') {
+            international.to_string()
+        } else if let Some(international) = phone.strip_prefix("00") {
+            international.to_string()
+        } else if let Some(national) = phone.strip_prefix('0') {
+            format!("{}{}", DEFAULT_COUNTRY_CODE, national)
+        } else {
+            return Err(PhoneError::Format);
+        };
+
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(PhoneError::Format);
+        }
+
+        if digits.starts_with('0') {
+            return Err(PhoneError::Format);
+        }
+
+        if let Some(national) = digits.strip_prefix(DEFAULT_COUNTRY_CODE) {
+            if national.len() != 9 || national.starts_with(['0', '1']) {
+                return Err(PhoneError::NationalNumber);
+            }
+        }
+
+        if !(8..=15).contains(&digits.len()) {
+            return Err(PhoneError::Length);
+        }
+
+        Ok(Phone { digits })
+    }
+
+    /// Converts a phone number to the E.164 format, leaving it untouched if it
+    /// cannot be parsed so that validation reports the original input.
+    pub fn normalize(phone: &str) -> String {
+        Phone::parse(phone)
+            .map(|phone| phone.to_string())
+            .unwrap_or(phone.to_string())
+    }
+}
+
+impl fmt::Display for Phone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "+{}", self.digits)
+    }
+}
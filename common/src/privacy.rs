@@ -0,0 +1,73 @@
+use crate::addon::ContractAddonResponse;
+use crate::address::AddressResponse;
+use crate::contact::ContactResponse;
+use crate::contract::ContractResponse;
+use crate::credit::DepositResponse;
+use crate::customer::{Customer, CustomerResponse};
+use crate::field::CustomFieldValueResponse;
+use crate::invoice::{InvoiceLineResponse, InvoiceResponse};
+use crate::merge::CustomerMergeResponse;
+use crate::note::NoteResponse;
+use crate::payment::PaymentResponse;
+use crate::tag::TagResponse;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Value replacing the personal fields of an anonymized customer.
+pub const ANONYMIZED: &str = "ANONYMIZED";
+
+/// All the personal data stored about a customer, along with its contracts,
+/// invoices and payments, as handed over on a data subject access request.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct CustomerExport {
+    pub exported_at: DateTime<Utc>,
+    pub customer: CustomerResponse,
+    pub addresses: Vec<AddressResponse>,
+    pub contacts: Vec<ContactResponse>,
+    pub notes: Vec<NoteResponse>,
+    pub tags: Vec<TagResponse>,
+    pub custom_fields: Vec<CustomFieldValueResponse>,
+    /// Customers previously merged into this one.
+    pub merges: Vec<CustomerMergeResponse>,
+    pub contracts: Vec<ContractExport>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct ContractExport {
+    pub contract: ContractResponse,
+    pub custom_fields: Vec<CustomFieldValueResponse>,
+    pub deposit: Option<DepositResponse>,
+    pub addons: Vec<ContractAddonResponse>,
+    pub invoices: Vec<InvoiceExport>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct InvoiceExport {
+    pub invoice: InvoiceResponse,
+    pub lines: Vec<InvoiceLineResponse>,
+    pub payments: Vec<PaymentResponse>,
+}
+
+/// Outcome of an anonymization, counting what was scrubbed and stating how long
+/// the financial records of the customer have to be kept.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct AnonymizationResponse {
+    pub customer: CustomerResponse,
+    pub addresses_scrubbed: u32,
+    pub contacts_removed: u32,
+    /// End of the legal retention period of the last invoice, if any.
+    pub financial_records_retained_until: Option<DateTime<Utc>>,
+}
+
+/// Customer whose personal data has just been scrubbed.
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct Anonymization {
+    pub customer: Customer,
+    pub addresses_scrubbed: u32,
+    pub contacts_removed: u32,
+}
@@ -0,0 +1,126 @@
+use crate::subscription::SubscriptionType;
+use crate::validation_config::RE_COUPON_CODE;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub enum DiscountType {
+    #[serde(rename = "PERCENTAGE")]
+    Percentage,
+    #[serde(rename = "FIXED")]
+    Fixed,
+}
+
+impl From<DiscountType> for String {
+    fn from(discount_type: DiscountType) -> Self {
+        match discount_type {
+            DiscountType::Percentage => "PERCENTAGE".to_string(),
+            DiscountType::Fixed => "FIXED".to_string(),
+        }
+    }
+}
+
+impl From<String> for DiscountType {
+    fn from(discount_type: String) -> Self {
+        match discount_type.as_str() {
+            "PERCENTAGE" => DiscountType::Percentage,
+            "FIXED" => DiscountType::Fixed,
+            _ => DiscountType::Percentage,
+        }
+    }
+}
+
+impl fmt::Display for DiscountType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiscountType::Percentage => write!(f, "PERCENTAGE"),
+            DiscountType::Fixed => write!(f, "FIXED"),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct Promotion {
+    pub id: u32,
+    pub code: String,
+    pub description: String,
+    pub discount_type: DiscountType,
+    pub discount_value: f64,
+    pub duration_cycles: u32,
+    /// `None` makes the promotion eligible for every subscription type.
+    pub subscription_type: Option<SubscriptionType>,
+    pub valid_from: DateTime<Utc>,
+    pub valid_until: DateTime<Utc>,
+    pub version: u32,
+}
+
+impl Promotion {
+    /// Discount granted on `amount` during the first `duration_cycles` billing cycles
+    /// of a contract, `cycle` being zero-based.
+    pub fn discount_for(&self, amount: f64, cycle: u32) -> f64 {
+        if cycle >= self.duration_cycles {
+            return 0.0;
+        }
+
+        let discount = match self.discount_type {
+            DiscountType::Percentage => amount * self.discount_value / 100.0,
+            DiscountType::Fixed => self.discount_value,
+        };
+
+        crate::penalty::round_to_cents(discount.min(amount))
+    }
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+#[validate(schema(function = "crate::validation_config::validate_promotion_request"))]
+pub struct PromotionRequest {
+    #[validate(regex = "RE_COUPON_CODE")]
+    pub code: String,
+    #[validate(length(min = 3, max = 100))]
+    pub description: String,
+    pub discount_type: DiscountType,
+    #[validate(range(min = 0.0))]
+    pub discount_value: f64,
+    #[validate(range(min = 1))]
+    pub duration_cycles: u32,
+    pub subscription_type: Option<SubscriptionType>,
+    pub valid_from: DateTime<Utc>,
+    pub valid_until: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct PromotionResponse {
+    pub id: u32,
+    pub code: String,
+    pub description: String,
+    pub discount_type: DiscountType,
+    pub discount_value: f64,
+    pub duration_cycles: u32,
+    pub subscription_type: Option<SubscriptionType>,
+    pub valid_from: DateTime<Utc>,
+    pub valid_until: DateTime<Utc>,
+    pub version: u32,
+}
+
+impl From<Promotion> for PromotionResponse {
+    fn from(promotion: Promotion) -> Self {
+        PromotionResponse {
+            id: promotion.id,
+            code: promotion.code,
+            description: promotion.description,
+            discount_type: promotion.discount_type,
+            discount_value: promotion.discount_value,
+            duration_cycles: promotion.duration_cycles,
+            subscription_type: promotion.subscription_type,
+            valid_from: promotion.valid_from,
+            valid_until: promotion.valid_until,
+            version: promotion.version,
+        }
+    }
+}
@@ -3,6 +3,7 @@ use std::fmt;
 use validator::Validate;
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 pub enum SubscriptionType {
     #[serde(rename = "MOBILE")]
     Mobile,
@@ -54,6 +55,7 @@ impl fmt::Display for SubscriptionType {
 }
 
 #[derive(Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 pub struct Subscription {
     pub id: u32,
     pub description: String,
@@ -62,9 +64,12 @@ pub struct Subscription {
     pub traffic: i32,
     pub price: f64,
     pub extra_traffic_price: f64,
+    /// Incremented on each update, the entity tag of the subscription.
+    pub version: u32,
 }
 
 #[derive(Serialize, Deserialize, Validate, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 pub struct SubscriptionRequest {
     #[validate(length(min = 3, max = 100))]
     pub description: String,
@@ -78,7 +83,32 @@ pub struct SubscriptionRequest {
     pub extra_traffic_price: f64,
 }
 
+impl From<Subscription> for SubscriptionRequest {
+    fn from(subscription: Subscription) -> Self {
+        SubscriptionRequest {
+            description: subscription.description,
+            subscription_type: subscription.subscription_type,
+            traffic: subscription.traffic,
+            price: subscription.price,
+            extra_traffic_price: subscription.extra_traffic_price,
+        }
+    }
+}
+
+/// Filters the listed subscriptions by type and by price range (inclusive).
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct SubscriptionListQuery {
+    #[serde(default, rename = "type")]
+    pub subscription_type: Option<SubscriptionType>,
+    #[serde(default)]
+    pub min_price: Option<f64>,
+    #[serde(default)]
+    pub max_price: Option<f64>,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 pub struct SubscriptionResponse {
     pub id: u32,
     pub description: String,
@@ -87,6 +117,7 @@ pub struct SubscriptionResponse {
     pub traffic: i32,
     pub price: f64,
     pub extra_traffic_price: f64,
+    pub version: u32,
 }
 
 impl From<Subscription> for SubscriptionResponse {
@@ -98,6 +129,7 @@ impl From<Subscription> for SubscriptionResponse {
             traffic: subscription.traffic,
             price: subscription.price,
             extra_traffic_price: subscription.extra_traffic_price,
+            version: subscription.version,
         }
     }
 }
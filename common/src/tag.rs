@@ -0,0 +1,51 @@
+use crate::validation_config::RE_TAG_NAME;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Label segmenting customers and contracts, e.g. `VIP`, `RESELLER` or `RISK`.
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct Tag {
+    pub id: u32,
+    pub name: String,
+    pub description: Option<String>,
+    pub version: u32,
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct TagRequest {
+    #[validate(regex = "RE_TAG_NAME")]
+    pub name: String,
+    #[serde(default)]
+    #[validate(length(min = 3, max = 100))]
+    pub description: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct TagResponse {
+    pub id: u32,
+    pub name: String,
+    pub description: Option<String>,
+    pub version: u32,
+}
+
+impl From<Tag> for TagResponse {
+    fn from(tag: Tag) -> Self {
+        TagResponse {
+            id: tag.id,
+            name: tag.name,
+            description: tag.description,
+            version: tag.version,
+        }
+    }
+}
+
+/// Names of the tags of a customer or contract, replacing the ones it had.
+#[derive(Serialize, Deserialize, Validate, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct TagAssignmentRequest {
+    #[validate(length(max = 20))]
+    pub tags: Vec<String>,
+}
@@ -0,0 +1,125 @@
+use crate::validation_config::RE_USERNAME;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use validator::Validate;
+
+/// Query parameter carrying the session token of the requests that cannot send
+/// headers, such as the downloads of exports.
+pub const ACCESS_TOKEN: &str = "access_token";
+
+/// Role of a user, administrators also managing the users.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub enum Role {
+    #[serde(rename = "ADMIN")]
+    Admin,
+    #[default]
+    #[serde(rename = "OPERATOR")]
+    Operator,
+}
+
+impl From<Role> for String {
+    fn from(role: Role) -> Self {
+        match role {
+            Role::Admin => "ADMIN".to_string(),
+            Role::Operator => "OPERATOR".to_string(),
+        }
+    }
+}
+
+impl From<String> for Role {
+    fn from(role: String) -> Self {
+        match role.as_str() {
+            "ADMIN" => Role::Admin,
+            _ => Role::Operator,
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Role::Admin => write!(f, "ADMIN"),
+            Role::Operator => write!(f, "OPERATOR"),
+        }
+    }
+}
+
+/// Account signing in to the API.
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct User {
+    pub id: u32,
+    pub username: String,
+    pub role: Role,
+    /// Inactive users cannot sign in, and their sessions are no longer accepted.
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub version: u32,
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct CreateUserRequest {
+    #[validate(regex = "RE_USERNAME")]
+    pub username: String,
+    #[validate(length(min = 8, max = 128))]
+    pub password: String,
+    #[serde(default)]
+    pub role: Role,
+}
+
+/// Changes to a user, its password being kept unless a new one is given.
+#[derive(Serialize, Deserialize, Validate, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct UpdateUserRequest {
+    pub role: Role,
+    pub active: bool,
+    #[serde(default)]
+    #[validate(length(min = 8, max = 128))]
+    pub password: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct UserResponse {
+    pub id: u32,
+    pub username: String,
+    pub role: Role,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub version: u32,
+}
+
+impl From<User> for UserResponse {
+    fn from(user: User) -> Self {
+        UserResponse {
+            id: user.id,
+            username: user.username,
+            role: user.role,
+            active: user.active,
+            created_at: user.created_at,
+            version: user.version,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct LoginRequest {
+    #[validate(length(min = 1, max = 50))]
+    pub username: String,
+    #[validate(length(min = 1, max = 128))]
+    pub password: String,
+}
+
+/// Session of a signed in user, whose `token` is sent as `Authorization: Bearer
+/// <token>` with every request until it expires or the user signs out.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct LoginResponse {
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+    pub user: UserResponse,
+}
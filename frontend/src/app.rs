@@ -1,17 +1,20 @@
+use gloo_net::http::Request;
 use material_yew::{
     drawer::MatDrawerAppContent,
-    top_app_bar_fixed::{MatTopAppBarNavigationIcon, MatTopAppBarTitle},
+    top_app_bar_fixed::{MatTopAppBarActionItems, MatTopAppBarNavigationIcon, MatTopAppBarTitle},
     MatDrawer, MatIconButton, MatList, MatListItem, MatTopAppBarFixed,
 };
 use yew::prelude::*;
 use yew_router::prelude::*;
 
-use crate::views::{contract, customer, home, invoice, payment, subscription};
+use crate::views::{auth, contract, customer, home, import, invoice, login, payment, subscription};
 
 #[derive(Clone, Routable, PartialEq)]
 pub enum Route {
     #[at("/")]
     Home,
+    #[at("/login")]
+    Login,
     #[at("/app/subscription/list")]
     SubscriptionList,
     #[at("/app/subscription/create")]
@@ -24,6 +27,8 @@ pub enum Route {
     CustomerList,
     #[at("/app/customer/create")]
     CustomerCreate,
+    #[at("/app/customer/duplicates")]
+    CustomerDuplicates,
     #[at("/app/customer/:id/edit")]
     CustomerEdit { id: u32 },
     #[at("/app/customer/:id/detail")]
@@ -40,6 +45,8 @@ pub enum Route {
     InvoiceDetail { id: u32 },
     #[at("/app/payment/create")]
     PaymentCreate,
+    #[at("/app/import")]
+    Import,
     #[not_found]
     #[at("/404")]
     NotFound,
@@ -56,6 +63,7 @@ pub enum Msg {
     NavIconClick,
     Opened,
     Closed,
+    Logout,
 }
 
 impl Component for App {
@@ -82,6 +90,22 @@ impl Component for App {
                 self.drawer_state = true;
                 false
             }
+            Msg::Logout => {
+                log::info!("Signing out");
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let logout_req = Request::post("http://localhost:8000/api/v1/auth/logout")
+                        .header("Authorization", &auth::bearer());
+
+                    if let Err(err) = logout_req.send().await {
+                        log::error!("Failed to sign out: {:?}", err);
+                    }
+
+                    auth::clear();
+                    auth::redirect_to_login();
+                });
+                false
+            }
         }
     }
 
@@ -99,6 +123,7 @@ impl Component for App {
                             <AppLink to={Route::Home}><MatListItem>{"Home"}</MatListItem></AppLink>
                             <AppLink to={Route::SubscriptionList}><MatListItem>{"Subscriptions"}</MatListItem></AppLink>
                             <AppLink to={Route::CustomerList}><MatListItem>{"Customers"}</MatListItem></AppLink>
+                            <AppLink to={Route::Import}><MatListItem>{"Import"}</MatListItem></AppLink>
                         </MatList>
                     </div>
                     <MatDrawerAppContent>
@@ -116,6 +141,12 @@ impl Component for App {
                                     </div>
                                 </MatTopAppBarTitle>
 
+                                <MatTopAppBarActionItems>
+                                    <span onclick={link.callback(|_| Msg::Logout)}>
+                                        <MatIconButton icon="logout" label="Sign out"></MatIconButton>
+                                    </span>
+                                </MatTopAppBarActionItems>
+
                             </MatTopAppBarFixed>
 
                             <Switch<Route> render={App::switch} />
@@ -128,15 +159,23 @@ impl Component for App {
 }
 
 impl App {
+    /// Page of the route, the login page standing in for every page until the user
+    /// signs in.
     fn switch(switch: Route) -> Html {
+        if auth::token().is_none() {
+            return html! { <login::Login /> };
+        }
+
         match switch {
             Route::Home => html! { <home::Home /> },
+            Route::Login => html! { <login::Login /> },
             Route::SubscriptionList => html! { <subscription::list::List /> },
             Route::SubscriptionCreate => html! { <subscription::create::Create /> },
             Route::SubscriptionEdit { id } => html! { <subscription::edit::Edit id={id} /> },
             Route::SubscriptionDetail { id } => html! { <subscription::detail::Detail id={id} /> },
             Route::CustomerList => html! { <customer::list::List /> },
             Route::CustomerCreate => html! { <customer::create::Create /> },
+            Route::CustomerDuplicates => html! { <customer::duplicates::Duplicates /> },
             Route::CustomerEdit { id } => html! { <customer::edit::Edit id={id} /> },
             Route::CustomerDetail { id } => html! { <customer::detail::Detail id={id} /> },
             Route::ContractCreate => html! { <contract::create::Create /> },
@@ -145,6 +184,7 @@ impl App {
             Route::InvoiceCreate => html! { <invoice::create::Create /> },
             Route::InvoiceDetail { id } => html! { <invoice::detail::Detail id={id} /> },
             Route::PaymentCreate => html! { <payment::create::Create /> },
+            Route::Import => html! { <import::Import /> },
             Route::NotFound => html! { <div class="center"><h1>{"404 Not Found"}</h1></div> },
         }
     }
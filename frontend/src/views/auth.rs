@@ -0,0 +1,38 @@
+use web_sys::Storage;
+
+/// Key of the local storage entry holding the session token.
+const TOKEN_KEY: &str = "isp-manager-token";
+
+fn storage() -> Option<Storage> {
+    web_sys::window().and_then(|window| window.local_storage().ok().flatten())
+}
+
+/// Session token of the signed in user, kept across reloads of the page.
+pub(crate) fn token() -> Option<String> {
+    storage().and_then(|storage| storage.get_item(TOKEN_KEY).ok().flatten())
+}
+
+pub(crate) fn store(token: &str) {
+    if let Some(storage) = storage() {
+        let _ = storage.set_item(TOKEN_KEY, token);
+    }
+}
+
+pub(crate) fn clear() {
+    if let Some(storage) = storage() {
+        let _ = storage.remove_item(TOKEN_KEY);
+    }
+}
+
+/// Value of the `Authorization` header of the requests to the backend.
+pub(crate) fn bearer() -> String {
+    format!("Bearer {}", token().unwrap_or_default())
+}
+
+/// Leaves the app for the login page, reloading it without the state of the
+/// signed out user.
+pub(crate) fn redirect_to_login() {
+    if let Some(window) = web_sys::window() {
+        let _ = window.location().set_href("/login");
+    }
+}
@@ -0,0 +1,72 @@
+use material_yew::dialog::{ActionType, MatDialogAction};
+use material_yew::{MatButton, MatDialog};
+use yew::{html, AttrValue, Callback, Component, Context, Html, Properties};
+
+const RELOAD: &str = "reload";
+const OVERWRITE: &str = "overwrite";
+
+/// Choice of the user whose changes conflict with those saved by someone else
+/// since the record was loaded.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Resolution {
+    /// Discards the changes, loading the record as saved.
+    Reload,
+    /// Saves the changes over those of the other user.
+    Overwrite,
+    /// Keeps editing without saving.
+    Dismiss,
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct ConflictProps {
+    pub open: bool,
+    /// Record being edited, e.g. `customer`.
+    pub entity: AttrValue,
+    pub onresolve: Callback<Resolution>,
+}
+
+/// Dialog shown when saving a record fails with 412 Precondition Failed, someone
+/// else having changed it since it was loaded.
+pub struct Conflict;
+
+impl Component for Conflict {
+    type Message = ();
+    type Properties = ConflictProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let onresolve = props.onresolve.clone();
+
+        html! {
+            <MatDialog
+                open={props.open}
+                heading={AttrValue::from("Edit conflict")}
+                onclosed={Callback::from(move |action: String| {
+                    onresolve.emit(match action.as_str() {
+                        RELOAD => Resolution::Reload,
+                        OVERWRITE => Resolution::Overwrite,
+                        _ => Resolution::Dismiss,
+                    })
+                })}>
+                <p>
+                    { format!(
+                        "This {} has been changed by someone else since you opened it. \
+                        Reload it to see their changes, discarding yours, or save yours over theirs.",
+                        props.entity
+                    ) }
+                </p>
+
+                <MatDialogAction action_type={ActionType::Primary} action={AttrValue::from(RELOAD)}>
+                    <MatButton label="Reload" />
+                </MatDialogAction>
+                <MatDialogAction action_type={ActionType::Secondary} action={AttrValue::from(OVERWRITE)}>
+                    <MatButton label="Overwrite" />
+                </MatDialogAction>
+            </MatDialog>
+        }
+    }
+}
@@ -1,7 +1,15 @@
 use crate::app::Route;
+use crate::views::auth;
+use crate::views::problem;
+use crate::views::segment::{Segment, Segmentation};
 use chrono::{DateTime, NaiveDate, Utc};
-use common::contract::CreateContractRequest;
+use common::address::{AddressResponse, AddressType};
+use common::bundle::BundleResponse;
+use common::contract::{ContractResponse, CreateContractRequest};
 use common::customer::CustomerResponse;
+use common::error::ErrorCode;
+use common::field::Entity;
+use common::page::MAX_PER_PAGE;
 use common::subscription::SubscriptionResponse;
 use gloo_net::http::Request;
 use material_yew::list::GraphicType;
@@ -9,8 +17,8 @@ use material_yew::select::ListIndex::Single;
 use material_yew::select::SelectedDetail;
 use material_yew::text_inputs::TextFieldType;
 use material_yew::{
-    MatButton, MatCircularProgress, MatIconButton, MatListItem, MatSelect, MatSnackbar,
-    MatTextField,
+    MatButton, MatCheckbox, MatCircularProgress, MatFormfield, MatIconButton, MatListItem,
+    MatSelect, MatSnackbar, MatTextField,
 };
 use validator::Validate;
 use wasm_bindgen::JsValue;
@@ -21,10 +29,19 @@ use yew_router::scope_ext::RouterScopeExt;
 pub struct Create {
     customers: Option<Vec<CustomerResponse>>,
     subscriptions: Option<Vec<SubscriptionResponse>>,
+    bundles: Option<Vec<BundleResponse>>,
+    service_addresses: Option<Vec<AddressResponse>>,
     state_customer_id: u32,
-    state_subscription_id: u32,
+    state_subscription_id: Option<u32>,
+    state_bundle_id: Option<u32>,
     state_start_date: DateTime<Utc>,
     state_end_date: DateTime<Utc>,
+    state_penalty_rate: f64,
+    state_coupon_code: String,
+    state_service_address_id: Option<u32>,
+    state_accept_deposit: bool,
+    state_deposit_required: bool,
+    state_segmentation: Option<Segmentation>,
     state_error: Option<String>,
     state_loading: bool,
 }
@@ -32,14 +49,25 @@ pub struct Create {
 pub enum Msg {
     CreateRequest,
     CreateResponse(Result<(), anyhow::Error>),
+    DepositRequired(String),
+    EditAcceptDeposit(bool),
     GetCustomersRequest,
     GetCustomersResponse(Result<Vec<CustomerResponse>, anyhow::Error>),
     GetSubscriptionsRequest,
     GetSubscriptionsResponse(Result<Vec<SubscriptionResponse>, anyhow::Error>),
+    GetBundlesRequest,
+    GetBundlesResponse(Result<Vec<BundleResponse>, anyhow::Error>),
+    GetServiceAddressesRequest,
+    GetServiceAddressesResponse(Result<Vec<AddressResponse>, anyhow::Error>),
     EditCustomerId(u32),
-    EditSubscriptionId(u32),
+    EditSubscriptionId(Option<usize>),
+    EditBundleId(Option<usize>),
     EditStartDate(DateTime<Utc>),
     EditEndDate(DateTime<Utc>),
+    EditPenaltyRate(f64),
+    EditCouponCode(String),
+    EditServiceAddressId(Option<usize>),
+    EditSegmentation(Segmentation),
     ShowErrorSnackbar(anyhow::Error),
     HideErrorSnackbar,
     ToggleLoading,
@@ -84,16 +112,14 @@ impl Create {
                     <MatSelect
                         label="Subscription"
                         outlined=true
-                        required=true
                         icon="shop"
                         onselected={ctx.link().callback(|e: SelectedDetail| {
-                            let Single(Some(value)) = e.index else { return Msg::EditSubscriptionId(0) };
+                            let Single(Some(value)) = e.index else { return Msg::EditSubscriptionId(None) };
 
-                            match value {
-                                0 => Msg::EditSubscriptionId(0),
-                                _ => Msg::EditSubscriptionId(value as u32),
-                            }
+                            // the first item stands for no subscription
+                            Msg::EditSubscriptionId(value.checked_sub(1))
                         })}>
+                        <MatListItem value="" graphic={GraphicType::Icon}>{ "-" }</MatListItem>
                         {
                             if let Some(subscriptions) = &self.subscriptions {
                                 subscriptions.iter().enumerate().map(|(index, subscription)| {
@@ -107,6 +133,54 @@ impl Create {
                         }
                     </MatSelect>
 
+                    <MatSelect
+                        label="Bundle"
+                        outlined=true
+                        icon="inventory_2"
+                        onselected={ctx.link().callback(|e: SelectedDetail| {
+                            let Single(Some(value)) = e.index else { return Msg::EditBundleId(None) };
+
+                            // the first item stands for no bundle
+                            Msg::EditBundleId(value.checked_sub(1))
+                        })}>
+                        <MatListItem value="" graphic={GraphicType::Icon}>{ "-" }</MatListItem>
+                        {
+                            if let Some(bundles) = &self.bundles {
+                                bundles.iter().enumerate().map(|(index, bundle)| {
+                                    html! {
+                                        <MatListItem value={index.to_string()} graphic={GraphicType::Icon}>{ &bundle.description }</MatListItem>
+                                    }
+                                }).collect::<Html>()
+                            } else {
+                                html! {}
+                            }
+                        }
+                    </MatSelect>
+
+                    <MatSelect
+                        label="Service address"
+                        outlined=true
+                        icon="home"
+                        onselected={ctx.link().callback(|e: SelectedDetail| {
+                            let Single(Some(value)) = e.index else { return Msg::EditServiceAddressId(None) };
+
+                            // the first item stands for no service address
+                            Msg::EditServiceAddressId(value.checked_sub(1))
+                        })}>
+                        <MatListItem value="" graphic={GraphicType::Icon}>{ "-" }</MatListItem>
+                        {
+                            if let Some(addresses) = &self.service_addresses {
+                                addresses.iter().enumerate().map(|(index, address)| {
+                                    html! {
+                                        <MatListItem value={index.to_string()} graphic={GraphicType::Icon}>{ address.to_string() }</MatListItem>
+                                    }
+                                }).collect::<Html>()
+                            } else {
+                                html! {}
+                            }
+                        }
+                    </MatSelect>
+
                     <MatTextField
                         outlined=true
                         label="Start date"
@@ -116,7 +190,7 @@ impl Create {
                         value={self.state_start_date.format("%Y-%m-%d").to_string()}
                         oninput={ctx.link().callback(|value: String| {
                             let date = NaiveDate::parse_from_str(value.as_str(), "%Y-%m-%d").unwrap();
-                            Msg::EditStartDate(DateTime::from_utc(date.and_hms(0, 0, 0), Utc))
+                            Msg::EditStartDate(date.and_hms_opt(0, 0, 0).unwrap().and_utc())
                         })}
                     />
 
@@ -129,16 +203,58 @@ impl Create {
                         value={self.state_end_date.format("%Y-%m-%d").to_string()}
                         oninput={ctx.link().callback(|value: String| {
                             let date = NaiveDate::parse_from_str(value.as_str(), "%Y-%m-%d").unwrap();
-                            Msg::EditEndDate(DateTime::from_utc(date.and_hms(0, 0, 0), Utc))
+                            Msg::EditEndDate(date.and_hms_opt(0, 0, 0).unwrap().and_utc())
                         })}
                     />
+
+                    <MatTextField
+                        outlined=true
+                        label="Daily penalty rate (%)"
+                        icon="percent"
+                        min="0"
+                        max="100"
+                        field_type={TextFieldType::Number}
+                        value={self.state_penalty_rate.to_string()}
+                        oninput={ctx.link().callback(|value: String| {
+                            Msg::EditPenaltyRate(value.parse::<f64>().unwrap_or(0.0))
+                        })}
+                    />
+
+                    <MatTextField
+                        outlined=true
+                        label="Coupon code"
+                        icon="sell"
+                        max_length=30
+                        value={self.state_coupon_code.clone()}
+                        oninput={ctx.link().callback(Msg::EditCouponCode)}
+                    />
                 </div>
 
+                <Segment
+                    entity={Entity::Contract}
+                    onchange={ctx.link().callback(Msg::EditSegmentation)}
+                />
+
                 <div class="row-flex">
                     <button class="btn-success" type="submit">
                         <MatButton label="Create" raised=true />
                     </button>
 
+                    {
+                        if self.state_deposit_required {
+                            html! {
+                                <MatFormfield label="Accept security deposit">
+                                    <MatCheckbox
+                                        checked={self.state_accept_deposit}
+                                        onchange={ctx.link().callback(Msg::EditAcceptDeposit)}
+                                    />
+                                </MatFormfield>
+                            }
+                        } else {
+                            html! {}
+                        }
+                    }
+
                     {
                         if self.state_loading {
                             html! {
@@ -161,14 +277,32 @@ impl Component for Create {
     fn create(ctx: &Context<Self>) -> Self {
         ctx.link().send_message(Msg::GetCustomersRequest);
         ctx.link().send_message(Msg::GetSubscriptionsRequest);
+        ctx.link().send_message(Msg::GetBundlesRequest);
 
         Self {
             customers: None,
             subscriptions: None,
+            bundles: None,
+            service_addresses: None,
             state_customer_id: 0,
-            state_subscription_id: 0,
-            state_start_date: Utc::now().date().and_hms(0, 0, 0),
-            state_end_date: Utc::now().date().and_hms(0, 0, 0),
+            state_subscription_id: None,
+            state_bundle_id: None,
+            state_start_date: Utc::now()
+                .date_naive()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc(),
+            state_end_date: Utc::now()
+                .date_naive()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc(),
+            state_penalty_rate: 0.0,
+            state_coupon_code: String::new(),
+            state_service_address_id: None,
+            state_accept_deposit: false,
+            state_deposit_required: false,
+            state_segmentation: None,
             state_error: None,
             state_loading: false,
         }
@@ -184,8 +318,13 @@ impl Component for Create {
                 let state = CreateContractRequest {
                     customer_id: self.state_customer_id,
                     subscription_id: self.state_subscription_id,
+                    bundle_id: self.state_bundle_id,
                     start_date: self.state_start_date,
                     end_date: self.state_end_date,
+                    penalty_rate: self.state_penalty_rate,
+                    coupon_code: Some(self.state_coupon_code.trim().to_uppercase())
+                        .filter(|code| !code.is_empty()),
+                    service_address_id: self.state_service_address_id,
                 };
 
                 log::debug!("State: {:?}", state);
@@ -203,25 +342,49 @@ impl Component for Create {
                 let contract = state.clone();
                 log::info!("Creating contract: {:?}", contract);
 
+                let accept_deposit = self.state_accept_deposit;
+                let segmentation = self.state_segmentation.clone();
+
                 wasm_bindgen_futures::spawn_local(async move {
                     let contract_json = JsValue::from(serde_json::to_string(&contract).unwrap());
 
-                    let create_contract_req = Request::post("http://localhost:8000/api/contract")
-                        .header("Content-Type", "application/json")
-                        .body(contract_json)
-                        .expect("Failed to build request.");
+                    let create_contract_req =
+                        Request::post("http://localhost:8000/api/v1/contract")
+                            .header("Authorization", &auth::bearer())
+                            .query([("accept_deposit", accept_deposit.to_string())])
+                            .header("Content-Type", "application/json")
+                            .body(contract_json)
+                            .expect("Failed to build request.");
 
                     let resp = create_contract_req.send().await;
 
                     match resp {
                         Ok(resp) => {
                             if resp.status() == 201 {
-                                link.send_message(Msg::CreateResponse(Ok(())));
+                                let result = match resp.json::<ContractResponse>().await {
+                                    Ok(contract) => match segmentation {
+                                        Some(segmentation) => {
+                                            segmentation.save(&Entity::Contract, contract.id).await
+                                        }
+                                        None => Ok(()),
+                                    },
+                                    Err(err) => {
+                                        Err(anyhow::anyhow!("Failed to parse response: {:?}", err))
+                                    }
+                                };
+
+                                link.send_message(Msg::CreateResponse(result));
                             } else {
-                                link.send_message(Msg::CreateResponse(Err(anyhow::anyhow!(
-                                    "Failed to create contract: {:?}",
-                                    resp
-                                ))));
+                                match problem::read(resp).await {
+                                    Ok(problem) if problem.code == ErrorCode::DepositRequired => {
+                                        link.send_message(Msg::DepositRequired(problem.detail));
+                                    }
+                                    problem => {
+                                        link.send_message(Msg::CreateResponse(Err(
+                                            problem::failed("Failed to create contract", problem),
+                                        )));
+                                    }
+                                }
                             }
                         }
                         Err(err) => {
@@ -248,9 +411,25 @@ impl Component for Create {
                 link.send_message(Msg::ShowErrorSnackbar(err));
                 false
             }
+            Msg::DepositRequired(message) => {
+                log::warn!("Security deposit required: {}", message);
+                self.state_deposit_required = true;
+                link.send_message(Msg::ToggleLoading);
+                link.send_message(Msg::ShowErrorSnackbar(anyhow::anyhow!(
+                    "Security deposit required: {}",
+                    message
+                )));
+                true
+            }
+            Msg::EditAcceptDeposit(accept_deposit) => {
+                self.state_accept_deposit = accept_deposit;
+                true
+            }
             Msg::GetCustomersRequest => {
                 wasm_bindgen_futures::spawn_local(async move {
-                    let get_customers_req = Request::get("http://localhost:8000/api/customer")
+                    let get_customers_req = Request::get("http://localhost:8000/api/v1/customer")
+                        .header("Authorization", &auth::bearer())
+                        .query([("per_page", MAX_PER_PAGE.to_string())])
                         .header("Content-Type", "application/json");
 
                     let resp = get_customers_req.send().await;
@@ -264,10 +443,11 @@ impl Component for Create {
 
                                 link.send_message(Msg::GetCustomersResponse(customers));
                             } else {
-                                link.send_message(Msg::GetCustomersResponse(Err(anyhow::anyhow!(
-                                    "Failed to get customers: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::GetCustomersResponse(Err(problem::error(
+                                    "Failed to get customers",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
@@ -291,7 +471,9 @@ impl Component for Create {
             Msg::GetSubscriptionsRequest => {
                 wasm_bindgen_futures::spawn_local(async move {
                     let get_subscriptions_req =
-                        Request::get("http://localhost:8000/api/subscription")
+                        Request::get("http://localhost:8000/api/v1/subscription")
+                            .header("Authorization", &auth::bearer())
+                            .query([("per_page", MAX_PER_PAGE.to_string())])
                             .header("Content-Type", "application/json");
 
                     let resp = get_subscriptions_req.send().await;
@@ -306,7 +488,7 @@ impl Component for Create {
                                 link.send_message(Msg::GetSubscriptionsResponse(subscriptions));
                             } else {
                                 link.send_message(Msg::GetSubscriptionsResponse(Err(
-                                    anyhow::anyhow!("Failed to get subscriptions: {:?}", resp),
+                                    problem::error("Failed to get subscriptions", resp).await,
                                 )));
                             }
                         }
@@ -328,26 +510,128 @@ impl Component for Create {
                 log::error!("Failed to retrieve subscriptions: {:?}", err);
                 false
             }
+            Msg::GetBundlesRequest => {
+                wasm_bindgen_futures::spawn_local(async move {
+                    let get_bundles_req = Request::get("http://localhost:8000/api/v1/bundle")
+                        .header("Authorization", &auth::bearer())
+                        .header("Content-Type", "application/json");
+
+                    let resp = get_bundles_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 200 {
+                                let bundles = resp.json().await.map_err(|err| {
+                                    anyhow::anyhow!("Failed to parse response: {:?}", err)
+                                });
+
+                                link.send_message(Msg::GetBundlesResponse(bundles));
+                            } else {
+                                link.send_message(Msg::GetBundlesResponse(Err(problem::error(
+                                    "Failed to get bundles",
+                                    resp,
+                                )
+                                .await)));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::GetBundlesResponse(Err(anyhow::anyhow!(
+                                "Failed to send request: {:?}",
+                                err
+                            ))));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::GetBundlesResponse(Ok(bundles)) => {
+                self.bundles = Some(bundles);
+                true
+            }
+            Msg::GetBundlesResponse(Err(err)) => {
+                log::error!("Failed to retrieve bundles: {:?}", err);
+                false
+            }
+            Msg::GetServiceAddressesRequest => {
+                let customer_id = self.state_customer_id;
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let get_addresses_req = Request::get(
+                        format!(
+                            "http://localhost:8000/api/v1/customer/{}/address",
+                            customer_id
+                        )
+                        .as_str(),
+                    )
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json");
+
+                    let resp = get_addresses_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 200 {
+                                let addresses = resp.json().await.map_err(|err| {
+                                    anyhow::anyhow!("Failed to parse response: {:?}", err)
+                                });
+
+                                link.send_message(Msg::GetServiceAddressesResponse(addresses));
+                            } else {
+                                link.send_message(Msg::GetServiceAddressesResponse(Err(
+                                    problem::error("Failed to get addresses", resp).await,
+                                )));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::GetServiceAddressesResponse(Err(
+                                anyhow::anyhow!("Failed to send request: {:?}", err),
+                            )));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::GetServiceAddressesResponse(Ok(addresses)) => {
+                self.service_addresses = Some(
+                    addresses
+                        .into_iter()
+                        .filter(|address| address.address_type == AddressType::Service)
+                        .collect(),
+                );
+                true
+            }
+            Msg::GetServiceAddressesResponse(Err(err)) => {
+                log::error!("Failed to retrieve addresses: {:?}", err);
+                false
+            }
             Msg::EditCustomerId(customer_id_index) => {
                 if let Some(customers) = self.customers.as_ref() {
                     let customer_id = customers[customer_id_index as usize].id;
                     self.state_customer_id = customer_id;
 
-                    true
-                } else {
-                    false
-                }
-            }
-            Msg::EditSubscriptionId(subscription_id) => {
-                if let Some(subscriptions) = self.subscriptions.as_ref() {
-                    let subscription_id = subscriptions[subscription_id as usize].id;
-                    self.state_subscription_id = subscription_id;
+                    // the service address has to belong to the selected customer
+                    self.service_addresses = None;
+                    self.state_service_address_id = None;
+                    link.send_message(Msg::GetServiceAddressesRequest);
 
                     true
                 } else {
                     false
                 }
             }
+            Msg::EditSubscriptionId(subscription_index) => {
+                self.state_subscription_id = subscription_index.and_then(|index| {
+                    self.subscriptions
+                        .as_ref()
+                        .map(|subscriptions| subscriptions[index].id)
+                });
+                true
+            }
+            Msg::EditBundleId(bundle_index) => {
+                self.state_bundle_id = bundle_index
+                    .and_then(|index| self.bundles.as_ref().map(|bundles| bundles[index].id));
+                true
+            }
             Msg::EditStartDate(start_date) => {
                 log::info!("Start date: {:?}", start_date);
                 self.state_start_date = start_date;
@@ -358,6 +642,26 @@ impl Component for Create {
                 self.state_end_date = end_date;
                 true
             }
+            Msg::EditPenaltyRate(penalty_rate) => {
+                self.state_penalty_rate = penalty_rate;
+                true
+            }
+            Msg::EditCouponCode(coupon_code) => {
+                self.state_coupon_code = coupon_code;
+                true
+            }
+            Msg::EditServiceAddressId(address_index) => {
+                self.state_service_address_id = address_index.and_then(|index| {
+                    self.service_addresses
+                        .as_ref()
+                        .map(|addresses| addresses[index].id)
+                });
+                true
+            }
+            Msg::EditSegmentation(segmentation) => {
+                self.state_segmentation = Some(segmentation);
+                false
+            }
             Msg::ShowErrorSnackbar(err) => {
                 self.state_error = Some(err.to_string());
                 true
@@ -0,0 +1,228 @@
+use crate::views::auth;
+use crate::views::problem;
+use common::credit::{DepositResponse, DepositStatus};
+use gloo_net::http::Request;
+use material_yew::{MatButton, MatCircularProgress};
+use yew::{html, Component, Context, Html, Properties};
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct DepositProps {
+    pub contract_id: u32,
+}
+
+pub struct Deposit {
+    /// `Some(None)` once the contract turns out to have no deposit.
+    deposit: Option<Option<DepositResponse>>,
+    error: Option<String>,
+}
+
+pub enum Msg {
+    GetRequest,
+    GetResponse(Result<Option<DepositResponse>, anyhow::Error>),
+    PayRequest,
+    RefundRequest,
+    UpdateResponse(Result<DepositResponse, anyhow::Error>),
+}
+
+impl Deposit {
+    fn render_deposit(&self, ctx: &Context<Deposit>, deposit: &DepositResponse) -> Html {
+        let format_date = |date: &Option<chrono::DateTime<chrono::Utc>>| {
+            date.map(|date| date.format("%Y-%m-%d").to_string())
+                .unwrap_or_default()
+        };
+
+        html! {
+            <table class="tftable" border="1">
+                <thead>
+                    <tr>
+                        <th>{ "Amount" }</th>
+                        <th>{ "Status" }</th>
+                        <th>{ "Required on" }</th>
+                        <th>{ "Paid on" }</th>
+                        <th>{ "Refunded on" }</th>
+                        <th>{ "Refunded amount" }</th>
+                        <th>{ "Actions" }</th>
+                    </tr>
+                </thead>
+
+                <tbody>
+                    <tr>
+                        <td>{ format!("{:.2}", deposit.amount) }</td>
+                        <td>{ &deposit.status }</td>
+                        <td>{ deposit.created_at.format("%Y-%m-%d").to_string() }</td>
+                        <td>{ format_date(&deposit.paid_at) }</td>
+                        <td>{ format_date(&deposit.refunded_at) }</td>
+                        <td>{ deposit.refunded_amount.map(|amount| format!("{:.2}", amount)).unwrap_or_default() }</td>
+                        <td>
+                            {
+                                match deposit.status {
+                                    DepositStatus::Pending => html! {
+                                        <button class="btn-success" onclick={ctx.link().callback(|_| Msg::PayRequest)}>
+                                            <MatButton label="Mark paid" />
+                                        </button>
+                                    },
+                                    DepositStatus::Held => html! {
+                                        <button class="btn-warning" onclick={ctx.link().callback(|_| Msg::RefundRequest)}>
+                                            <MatButton label="Refund" />
+                                        </button>
+                                    },
+                                    DepositStatus::Refunded => html! {},
+                                }
+                            }
+                        </td>
+                    </tr>
+                </tbody>
+            </table>
+        }
+    }
+}
+
+impl Component for Deposit {
+    type Message = Msg;
+    type Properties = DepositProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link().send_message(Msg::GetRequest);
+
+        Self {
+            deposit: None,
+            error: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let link = ctx.link().clone();
+        let contract_id = ctx.props().contract_id;
+
+        match msg {
+            Msg::GetRequest => {
+                wasm_bindgen_futures::spawn_local(async move {
+                    let get_deposit_req = Request::get(
+                        format!(
+                            "http://localhost:8000/api/v1/contract/{}/deposit",
+                            contract_id
+                        )
+                        .as_str(),
+                    )
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json");
+
+                    let resp = get_deposit_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 200 {
+                                let deposit = resp.json().await.map(Some).map_err(|err| {
+                                    anyhow::anyhow!("Failed to parse response: {:?}", err)
+                                });
+
+                                link.send_message(Msg::GetResponse(deposit));
+                            } else if resp.status() == 404 {
+                                link.send_message(Msg::GetResponse(Ok(None)));
+                            } else {
+                                link.send_message(Msg::GetResponse(Err(problem::error(
+                                    "Failed to get deposit",
+                                    resp,
+                                )
+                                .await)));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::GetResponse(Err(anyhow::anyhow!(
+                                "Failed to send request: {:?}",
+                                err
+                            ))));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::GetResponse(Ok(deposit)) => {
+                self.deposit = Some(deposit);
+                true
+            }
+            Msg::GetResponse(Err(err)) => {
+                log::error!("Failed to get deposit: {:?}", err);
+                false
+            }
+            Msg::PayRequest | Msg::RefundRequest => {
+                let action = match msg {
+                    Msg::PayRequest => "pay",
+                    _ => "refund",
+                };
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let update_deposit_req = Request::post(
+                        format!(
+                            "http://localhost:8000/api/v1/contract/{}/deposit/{}",
+                            contract_id, action
+                        )
+                        .as_str(),
+                    )
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json");
+
+                    let resp = update_deposit_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 200 {
+                                let deposit = resp.json().await.map_err(|err| {
+                                    anyhow::anyhow!("Failed to parse response: {:?}", err)
+                                });
+
+                                link.send_message(Msg::UpdateResponse(deposit));
+                            } else {
+                                let action = format!("Failed to {} deposit", action);
+                                link.send_message(Msg::UpdateResponse(Err(problem::error(
+                                    &action, resp,
+                                )
+                                .await)));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::UpdateResponse(Err(anyhow::anyhow!(
+                                "Failed to send request: {:?}",
+                                err
+                            ))));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::UpdateResponse(Ok(deposit)) => {
+                self.deposit = Some(Some(deposit));
+                self.error = None;
+                true
+            }
+            Msg::UpdateResponse(Err(err)) => {
+                log::error!("Failed to update deposit: {:?}", err);
+                self.error = Some(err.to_string());
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        match &self.deposit {
+            Some(Some(deposit)) => html! {
+                <>
+                    { self.render_deposit(ctx, deposit) }
+                    {
+                        if let Some(error) = &self.error {
+                            html! { <p>{ error }</p> }
+                        } else {
+                            html! {}
+                        }
+                    }
+                </>
+            },
+            Some(None) => html! { <p>{ "No security deposit required." }</p> },
+            None => html! {
+                <div>
+                    <MatCircularProgress indeterminate=true />
+                </div>
+            },
+        }
+    }
+}
@@ -1,8 +1,23 @@
 use crate::app::{AppLink, Route};
+use crate::views::auth;
+use crate::views::contract::deposit::Deposit;
+use crate::views::problem;
+use chrono::{DateTime, NaiveDate, Utc};
+use common::addon::{AddonResponse, ContractAddonRequest, ContractAddonResponse};
 use common::contract::ContractResponse;
+use common::etag;
 use common::invoice::InvoiceResponse;
 use gloo_net::http::Request;
-use material_yew::{MatButton, MatCircularProgress, MatIconButton};
+use material_yew::list::GraphicType;
+use material_yew::select::ListIndex::Single;
+use material_yew::select::SelectedDetail;
+use material_yew::text_inputs::TextFieldType;
+use material_yew::{
+    MatButton, MatCircularProgress, MatIconButton, MatListItem, MatSelect, MatTextField,
+};
+use validator::Validate;
+use wasm_bindgen::JsValue;
+use web_sys::SubmitEvent;
 use yew::{html, AttrValue, Component, Context, Html, Properties};
 use yew_router::scope_ext::RouterScopeExt;
 
@@ -14,6 +29,12 @@ pub struct DetailProps {
 pub struct Detail {
     contract: Option<ContractResponse>,
     invoices: Option<Vec<InvoiceResponse>>,
+    addons: Option<Vec<AddonResponse>>,
+    contract_addons: Option<Vec<ContractAddonResponse>>,
+    state_addon_id: Option<u32>,
+    state_addon_start_date: DateTime<Utc>,
+    state_addon_end_date: DateTime<Utc>,
+    state_addon_price: String,
 }
 
 pub enum Msg {
@@ -23,14 +44,27 @@ pub enum Msg {
     GetInvoicesResponse(Result<Vec<InvoiceResponse>, anyhow::Error>),
     DeleteInvoiceRequest(u32),
     DeleteInvoiceResponse(Result<(), anyhow::Error>),
-    DeleteRequest(u32),
+    DeleteRequest(u32, u32),
     DeleteResponse(Result<(), anyhow::Error>),
+    GetAddonsRequest,
+    GetAddonsResponse(Result<Vec<AddonResponse>, anyhow::Error>),
+    GetContractAddonsRequest,
+    GetContractAddonsResponse(Result<Vec<ContractAddonResponse>, anyhow::Error>),
+    EditAddonId(usize),
+    EditAddonStartDate(DateTime<Utc>),
+    EditAddonEndDate(DateTime<Utc>),
+    EditAddonPrice(String),
+    AddAddonRequest,
+    AddAddonResponse(Result<(), anyhow::Error>),
+    RemoveAddonRequest(u32, u32),
+    RemoveAddonResponse(Result<(), anyhow::Error>),
 }
 
 impl Detail {
     fn render_contract(&self, ctx: &Context<Detail>) -> Html {
         if let Some(contract) = &self.contract {
             let contract_id = contract.id;
+            let contract_version = contract.version;
 
             html! {
                 <table class="tftable" border="1">
@@ -38,9 +72,12 @@ impl Detail {
                         <tr>
                             <th>{ "ID" }</th>
                             <th>{ "Customer ID" }</th>
-                            <th>{ "Subscription ID" }</th>
+                            <th>{ "Subscription / Bundle" }</th>
                             <th>{ "Start Date" }</th>
                             <th>{ "End Date" }</th>
+                            <th>{ "Daily Penalty Rate (%)" }</th>
+                            <th>{ "Promotion ID" }</th>
+                            <th>{ "Service Address ID" }</th>
                             <th>{ "Actions" }</th>
                         </tr>
                     </thead>
@@ -54,12 +91,21 @@ impl Detail {
                                 </AppLink>
                             </td>
                             <td>
-                                <AppLink to={Route::SubscriptionDetail { id: contract.subscription_id }}>
-                                    { &contract.subscription_id }
-                                </AppLink>
+                                {
+                                    match (contract.subscription_id, contract.bundle_id) {
+                                        (Some(id), _) => html! {
+                                            <AppLink to={Route::SubscriptionDetail { id }}>{ id }</AppLink>
+                                        },
+                                        (None, Some(id)) => html! { format!("Bundle {}", id) },
+                                        (None, None) => html! { "-" },
+                                    }
+                                }
                             </td>
                             <td>{ contract.start_date.format("%m-%d-%Y").to_string() }</td>
                             <td>{ &contract.end_date.format("%m-%d-%Y").to_string() }</td>
+                            <td>{ &contract.penalty_rate }</td>
+                            <td>{ contract.promotion_id.map(|id| id.to_string()).unwrap_or("-".to_string()) }</td>
+                            <td>{ contract.service_address_id.map(|id| id.to_string()).unwrap_or("-".to_string()) }</td>
                             <td>
                                 <AppLink to={Route::ContractEdit { id: contract.id }}>
                                     <button class="btn-warning">
@@ -67,7 +113,7 @@ impl Detail {
                                     </button>
                                 </AppLink>
 
-                                <button class="btn-danger" onclick={ctx.link().callback(move |_| Msg::DeleteRequest(contract_id))}>
+                                <button class="btn-danger" onclick={ctx.link().callback(move |_| Msg::DeleteRequest(contract_id, contract_version))}>
                                     <MatIconButton icon="delete" />
                                 </button>
                             </td>
@@ -137,6 +183,138 @@ impl Detail {
             </tr>
         }
     }
+
+    fn render_addons(&self, ctx: &Context<Detail>) -> Html {
+        if let Some(contract_addons) = &self.contract_addons {
+            html! {
+                <table class="tftable" border="1">
+                    <thead>
+                        <tr>
+                            <th>{ "ID" }</th>
+                            <th>{ "Add-on" }</th>
+                            <th>{ "Start Date" }</th>
+                            <th>{ "End Date" }</th>
+                            <th>{ "Price" }</th>
+                            <th>{ "Actions" }</th>
+                        </tr>
+                    </thead>
+
+                    <tbody>
+                        { contract_addons.iter().map(|contract_addon| self.render_addon(ctx, contract_addon)).collect::<Html>() }
+                    </tbody>
+                </table>
+            }
+        } else {
+            html! {
+                <div>
+                    <MatCircularProgress indeterminate=true />
+                </div>
+            }
+        }
+    }
+
+    fn render_addon(&self, ctx: &Context<Detail>, contract_addon: &ContractAddonResponse) -> Html {
+        let contract_addon_id = contract_addon.id;
+        let contract_addon_version = contract_addon.version;
+        let description = self
+            .addons
+            .as_ref()
+            .and_then(|addons| addons.iter().find(|a| a.id == contract_addon.addon_id))
+            .map(|addon| addon.description.clone())
+            .unwrap_or(contract_addon.addon_id.to_string());
+
+        html! {
+            <tr>
+                <td>{ &contract_addon.id }</td>
+                <td>{ description }</td>
+                <td>{ contract_addon.start_date.format("%m-%d-%Y").to_string() }</td>
+                <td>{ contract_addon.end_date.format("%m-%d-%Y").to_string() }</td>
+                <td>{ &contract_addon.price }</td>
+                <td>
+                    <button class="btn-danger" onclick={ctx.link().callback(move |_| Msg::RemoveAddonRequest(contract_addon_id, contract_addon_version))}>
+                        <MatIconButton icon="delete" />
+                    </button>
+                </td>
+            </tr>
+        }
+    }
+
+    fn render_addon_form(&self, ctx: &Context<Detail>) -> Html {
+        let onsubmit = ctx.link().callback(|e: SubmitEvent| {
+            e.prevent_default();
+            Msg::AddAddonRequest
+        });
+
+        html! {
+            <form {onsubmit}>
+                <div class="form-input">
+                    <MatSelect
+                        label="Add-on"
+                        outlined=true
+                        required=true
+                        icon="extension"
+                        onselected={ctx.link().callback(|e: SelectedDetail| {
+                            let Single(Some(value)) = e.index else { return Msg::EditAddonId(0) };
+                            Msg::EditAddonId(value)
+                        })}>
+                        {
+                            if let Some(addons) = &self.addons {
+                                addons.iter().enumerate().map(|(index, addon)| {
+                                    html! {
+                                        <MatListItem value={index.to_string()} graphic={GraphicType::Icon}>{ &addon.description }</MatListItem>
+                                    }
+                                }).collect::<Html>()
+                            } else {
+                                html! {}
+                            }
+                        }
+                    </MatSelect>
+
+                    <MatTextField
+                        outlined=true
+                        label="Start date"
+                        icon="event"
+                        required=true
+                        field_type={TextFieldType::Date}
+                        value={self.state_addon_start_date.format("%Y-%m-%d").to_string()}
+                        oninput={ctx.link().callback(|value: String| {
+                            let date = NaiveDate::parse_from_str(value.as_str(), "%Y-%m-%d").unwrap();
+                            Msg::EditAddonStartDate(date.and_hms_opt(0, 0, 0).unwrap().and_utc())
+                        })}
+                    />
+
+                    <MatTextField
+                        outlined=true
+                        label="End date"
+                        icon="event"
+                        required=true
+                        field_type={TextFieldType::Date}
+                        value={self.state_addon_end_date.format("%Y-%m-%d").to_string()}
+                        oninput={ctx.link().callback(|value: String| {
+                            let date = NaiveDate::parse_from_str(value.as_str(), "%Y-%m-%d").unwrap();
+                            Msg::EditAddonEndDate(date.and_hms_opt(0, 0, 0).unwrap().and_utc())
+                        })}
+                    />
+
+                    <MatTextField
+                        outlined=true
+                        label="Price (defaults to catalog price)"
+                        icon="payments"
+                        min="0"
+                        field_type={TextFieldType::Number}
+                        value={self.state_addon_price.clone()}
+                        oninput={ctx.link().callback(Msg::EditAddonPrice)}
+                    />
+                </div>
+
+                <div class="row-flex">
+                    <button class="btn-success" type="submit">
+                        <MatButton label="Add add-on" raised=true />
+                    </button>
+                </div>
+            </form>
+        }
+    }
 }
 
 impl Component for Detail {
@@ -146,10 +324,24 @@ impl Component for Detail {
     fn create(ctx: &Context<Self>) -> Self {
         ctx.link().send_message(Msg::GetRequest);
         ctx.link().send_message(Msg::GetInvoicesRequest);
+        ctx.link().send_message(Msg::GetAddonsRequest);
+        ctx.link().send_message(Msg::GetContractAddonsRequest);
+
+        let today = Utc::now()
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            .and_utc();
 
         Self {
             contract: None,
             invoices: None,
+            addons: None,
+            contract_addons: None,
+            state_addon_id: None,
+            state_addon_start_date: today,
+            state_addon_end_date: today,
+            state_addon_price: String::new(),
         }
     }
 
@@ -163,8 +355,9 @@ impl Component for Detail {
 
                 wasm_bindgen_futures::spawn_local(async move {
                     let get_contract_req = Request::get(
-                        format!("http://localhost:8000/api/contract/{}", props.id).as_str(),
+                        format!("http://localhost:8000/api/v1/contract/{}", props.id).as_str(),
                     )
+                    .header("Authorization", &auth::bearer())
                     .header("Content-Type", "application/json");
 
                     let resp = get_contract_req.send().await;
@@ -179,10 +372,11 @@ impl Component for Detail {
 
                                 link.send_message(Msg::GetResponse(contract));
                             } else {
-                                link.send_message(Msg::GetResponse(Err(anyhow::anyhow!(
-                                    "Failed to get contract: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::GetResponse(Err(problem::error(
+                                    "Failed to get contract",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
@@ -208,8 +402,10 @@ impl Component for Detail {
 
                 wasm_bindgen_futures::spawn_local(async move {
                     let get_invoices_req = Request::get(
-                        format!("http://localhost:8000/api/contract/{}/invoice", props.id).as_str(),
+                        format!("http://localhost:8000/api/v1/contract/{}/invoice", props.id)
+                            .as_str(),
                     )
+                    .header("Authorization", &auth::bearer())
                     .header("Content-Type", "application/json");
 
                     let resp = get_invoices_req.send().await;
@@ -224,10 +420,11 @@ impl Component for Detail {
 
                                 link.send_message(Msg::GetInvoicesResponse(invoices));
                             } else {
-                                link.send_message(Msg::GetInvoicesResponse(Err(anyhow::anyhow!(
-                                    "Failed to get invoices: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::GetInvoicesResponse(Err(problem::error(
+                                    "Failed to get invoices",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
@@ -253,8 +450,9 @@ impl Component for Detail {
 
                 wasm_bindgen_futures::spawn_local(async move {
                     let delete_invoice_req = Request::delete(
-                        format!("http://localhost:8000/api/invoice/{}", id).as_str(),
+                        format!("http://localhost:8000/api/v1/invoice/{}", id).as_str(),
                     )
+                    .header("Authorization", &auth::bearer())
                     .header("Content-Type", "application/json");
 
                     let resp = delete_invoice_req.send().await;
@@ -264,9 +462,11 @@ impl Component for Detail {
                             if resp.status() == 204 {
                                 link.send_message(Msg::DeleteInvoiceResponse(Ok(())));
                             } else {
-                                link.send_message(Msg::DeleteInvoiceResponse(Err(
-                                    anyhow::anyhow!("Failed to delete invoice: {:?}", resp),
-                                )));
+                                link.send_message(Msg::DeleteInvoiceResponse(Err(problem::error(
+                                    "Failed to delete invoice",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
@@ -287,14 +487,16 @@ impl Component for Detail {
                 log::error!("Failed to delete invoice: {:?}", err);
                 false
             }
-            Msg::DeleteRequest(id) => {
+            Msg::DeleteRequest(id, version) => {
                 log::info!("Deleting contract {}", id);
 
                 wasm_bindgen_futures::spawn_local(async move {
                     let delete_contract_req = Request::delete(
-                        format!("http://localhost:8000/api/contract/{}", id).as_str(),
+                        format!("http://localhost:8000/api/v1/contract/{}", id).as_str(),
                     )
-                    .header("Content-Type", "application/json");
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json")
+                    .header("If-Match", &etag::of(version));
 
                     let resp = delete_contract_req.send().await;
 
@@ -303,10 +505,11 @@ impl Component for Detail {
                             if resp.status() == 204 {
                                 link.send_message(Msg::DeleteResponse(Ok(())));
                             } else {
-                                link.send_message(Msg::DeleteResponse(Err(anyhow::anyhow!(
-                                    "Failed to delete contract: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::DeleteResponse(Err(problem::error(
+                                    "Failed to delete contract",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
@@ -329,6 +532,233 @@ impl Component for Detail {
                 log::error!("Failed deleting contract: {:?}", err);
                 false
             }
+            Msg::GetAddonsRequest => {
+                wasm_bindgen_futures::spawn_local(async move {
+                    let get_addons_req = Request::get("http://localhost:8000/api/v1/addon")
+                        .header("Authorization", &auth::bearer())
+                        .header("Content-Type", "application/json");
+
+                    let resp = get_addons_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 200 {
+                                let addons =
+                                    resp.json::<Vec<AddonResponse>>().await.map_err(|err| {
+                                        anyhow::anyhow!("Failed to parse response: {}", err)
+                                    });
+
+                                link.send_message(Msg::GetAddonsResponse(addons));
+                            } else {
+                                link.send_message(Msg::GetAddonsResponse(Err(problem::error(
+                                    "Failed to get add-ons",
+                                    resp,
+                                )
+                                .await)));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::GetAddonsResponse(Err(anyhow::anyhow!(
+                                "Failed to send request: {}",
+                                err
+                            ))));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::GetAddonsResponse(Ok(addons)) => {
+                self.addons = Some(addons);
+                true
+            }
+            Msg::GetAddonsResponse(Err(err)) => {
+                log::error!("Failed retrieving add-ons data: {:?}", err);
+                false
+            }
+            Msg::GetContractAddonsRequest => {
+                log::info!("Fetching add-ons for contract with id {}", props.id);
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let get_contract_addons_req = Request::get(
+                        format!("http://localhost:8000/api/v1/contract/{}/addon", props.id)
+                            .as_str(),
+                    )
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json");
+
+                    let resp = get_contract_addons_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 200 {
+                                let contract_addons =
+                                    resp.json::<Vec<ContractAddonResponse>>().await.map_err(
+                                        |err| anyhow::anyhow!("Failed to parse response: {}", err),
+                                    );
+
+                                link.send_message(Msg::GetContractAddonsResponse(contract_addons));
+                            } else {
+                                link.send_message(Msg::GetContractAddonsResponse(Err(
+                                    problem::error("Failed to get contract add-ons", resp).await,
+                                )));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::GetContractAddonsResponse(Err(
+                                anyhow::anyhow!("Failed to send request: {}", err),
+                            )));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::GetContractAddonsResponse(Ok(contract_addons)) => {
+                self.contract_addons = Some(contract_addons);
+                true
+            }
+            Msg::GetContractAddonsResponse(Err(err)) => {
+                log::error!("Failed retrieving contract add-ons data: {:?}", err);
+                false
+            }
+            Msg::EditAddonId(addon_index) => {
+                self.state_addon_id = self
+                    .addons
+                    .as_ref()
+                    .and_then(|addons| addons.get(addon_index))
+                    .map(|addon| addon.id);
+                true
+            }
+            Msg::EditAddonStartDate(start_date) => {
+                self.state_addon_start_date = start_date;
+                true
+            }
+            Msg::EditAddonEndDate(end_date) => {
+                self.state_addon_end_date = end_date;
+                true
+            }
+            Msg::EditAddonPrice(price) => {
+                self.state_addon_price = price;
+                true
+            }
+            Msg::AddAddonRequest => {
+                let Some(addon_id) = self.state_addon_id else {
+                    link.send_message(Msg::AddAddonResponse(Err(anyhow::anyhow!(
+                        "No add-on selected"
+                    ))));
+                    return false;
+                };
+
+                let contract_addon = ContractAddonRequest {
+                    addon_id,
+                    start_date: self.state_addon_start_date,
+                    end_date: self.state_addon_end_date,
+                    price: self.state_addon_price.trim().parse::<f64>().ok(),
+                };
+
+                let validation_result = contract_addon.validate();
+
+                if validation_result.is_err() {
+                    link.send_message(Msg::AddAddonResponse(Err(anyhow::anyhow!(
+                        "Validation failed: {:?}",
+                        validation_result
+                    ))));
+                    return false;
+                }
+
+                log::info!("Adding add-on to contract: {:?}", contract_addon);
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let contract_addon_json =
+                        JsValue::from(serde_json::to_string(&contract_addon).unwrap());
+
+                    let add_addon_req = Request::post(
+                        format!("http://localhost:8000/api/v1/contract/{}/addon", props.id)
+                            .as_str(),
+                    )
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json")
+                    .body(contract_addon_json)
+                    .expect("Failed to build request.");
+
+                    let resp = add_addon_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 201 {
+                                link.send_message(Msg::AddAddonResponse(Ok(())));
+                            } else {
+                                link.send_message(Msg::AddAddonResponse(Err(problem::error(
+                                    "Failed to add add-on",
+                                    resp,
+                                )
+                                .await)));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::AddAddonResponse(Err(anyhow::anyhow!(
+                                "Failed to send request: {}",
+                                err
+                            ))));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::AddAddonResponse(Ok(_)) => {
+                link.send_message(Msg::GetContractAddonsRequest);
+                false
+            }
+            Msg::AddAddonResponse(Err(err)) => {
+                log::error!("Failed to add add-on: {:?}", err);
+                false
+            }
+            Msg::RemoveAddonRequest(contract_addon_id, version) => {
+                log::info!("Removing add-on {}", contract_addon_id);
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let remove_addon_req = Request::delete(
+                        format!(
+                            "http://localhost:8000/api/v1/contract/{}/addon/{}",
+                            props.id, contract_addon_id
+                        )
+                        .as_str(),
+                    )
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json")
+                    .header("If-Match", &etag::of(version));
+
+                    let resp = remove_addon_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 204 {
+                                link.send_message(Msg::RemoveAddonResponse(Ok(())));
+                            } else {
+                                link.send_message(Msg::RemoveAddonResponse(Err(problem::error(
+                                    "Failed to remove add-on",
+                                    resp,
+                                )
+                                .await)));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::RemoveAddonResponse(Err(anyhow::anyhow!(
+                                "Failed to send request: {}",
+                                err
+                            ))));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::RemoveAddonResponse(Ok(_)) => {
+                link.send_message(Msg::GetContractAddonsRequest);
+                false
+            }
+            Msg::RemoveAddonResponse(Err(err)) => {
+                log::error!("Failed to remove add-on: {:?}", err);
+                false
+            }
         }
     }
 
@@ -338,6 +768,13 @@ impl Component for Detail {
                 <h2>{ "Contract details" }</h2>
                 { self.render_contract(ctx) }
 
+                <h2>{ "Security deposit" }</h2>
+                <Deposit contract_id={ctx.props().id} />
+
+                <h2>{ "Add-ons" }</h2>
+                { self.render_addon_form(ctx) }
+                { self.render_addons(ctx) }
+
                 <h2>{ "Invoices" }</h2>
                 <h3>
                     <AppLink to={Route::InvoiceCreate}>
@@ -1,5 +1,12 @@
+use crate::views::auth;
+use crate::views::conflict::{Conflict, Resolution};
+use crate::views::problem;
+use crate::views::segment::{Segment, Segmentation};
 use chrono::{DateTime, NaiveDate, Utc};
 use common::contract::{ContractResponse, UpdateContractRequest};
+use common::etag;
+use common::field::Entity;
+use common::patch;
 use gloo_net::http::Request;
 use material_yew::text_inputs::TextFieldType;
 use material_yew::{MatButton, MatCircularProgress, MatIconButton, MatSnackbar, MatTextField};
@@ -17,6 +24,12 @@ pub struct EditProps {
 pub struct Edit {
     state_start_date: DateTime<Utc>,
     state_end_date: DateTime<Utc>,
+    state_penalty_rate: f64,
+    state_service_address_id: Option<u32>,
+    state_segmentation: Option<Segmentation>,
+    state_original: Option<UpdateContractRequest>,
+    state_etag: String,
+    state_conflict: bool,
     state_error: Option<String>,
     state_loading: bool,
 }
@@ -28,12 +41,25 @@ pub enum Msg {
     EditResponse(Result<(), anyhow::Error>),
     EditStartDate(DateTime<Utc>),
     EditEndDate(DateTime<Utc>),
+    EditPenaltyRate(f64),
+    EditSegmentation(Segmentation),
+    EditConflict,
+    ResolveConflict(Resolution),
     ShowErrorSnackbar(anyhow::Error),
     HideErrorSnackbar,
     ToggleLoading,
 }
 
 impl Edit {
+    fn request(&self) -> UpdateContractRequest {
+        UpdateContractRequest {
+            start_date: self.state_start_date,
+            end_date: self.state_end_date,
+            penalty_rate: Some(self.state_penalty_rate),
+            service_address_id: self.state_service_address_id,
+        }
+    }
+
     fn render_form(&self, ctx: &Context<Edit>) -> Html {
         let onsubmit = ctx.link().callback(|e: SubmitEvent| {
             e.prevent_default();
@@ -52,7 +78,7 @@ impl Edit {
                         value={self.state_start_date.format("%Y-%m-%d").to_string()}
                         oninput={ctx.link().callback(|value: String| {
                             let date = NaiveDate::parse_from_str(value.as_str(), "%Y-%m-%d").unwrap();
-                            Msg::EditStartDate(DateTime::from_utc(date.and_hms(0, 0, 0), Utc))
+                            Msg::EditStartDate(date.and_hms_opt(0, 0, 0).unwrap().and_utc())
                         })}
                     />
 
@@ -65,11 +91,30 @@ impl Edit {
                         value={self.state_end_date.format("%Y-%m-%d").to_string()}
                         oninput={ctx.link().callback(|value: String| {
                             let date = NaiveDate::parse_from_str(value.as_str(), "%Y-%m-%d").unwrap();
-                            Msg::EditEndDate(DateTime::from_utc(date.and_hms(0, 0, 0), Utc))
+                            Msg::EditEndDate(date.and_hms_opt(0, 0, 0).unwrap().and_utc())
+                        })}
+                    />
+
+                    <MatTextField
+                        outlined=true
+                        label="Daily penalty rate (%)"
+                        icon="percent"
+                        min="0"
+                        max="100"
+                        field_type={TextFieldType::Number}
+                        value={self.state_penalty_rate.to_string()}
+                        oninput={ctx.link().callback(|value: String| {
+                            Msg::EditPenaltyRate(value.parse::<f64>().unwrap_or(0.0))
                         })}
                     />
                 </div>
 
+                <Segment
+                    entity={Entity::Contract}
+                    id={ctx.props().id}
+                    onchange={ctx.link().callback(Msg::EditSegmentation)}
+                />
+
                 <div class="row-flex">
                     <button class="btn-success" type="submit">
                         <MatButton label="Edit" raised=true />
@@ -98,8 +143,22 @@ impl Component for Edit {
         ctx.link().send_message(Msg::GetRequest);
 
         Self {
-            state_start_date: Utc::now().date().and_hms(0, 0, 0),
-            state_end_date: Utc::now().date().and_hms(0, 0, 0),
+            state_start_date: Utc::now()
+                .date_naive()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc(),
+            state_end_date: Utc::now()
+                .date_naive()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc(),
+            state_penalty_rate: 0.0,
+            state_service_address_id: None,
+            state_segmentation: None,
+            state_original: None,
+            state_etag: String::new(),
+            state_conflict: false,
             state_error: None,
             state_loading: false,
         }
@@ -115,8 +174,9 @@ impl Component for Edit {
 
                 wasm_bindgen_futures::spawn_local(async move {
                     let get_contract_req = Request::get(
-                        format!("http://localhost:8000/api/contract/{}", props.id).as_str(),
+                        format!("http://localhost:8000/api/v1/contract/{}", props.id).as_str(),
                     )
+                    .header("Authorization", &auth::bearer())
                     .header("Content-Type", "application/json");
 
                     let resp = get_contract_req.send().await;
@@ -131,10 +191,11 @@ impl Component for Edit {
 
                                 link.send_message(Msg::GetResponse(contract));
                             } else {
-                                link.send_message(Msg::GetResponse(Err(anyhow::anyhow!(
-                                    "Failed to get contract: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::GetResponse(Err(problem::error(
+                                    "Failed to get contract",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
@@ -148,8 +209,12 @@ impl Component for Edit {
                 false
             }
             Msg::GetResponse(Ok(contract)) => {
+                self.state_etag = etag::of(contract.version);
                 self.state_start_date = contract.start_date;
                 self.state_end_date = contract.end_date;
+                self.state_penalty_rate = contract.penalty_rate;
+                self.state_service_address_id = contract.service_address_id;
+                self.state_original = Some(self.request());
                 true
             }
             Msg::GetResponse(Err(err)) => {
@@ -159,10 +224,7 @@ impl Component for Edit {
             Msg::EditRequest => {
                 link.send_message(Msg::ToggleLoading);
 
-                let state = UpdateContractRequest {
-                    start_date: self.state_start_date,
-                    end_date: self.state_end_date,
-                };
+                let state = self.request();
 
                 log::debug!("State: {:?}", state);
 
@@ -176,16 +238,26 @@ impl Component for Edit {
                     return false;
                 }
 
-                let contract = state.clone();
-                log::info!("Creating contract: {:?}", contract);
+                // only the fields changed since the contract was loaded are sent
+                let contract = patch::diff(
+                    &serde_json::to_value(&self.state_original).unwrap(),
+                    &serde_json::to_value(&state).unwrap(),
+                );
+                log::info!("Updating contract: {:?}", contract);
+
+                let segmentation = self.state_segmentation.clone();
+
+                let if_match = self.state_etag.clone();
 
                 wasm_bindgen_futures::spawn_local(async move {
                     let contract_json = JsValue::from(serde_json::to_string(&contract).unwrap());
 
-                    let create_contract_req = Request::put(
-                        format!("http://localhost:8000/api/contract/{}", props.id).as_str(),
+                    let create_contract_req = Request::patch(
+                        format!("http://localhost:8000/api/v1/contract/{}", props.id).as_str(),
                     )
-                    .header("Content-Type", "application/json")
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/merge-patch+json")
+                    .header("If-Match", &if_match)
                     .body(contract_json)
                     .expect("Failed to build request.");
 
@@ -194,12 +266,22 @@ impl Component for Edit {
                     match resp {
                         Ok(resp) => {
                             if resp.status() == 200 {
-                                link.send_message(Msg::EditResponse(Ok(())));
+                                let result = match segmentation {
+                                    Some(segmentation) => {
+                                        segmentation.save(&Entity::Contract, props.id).await
+                                    }
+                                    None => Ok(()),
+                                };
+
+                                link.send_message(Msg::EditResponse(result));
+                            } else if resp.status() == 412 {
+                                link.send_message(Msg::EditConflict);
                             } else {
-                                link.send_message(Msg::EditResponse(Err(anyhow::anyhow!(
-                                    "Failed to create contract: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::EditResponse(Err(problem::error(
+                                    "Failed to create contract",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
@@ -234,6 +316,31 @@ impl Component for Edit {
                 self.state_end_date = end_date;
                 true
             }
+            Msg::EditPenaltyRate(penalty_rate) => {
+                self.state_penalty_rate = penalty_rate;
+                true
+            }
+            Msg::EditSegmentation(segmentation) => {
+                self.state_segmentation = Some(segmentation);
+                false
+            }
+            Msg::EditConflict => {
+                link.send_message(Msg::ToggleLoading);
+                self.state_conflict = true;
+                true
+            }
+            Msg::ResolveConflict(resolution) => {
+                self.state_conflict = false;
+                match resolution {
+                    Resolution::Reload => link.send_message(Msg::GetRequest),
+                    Resolution::Overwrite => {
+                        self.state_etag = etag::ANY.to_string();
+                        link.send_message(Msg::EditRequest);
+                    }
+                    Resolution::Dismiss => {}
+                }
+                true
+            }
             Msg::ShowErrorSnackbar(err) => {
                 self.state_error = Some(err.to_string());
                 true
@@ -257,6 +364,11 @@ impl Component for Edit {
                 <h2>{ "Edit contract" }</h2>
                 { self.render_form(ctx) }
 
+                <Conflict
+                    open={self.state_conflict}
+                    entity="contract"
+                    onresolve={link.callback(Msg::ResolveConflict)} />
+
                 <MatSnackbar
                     open={self.state_error.is_some()}
                     label_text={self.state_error.clone().unwrap_or("".to_string())}
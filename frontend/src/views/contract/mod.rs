@@ -1,3 +1,4 @@
 pub(crate) mod create;
+pub(crate) mod deposit;
 pub(crate) mod detail;
 pub(crate) mod edit;
@@ -0,0 +1,409 @@
+use crate::views::auth;
+use crate::views::problem;
+use common::address::{AddressRequest, AddressResponse, AddressType};
+use common::etag;
+use gloo_net::http::Request;
+use material_yew::list::GraphicType;
+use material_yew::select::ListIndex::Single;
+use material_yew::select::SelectedDetail;
+use material_yew::{
+    MatButton, MatCircularProgress, MatIconButton, MatListItem, MatSelect, MatTextField,
+};
+use validator::Validate;
+use wasm_bindgen::JsValue;
+use web_sys::SubmitEvent;
+use yew::{html, Component, Context, Html, Properties};
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct AddressesProps {
+    pub customer_id: u32,
+}
+
+pub struct Addresses {
+    addresses: Option<Vec<AddressResponse>>,
+    state_address_type: AddressType,
+    state_street: String,
+    state_number: String,
+    state_city: String,
+    state_county: String,
+    state_postal_code: String,
+}
+
+pub enum Msg {
+    GetRequest,
+    GetResponse(Result<Vec<AddressResponse>, anyhow::Error>),
+    EditAddressType(AddressType),
+    EditStreet(String),
+    EditNumber(String),
+    EditCity(String),
+    EditCounty(String),
+    EditPostalCode(String),
+    AddRequest,
+    AddResponse(Result<(), anyhow::Error>),
+    RemoveRequest(u32, u32),
+    RemoveResponse(Result<(), anyhow::Error>),
+}
+
+impl Addresses {
+    fn render_addresses(&self, ctx: &Context<Addresses>) -> Html {
+        if let Some(addresses) = &self.addresses {
+            html! {
+                <table class="tftable" border="1">
+                    <thead>
+                        <tr>
+                            <th>{ "ID" }</th>
+                            <th>{ "Type" }</th>
+                            <th>{ "Street" }</th>
+                            <th>{ "Number" }</th>
+                            <th>{ "City" }</th>
+                            <th>{ "County" }</th>
+                            <th>{ "Postal Code" }</th>
+                            <th>{ "Actions" }</th>
+                        </tr>
+                    </thead>
+
+                    <tbody>
+                        { addresses.iter().map(|address| self.render_address(ctx, address)).collect::<Html>() }
+                    </tbody>
+                </table>
+            }
+        } else {
+            html! {
+                <div>
+                    <MatCircularProgress indeterminate=true />
+                </div>
+            }
+        }
+    }
+
+    fn render_address(&self, ctx: &Context<Addresses>, address: &AddressResponse) -> Html {
+        let address_id = address.id;
+        let address_version = address.version;
+
+        html! {
+            <tr>
+                <td>{ &address.id }</td>
+                <td>{ &address.address_type }</td>
+                <td>{ &address.street }</td>
+                <td>{ &address.number }</td>
+                <td>{ &address.city }</td>
+                <td>{ &address.county }</td>
+                <td>{ &address.postal_code }</td>
+                <td>
+                    <button class="btn-danger" onclick={ctx.link().callback(move |_| Msg::RemoveRequest(address_id, address_version))}>
+                        <MatIconButton icon="delete" />
+                    </button>
+                </td>
+            </tr>
+        }
+    }
+
+    fn render_form(&self, ctx: &Context<Addresses>) -> Html {
+        let onsubmit = ctx.link().callback(|e: SubmitEvent| {
+            e.prevent_default();
+            Msg::AddRequest
+        });
+
+        html! {
+            <form {onsubmit}>
+                <div class="form-input">
+                    <MatSelect
+                        label="Type"
+                        outlined=true
+                        required=true
+                        icon="home"
+                        onselected={ctx.link().callback(|e: SelectedDetail| {
+                            match e.index {
+                                Single(Some(1)) => Msg::EditAddressType(AddressType::Service),
+                                Single(Some(2)) => Msg::EditAddressType(AddressType::Correspondence),
+                                _ => Msg::EditAddressType(AddressType::Billing),
+                            }
+                        })}>
+                        <MatListItem value="BILLING" graphic={GraphicType::Icon} selected=true>{ "Billing" }</MatListItem>
+                        <MatListItem value="SERVICE" graphic={GraphicType::Icon}>{ "Service installation" }</MatListItem>
+                        <MatListItem value="CORRESPONDENCE" graphic={GraphicType::Icon}>{ "Correspondence" }</MatListItem>
+                    </MatSelect>
+
+                    <MatTextField
+                        outlined=true
+                        label="Street"
+                        required=true
+                        max_length=100
+                        value={self.state_street.clone()}
+                        oninput={ctx.link().callback(Msg::EditStreet)}
+                    />
+
+                    <MatTextField
+                        outlined=true
+                        label="Number"
+                        required=true
+                        max_length=20
+                        value={self.state_number.clone()}
+                        oninput={ctx.link().callback(Msg::EditNumber)}
+                    />
+
+                    <MatTextField
+                        outlined=true
+                        label="City"
+                        required=true
+                        max_length=50
+                        value={self.state_city.clone()}
+                        oninput={ctx.link().callback(Msg::EditCity)}
+                    />
+
+                    <MatTextField
+                        outlined=true
+                        label="County"
+                        required=true
+                        max_length=50
+                        value={self.state_county.clone()}
+                        oninput={ctx.link().callback(Msg::EditCounty)}
+                    />
+
+                    <MatTextField
+                        outlined=true
+                        label="Postal code"
+                        required=true
+                        pattern={r"^\d{6}$"}
+                        value={self.state_postal_code.clone()}
+                        oninput={ctx.link().callback(Msg::EditPostalCode)}
+                    />
+                </div>
+
+                <div class="row-flex">
+                    <button class="btn-success" type="submit">
+                        <MatButton label="Add address" raised=true />
+                    </button>
+                </div>
+            </form>
+        }
+    }
+}
+
+impl Component for Addresses {
+    type Message = Msg;
+    type Properties = AddressesProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link().send_message(Msg::GetRequest);
+
+        Self {
+            addresses: None,
+            state_address_type: AddressType::Billing,
+            state_street: String::new(),
+            state_number: String::new(),
+            state_city: String::new(),
+            state_county: String::new(),
+            state_postal_code: String::new(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let link = ctx.link().clone();
+        let props = ctx.props().clone();
+
+        match msg {
+            Msg::GetRequest => {
+                log::info!("Requesting addresses for customer {}", props.customer_id);
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let get_addresses_req = Request::get(
+                        format!(
+                            "http://localhost:8000/api/v1/customer/{}/address",
+                            props.customer_id
+                        )
+                        .as_str(),
+                    )
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json");
+
+                    let resp = get_addresses_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 200 {
+                                let addresses =
+                                    resp.json::<Vec<AddressResponse>>().await.map_err(|err| {
+                                        anyhow::anyhow!("Failed parsing response: {}", err)
+                                    });
+
+                                link.send_message(Msg::GetResponse(addresses));
+                            } else {
+                                link.send_message(Msg::GetResponse(Err(problem::error(
+                                    "Failed retrieving addresses data",
+                                    resp,
+                                )
+                                .await)));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::GetResponse(Err(anyhow::anyhow!(
+                                "Failed to send request: {}",
+                                err
+                            ))));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::GetResponse(Ok(addresses)) => {
+                self.addresses = Some(addresses);
+                true
+            }
+            Msg::GetResponse(Err(err)) => {
+                log::error!("Failed retrieving addresses data: {:?}", err);
+                self.addresses = None;
+                true
+            }
+            Msg::EditAddressType(address_type) => {
+                self.state_address_type = address_type;
+                true
+            }
+            Msg::EditStreet(street) => {
+                self.state_street = street;
+                true
+            }
+            Msg::EditNumber(number) => {
+                self.state_number = number;
+                true
+            }
+            Msg::EditCity(city) => {
+                self.state_city = city;
+                true
+            }
+            Msg::EditCounty(county) => {
+                self.state_county = county;
+                true
+            }
+            Msg::EditPostalCode(postal_code) => {
+                self.state_postal_code = postal_code;
+                true
+            }
+            Msg::AddRequest => {
+                let address = AddressRequest {
+                    address_type: self.state_address_type.clone(),
+                    street: self.state_street.clone(),
+                    number: self.state_number.clone(),
+                    city: self.state_city.clone(),
+                    county: self.state_county.clone(),
+                    postal_code: self.state_postal_code.clone(),
+                };
+
+                let validation_result = address.validate();
+
+                if validation_result.is_err() {
+                    link.send_message(Msg::AddResponse(Err(anyhow::anyhow!(
+                        "Validation failed: {:?}",
+                        validation_result
+                    ))));
+                    return false;
+                }
+
+                log::info!("Adding address to customer: {:?}", address);
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let address_json = JsValue::from(serde_json::to_string(&address).unwrap());
+
+                    let add_address_req = Request::post(
+                        format!(
+                            "http://localhost:8000/api/v1/customer/{}/address",
+                            props.customer_id
+                        )
+                        .as_str(),
+                    )
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json")
+                    .body(address_json)
+                    .expect("Failed to build request.");
+
+                    let resp = add_address_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 201 {
+                                link.send_message(Msg::AddResponse(Ok(())));
+                            } else {
+                                link.send_message(Msg::AddResponse(Err(problem::error(
+                                    "Failed to add address",
+                                    resp,
+                                )
+                                .await)));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::AddResponse(Err(anyhow::anyhow!(
+                                "Failed to send request: {}",
+                                err
+                            ))));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::AddResponse(Ok(_)) => {
+                link.send_message(Msg::GetRequest);
+                false
+            }
+            Msg::AddResponse(Err(err)) => {
+                log::error!("Failed to add address: {:?}", err);
+                false
+            }
+            Msg::RemoveRequest(address_id, version) => {
+                log::info!("Removing address {}", address_id);
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let remove_address_req = Request::delete(
+                        format!(
+                            "http://localhost:8000/api/v1/customer/{}/address/{}",
+                            props.customer_id, address_id
+                        )
+                        .as_str(),
+                    )
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json")
+                    .header("If-Match", &etag::of(version));
+
+                    let resp = remove_address_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 204 {
+                                link.send_message(Msg::RemoveResponse(Ok(())));
+                            } else {
+                                link.send_message(Msg::RemoveResponse(Err(problem::error(
+                                    "Failed to remove address",
+                                    resp,
+                                )
+                                .await)));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::RemoveResponse(Err(anyhow::anyhow!(
+                                "Failed to send request: {}",
+                                err
+                            ))));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::RemoveResponse(Ok(_)) => {
+                link.send_message(Msg::GetRequest);
+                false
+            }
+            Msg::RemoveResponse(Err(err)) => {
+                log::error!("Failed to remove address: {:?}", err);
+                false
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <>
+                { self.render_form(ctx) }
+                { self.render_addresses(ctx) }
+            </>
+        }
+    }
+}
@@ -0,0 +1,376 @@
+use crate::views::auth;
+use crate::views::problem;
+use common::contact::{ContactRequest, ContactResponse, ContactRole};
+use common::etag;
+use common::phone::Phone;
+use gloo_net::http::Request;
+use material_yew::list::GraphicType;
+use material_yew::select::ListIndex::Single;
+use material_yew::select::SelectedDetail;
+use material_yew::text_inputs::TextFieldType;
+use material_yew::{
+    MatButton, MatCircularProgress, MatIconButton, MatListItem, MatSelect, MatTextField,
+};
+use validator::Validate;
+use wasm_bindgen::JsValue;
+use web_sys::SubmitEvent;
+use yew::{html, AttrValue, Component, Context, Html, Properties};
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct ContactsProps {
+    pub customer_id: u32,
+}
+
+pub struct Contacts {
+    contacts: Option<Vec<ContactResponse>>,
+    state_role: ContactRole,
+    state_name: String,
+    state_email: String,
+    state_phone: String,
+}
+
+pub enum Msg {
+    GetRequest,
+    GetResponse(Result<Vec<ContactResponse>, anyhow::Error>),
+    EditRole(ContactRole),
+    EditName(String),
+    EditEmail(String),
+    EditPhone(String),
+    AddRequest,
+    AddResponse(Result<(), anyhow::Error>),
+    RemoveRequest(u32, u32),
+    RemoveResponse(Result<(), anyhow::Error>),
+}
+
+impl Contacts {
+    fn render_contacts(&self, ctx: &Context<Contacts>) -> Html {
+        if let Some(contacts) = &self.contacts {
+            html! {
+                <table class="tftable" border="1">
+                    <thead>
+                        <tr>
+                            <th>{ "ID" }</th>
+                            <th>{ "Role" }</th>
+                            <th>{ "Name" }</th>
+                            <th>{ "Email" }</th>
+                            <th>{ "Phone" }</th>
+                            <th>{ "Actions" }</th>
+                        </tr>
+                    </thead>
+
+                    <tbody>
+                        { contacts.iter().map(|contact| self.render_contact(ctx, contact)).collect::<Html>() }
+                    </tbody>
+                </table>
+            }
+        } else {
+            html! {
+                <div>
+                    <MatCircularProgress indeterminate=true />
+                </div>
+            }
+        }
+    }
+
+    fn render_contact(&self, ctx: &Context<Contacts>, contact: &ContactResponse) -> Html {
+        let contact_id = contact.id;
+        let contact_version = contact.version;
+
+        html! {
+            <tr>
+                <td>{ &contact.id }</td>
+                <td>{ &contact.role }</td>
+                <td>{ &contact.name }</td>
+                <td>{ &contact.email }</td>
+                <td>{ contact.phone.clone().unwrap_or("-".to_string()) }</td>
+                <td>
+                    <button class="btn-danger" onclick={ctx.link().callback(move |_| Msg::RemoveRequest(contact_id, contact_version))}>
+                        <MatIconButton icon="delete" />
+                    </button>
+                </td>
+            </tr>
+        }
+    }
+
+    fn render_form(&self, ctx: &Context<Contacts>) -> Html {
+        let onsubmit = ctx.link().callback(|e: SubmitEvent| {
+            e.prevent_default();
+            Msg::AddRequest
+        });
+
+        html! {
+            <form {onsubmit}>
+                <div class="form-input">
+                    <MatSelect
+                        label="Role"
+                        outlined=true
+                        required=true
+                        icon="contacts"
+                        onselected={ctx.link().callback(|e: SelectedDetail| {
+                            match e.index {
+                                Single(Some(1)) => Msg::EditRole(ContactRole::Billing),
+                                Single(Some(2)) => Msg::EditRole(ContactRole::Technical),
+                                _ => Msg::EditRole(ContactRole::Primary),
+                            }
+                        })}>
+                        <MatListItem value="PRIMARY" graphic={GraphicType::Icon} selected=true>{ "Primary" }</MatListItem>
+                        <MatListItem value="BILLING" graphic={GraphicType::Icon}>{ "Billing" }</MatListItem>
+                        <MatListItem value="TECHNICAL" graphic={GraphicType::Icon}>{ "Technical" }</MatListItem>
+                    </MatSelect>
+
+                    <MatTextField
+                        outlined=true
+                        label="Name"
+                        required=true
+                        max_length=100
+                        value={self.state_name.clone()}
+                        oninput={ctx.link().callback(Msg::EditName)}
+                    />
+
+                    <MatTextField
+                        outlined=true
+                        label="Email"
+                        required=true
+                        max_length=100
+                        field_type={TextFieldType::Email}
+                        value={self.state_email.clone()}
+                        oninput={ctx.link().callback(Msg::EditEmail)}
+                    />
+
+                    <MatTextField
+                        outlined=true
+                        label="Phone"
+                        field_type={TextFieldType::Tel}
+                        helper={Phone::parse(&self.state_phone).err().filter(|_| !self.state_phone.is_empty()).map(|e| AttrValue::from(e.to_string())).unwrap_or(AttrValue::from("e.g. 0722 123 456 or +40 722 123 456"))}
+                        helper_persistent=true
+                        value={self.state_phone.clone()}
+                        oninput={ctx.link().callback(Msg::EditPhone)}
+                    />
+                </div>
+
+                <div class="row-flex">
+                    <button class="btn-success" type="submit">
+                        <MatButton label="Add contact" raised=true />
+                    </button>
+                </div>
+            </form>
+        }
+    }
+}
+
+impl Component for Contacts {
+    type Message = Msg;
+    type Properties = ContactsProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link().send_message(Msg::GetRequest);
+
+        Self {
+            contacts: None,
+            state_role: ContactRole::Primary,
+            state_name: String::new(),
+            state_email: String::new(),
+            state_phone: String::new(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let link = ctx.link().clone();
+        let props = ctx.props().clone();
+
+        match msg {
+            Msg::GetRequest => {
+                log::info!("Requesting contacts for customer {}", props.customer_id);
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let get_contacts_req = Request::get(
+                        format!(
+                            "http://localhost:8000/api/v1/customer/{}/contact",
+                            props.customer_id
+                        )
+                        .as_str(),
+                    )
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json");
+
+                    let resp = get_contacts_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 200 {
+                                let contacts =
+                                    resp.json::<Vec<ContactResponse>>().await.map_err(|err| {
+                                        anyhow::anyhow!("Failed parsing response: {}", err)
+                                    });
+
+                                link.send_message(Msg::GetResponse(contacts));
+                            } else {
+                                link.send_message(Msg::GetResponse(Err(problem::error(
+                                    "Failed retrieving contacts data",
+                                    resp,
+                                )
+                                .await)));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::GetResponse(Err(anyhow::anyhow!(
+                                "Failed to send request: {}",
+                                err
+                            ))));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::GetResponse(Ok(contacts)) => {
+                self.contacts = Some(contacts);
+                true
+            }
+            Msg::GetResponse(Err(err)) => {
+                log::error!("Failed retrieving contacts data: {:?}", err);
+                self.contacts = None;
+                true
+            }
+            Msg::EditRole(role) => {
+                self.state_role = role;
+                true
+            }
+            Msg::EditName(name) => {
+                self.state_name = name;
+                true
+            }
+            Msg::EditEmail(email) => {
+                self.state_email = email;
+                true
+            }
+            Msg::EditPhone(phone) => {
+                self.state_phone = phone;
+                true
+            }
+            Msg::AddRequest => {
+                let contact = ContactRequest {
+                    role: self.state_role.clone(),
+                    name: self.state_name.clone(),
+                    email: self.state_email.clone(),
+                    phone: Some(Phone::normalize(&self.state_phone))
+                        .filter(|phone| !phone.is_empty()),
+                };
+
+                let validation_result = contact.validate();
+
+                if validation_result.is_err() {
+                    link.send_message(Msg::AddResponse(Err(anyhow::anyhow!(
+                        "Validation failed: {:?}",
+                        validation_result
+                    ))));
+                    return false;
+                }
+
+                log::info!("Adding contact to customer: {:?}", contact);
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let contact_json = JsValue::from(serde_json::to_string(&contact).unwrap());
+
+                    let add_contact_req = Request::post(
+                        format!(
+                            "http://localhost:8000/api/v1/customer/{}/contact",
+                            props.customer_id
+                        )
+                        .as_str(),
+                    )
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json")
+                    .body(contact_json)
+                    .expect("Failed to build request.");
+
+                    let resp = add_contact_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 201 {
+                                link.send_message(Msg::AddResponse(Ok(())));
+                            } else {
+                                link.send_message(Msg::AddResponse(Err(problem::error(
+                                    "Failed to add contact",
+                                    resp,
+                                )
+                                .await)));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::AddResponse(Err(anyhow::anyhow!(
+                                "Failed to send request: {}",
+                                err
+                            ))));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::AddResponse(Ok(_)) => {
+                link.send_message(Msg::GetRequest);
+                false
+            }
+            Msg::AddResponse(Err(err)) => {
+                log::error!("Failed to add contact: {:?}", err);
+                false
+            }
+            Msg::RemoveRequest(contact_id, version) => {
+                log::info!("Removing contact {}", contact_id);
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let remove_contact_req = Request::delete(
+                        format!(
+                            "http://localhost:8000/api/v1/customer/{}/contact/{}",
+                            props.customer_id, contact_id
+                        )
+                        .as_str(),
+                    )
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json")
+                    .header("If-Match", &etag::of(version));
+
+                    let resp = remove_contact_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 204 {
+                                link.send_message(Msg::RemoveResponse(Ok(())));
+                            } else {
+                                link.send_message(Msg::RemoveResponse(Err(problem::error(
+                                    "Failed to remove contact",
+                                    resp,
+                                )
+                                .await)));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::RemoveResponse(Err(anyhow::anyhow!(
+                                "Failed to send request: {}",
+                                err
+                            ))));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::RemoveResponse(Ok(_)) => {
+                link.send_message(Msg::GetRequest);
+                false
+            }
+            Msg::RemoveResponse(Err(err)) => {
+                log::error!("Failed to remove contact: {:?}", err);
+                false
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <>
+                { self.render_form(ctx) }
+                { self.render_contacts(ctx) }
+            </>
+        }
+    }
+}
@@ -1,20 +1,43 @@
 use crate::app::Route;
-use common::customer::CustomerRequest;
+use crate::views::auth;
+use crate::views::problem;
+use crate::views::segment::{Segment, Segmentation};
+use common::cnp::Cnp;
+use common::cui::{Cui, VatNumber};
+use common::customer::{CustomerKind, CustomerRequest, CustomerResponse};
+use common::error::ErrorCode;
+use common::field::Entity;
+use common::phone::Phone;
 use gloo_net::http::Request;
+use material_yew::list::GraphicType;
+use material_yew::select::ListIndex::Single;
+use material_yew::select::SelectedDetail;
 use material_yew::text_inputs::TextFieldType;
-use material_yew::{MatButton, MatCircularProgress, MatIconButton, MatSnackbar, MatTextField};
+use material_yew::{
+    MatButton, MatCheckbox, MatCircularProgress, MatFormfield, MatIconButton, MatListItem,
+    MatSelect, MatSnackbar, MatTextField,
+};
 use validator::Validate;
 use wasm_bindgen::JsValue;
 use web_sys::SubmitEvent;
-use yew::{html, Component, Context, Html};
+use yew::{html, AttrValue, Component, Context, Html};
 use yew_router::scope_ext::RouterScopeExt;
 
 pub struct Create {
+    state_kind: CustomerKind,
     state_name: String,
     state_fullname: String,
     state_address: String,
     state_phone: String,
     state_cnp: String,
+    state_cui: String,
+    state_trade_register_number: String,
+    state_vat_payer: bool,
+    state_reverse_charge: bool,
+    state_legal_representative: String,
+    state_force: bool,
+    state_possible_duplicate: bool,
+    state_segmentation: Option<Segmentation>,
     state_error: Option<String>,
     state_loading: bool,
 }
@@ -22,17 +45,42 @@ pub struct Create {
 pub enum Msg {
     CreateRequest,
     CreateResponse(Result<(), anyhow::Error>),
+    PossibleDuplicate(String),
+    EditForce(bool),
+    EditKind(CustomerKind),
     EditName(String),
     EditFullname(String),
     EditAddress(String),
     EditPhone(String),
     EditCnp(String),
+    EditCui(String),
+    EditTradeRegisterNumber(String),
+    EditVatPayer(bool),
+    EditReverseCharge(bool),
+    EditLegalRepresentative(String),
+    EditSegmentation(Segmentation),
     ShowErrorSnackbar(anyhow::Error),
     HideErrorSnackbar,
     ToggleLoading,
 }
 
 impl Create {
+    /// Error of the CUI or, for a business invoiced with reverse charge, of its
+    /// EU VAT number.
+    fn cui_error(&self) -> Option<String> {
+        if self.state_cui.is_empty() {
+            return None;
+        }
+
+        if self.state_reverse_charge {
+            VatNumber::parse(&self.state_cui)
+                .err()
+                .map(|e| e.to_string())
+        } else {
+            Cui::parse(&self.state_cui).err().map(|e| e.to_string())
+        }
+    }
+
     fn render_form(&self, ctx: &Context<Create>) -> Html {
         let onsubmit = ctx.link().callback(|e: SubmitEvent| {
             e.prevent_default();
@@ -42,6 +90,21 @@ impl Create {
         html! {
             <form {onsubmit}>
                 <div class="form-input">
+                    <MatSelect
+                        label="Kind"
+                        outlined=true
+                        required=true
+                        icon="badge"
+                        onselected={ctx.link().callback(|e: SelectedDetail| {
+                            match e.index {
+                                Single(Some(1)) => Msg::EditKind(CustomerKind::Business),
+                                _ => Msg::EditKind(CustomerKind::Individual),
+                            }
+                        })}>
+                        <MatListItem value="INDIVIDUAL" graphic={GraphicType::Icon} selected={self.state_kind == CustomerKind::Individual}>{ "Individual" }</MatListItem>
+                        <MatListItem value="BUSINESS" graphic={GraphicType::Icon} selected={self.state_kind == CustomerKind::Business}>{ "Business" }</MatListItem>
+                    </MatSelect>
+
                     <MatTextField
                         outlined=true
                         label="Name"
@@ -77,25 +140,101 @@ impl Create {
                         label="Phone"
                         required=true
                         field_type={TextFieldType::Tel}
+                        helper={Phone::parse(&self.state_phone).err().filter(|_| !self.state_phone.is_empty()).map(|e| AttrValue::from(e.to_string())).unwrap_or(AttrValue::from("e.g. 0722 123 456 or +40 722 123 456"))}
+                        helper_persistent=true
                         value={self.state_phone.clone()}
                         oninput={ctx.link().callback(Msg::EditPhone)}
                     />
 
-                    <MatTextField
-                        outlined=true
-                        label="CNP"
-                        required=true
-                        pattern={r"^\d{13}$"}
-                        value={self.state_cnp.clone()}
-                        oninput={ctx.link().callback(Msg::EditCnp)}
-                    />
+                    {
+                        if self.state_kind == CustomerKind::Individual {
+                            html! {
+                                <MatTextField
+                                    outlined=true
+                                    label="CNP"
+                                    required=true
+                                    pattern={r"^\d{13}$"}
+                                    helper={Cnp::parse(&self.state_cnp).err().filter(|_| !self.state_cnp.is_empty()).map(|e| AttrValue::from(e.to_string()))}
+                                    helper_persistent=true
+                                    value={self.state_cnp.clone()}
+                                    oninput={ctx.link().callback(Msg::EditCnp)}
+                                />
+                            }
+                        } else {
+                            html! {
+                                <>
+                                    <MatTextField
+                                        outlined=true
+                                        label={if self.state_reverse_charge { "EU VAT number" } else { "CUI" }}
+                                        required=true
+                                        helper={self.cui_error().map(AttrValue::from)}
+                                        helper_persistent=true
+                                        value={self.state_cui.clone()}
+                                        oninput={ctx.link().callback(Msg::EditCui)}
+                                    />
+
+                                    <MatTextField
+                                        outlined=true
+                                        label="Trade register number"
+                                        required={!self.state_reverse_charge}
+                                        helper="e.g. J40/1234/2020"
+                                        value={self.state_trade_register_number.clone()}
+                                        oninput={ctx.link().callback(Msg::EditTradeRegisterNumber)}
+                                    />
+
+                                    <MatTextField
+                                        outlined=true
+                                        label="Legal representative"
+                                        required={!self.state_reverse_charge}
+                                        max_length=100
+                                        value={self.state_legal_representative.clone()}
+                                        oninput={ctx.link().callback(Msg::EditLegalRepresentative)}
+                                    />
+
+                                    <MatFormfield label="VAT payer">
+                                        <MatCheckbox
+                                            checked={self.state_vat_payer}
+                                            onchange={ctx.link().callback(Msg::EditVatPayer)}
+                                        />
+                                    </MatFormfield>
+
+                                    <MatFormfield label="Reverse charge (VAT payer of another EU country)">
+                                        <MatCheckbox
+                                            checked={self.state_reverse_charge}
+                                            onchange={ctx.link().callback(Msg::EditReverseCharge)}
+                                        />
+                                    </MatFormfield>
+                                </>
+                            }
+                        }
+                    }
                 </div>
 
+                <Segment
+                    entity={Entity::Customer}
+                    onchange={ctx.link().callback(Msg::EditSegmentation)}
+                />
+
                 <div class="row-flex">
                     <button class="btn-success" type="submit">
                         <MatButton label="Create" raised=true />
                     </button>
 
+                    {
+                        if self.state_possible_duplicate {
+                            html! {
+                                <MatFormfield label="Create anyway">
+                                    <MatCheckbox
+                                        checked={self.state_force}
+                                        onchange={ctx.link().callback(Msg::EditForce)}
+                                    />
+                                </MatFormfield>
+                            }
+                        } else {
+                            html! {}
+                        }
+                    }
+
                     {
                         if self.state_loading {
                             html! {
@@ -117,11 +256,20 @@ impl Component for Create {
 
     fn create(_ctx: &Context<Self>) -> Self {
         Self {
+            state_kind: CustomerKind::Individual,
             state_name: String::new(),
             state_fullname: String::new(),
             state_address: String::new(),
             state_phone: String::new(),
             state_cnp: String::new(),
+            state_cui: String::new(),
+            state_trade_register_number: String::new(),
+            state_vat_payer: false,
+            state_reverse_charge: false,
+            state_legal_representative: String::new(),
+            state_force: false,
+            state_possible_duplicate: false,
+            state_segmentation: None,
             state_error: None,
             state_loading: false,
         }
@@ -134,12 +282,21 @@ impl Component for Create {
             Msg::CreateRequest => {
                 link.send_message(Msg::ToggleLoading);
 
+                let individual = self.state_kind == CustomerKind::Individual;
+                let business_field = |value: &String| Some(value.clone()).filter(|_| !individual);
+
                 let state = CustomerRequest {
+                    kind: self.state_kind.clone(),
                     name: self.state_name.clone(),
                     fullname: self.state_fullname.clone(),
                     address: self.state_address.clone(),
-                    phone: self.state_phone.clone(),
-                    cnp: self.state_cnp.clone(),
+                    phone: Phone::normalize(&self.state_phone),
+                    cnp: Some(self.state_cnp.clone()).filter(|_| individual),
+                    cui: business_field(&self.state_cui),
+                    trade_register_number: business_field(&self.state_trade_register_number),
+                    vat_payer: self.state_vat_payer && !individual,
+                    reverse_charge: self.state_reverse_charge && !individual,
+                    legal_representative: business_field(&self.state_legal_representative),
                 };
 
                 let validation_result = state.validate();
@@ -153,27 +310,51 @@ impl Component for Create {
                 }
 
                 let customer = state.clone();
+                let force = self.state_force;
                 log::info!("Creating customer: {:?}", customer);
 
+                let segmentation = self.state_segmentation.clone();
+
                 wasm_bindgen_futures::spawn_local(async move {
                     let customer_json = JsValue::from(serde_json::to_string(&customer).unwrap());
 
-                    let create_customer_req = Request::post("http://localhost:8000/api/customer")
-                        .header("Content-Type", "application/json")
-                        .body(customer_json)
-                        .expect("Failed to build request.");
+                    let create_customer_req =
+                        Request::post("http://localhost:8000/api/v1/customer")
+                            .header("Authorization", &auth::bearer())
+                            .query([("force", force.to_string())])
+                            .header("Content-Type", "application/json")
+                            .body(customer_json)
+                            .expect("Failed to build request.");
 
                     let resp = create_customer_req.send().await;
 
                     match resp {
                         Ok(resp) => {
                             if resp.status() == 201 {
-                                link.send_message(Msg::CreateResponse(Ok(())));
+                                let result = match resp.json::<CustomerResponse>().await {
+                                    Ok(customer) => match segmentation {
+                                        Some(segmentation) => {
+                                            segmentation.save(&Entity::Customer, customer.id).await
+                                        }
+                                        None => Ok(()),
+                                    },
+                                    Err(err) => {
+                                        Err(anyhow::anyhow!("Failed to parse response: {:?}", err))
+                                    }
+                                };
+
+                                link.send_message(Msg::CreateResponse(result));
                             } else {
-                                link.send_message(Msg::CreateResponse(Err(anyhow::anyhow!(
-                                    "Failed to create customer: {:?}",
-                                    resp
-                                ))));
+                                match problem::read(resp).await {
+                                    Ok(problem) if problem.code == ErrorCode::DuplicateCustomer => {
+                                        link.send_message(Msg::PossibleDuplicate(problem.detail));
+                                    }
+                                    problem => {
+                                        link.send_message(Msg::CreateResponse(Err(
+                                            problem::failed("Failed to create customer", problem),
+                                        )));
+                                    }
+                                }
                             }
                         }
                         Err(err) => {
@@ -198,6 +379,24 @@ impl Component for Create {
                 link.send_message(Msg::ShowErrorSnackbar(err));
                 false
             }
+            Msg::PossibleDuplicate(message) => {
+                log::warn!("Possible duplicate customer: {}", message);
+                self.state_possible_duplicate = true;
+                link.send_message(Msg::ToggleLoading);
+                link.send_message(Msg::ShowErrorSnackbar(anyhow::anyhow!(
+                    "Possible duplicate customer: {}",
+                    message
+                )));
+                true
+            }
+            Msg::EditForce(force) => {
+                self.state_force = force;
+                true
+            }
+            Msg::EditKind(kind) => {
+                self.state_kind = kind;
+                true
+            }
             Msg::EditName(name) => {
                 self.state_name = name;
                 true
@@ -218,6 +417,30 @@ impl Component for Create {
                 self.state_cnp = cnp;
                 true
             }
+            Msg::EditCui(cui) => {
+                self.state_cui = cui;
+                true
+            }
+            Msg::EditTradeRegisterNumber(trade_register_number) => {
+                self.state_trade_register_number = trade_register_number;
+                true
+            }
+            Msg::EditVatPayer(vat_payer) => {
+                self.state_vat_payer = vat_payer;
+                true
+            }
+            Msg::EditReverseCharge(reverse_charge) => {
+                self.state_reverse_charge = reverse_charge;
+                true
+            }
+            Msg::EditLegalRepresentative(legal_representative) => {
+                self.state_legal_representative = legal_representative;
+                true
+            }
+            Msg::EditSegmentation(segmentation) => {
+                self.state_segmentation = Some(segmentation);
+                false
+            }
             Msg::ShowErrorSnackbar(err) => {
                 self.state_error = Some(err.to_string());
                 true
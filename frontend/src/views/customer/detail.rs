@@ -1,7 +1,14 @@
 use crate::app::{AppLink, Route};
+use crate::views::auth;
+use crate::views::customer::addresses::Addresses;
+use crate::views::customer::contacts::Contacts;
+use crate::views::customer::notes::Notes;
+use crate::views::problem;
 use common::contract::ContractResponse;
 use common::customer::CustomerResponse;
+use common::etag;
 use common::invoice::InvoiceResponse;
+use common::user::ACCESS_TOKEN;
 use gloo_net::http::Request;
 use material_yew::{MatButton, MatCircularProgress, MatIconButton};
 use yew::{html, AttrValue, Component, Context, Html, Properties};
@@ -20,14 +27,16 @@ pub struct Detail {
 
 pub enum Msg {
     GetRequest,
-    GetResponse(Result<CustomerResponse, anyhow::Error>),
+    GetResponse(Result<Box<CustomerResponse>, anyhow::Error>),
     GetContractsRequest,
     GetContractsResponse(Result<Vec<ContractResponse>, anyhow::Error>),
-    DeleteContractRequest(u32),
+    DeleteContractRequest(u32, u32),
     DeleteContractResponse(Result<(), anyhow::Error>),
     GetUnpaidInvoicesRequest,
     GetUnpaidInvoicesResponse(Result<Vec<InvoiceResponse>, anyhow::Error>),
-    DeleteRequest(u32),
+    AnonymizeRequest(u32),
+    AnonymizeResponse(Result<(), anyhow::Error>),
+    DeleteRequest(u32, u32),
     DeleteResponse(Result<(), anyhow::Error>),
 }
 
@@ -35,6 +44,7 @@ impl Detail {
     fn render_customer(&self, ctx: &Context<Detail>) -> Html {
         if let Some(customer) = &self.customer {
             let customer_id = customer.id;
+            let customer_version = customer.version;
 
             html! {
                 <table class="tftable" border="1">
@@ -45,7 +55,16 @@ impl Detail {
                             <th>{ "Fullname" }</th>
                             <th>{ "Address" }</th>
                             <th>{ "Phone" }</th>
+                            <th>{ "Kind" }</th>
                             <th>{ "CNP" }</th>
+                            <th>{ "Birth Date" }</th>
+                            <th>{ "County" }</th>
+                            <th>{ "CUI" }</th>
+                            <th>{ "Trade Register Number" }</th>
+                            <th>{ "VAT Payer" }</th>
+                            <th>{ "Reverse Charge" }</th>
+                            <th>{ "Legal Representative" }</th>
+                            <th>{ "Anonymized At" }</th>
                             <th>{ "Actions" }</th>
                         </tr>
                     </thead>
@@ -57,15 +76,44 @@ impl Detail {
                             <td>{ &customer.fullname }</td>
                             <td>{ &customer.address }</td>
                             <td>{ &customer.phone }</td>
-                            <td>{ &customer.cnp }</td>
+                            <td>{ &customer.kind }</td>
+                            <td>{ customer.cnp.clone().unwrap_or("-".to_string()) }</td>
+                            <td>{ customer.birth_date.map(|date| date.format("%m-%d-%Y").to_string()).unwrap_or("-".to_string()) }</td>
+                            <td>{ customer.county.clone().unwrap_or("-".to_string()) }</td>
+                            <td>{ customer.cui.clone().unwrap_or("-".to_string()) }</td>
+                            <td>{ customer.trade_register_number.clone().unwrap_or("-".to_string()) }</td>
+                            <td>{ if customer.vat_payer { "Yes" } else { "No" } }</td>
+                            <td>{ if customer.reverse_charge { "Yes" } else { "No" } }</td>
+                            <td>{ customer.legal_representative.clone().unwrap_or("-".to_string()) }</td>
+                            <td>{ customer.anonymized_at.map(|date| date.format("%m-%d-%Y").to_string()).unwrap_or("-".to_string()) }</td>
                             <td>
-                                <AppLink to={Route::CustomerEdit { id: customer.id }}>
-                                    <button class="btn-warning">
-                                        <MatIconButton icon="edit" />
+                                <a href={format!("http://localhost:8000/api/v1/customer/{}/export?{}={}", customer_id, ACCESS_TOKEN, auth::token().unwrap_or_default())} download={format!("customer-{}.json", customer_id)}>
+                                    <button class="btn-info">
+                                        <MatIconButton icon="download" />
                                     </button>
-                                </AppLink>
-
-                                <button class="btn-danger" onclick={ctx.link().callback(move |_| Msg::DeleteRequest(customer_id))}>
+                                </a>
+
+                                {
+                                    if customer.anonymized_at.is_none() {
+                                        html! {
+                                            <>
+                                                <AppLink to={Route::CustomerEdit { id: customer.id }}>
+                                                    <button class="btn-warning">
+                                                        <MatIconButton icon="edit" />
+                                                    </button>
+                                                </AppLink>
+
+                                                <button class="btn-warning" onclick={ctx.link().callback(move |_| Msg::AnonymizeRequest(customer_id))}>
+                                                    <MatIconButton icon="visibility_off" />
+                                                </button>
+                                            </>
+                                        }
+                                    } else {
+                                        html! {}
+                                    }
+                                }
+
+                                <button class="btn-danger" onclick={ctx.link().callback(move |_| Msg::DeleteRequest(customer_id, customer_version))}>
                                     <MatIconButton icon="delete" />
                                 </button>
                             </td>
@@ -89,7 +137,7 @@ impl Detail {
                     <thead>
                         <tr>
                             <th>{ "ID" }</th>
-                            <th>{ "Subscription ID" }</th>
+                            <th>{ "Subscription / Bundle" }</th>
                             <th>{ "Start date" }</th>
                             <th>{ "End date" }</th>
                             <th>{ "Actions" }</th>
@@ -112,14 +160,21 @@ impl Detail {
 
     fn render_contract(&self, ctx: &Context<Detail>, contract: &ContractResponse) -> Html {
         let contract_id = contract.id;
+        let contract_version = contract.version;
 
         html! {
             <tr>
                 <td>{ &contract.id }</td>
                 <td>
-                    <AppLink to={Route::SubscriptionDetail { id: contract.subscription_id }}>
-                        { &contract.subscription_id }
-                    </AppLink>
+                    {
+                        match (contract.subscription_id, contract.bundle_id) {
+                            (Some(id), _) => html! {
+                                <AppLink to={Route::SubscriptionDetail { id }}>{ id }</AppLink>
+                            },
+                            (None, Some(id)) => html! { format!("Bundle {}", id) },
+                            (None, None) => html! { "-" },
+                        }
+                    }
                 </td>
                 <td>{ contract.start_date.format("%m-%d-%Y").to_string() }</td>
                 <td>{ contract.end_date.format("%m-%d-%Y").to_string() }</td>
@@ -136,7 +191,7 @@ impl Detail {
                         </button>
                     </AppLink>
 
-                    <button class="btn-danger" onclick={ctx.link().callback(move |_| Msg::DeleteContractRequest(contract_id))}>
+                    <button class="btn-danger" onclick={ctx.link().callback(move |_| Msg::DeleteContractRequest(contract_id, contract_version))}>
                         <MatIconButton icon="delete" />
                     </button>
                 </td>
@@ -173,7 +228,7 @@ impl Detail {
         }
     }
 
-    fn render_invoice(&self, ctx: &Context<Detail>, invoice: &InvoiceResponse) -> Html {
+    fn render_invoice(&self, _ctx: &Context<Detail>, invoice: &InvoiceResponse) -> Html {
         html! {
             <tr>
                  <td>{ &invoice.id }</td>
@@ -219,8 +274,9 @@ impl Component for Detail {
 
                 wasm_bindgen_futures::spawn_local(async move {
                     let get_customer_req = Request::get(
-                        format!("http://localhost:8000/api/customer/{}", props.id).as_str(),
+                        format!("http://localhost:8000/api/v1/customer/{}", props.id).as_str(),
                     )
+                    .header("Authorization", &auth::bearer())
                     .header("Content-Type", "application/json");
 
                     let resp = get_customer_req.send().await;
@@ -229,16 +285,17 @@ impl Component for Detail {
                         Ok(resp) => {
                             if resp.status() == 200 {
                                 let customer =
-                                    resp.json::<CustomerResponse>().await.map_err(|err| {
-                                        anyhow::anyhow!("Failed parsing response: {}", err)
-                                    });
+                                    resp.json::<CustomerResponse>().await.map(Box::new).map_err(
+                                        |err| anyhow::anyhow!("Failed parsing response: {}", err),
+                                    );
 
                                 link.send_message(Msg::GetResponse(customer));
                             } else {
-                                link.send_message(Msg::GetResponse(Err(anyhow::anyhow!(
-                                    "Failed retrieving customer data: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::GetResponse(Err(problem::error(
+                                    "Failed retrieving customer data",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
@@ -252,7 +309,7 @@ impl Component for Detail {
                 false
             }
             Msg::GetResponse(Ok(customer)) => {
-                self.customer = Some(customer);
+                self.customer = Some(*customer);
                 true
             }
             Msg::GetResponse(Err(err)) => {
@@ -265,9 +322,13 @@ impl Component for Detail {
 
                 wasm_bindgen_futures::spawn_local(async move {
                     let get_contracts_req = Request::get(
-                        format!("http://localhost:8000/api/customer/{}/contract", props.id)
-                            .as_str(),
+                        format!(
+                            "http://localhost:8000/api/v1/customer/{}/contract",
+                            props.id
+                        )
+                        .as_str(),
                     )
+                    .header("Authorization", &auth::bearer())
                     .header("Content-Type", "application/json");
 
                     let resp = get_contracts_req.send().await;
@@ -282,10 +343,11 @@ impl Component for Detail {
 
                                 link.send_message(Msg::GetContractsResponse(contracts));
                             } else {
-                                link.send_message(Msg::GetContractsResponse(Err(anyhow::anyhow!(
-                                    "Failed retrieving contracts data: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::GetContractsResponse(Err(problem::error(
+                                    "Failed retrieving contracts data",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
@@ -308,14 +370,16 @@ impl Component for Detail {
                 self.contracts = None;
                 true
             }
-            Msg::DeleteContractRequest(id) => {
+            Msg::DeleteContractRequest(id, version) => {
                 log::info!("Deleting contract {}", id);
 
                 wasm_bindgen_futures::spawn_local(async move {
                     let delete_contract_req = Request::delete(
-                        format!("http://localhost:8000/api/contract/{}", id).as_str(),
+                        format!("http://localhost:8000/api/v1/contract/{}", id).as_str(),
                     )
-                    .header("Content-Type", "application/json");
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json")
+                    .header("If-Match", &etag::of(version));
 
                     let resp = delete_contract_req.send().await;
 
@@ -325,7 +389,7 @@ impl Component for Detail {
                                 link.send_message(Msg::DeleteContractResponse(Ok(())));
                             } else {
                                 link.send_message(Msg::DeleteContractResponse(Err(
-                                    anyhow::anyhow!("Failed to delete contract: {:?}", resp),
+                                    problem::error("Failed to delete contract", resp).await,
                                 )));
                             }
                         }
@@ -352,8 +416,10 @@ impl Component for Detail {
 
                 wasm_bindgen_futures::spawn_local(async move {
                     let get_unpaid_invoices_req = Request::get(
-                        format!("http://localhost:8000/api/customer/{}/invoice", props.id).as_str(),
+                        format!("http://localhost:8000/api/v1/customer/{}/invoice", props.id)
+                            .as_str(),
                     )
+                    .header("Authorization", &auth::bearer())
                     .header("Content-Type", "application/json");
 
                     let resp = get_unpaid_invoices_req.send().await;
@@ -369,10 +435,8 @@ impl Component for Detail {
                                 link.send_message(Msg::GetUnpaidInvoicesResponse(invoices));
                             } else {
                                 link.send_message(Msg::GetUnpaidInvoicesResponse(Err(
-                                    anyhow::anyhow!(
-                                        "Failed retrieving unpaid invoices data: {:?}",
-                                        resp
-                                    ),
+                                    problem::error("Failed retrieving unpaid invoices data", resp)
+                                        .await,
                                 )));
                             }
                         }
@@ -395,15 +459,59 @@ impl Component for Detail {
                 self.unpaid_invoices = None;
                 true
             }
-            Msg::DeleteRequest(id) => {
+            Msg::AnonymizeRequest(id) => {
+                log::info!("Anonymizing customer with id {}", id);
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let anonymize_customer_req = Request::post(
+                        format!("http://localhost:8000/api/v1/customer/{}/anonymize", id).as_str(),
+                    )
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json");
+
+                    let resp = anonymize_customer_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 200 {
+                                link.send_message(Msg::AnonymizeResponse(Ok(())));
+                            } else {
+                                link.send_message(Msg::AnonymizeResponse(Err(problem::error(
+                                    "Failed to anonymize customer",
+                                    resp,
+                                )
+                                .await)));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::AnonymizeResponse(Err(anyhow::anyhow!(
+                                "Failed to send request: {}",
+                                err
+                            ))));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::AnonymizeResponse(Ok(_)) => {
+                link.send_message(Msg::GetRequest);
+                false
+            }
+            Msg::AnonymizeResponse(Err(err)) => {
+                log::error!("Failed to anonymize customer: {:?}", err);
+                false
+            }
+            Msg::DeleteRequest(id, version) => {
                 log::info!("Deleting customer with id {}", id);
 
                 let link = ctx.link().clone();
                 wasm_bindgen_futures::spawn_local(async move {
                     let delete_customer_req = Request::delete(
-                        format!("http://localhost:8000/api/customer/{}", id).as_str(),
+                        format!("http://localhost:8000/api/v1/customer/{}", id).as_str(),
                     )
-                    .header("Content-Type", "application/json");
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json")
+                    .header("If-Match", &etag::of(version));
 
                     let resp = delete_customer_req.send().await;
 
@@ -412,10 +520,11 @@ impl Component for Detail {
                             if resp.status() == 204 {
                                 link.send_message(Msg::DeleteResponse(Ok(())));
                             } else {
-                                link.send_message(Msg::DeleteResponse(Err(anyhow::anyhow!(
-                                    "Failed to delete customer: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::DeleteResponse(Err(problem::error(
+                                    "Failed to delete customer",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
@@ -445,6 +554,15 @@ impl Component for Detail {
                 <h2>{ "Customer details" }</h2>
                 { self.render_customer(ctx) }
 
+                <h2>{ "Addresses" }</h2>
+                <Addresses customer_id={ctx.props().id} />
+
+                <h2>{ "Contacts" }</h2>
+                <Contacts customer_id={ctx.props().id} />
+
+                <h2>{ "Notes" }</h2>
+                <Notes customer_id={ctx.props().id} />
+
                 <h2>{ "Contracts" }</h2>
                 <h3>
                     <AppLink to={Route::ContractCreate}>
@@ -0,0 +1,241 @@
+use crate::app::{AppLink, Route};
+use crate::views::auth;
+use crate::views::problem;
+use common::customer::CustomerResponse;
+use common::merge::{CustomerDuplicateResponse, CustomerMergeRequest, CustomerMergeResponse};
+use gloo_net::http::Request;
+use material_yew::{MatButton, MatCircularProgress, MatIconButton, MatSnackbar};
+use wasm_bindgen::JsValue;
+use yew::{html, Component, Context, Html};
+
+pub struct Duplicates {
+    duplicates: Option<Vec<CustomerDuplicateResponse>>,
+    state_message: Option<String>,
+}
+
+pub enum Msg {
+    GetAllRequest,
+    GetAllResponse(Result<Vec<CustomerDuplicateResponse>, anyhow::Error>),
+    MergeRequest(u32, u32),
+    MergeResponse(Result<CustomerMergeResponse, anyhow::Error>),
+    HideSnackbar,
+}
+
+impl Duplicates {
+    fn render_table(&self, ctx: &Context<Duplicates>) -> Html {
+        if let Some(duplicates) = &self.duplicates {
+            html! {
+                <table class="tftable" border="1">
+                    <thead>
+                        <tr>
+                            <th>{ "Customer" }</th>
+                            <th>{ "Duplicate" }</th>
+                            <th>{ "Reason" }</th>
+                            <th>{ "Actions" }</th>
+                        </tr>
+                    </thead>
+
+                    <tbody>
+                        { duplicates.iter().map(|duplicate| self.render_item(ctx, duplicate)).collect::<Html>() }
+                    </tbody>
+                </table>
+            }
+        } else {
+            html! {
+                <div>
+                    <MatCircularProgress indeterminate=true />
+                </div>
+            }
+        }
+    }
+
+    fn render_customer(customer: &CustomerResponse) -> Html {
+        html! {
+            <AppLink to={Route::CustomerDetail { id: customer.id }}>
+                { format!("#{} {} ({}, {})", customer.id, customer.fullname, customer.phone,
+                    customer.cnp.clone().or(customer.cui.clone()).unwrap_or_default()) }
+            </AppLink>
+        }
+    }
+
+    fn render_item(
+        &self,
+        ctx: &Context<Duplicates>,
+        duplicate: &CustomerDuplicateResponse,
+    ) -> Html {
+        let customer_id = duplicate.customer.id;
+        let duplicate_id = duplicate.duplicate.id;
+
+        html! {
+            <tr>
+                <td>{ Duplicates::render_customer(&duplicate.customer) }</td>
+                <td>{ Duplicates::render_customer(&duplicate.duplicate) }</td>
+                <td>{ &duplicate.reason }</td>
+                <td>
+                    <button class="btn-warning" onclick={ctx.link().callback(move |_| Msg::MergeRequest(customer_id, duplicate_id))}>
+                        <MatButton label="Merge duplicate into customer" icon="merge" />
+                    </button>
+                </td>
+            </tr>
+        }
+    }
+}
+
+impl Component for Duplicates {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link().send_message(Msg::GetAllRequest);
+
+        Self {
+            duplicates: None,
+            state_message: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let link = ctx.link().clone();
+
+        match msg {
+            Msg::GetAllRequest => {
+                log::info!("Requesting duplicate customers");
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let get_duplicates_req =
+                        Request::get("http://localhost:8000/api/v1/customer/duplicates")
+                            .header("Authorization", &auth::bearer())
+                            .header("Content-Type", "application/json");
+
+                    let resp = get_duplicates_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 200 {
+                                let duplicates =
+                                    resp.json::<Vec<CustomerDuplicateResponse>>().await.map_err(
+                                        |err| anyhow::anyhow!("Failed to parse response: {}", err),
+                                    );
+
+                                link.send_message(Msg::GetAllResponse(duplicates));
+                            } else {
+                                link.send_message(Msg::GetAllResponse(Err(problem::error(
+                                    "Failed to get duplicate customers",
+                                    resp,
+                                )
+                                .await)));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::GetAllResponse(Err(anyhow::anyhow!(
+                                "Failed to send request: {}",
+                                err
+                            ))));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::GetAllResponse(Ok(duplicates)) => {
+                self.duplicates = Some(duplicates);
+                true
+            }
+            Msg::GetAllResponse(Err(err)) => {
+                log::error!("Failed to get duplicate customers: {:?}", err);
+                false
+            }
+            Msg::MergeRequest(id, source_customer_id) => {
+                log::info!(
+                    "Merging customer {} into customer {}",
+                    source_customer_id,
+                    id
+                );
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let merge_json = JsValue::from(
+                        serde_json::to_string(&CustomerMergeRequest { source_customer_id })
+                            .unwrap(),
+                    );
+
+                    let merge_customer_req = Request::post(
+                        format!("http://localhost:8000/api/v1/customer/{}/merge", id).as_str(),
+                    )
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json")
+                    .body(merge_json)
+                    .expect("Failed to build request.");
+
+                    let resp = merge_customer_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 201 {
+                                let merge =
+                                    resp.json::<CustomerMergeResponse>().await.map_err(|err| {
+                                        anyhow::anyhow!("Failed to parse response: {}", err)
+                                    });
+
+                                link.send_message(Msg::MergeResponse(merge));
+                            } else {
+                                link.send_message(Msg::MergeResponse(Err(problem::error(
+                                    "Failed to merge customers",
+                                    resp,
+                                )
+                                .await)));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::MergeResponse(Err(anyhow::anyhow!(
+                                "Failed to send request: {}",
+                                err
+                            ))));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::MergeResponse(Ok(merge)) => {
+                self.state_message = Some(format!(
+                    "Customer {} merged into customer {}: {} contracts, {} invoices and {} payments moved",
+                    merge.source_customer_id,
+                    merge.target_customer_id,
+                    merge.contracts_moved,
+                    merge.invoices_moved,
+                    merge.payments_moved
+                ));
+                link.send_message(Msg::GetAllRequest);
+                true
+            }
+            Msg::MergeResponse(Err(err)) => {
+                log::error!("Failed to merge customers: {:?}", err);
+                self.state_message = Some(err.to_string());
+                true
+            }
+            Msg::HideSnackbar => {
+                self.state_message = None;
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link().clone();
+
+        html! {
+            <div class="box">
+                <h2>{ "Duplicate customers" }</h2>
+                { self.render_table(ctx) }
+
+                <MatSnackbar
+                    open={self.state_message.is_some()}
+                    label_text={self.state_message.clone().unwrap_or("".to_string())}
+                    stacked=true>
+
+                    <span onclick={link.callback(|_| Msg::HideSnackbar)} class="snackbar-dismiss-slot" slot="dismiss">
+                        <MatIconButton icon="close" />
+                    </span>
+                </MatSnackbar>
+            </div>
+        }
+    }
+}
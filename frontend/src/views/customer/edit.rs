@@ -1,12 +1,28 @@
 use crate::app::Route;
-use common::customer::{CustomerRequest, CustomerResponse};
+use crate::views::auth;
+use crate::views::conflict::{Conflict, Resolution};
+use crate::views::problem;
+use crate::views::segment::{Segment, Segmentation};
+use common::cnp::Cnp;
+use common::cui::{Cui, VatNumber};
+use common::customer::{CustomerKind, CustomerRequest, CustomerResponse};
+use common::etag;
+use common::field::Entity;
+use common::patch;
+use common::phone::Phone;
 use gloo_net::http::Request;
+use material_yew::list::GraphicType;
+use material_yew::select::ListIndex::Single;
+use material_yew::select::SelectedDetail;
 use material_yew::text_inputs::TextFieldType;
-use material_yew::{MatButton, MatCircularProgress, MatIconButton, MatSnackbar, MatTextField};
+use material_yew::{
+    MatButton, MatCheckbox, MatCircularProgress, MatFormfield, MatIconButton, MatListItem,
+    MatSelect, MatSnackbar, MatTextField,
+};
 use validator::Validate;
 use wasm_bindgen::JsValue;
 use web_sys::SubmitEvent;
-use yew::{html, Component, Context, Html, Properties};
+use yew::{html, AttrValue, Component, Context, Html, Properties};
 use yew_router::scope_ext::RouterScopeExt;
 
 #[derive(Clone, Properties, PartialEq)]
@@ -15,31 +31,85 @@ pub struct EditProps {
 }
 
 pub struct Edit {
+    state_kind: CustomerKind,
     state_name: String,
     state_fullname: String,
     state_address: String,
     state_phone: String,
     state_cnp: String,
+    state_cui: String,
+    state_trade_register_number: String,
+    state_vat_payer: bool,
+    state_reverse_charge: bool,
+    state_legal_representative: String,
+    state_segmentation: Option<Segmentation>,
+    state_original: Option<CustomerRequest>,
+    state_etag: String,
+    state_conflict: bool,
     state_error: Option<String>,
     state_loading: bool,
 }
 
 pub enum Msg {
     GetRequest,
-    GetResponse(Result<CustomerResponse, anyhow::Error>),
+    GetResponse(Result<Box<CustomerResponse>, anyhow::Error>),
     EditRequest,
     EditResponse(Result<(), anyhow::Error>),
+    EditKind(CustomerKind),
     EditName(String),
     EditFullname(String),
     EditAddress(String),
     EditPhone(String),
     EditCnp(String),
+    EditCui(String),
+    EditTradeRegisterNumber(String),
+    EditVatPayer(bool),
+    EditReverseCharge(bool),
+    EditLegalRepresentative(String),
+    EditSegmentation(Segmentation),
+    EditConflict,
+    ResolveConflict(Resolution),
     ShowErrorSnackbar(anyhow::Error),
     HideErrorSnackbar,
     ToggleLoading,
 }
 
 impl Edit {
+    /// Error of the CUI or, for a business invoiced with reverse charge, of its
+    /// EU VAT number.
+    fn cui_error(&self) -> Option<String> {
+        if self.state_cui.is_empty() {
+            return None;
+        }
+
+        if self.state_reverse_charge {
+            VatNumber::parse(&self.state_cui)
+                .err()
+                .map(|e| e.to_string())
+        } else {
+            Cui::parse(&self.state_cui).err().map(|e| e.to_string())
+        }
+    }
+
+    fn request(&self) -> CustomerRequest {
+        let individual = self.state_kind == CustomerKind::Individual;
+        let business_field = |value: &String| Some(value.clone()).filter(|_| !individual);
+
+        CustomerRequest {
+            kind: self.state_kind.clone(),
+            name: self.state_name.clone(),
+            fullname: self.state_fullname.clone(),
+            address: self.state_address.clone(),
+            phone: Phone::normalize(&self.state_phone),
+            cnp: Some(self.state_cnp.clone()).filter(|_| individual),
+            cui: business_field(&self.state_cui),
+            trade_register_number: business_field(&self.state_trade_register_number),
+            vat_payer: self.state_vat_payer && !individual,
+            reverse_charge: self.state_reverse_charge && !individual,
+            legal_representative: business_field(&self.state_legal_representative),
+        }
+    }
+
     fn render_form(&self, ctx: &Context<Edit>) -> Html {
         let onsubmit = ctx.link().callback(|e: SubmitEvent| {
             e.prevent_default();
@@ -49,6 +119,21 @@ impl Edit {
         html! {
             <form {onsubmit}>
                 <div class="form-input">
+                    <MatSelect
+                        label="Kind"
+                        outlined=true
+                        required=true
+                        icon="badge"
+                        onselected={ctx.link().callback(|e: SelectedDetail| {
+                            match e.index {
+                                Single(Some(1)) => Msg::EditKind(CustomerKind::Business),
+                                _ => Msg::EditKind(CustomerKind::Individual),
+                            }
+                        })}>
+                        <MatListItem value="INDIVIDUAL" graphic={GraphicType::Icon} selected={self.state_kind == CustomerKind::Individual}>{ "Individual" }</MatListItem>
+                        <MatListItem value="BUSINESS" graphic={GraphicType::Icon} selected={self.state_kind == CustomerKind::Business}>{ "Business" }</MatListItem>
+                    </MatSelect>
+
                     <MatTextField
                         outlined=true
                         label="Name"
@@ -84,20 +169,82 @@ impl Edit {
                         label="Phone"
                         required=true
                         field_type={TextFieldType::Tel}
+                        helper={Phone::parse(&self.state_phone).err().filter(|_| !self.state_phone.is_empty()).map(|e| AttrValue::from(e.to_string())).unwrap_or(AttrValue::from("e.g. 0722 123 456 or +40 722 123 456"))}
+                        helper_persistent=true
                         value={self.state_phone.clone()}
                         oninput={ctx.link().callback(Msg::EditPhone)}
                     />
 
-                    <MatTextField
-                        outlined=true
-                        label="CNP"
-                        required=true
-                        pattern={r"^\d{13}$"}
-                        value={self.state_cnp.clone()}
-                        oninput={ctx.link().callback(Msg::EditCnp)}
-                    />
+                    {
+                        if self.state_kind == CustomerKind::Individual {
+                            html! {
+                                <MatTextField
+                                    outlined=true
+                                    label="CNP"
+                                    required=true
+                                    pattern={r"^\d{13}$"}
+                                    helper={Cnp::parse(&self.state_cnp).err().filter(|_| !self.state_cnp.is_empty()).map(|e| AttrValue::from(e.to_string()))}
+                                    helper_persistent=true
+                                    value={self.state_cnp.clone()}
+                                    oninput={ctx.link().callback(Msg::EditCnp)}
+                                />
+                            }
+                        } else {
+                            html! {
+                                <>
+                                    <MatTextField
+                                        outlined=true
+                                        label={if self.state_reverse_charge { "EU VAT number" } else { "CUI" }}
+                                        required=true
+                                        helper={self.cui_error().map(AttrValue::from)}
+                                        helper_persistent=true
+                                        value={self.state_cui.clone()}
+                                        oninput={ctx.link().callback(Msg::EditCui)}
+                                    />
+
+                                    <MatTextField
+                                        outlined=true
+                                        label="Trade register number"
+                                        required={!self.state_reverse_charge}
+                                        helper="e.g. J40/1234/2020"
+                                        value={self.state_trade_register_number.clone()}
+                                        oninput={ctx.link().callback(Msg::EditTradeRegisterNumber)}
+                                    />
+
+                                    <MatTextField
+                                        outlined=true
+                                        label="Legal representative"
+                                        required={!self.state_reverse_charge}
+                                        max_length=100
+                                        value={self.state_legal_representative.clone()}
+                                        oninput={ctx.link().callback(Msg::EditLegalRepresentative)}
+                                    />
+
+                                    <MatFormfield label="VAT payer">
+                                        <MatCheckbox
+                                            checked={self.state_vat_payer}
+                                            onchange={ctx.link().callback(Msg::EditVatPayer)}
+                                        />
+                                    </MatFormfield>
+
+                                    <MatFormfield label="Reverse charge (VAT payer of another EU country)">
+                                        <MatCheckbox
+                                            checked={self.state_reverse_charge}
+                                            onchange={ctx.link().callback(Msg::EditReverseCharge)}
+                                        />
+                                    </MatFormfield>
+                                </>
+                            }
+                        }
+                    }
                 </div>
 
+                <Segment
+                    entity={Entity::Customer}
+                    id={ctx.props().id}
+                    onchange={ctx.link().callback(Msg::EditSegmentation)}
+                />
+
                 <div class="row-flex">
                     <button class="btn-success" type="submit">
                         <MatButton label="Edit" raised=true />
@@ -126,11 +273,21 @@ impl Component for Edit {
         ctx.link().send_message(Msg::GetRequest);
 
         Self {
+            state_kind: CustomerKind::Individual,
             state_name: String::new(),
             state_fullname: String::new(),
             state_address: String::new(),
             state_phone: String::new(),
             state_cnp: String::new(),
+            state_cui: String::new(),
+            state_trade_register_number: String::new(),
+            state_vat_payer: false,
+            state_reverse_charge: false,
+            state_legal_representative: String::new(),
+            state_segmentation: None,
+            state_original: None,
+            state_etag: String::new(),
+            state_conflict: false,
             state_error: None,
             state_loading: false,
         }
@@ -144,8 +301,9 @@ impl Component for Edit {
             Msg::GetRequest => {
                 wasm_bindgen_futures::spawn_local(async move {
                     let get_customer_req = Request::get(
-                        format!("http://localhost:8000/api/customer/{}", props.id).as_str(),
+                        format!("http://localhost:8000/api/v1/customer/{}", props.id).as_str(),
                     )
+                    .header("Authorization", &auth::bearer())
                     .header("Content-Type", "application/json");
 
                     let resp = get_customer_req.send().await;
@@ -153,16 +311,17 @@ impl Component for Edit {
                     match resp {
                         Ok(resp) => {
                             if resp.status() == 200 {
-                                let customer = resp.json().await.map_err(|err| {
+                                let customer = resp.json().await.map(Box::new).map_err(|err| {
                                     anyhow::anyhow!("Failed to parse response: {:?}", err)
                                 });
 
                                 link.send_message(Msg::GetResponse(customer));
                             } else {
-                                link.send_message(Msg::GetResponse(Err(anyhow::anyhow!(
-                                    "Failed to get customer: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::GetResponse(Err(problem::error(
+                                    "Failed to get customer",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
@@ -177,11 +336,20 @@ impl Component for Edit {
             }
             Msg::GetResponse(Ok(customer)) => {
                 log::info!("Customer: {:?}", customer);
+                self.state_etag = etag::of(customer.version);
+                self.state_kind = customer.kind;
                 self.state_name = customer.name;
                 self.state_fullname = customer.fullname;
                 self.state_address = customer.address;
                 self.state_phone = customer.phone;
-                self.state_cnp = customer.cnp;
+                self.state_cnp = customer.cnp.unwrap_or_default();
+                self.state_cui = customer.cui.unwrap_or_default();
+                self.state_trade_register_number =
+                    customer.trade_register_number.unwrap_or_default();
+                self.state_vat_payer = customer.vat_payer;
+                self.state_reverse_charge = customer.reverse_charge;
+                self.state_legal_representative = customer.legal_representative.unwrap_or_default();
+                self.state_original = Some(self.request());
                 true
             }
             Msg::GetResponse(Err(err)) => {
@@ -192,13 +360,7 @@ impl Component for Edit {
             Msg::EditRequest => {
                 link.send_message(Msg::ToggleLoading);
 
-                let state = CustomerRequest {
-                    name: self.state_name.clone(),
-                    fullname: self.state_fullname.clone(),
-                    address: self.state_address.clone(),
-                    phone: self.state_phone.clone(),
-                    cnp: self.state_cnp.clone(),
-                };
+                let state = self.request();
 
                 let validation_result = state.validate();
 
@@ -211,16 +373,26 @@ impl Component for Edit {
                     return false;
                 }
 
-                let customer = state.clone();
+                // only the fields changed since the customer was loaded are sent
+                let customer = patch::diff(
+                    &serde_json::to_value(&self.state_original).unwrap(),
+                    &serde_json::to_value(&state).unwrap(),
+                );
                 log::info!("Updating customer: {:?}", customer);
 
+                let segmentation = self.state_segmentation.clone();
+
+                let if_match = self.state_etag.clone();
+
                 wasm_bindgen_futures::spawn_local(async move {
                     let customer_json = JsValue::from(serde_json::to_string(&customer).unwrap());
 
-                    let update_customer_req = Request::put(
-                        format!("http://localhost:8000/api/customer/{}", props.id).as_str(),
+                    let update_customer_req = Request::patch(
+                        format!("http://localhost:8000/api/v1/customer/{}", props.id).as_str(),
                     )
-                    .header("Content-Type", "application/json")
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/merge-patch+json")
+                    .header("If-Match", &if_match)
                     .body(customer_json)
                     .expect("Failed to build request.");
 
@@ -229,12 +401,22 @@ impl Component for Edit {
                     match resp {
                         Ok(resp) => {
                             if resp.status() == 200 {
-                                link.send_message(Msg::EditResponse(Ok(())));
+                                let result = match segmentation {
+                                    Some(segmentation) => {
+                                        segmentation.save(&Entity::Customer, props.id).await
+                                    }
+                                    None => Ok(()),
+                                };
+
+                                link.send_message(Msg::EditResponse(result));
+                            } else if resp.status() == 412 {
+                                link.send_message(Msg::EditConflict);
                             } else {
-                                link.send_message(Msg::EditResponse(Err(anyhow::anyhow!(
-                                    "Failed to update customer: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::EditResponse(Err(problem::error(
+                                    "Failed to update customer",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
@@ -259,6 +441,10 @@ impl Component for Edit {
                 link.send_message(Msg::ShowErrorSnackbar(err));
                 false
             }
+            Msg::EditKind(kind) => {
+                self.state_kind = kind;
+                true
+            }
             Msg::EditName(name) => {
                 self.state_name = name;
                 true
@@ -279,6 +465,47 @@ impl Component for Edit {
                 self.state_cnp = cnp;
                 true
             }
+            Msg::EditCui(cui) => {
+                self.state_cui = cui;
+                true
+            }
+            Msg::EditTradeRegisterNumber(trade_register_number) => {
+                self.state_trade_register_number = trade_register_number;
+                true
+            }
+            Msg::EditVatPayer(vat_payer) => {
+                self.state_vat_payer = vat_payer;
+                true
+            }
+            Msg::EditReverseCharge(reverse_charge) => {
+                self.state_reverse_charge = reverse_charge;
+                true
+            }
+            Msg::EditLegalRepresentative(legal_representative) => {
+                self.state_legal_representative = legal_representative;
+                true
+            }
+            Msg::EditSegmentation(segmentation) => {
+                self.state_segmentation = Some(segmentation);
+                false
+            }
+            Msg::EditConflict => {
+                link.send_message(Msg::ToggleLoading);
+                self.state_conflict = true;
+                true
+            }
+            Msg::ResolveConflict(resolution) => {
+                self.state_conflict = false;
+                match resolution {
+                    Resolution::Reload => link.send_message(Msg::GetRequest),
+                    Resolution::Overwrite => {
+                        self.state_etag = etag::ANY.to_string();
+                        link.send_message(Msg::EditRequest);
+                    }
+                    Resolution::Dismiss => {}
+                }
+                true
+            }
             Msg::ShowErrorSnackbar(err) => {
                 self.state_error = Some(err.to_string());
                 true
@@ -302,6 +529,11 @@ impl Component for Edit {
                 <h2>{ "Edit customer" }</h2>
                 { self.render_form(ctx) }
 
+                <Conflict
+                    open={self.state_conflict}
+                    entity="customer"
+                    onresolve={link.callback(Msg::ResolveConflict)} />
+
                 <MatSnackbar
                     open={self.state_error.is_some()}
                     label_text={self.state_error.clone().unwrap_or("".to_string())}
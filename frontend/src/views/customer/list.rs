@@ -1,23 +1,162 @@
 use crate::app::{AppLink, Route};
-use common::customer::CustomerResponse;
+use crate::views::auth;
+use crate::views::export::Export;
+use crate::views::problem;
+use common::customer::{CustomerResponse, CustomerSearchResponse};
+use common::etag;
+use common::page::DEFAULT_PER_PAGE;
 use gloo_net::http::Request;
-use material_yew::{MatButton, MatCircularProgress, MatIconButton};
+use material_yew::text_inputs::TextFieldType;
+use material_yew::{MatButton, MatCircularProgress, MatIconButton, MatTextField};
+use web_sys::SubmitEvent;
 use yew::{html, AttrValue, Component, Context, Html};
 
 pub struct List {
     customers: Option<Vec<CustomerResponse>>,
+    /// Customers matching the filters, across all pages.
+    total: u32,
+    search_results: Option<Vec<CustomerSearchResponse>>,
+    state_query: String,
+    state_tag: String,
+    state_field: String,
+    state_value: String,
+    state_page: u32,
 }
 
 pub enum Msg {
     GetAllRequest,
-    GetAllResponse(Result<Vec<CustomerResponse>, anyhow::Error>),
-    DeleteRequest(u32),
+    GetAllResponse(Result<(Vec<CustomerResponse>, u32), anyhow::Error>),
+    GoToPage(u32),
+    EditQuery(String),
+    EditTag(String),
+    EditField(String),
+    EditValue(String),
+    SearchRequest,
+    SearchResponse(Result<Vec<CustomerSearchResponse>, anyhow::Error>),
+    DeleteRequest(u32, u32),
     DeleteResponse(Result<(), anyhow::Error>),
 }
 
 impl List {
+    /// Filters of the list, without its page.
+    fn filters(&self) -> Vec<(String, String)> {
+        [
+            ("tag", &self.state_tag),
+            ("field", &self.state_field),
+            ("value", &self.state_value),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value.trim().to_string()))
+        .filter(|(_, value)| !value.is_empty())
+        .collect()
+    }
+
+    fn render_search(&self, ctx: &Context<List>) -> Html {
+        let onsubmit = ctx.link().callback(|e: SubmitEvent| {
+            e.prevent_default();
+            Msg::SearchRequest
+        });
+
+        html! {
+            <form {onsubmit}>
+                <div class="row-flex">
+                    <MatTextField
+                        outlined=true
+                        label="Search"
+                        icon="search"
+                        field_type={TextFieldType::Search}
+                        helper="Name, phone, CNP / CUI, address, contract ID or invoice number"
+                        max_length=100
+                        value={self.state_query.clone()}
+                        oninput={ctx.link().callback(Msg::EditQuery)}
+                    />
+
+                    <button class="btn-info" type="submit">
+                        <MatButton label="Search" raised=true />
+                    </button>
+                </div>
+            </form>
+        }
+    }
+
+    fn render_filters(&self, ctx: &Context<List>) -> Html {
+        let onsubmit = ctx.link().callback(|e: SubmitEvent| {
+            e.prevent_default();
+            Msg::GoToPage(1)
+        });
+
+        html! {
+            <form {onsubmit}>
+                <div class="row-flex">
+                    <MatTextField
+                        outlined=true
+                        label="Tag"
+                        icon="label"
+                        helper="e.g. VIP"
+                        value={self.state_tag.clone()}
+                        oninput={ctx.link().callback(Msg::EditTag)}
+                    />
+
+                    <MatTextField
+                        outlined=true
+                        label="Custom field"
+                        helper="Field key, e.g. segment"
+                        value={self.state_field.clone()}
+                        oninput={ctx.link().callback(Msg::EditField)}
+                    />
+
+                    <MatTextField
+                        outlined=true
+                        label="Value"
+                        helper="Any value when empty"
+                        value={self.state_value.clone()}
+                        oninput={ctx.link().callback(Msg::EditValue)}
+                    />
+
+                    <button class="btn-info" type="submit">
+                        <MatButton label="Filter" raised=true />
+                    </button>
+                </div>
+            </form>
+        }
+    }
+
+    fn render_search_results(
+        &self,
+        ctx: &Context<List>,
+        results: &[CustomerSearchResponse],
+    ) -> Html {
+        html! {
+            <table class="tftable" border="1">
+                <thead>
+                    <tr>
+                        <th>{ "ID" }</th>
+                        <th>{ "Name" }</th>
+                        <th>{ "Fullname" }</th>
+                        <th>{ "Address" }</th>
+                        <th>{ "Phone" }</th>
+                        <th>{ "Kind" }</th>
+                        <th>{ "CNP / CUI" }</th>
+                        <th>{ "Matched on" }</th>
+                        <th>{ "Actions" }</th>
+                    </tr>
+                </thead>
+
+                <tbody>
+                    {
+                        results.iter().map(|result| {
+                            self.render_item(ctx, &result.customer, Some(&result.matched_fields))
+                        }).collect::<Html>()
+                    }
+                </tbody>
+            </table>
+        }
+    }
+
     fn render_table(&self, ctx: &Context<List>) -> Html {
-        if let Some(customers) = &self.customers {
+        if let Some(results) = &self.search_results {
+            self.render_search_results(ctx, results)
+        } else if let Some(customers) = &self.customers {
             html! {
                 <table class="tftable" border="1">
                     <thead>
@@ -27,13 +166,14 @@ impl List {
                             <th>{ "Fullname" }</th>
                             <th>{ "Address" }</th>
                             <th>{ "Phone" }</th>
-                            <th>{ "CNP" }</th>
+                            <th>{ "Kind" }</th>
+                            <th>{ "CNP / CUI" }</th>
                             <th>{ "Actions" }</th>
                         </tr>
                     </thead>
 
                     <tbody>
-                        { customers.iter().map(|customer| self.render_item(ctx, customer)).collect::<Html>() }
+                        { customers.iter().map(|customer| self.render_item(ctx, customer, None)).collect::<Html>() }
                     </tbody>
                 </table>
             }
@@ -46,8 +186,35 @@ impl List {
         }
     }
 
-    fn render_item(&self, ctx: &Context<List>, customer: &CustomerResponse) -> Html {
+    fn render_pages(&self, ctx: &Context<List>) -> Html {
+        if self.search_results.is_some() || self.customers.is_none() {
+            return html! {};
+        }
+
+        let page = self.state_page;
+        let last_page = self.total.div_ceil(DEFAULT_PER_PAGE).max(1);
+
+        html! {
+            <div class="row-flex">
+                <button class="btn-info" disabled={page <= 1} onclick={ctx.link().callback(move |_| Msg::GoToPage(page - 1))}>
+                    <MatIconButton icon="chevron_left" />
+                </button>
+                <p>{ format!("Page {} of {} ({} customers)", page, last_page, self.total) }</p>
+                <button class="btn-info" disabled={page >= last_page} onclick={ctx.link().callback(move |_| Msg::GoToPage(page + 1))}>
+                    <MatIconButton icon="chevron_right" />
+                </button>
+            </div>
+        }
+    }
+
+    fn render_item(
+        &self,
+        ctx: &Context<List>,
+        customer: &CustomerResponse,
+        matched_fields: Option<&Vec<String>>,
+    ) -> Html {
         let customer_id = customer.id;
+        let customer_version = customer.version;
 
         html! {
             <tr>
@@ -56,7 +223,15 @@ impl List {
                 <td>{ &customer.fullname }</td>
                 <td>{ &customer.address }</td>
                 <td>{ &customer.phone }</td>
-                <td>{ &customer.cnp }</td>
+                <td>{ &customer.kind }</td>
+                <td>{ customer.cnp.clone().or(customer.cui.clone()).unwrap_or_default() }</td>
+                {
+                    if let Some(matched_fields) = matched_fields {
+                        html! { <td>{ matched_fields.join(", ") }</td> }
+                    } else {
+                        html! {}
+                    }
+                }
                 <td>
                     <AppLink to={Route::CustomerDetail { id: customer.id }}>
                         <button class="btn-info">
@@ -70,7 +245,7 @@ impl List {
                         </button>
                     </AppLink>
 
-                    <button class="btn-danger" onclick={ctx.link().callback(move |_| Msg::DeleteRequest(customer_id))}>
+                    <button class="btn-danger" onclick={ctx.link().callback(move |_| Msg::DeleteRequest(customer_id, customer_version))}>
                         <MatIconButton icon="delete" />
                     </button>
                 </td>
@@ -86,7 +261,16 @@ impl Component for List {
     fn create(ctx: &Context<Self>) -> Self {
         ctx.link().send_message(Msg::GetAllRequest);
 
-        Self { customers: None }
+        Self {
+            customers: None,
+            total: 0,
+            search_results: None,
+            state_query: String::new(),
+            state_tag: String::new(),
+            state_field: String::new(),
+            state_value: String::new(),
+            state_page: 1,
+        }
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
@@ -96,8 +280,17 @@ impl Component for List {
             Msg::GetAllRequest => {
                 log::info!("Requesting all customers");
 
+                let mut filters = self.filters();
+                filters.push(("page".to_string(), self.state_page.to_string()));
+
                 wasm_bindgen_futures::spawn_local(async move {
-                    let get_customers_req = Request::get("http://localhost:8000/api/customer")
+                    let get_customers_req = Request::get("http://localhost:8000/api/v1/customer")
+                        .header("Authorization", &auth::bearer())
+                        .query(
+                            filters
+                                .iter()
+                                .map(|(name, value)| (name.as_str(), value.as_str())),
+                        )
                         .header("Content-Type", "application/json");
 
                     let resp = get_customers_req.send().await;
@@ -105,17 +298,26 @@ impl Component for List {
                     match resp {
                         Ok(resp) => {
                             if resp.status() == 200 {
-                                let customers =
-                                    resp.json::<Vec<CustomerResponse>>().await.map_err(|err| {
+                                let total = resp
+                                    .headers()
+                                    .get("x-total-count")
+                                    .and_then(|total| total.parse().ok())
+                                    .unwrap_or_default();
+                                let customers = resp
+                                    .json::<Vec<CustomerResponse>>()
+                                    .await
+                                    .map(|customers| (customers, total))
+                                    .map_err(|err| {
                                         anyhow::anyhow!("Failed to parse response: {}", err)
                                     });
 
                                 link.send_message(Msg::GetAllResponse(customers));
                             } else {
-                                link.send_message(Msg::GetAllResponse(Err(anyhow::anyhow!(
-                                    "Failed to get customers: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::GetAllResponse(Err(problem::error(
+                                    "Failed to get customers",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
@@ -128,20 +330,102 @@ impl Component for List {
                 });
                 false
             }
-            Msg::GetAllResponse(Ok(customers)) => {
+            Msg::GetAllResponse(Ok((customers, total))) => {
                 self.customers = Some(customers);
+                self.total = total;
                 true
             }
-            Msg::GetAllResponse(Err(_)) => false,
-            Msg::DeleteRequest(id) => {
+            Msg::GetAllResponse(Err(err)) => {
+                log::error!("Failed to get customers: {:?}", err);
+                false
+            }
+            Msg::GoToPage(page) => {
+                self.state_page = page;
+                link.send_message(Msg::GetAllRequest);
+                false
+            }
+            Msg::EditQuery(query) => {
+                self.state_query = query;
+                true
+            }
+            Msg::EditTag(tag) => {
+                self.state_tag = tag;
+                true
+            }
+            Msg::EditField(field) => {
+                self.state_field = field;
+                true
+            }
+            Msg::EditValue(value) => {
+                self.state_value = value;
+                true
+            }
+            Msg::SearchRequest => {
+                let query = self.state_query.trim().to_string();
+
+                if query.is_empty() {
+                    self.search_results = None;
+                    link.send_message(Msg::GetAllRequest);
+                    return true;
+                }
+
+                log::info!("Searching customers matching {:?}", query);
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let search_customers_req =
+                        Request::get("http://localhost:8000/api/v1/customer/search")
+                            .header("Authorization", &auth::bearer())
+                            .query([("q", query.as_str())])
+                            .header("Content-Type", "application/json");
+
+                    let resp = search_customers_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 200 {
+                                let results =
+                                    resp.json::<Vec<CustomerSearchResponse>>().await.map_err(
+                                        |err| anyhow::anyhow!("Failed to parse response: {}", err),
+                                    );
+
+                                link.send_message(Msg::SearchResponse(results));
+                            } else {
+                                link.send_message(Msg::SearchResponse(Err(problem::error(
+                                    "Failed to search customers",
+                                    resp,
+                                )
+                                .await)));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::SearchResponse(Err(anyhow::anyhow!(
+                                "Failed to send request: {}",
+                                err
+                            ))));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::SearchResponse(Ok(results)) => {
+                self.search_results = Some(results);
+                true
+            }
+            Msg::SearchResponse(Err(err)) => {
+                log::error!("Failed to search customers: {:?}", err);
+                false
+            }
+            Msg::DeleteRequest(id, version) => {
                 log::info!("Deleting customer with id {}", id);
 
                 let link = ctx.link().clone();
                 wasm_bindgen_futures::spawn_local(async move {
                     let delete_customer_req = Request::delete(
-                        format!("http://localhost:8000/api/customer/{}", id).as_str(),
+                        format!("http://localhost:8000/api/v1/customer/{}", id).as_str(),
                     )
-                    .header("Content-Type", "application/json");
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json")
+                    .header("If-Match", &etag::of(version));
 
                     let resp = delete_customer_req.send().await;
 
@@ -150,10 +434,11 @@ impl Component for List {
                             if resp.status() == 204 {
                                 link.send_message(Msg::DeleteResponse(Ok(())));
                             } else {
-                                link.send_message(Msg::DeleteResponse(Err(anyhow::anyhow!(
-                                    "Failed to delete customer: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::DeleteResponse(Err(problem::error(
+                                    "Failed to delete customer",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
@@ -168,6 +453,9 @@ impl Component for List {
             }
             Msg::DeleteResponse(Ok(_)) => {
                 link.send_message(Msg::GetAllRequest);
+                if self.search_results.is_some() {
+                    link.send_message(Msg::SearchRequest);
+                }
                 false
             }
             Msg::DeleteResponse(Err(err)) => {
@@ -185,8 +473,17 @@ impl Component for List {
                     <AppLink to={Route::CustomerCreate}>
                         <MatButton label="Create new customer" icon={AttrValue::from("add")} raised=true />
                     </AppLink>
+
+                    <AppLink to={Route::CustomerDuplicates}>
+                        <MatButton label="Duplicates" icon={AttrValue::from("people")} />
+                    </AppLink>
+
+                    <Export path="/api/v1/customer" filters={self.filters()} />
                 </h3>
+                { self.render_search(ctx) }
+                { self.render_filters(ctx) }
                 { self.render_table(ctx) }
+                { self.render_pages(ctx) }
             </div>
         }
     }
@@ -1,4 +1,8 @@
+pub(crate) mod addresses;
+pub(crate) mod contacts;
 pub(crate) mod create;
 pub(crate) mod detail;
+pub(crate) mod duplicates;
 pub(crate) mod edit;
 pub(crate) mod list;
+pub(crate) mod notes;
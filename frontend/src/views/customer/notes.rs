@@ -0,0 +1,526 @@
+use crate::app::{AppLink, Route};
+use crate::views::auth;
+use crate::views::problem;
+use common::etag;
+use common::note::{NoteCategory, NoteKind, NoteRequest, NoteResponse};
+use gloo_net::http::Request;
+use material_yew::list::GraphicType;
+use material_yew::select::ListIndex::Single;
+use material_yew::select::SelectedDetail;
+use material_yew::text_inputs::TextFieldType;
+use material_yew::{
+    MatButton, MatCheckbox, MatCircularProgress, MatFormfield, MatIconButton, MatListItem,
+    MatSelect, MatTextArea, MatTextField,
+};
+use validator::Validate;
+use wasm_bindgen::JsValue;
+use web_sys::SubmitEvent;
+use yew::{html, Component, Context, Html, Properties};
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct NotesProps {
+    pub customer_id: u32,
+}
+
+pub struct Notes {
+    notes: Option<Vec<NoteResponse>>,
+    state_kind: NoteKind,
+    state_category: NoteCategory,
+    state_contract_id: String,
+    state_invoice_id: String,
+    state_author: String,
+    state_body: String,
+    state_pinned: bool,
+}
+
+pub enum Msg {
+    GetRequest,
+    GetResponse(Result<Vec<NoteResponse>, anyhow::Error>),
+    EditKind(NoteKind),
+    EditCategory(NoteCategory),
+    EditContractId(String),
+    EditInvoiceId(String),
+    EditAuthor(String),
+    EditBody(String),
+    EditPinned(bool),
+    AddRequest,
+    AddResponse(Result<(), anyhow::Error>),
+    TogglePinRequest(u32),
+    TogglePinResponse(Result<(), anyhow::Error>),
+    RemoveRequest(u32, u32),
+    RemoveResponse(Result<(), anyhow::Error>),
+}
+
+impl Notes {
+    fn render_notes(&self, ctx: &Context<Notes>) -> Html {
+        if let Some(notes) = &self.notes {
+            if notes.is_empty() {
+                return html! { <p>{ "No notes yet." }</p> };
+            }
+
+            html! {
+                <ul class="timeline">
+                    { notes.iter().map(|note| self.render_note(ctx, note)).collect::<Html>() }
+                </ul>
+            }
+        } else {
+            html! {
+                <div>
+                    <MatCircularProgress indeterminate=true />
+                </div>
+            }
+        }
+    }
+
+    fn render_note(&self, ctx: &Context<Notes>, note: &NoteResponse) -> Html {
+        let note_id = note.id;
+        let note_version = note.version;
+        let icon = match note.kind {
+            NoteKind::Note => "notes",
+            NoteKind::Call => "call",
+            NoteKind::Email => "email",
+            NoteKind::Visit => "home",
+        };
+
+        html! {
+            <li class={if note.pinned { "timeline-item pinned" } else { "timeline-item" }}>
+                <div class="row-flex">
+                    <MatIconButton icon={icon} />
+                    <strong>{ note.created_at.format("%m-%d-%Y %H:%M").to_string() }</strong>
+                    <span>{ format!("{} / {}", note.kind, note.category) }</span>
+                    <span>{ format!("by {}", note.author) }</span>
+                    {
+                        if let Some(contract_id) = note.contract_id {
+                            html! {
+                                <AppLink to={Route::ContractDetail { id: contract_id }}>
+                                    { format!("Contract #{}", contract_id) }
+                                </AppLink>
+                            }
+                        } else {
+                            html! {}
+                        }
+                    }
+                    {
+                        if let Some(invoice_id) = note.invoice_id {
+                            html! {
+                                <AppLink to={Route::InvoiceDetail { id: invoice_id }}>
+                                    { format!("Invoice #{}", invoice_id) }
+                                </AppLink>
+                            }
+                        } else {
+                            html! {}
+                        }
+                    }
+                    <button onclick={ctx.link().callback(move |_| Msg::TogglePinRequest(note_id))}>
+                        <MatIconButton icon={if note.pinned { "push_pin" } else { "outlined_flag" }} />
+                    </button>
+                    <button class="btn-danger" onclick={ctx.link().callback(move |_| Msg::RemoveRequest(note_id, note_version))}>
+                        <MatIconButton icon="delete" />
+                    </button>
+                </div>
+                <p>{ &note.body }</p>
+            </li>
+        }
+    }
+
+    fn render_form(&self, ctx: &Context<Notes>) -> Html {
+        let onsubmit = ctx.link().callback(|e: SubmitEvent| {
+            e.prevent_default();
+            Msg::AddRequest
+        });
+
+        html! {
+            <form {onsubmit}>
+                <div class="form-input">
+                    <MatSelect
+                        label="Kind"
+                        outlined=true
+                        required=true
+                        icon="forum"
+                        onselected={ctx.link().callback(|e: SelectedDetail| {
+                            match e.index {
+                                Single(Some(1)) => Msg::EditKind(NoteKind::Call),
+                                Single(Some(2)) => Msg::EditKind(NoteKind::Email),
+                                Single(Some(3)) => Msg::EditKind(NoteKind::Visit),
+                                _ => Msg::EditKind(NoteKind::Note),
+                            }
+                        })}>
+                        <MatListItem value="NOTE" graphic={GraphicType::Icon} selected=true>{ "Note" }</MatListItem>
+                        <MatListItem value="CALL" graphic={GraphicType::Icon}>{ "Call" }</MatListItem>
+                        <MatListItem value="EMAIL" graphic={GraphicType::Icon}>{ "Email" }</MatListItem>
+                        <MatListItem value="VISIT" graphic={GraphicType::Icon}>{ "Visit" }</MatListItem>
+                    </MatSelect>
+
+                    <MatSelect
+                        label="Category"
+                        outlined=true
+                        required=true
+                        icon="label"
+                        onselected={ctx.link().callback(|e: SelectedDetail| {
+                            match e.index {
+                                Single(Some(1)) => Msg::EditCategory(NoteCategory::Billing),
+                                Single(Some(2)) => Msg::EditCategory(NoteCategory::Technical),
+                                Single(Some(3)) => Msg::EditCategory(NoteCategory::Sales),
+                                Single(Some(4)) => Msg::EditCategory(NoteCategory::Complaint),
+                                _ => Msg::EditCategory(NoteCategory::General),
+                            }
+                        })}>
+                        <MatListItem value="GENERAL" graphic={GraphicType::Icon} selected=true>{ "General" }</MatListItem>
+                        <MatListItem value="BILLING" graphic={GraphicType::Icon}>{ "Billing" }</MatListItem>
+                        <MatListItem value="TECHNICAL" graphic={GraphicType::Icon}>{ "Technical" }</MatListItem>
+                        <MatListItem value="SALES" graphic={GraphicType::Icon}>{ "Sales" }</MatListItem>
+                        <MatListItem value="COMPLAINT" graphic={GraphicType::Icon}>{ "Complaint" }</MatListItem>
+                    </MatSelect>
+
+                    <MatTextField
+                        outlined=true
+                        label="Contract ID"
+                        field_type={TextFieldType::Number}
+                        value={self.state_contract_id.clone()}
+                        oninput={ctx.link().callback(Msg::EditContractId)}
+                    />
+
+                    <MatTextField
+                        outlined=true
+                        label="Invoice ID"
+                        field_type={TextFieldType::Number}
+                        value={self.state_invoice_id.clone()}
+                        oninput={ctx.link().callback(Msg::EditInvoiceId)}
+                    />
+
+                    <MatTextField
+                        outlined=true
+                        label="Author"
+                        required=true
+                        max_length=100
+                        value={self.state_author.clone()}
+                        oninput={ctx.link().callback(Msg::EditAuthor)}
+                    />
+
+                    <MatTextArea
+                        outlined=true
+                        label="Note"
+                        required=true
+                        max_length=2000
+                        value={self.state_body.clone()}
+                        oninput={ctx.link().callback(Msg::EditBody)}
+                    />
+
+                    <MatFormfield label="Pinned">
+                        <MatCheckbox
+                            checked={self.state_pinned}
+                            onchange={ctx.link().callback(Msg::EditPinned)}
+                        />
+                    </MatFormfield>
+                </div>
+
+                <div class="row-flex">
+                    <button class="btn-success" type="submit">
+                        <MatButton label="Add note" raised=true />
+                    </button>
+                </div>
+            </form>
+        }
+    }
+}
+
+impl Component for Notes {
+    type Message = Msg;
+    type Properties = NotesProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link().send_message(Msg::GetRequest);
+
+        Self {
+            notes: None,
+            state_kind: NoteKind::Note,
+            state_category: NoteCategory::General,
+            state_contract_id: String::new(),
+            state_invoice_id: String::new(),
+            state_author: String::new(),
+            state_body: String::new(),
+            state_pinned: false,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let link = ctx.link().clone();
+        let props = ctx.props().clone();
+
+        match msg {
+            Msg::GetRequest => {
+                log::info!("Requesting notes for customer {}", props.customer_id);
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let get_notes_req = Request::get(
+                        format!(
+                            "http://localhost:8000/api/v1/customer/{}/note",
+                            props.customer_id
+                        )
+                        .as_str(),
+                    )
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json");
+
+                    let resp = get_notes_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 200 {
+                                let notes = resp.json::<Vec<NoteResponse>>().await.map_err(|err| {
+                                    anyhow::anyhow!("Failed parsing response: {}", err)
+                                });
+
+                                link.send_message(Msg::GetResponse(notes));
+                            } else {
+                                link.send_message(Msg::GetResponse(Err(problem::error(
+                                    "Failed retrieving notes data",
+                                    resp,
+                                )
+                                .await)));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::GetResponse(Err(anyhow::anyhow!(
+                                "Failed to send request: {}",
+                                err
+                            ))));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::GetResponse(Ok(notes)) => {
+                self.notes = Some(notes);
+                true
+            }
+            Msg::GetResponse(Err(err)) => {
+                log::error!("Failed retrieving notes data: {:?}", err);
+                self.notes = None;
+                true
+            }
+            Msg::EditKind(kind) => {
+                self.state_kind = kind;
+                true
+            }
+            Msg::EditCategory(category) => {
+                self.state_category = category;
+                true
+            }
+            Msg::EditContractId(contract_id) => {
+                self.state_contract_id = contract_id;
+                true
+            }
+            Msg::EditInvoiceId(invoice_id) => {
+                self.state_invoice_id = invoice_id;
+                true
+            }
+            Msg::EditAuthor(author) => {
+                self.state_author = author;
+                true
+            }
+            Msg::EditBody(body) => {
+                self.state_body = body;
+                true
+            }
+            Msg::EditPinned(pinned) => {
+                self.state_pinned = pinned;
+                true
+            }
+            Msg::AddRequest => {
+                let note = NoteRequest {
+                    kind: self.state_kind.clone(),
+                    category: self.state_category.clone(),
+                    contract_id: self.state_contract_id.trim().parse().ok(),
+                    invoice_id: self.state_invoice_id.trim().parse().ok(),
+                    author: self.state_author.clone(),
+                    body: self.state_body.clone(),
+                    pinned: self.state_pinned,
+                };
+
+                let validation_result = note.validate();
+
+                if validation_result.is_err() {
+                    link.send_message(Msg::AddResponse(Err(anyhow::anyhow!(
+                        "Validation failed: {:?}",
+                        validation_result
+                    ))));
+                    return false;
+                }
+
+                log::info!("Adding note to customer: {:?}", note);
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let note_json = JsValue::from(serde_json::to_string(&note).unwrap());
+
+                    let add_note_req = Request::post(
+                        format!(
+                            "http://localhost:8000/api/v1/customer/{}/note",
+                            props.customer_id
+                        )
+                        .as_str(),
+                    )
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json")
+                    .body(note_json)
+                    .expect("Failed to build request.");
+
+                    let resp = add_note_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 201 {
+                                link.send_message(Msg::AddResponse(Ok(())));
+                            } else {
+                                link.send_message(Msg::AddResponse(Err(problem::error(
+                                    "Failed to add note",
+                                    resp,
+                                )
+                                .await)));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::AddResponse(Err(anyhow::anyhow!(
+                                "Failed to send request: {}",
+                                err
+                            ))));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::AddResponse(Ok(_)) => {
+                self.state_body = String::new();
+                self.state_pinned = false;
+                link.send_message(Msg::GetRequest);
+                true
+            }
+            Msg::AddResponse(Err(err)) => {
+                log::error!("Failed to add note: {:?}", err);
+                false
+            }
+            Msg::TogglePinRequest(note_id) => {
+                let Some(note) = self.notes.iter().flatten().find(|note| note.id == note_id) else {
+                    return false;
+                };
+
+                let version = note.version;
+                let note = NoteRequest {
+                    kind: note.kind.clone(),
+                    category: note.category.clone(),
+                    contract_id: note.contract_id,
+                    invoice_id: note.invoice_id,
+                    author: note.author.clone(),
+                    body: note.body.clone(),
+                    pinned: !note.pinned,
+                };
+
+                log::info!("Setting pinned to {} on note {}", note.pinned, note_id);
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let note_json = JsValue::from(serde_json::to_string(&note).unwrap());
+
+                    let update_note_req = Request::put(
+                        format!(
+                            "http://localhost:8000/api/v1/customer/{}/note/{}",
+                            props.customer_id, note_id
+                        )
+                        .as_str(),
+                    )
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json")
+                    .header("If-Match", &etag::of(version))
+                    .body(note_json)
+                    .expect("Failed to build request.");
+
+                    let resp = update_note_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 200 {
+                                link.send_message(Msg::TogglePinResponse(Ok(())));
+                            } else {
+                                link.send_message(Msg::TogglePinResponse(Err(problem::error(
+                                    "Failed to update note",
+                                    resp,
+                                )
+                                .await)));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::TogglePinResponse(Err(anyhow::anyhow!(
+                                "Failed to send request: {}",
+                                err
+                            ))));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::TogglePinResponse(Ok(_)) => {
+                link.send_message(Msg::GetRequest);
+                false
+            }
+            Msg::TogglePinResponse(Err(err)) => {
+                log::error!("Failed to update note: {:?}", err);
+                false
+            }
+            Msg::RemoveRequest(note_id, version) => {
+                log::info!("Removing note {}", note_id);
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let remove_note_req = Request::delete(
+                        format!(
+                            "http://localhost:8000/api/v1/customer/{}/note/{}",
+                            props.customer_id, note_id
+                        )
+                        .as_str(),
+                    )
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json")
+                    .header("If-Match", &etag::of(version));
+
+                    let resp = remove_note_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 204 {
+                                link.send_message(Msg::RemoveResponse(Ok(())));
+                            } else {
+                                link.send_message(Msg::RemoveResponse(Err(problem::error(
+                                    "Failed to remove note",
+                                    resp,
+                                )
+                                .await)));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::RemoveResponse(Err(anyhow::anyhow!(
+                                "Failed to send request: {}",
+                                err
+                            ))));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::RemoveResponse(Ok(_)) => {
+                link.send_message(Msg::GetRequest);
+                false
+            }
+            Msg::RemoveResponse(Err(err)) => {
+                log::error!("Failed to remove note: {:?}", err);
+                false
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <>
+                { self.render_form(ctx) }
+                { self.render_notes(ctx) }
+            </>
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use crate::views::auth;
+use common::export::ExportFormat;
+use common::user::ACCESS_TOKEN;
+use material_yew::MatButton;
+use web_sys::Url;
+use yew::prelude::*;
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct ExportProps {
+    /// Path of the list endpoint, e.g. `/api/v1/customer`.
+    pub path: AttrValue,
+    /// Filters of the list, the export holding every record matching them.
+    #[prop_or_default]
+    pub filters: Vec<(String, String)>,
+}
+
+/// Buttons downloading every record of a list in each export format, streamed by
+/// the backend rather than built page by page.
+#[function_component(Export)]
+pub fn export(props: &ExportProps) -> Html {
+    ExportFormat::ALL
+        .into_iter()
+        .filter(|format| *format != ExportFormat::Json)
+        .map(|format| {
+            let Ok(url) = Url::new(&format!("http://localhost:8000{}", props.path)) else {
+                return html! {};
+            };
+            let params = url.search_params();
+            for (name, value) in &props.filters {
+                params.append(name, value);
+            }
+            params.append("format", format.extension());
+            // the downloads cannot send the Authorization header
+            params.append(ACCESS_TOKEN, &auth::token().unwrap_or_default());
+
+            html! {
+                <a href={url.href()} download="">
+                    <MatButton label={format!("Export {}", format.extension().to_uppercase())}
+                        icon={AttrValue::from("download")} />
+                </a>
+            }
+        })
+        .collect()
+}
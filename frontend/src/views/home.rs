@@ -1,3 +1,4 @@
+use crate::views::export::Export;
 use yew::prelude::*;
 
 #[function_component(Home)]
@@ -8,6 +9,12 @@ pub fn home() -> Html {
             <p>
                 {"Welcome to the ISP Manager! Here you can manage all the data related to your customers and subscriptions."}
             </p>
+
+            <h3>{"Invoices"}</h3>
+            <Export path="/api/v1/invoice" />
+
+            <h3>{"Payments"}</h3>
+            <Export path="/api/v1/payment" />
         </div>
     }
 }
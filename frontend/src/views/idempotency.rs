@@ -0,0 +1,10 @@
+use chrono::Utc;
+
+/// New `Idempotency-Key` for a creation request, which is sent again with each retry
+/// of the request so that the backend executes it only once.
+pub(crate) fn key() -> String {
+    web_sys::window()
+        .and_then(|window| window.crypto().ok())
+        .map(|crypto| crypto.random_uuid())
+        .unwrap_or_else(|| Utc::now().timestamp_micros().to_string())
+}
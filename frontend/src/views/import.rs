@@ -0,0 +1,378 @@
+use crate::views::auth;
+use crate::views::problem::{self, Language};
+use common::bulk::BulkItemStatus;
+use common::import::{ImportResponse, ImportRowResult, FILE_PART, MAPPING_PART};
+use gloo_net::http::Request;
+use material_yew::list::GraphicType;
+use material_yew::select::ListIndex::Single;
+use material_yew::select::SelectedDetail;
+use material_yew::{
+    MatButton, MatCircularProgress, MatIconButton, MatListItem, MatSelect, MatSnackbar,
+};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use web_sys::{Event, File, FormData, HtmlInputElement};
+use yew::{html, Component, Context, Html, TargetCast};
+
+/// Records that can be imported, with the path of their import endpoint.
+const ENTITIES: [(&str, &str); 3] = [
+    ("Customers", "customer"),
+    ("Subscriptions", "subscription"),
+    ("Contracts", "contract"),
+];
+
+type Report = ImportResponse<Value>;
+
+pub struct Import {
+    state_entity: usize,
+    state_file: Option<File>,
+    /// Field of each column, empty until the first preview maps the columns by name.
+    state_mapping: BTreeMap<String, String>,
+    report: Option<Report>,
+    state_error: Option<String>,
+    state_loading: bool,
+}
+
+pub enum Msg {
+    EditEntity(usize),
+    EditFile(Option<File>),
+    EditMapping(String, Option<String>),
+    ImportRequest(bool),
+    ImportResponse(Result<Report, anyhow::Error>),
+    ShowErrorSnackbar(anyhow::Error),
+    HideErrorSnackbar,
+    ToggleLoading,
+}
+
+impl Import {
+    fn render_form(&self, ctx: &Context<Import>) -> Html {
+        let onchange = ctx.link().callback(|e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            Msg::EditFile(input.files().and_then(|files| files.get(0)))
+        });
+
+        html! {
+            <div>
+                <div class="form-input">
+                    <MatSelect
+                            label="Records"
+                            outlined=true
+                            required=true
+                            icon="category"
+                            onselected={ctx.link().callback(|e: SelectedDetail| {
+                                let Single(Some(index)) = e.index else { return Msg::EditEntity(0) };
+                                Msg::EditEntity(index)
+                            })}>
+                            {
+                                ENTITIES.iter().enumerate().map(|(index, (label, _))| {
+                                    html! {
+                                        <MatListItem value={index.to_string()} selected={index == self.state_entity} graphic={GraphicType::Icon}>{ label }</MatListItem>
+                                    }
+                                }).collect::<Html>()
+                            }
+                    </MatSelect>
+
+                    <input type="file" accept=".csv,.xlsx" {onchange} />
+                </div>
+
+                <div class="row-flex">
+                    <button class="btn-success" disabled={self.state_file.is_none()}
+                        onclick={ctx.link().callback(|_| Msg::ImportRequest(false))}>
+                        <MatButton label="Preview" icon="preview" raised=true />
+                    </button>
+
+                    <button class="btn-warning" disabled={!self.can_commit()}
+                        onclick={ctx.link().callback(|_| Msg::ImportRequest(true))}>
+                        <MatButton label="Import" icon="upload_file" raised=true />
+                    </button>
+
+                    {
+                        if self.state_loading {
+                            html! {
+                                <MatCircularProgress indeterminate=true />
+                            }
+                        } else {
+                            html! {}
+                        }
+                    }
+                </div>
+            </div>
+        }
+    }
+
+    /// Only a previewed file whose rows are all valid is imported.
+    fn can_commit(&self) -> bool {
+        matches!(&self.report, Some(report) if !report.committed && report.failed == 0 && !report.rows.is_empty())
+    }
+
+    fn render_mapping(&self, ctx: &Context<Import>, report: &Report) -> Html {
+        html! {
+            <table class="tftable" border="1">
+                <thead>
+                    <tr>
+                        <th>{ "Column" }</th>
+                        <th>{ "Field" }</th>
+                    </tr>
+                </thead>
+
+                <tbody>
+                    {
+                        report.columns.iter().map(|column| {
+                            let mapped = report.mapping.get(column);
+                            let fields = report.fields.clone();
+                            let name = column.clone();
+                            let onselected = ctx.link().callback(move |e: SelectedDetail| {
+                                let field = match e.index {
+                                    Single(Some(index)) if index > 0 => fields.get(index - 1).cloned(),
+                                    _ => None,
+                                };
+                                Msg::EditMapping(name.clone(), field)
+                            });
+
+                            html! {
+                                <tr>
+                                    <td>{ column }</td>
+                                    <td>
+                                        <MatSelect outlined=true {onselected}>
+                                            <MatListItem value="0" selected={mapped.is_none()}>{ "Not imported" }</MatListItem>
+                                            {
+                                                report.fields.iter().enumerate().map(|(index, field)| {
+                                                    html! {
+                                                        <MatListItem value={(index + 1).to_string()} selected={mapped == Some(field)}>{ field }</MatListItem>
+                                                    }
+                                                }).collect::<Html>()
+                                            }
+                                        </MatSelect>
+                                    </td>
+                                </tr>
+                            }
+                        }).collect::<Html>()
+                    }
+                </tbody>
+            </table>
+        }
+    }
+
+    fn render_rows(report: &Report) -> Html {
+        let fields: Vec<&String> = report.mapping.values().collect();
+
+        html! {
+            <>
+                <p>
+                    {
+                        if report.committed {
+                            format!("Imported {} rows, {} failed", report.succeeded, report.failed)
+                        } else {
+                            format!("{} rows are valid, {} failed", report.succeeded, report.failed)
+                        }
+                    }
+                </p>
+
+                <table class="tftable" border="1">
+                    <thead>
+                        <tr>
+                            <th>{ "Line" }</th>
+                            <th>{ "Status" }</th>
+                            { fields.iter().map(|field| html! { <th>{ field }</th> }).collect::<Html>() }
+                            <th>{ "Errors" }</th>
+                        </tr>
+                    </thead>
+
+                    <tbody>
+                        { report.rows.iter().map(|row| Import::render_row(&fields, row)).collect::<Html>() }
+                    </tbody>
+                </table>
+            </>
+        }
+    }
+
+    fn render_row(fields: &[&String], row: &ImportRowResult<Value>) -> Html {
+        let status = match row.status {
+            BulkItemStatus::Created => "Created",
+            BulkItemStatus::Updated => "Updated",
+            BulkItemStatus::Failed => "Failed",
+            BulkItemStatus::Valid => "Valid",
+            BulkItemStatus::RolledBack => "Not imported",
+        };
+        let errors = match &row.errors {
+            Some(errors) => errors
+                .iter()
+                .map(|error| format!("{}: {}", error.field, error.field_errors.join(", ")))
+                .collect::<Vec<_>>()
+                .join("; "),
+            None => row
+                .error_code
+                .map(|code| problem::message(code, Language::current()).to_string())
+                .or_else(|| row.message.clone())
+                .unwrap_or_default(),
+        };
+
+        html! {
+            <tr>
+                <td>{ row.line }</td>
+                <td>{ status }</td>
+                {
+                    fields.iter().map(|field| html! {
+                        <td>{ row.values.get(*field).cloned().unwrap_or_default() }</td>
+                    }).collect::<Html>()
+                }
+                <td>{ errors }</td>
+            </tr>
+        }
+    }
+}
+
+impl Component for Import {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {
+            state_entity: 0,
+            state_file: None,
+            state_mapping: BTreeMap::new(),
+            report: None,
+            state_error: None,
+            state_loading: false,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let link = ctx.link().clone();
+
+        match msg {
+            Msg::EditEntity(entity) => {
+                self.state_entity = entity;
+                self.state_mapping.clear();
+                self.report = None;
+                true
+            }
+            Msg::EditFile(file) => {
+                self.state_file = file;
+                self.state_mapping.clear();
+                self.report = None;
+                true
+            }
+            Msg::EditMapping(column, field) => {
+                match field {
+                    Some(field) => self.state_mapping.insert(column, field),
+                    None => self.state_mapping.remove(&column),
+                };
+                link.send_message(Msg::ImportRequest(false));
+                false
+            }
+            Msg::ImportRequest(commit) => {
+                let Some(file) = self.state_file.clone() else {
+                    return false;
+                };
+                link.send_message(Msg::ToggleLoading);
+
+                let form = FormData::new().expect("Failed to build form.");
+                form.append_with_blob_and_filename(FILE_PART, &file, &file.name())
+                    .expect("Failed to add the file.");
+                if !self.state_mapping.is_empty() {
+                    let mapping = serde_json::to_string(&self.state_mapping).unwrap();
+                    form.append_with_str(MAPPING_PART, &mapping)
+                        .expect("Failed to add the mapping.");
+                }
+
+                let (_, entity) = ENTITIES[self.state_entity];
+                log::info!("Importing {} from {}", entity, file.name());
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let import_req =
+                        Request::post(&format!("http://localhost:8000/api/v1/{}/import", entity))
+                            .header("Authorization", &auth::bearer())
+                            .query([("commit", commit.to_string())])
+                            .body(form)
+                            .expect("Failed to build request.");
+
+                    let resp = import_req.send().await;
+
+                    match resp {
+                        Ok(resp) => match resp.status() {
+                            200 | 201 | 207 | 422 => {
+                                let report = resp.json::<Report>().await.map_err(|err| {
+                                    anyhow::anyhow!("Failed to parse response: {:?}", err)
+                                });
+
+                                link.send_message(Msg::ImportResponse(report));
+                            }
+                            _ => {
+                                link.send_message(Msg::ImportResponse(Err(problem::error(
+                                    "Failed to import the file",
+                                    resp,
+                                )
+                                .await)));
+                            }
+                        },
+                        Err(err) => {
+                            link.send_message(Msg::ImportResponse(Err(anyhow::anyhow!(
+                                "Failed to send request: {:?}",
+                                err
+                            ))));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::ImportResponse(Ok(report)) => {
+                link.send_message(Msg::ToggleLoading);
+                self.state_mapping = report.mapping.clone();
+                self.report = Some(report);
+                true
+            }
+            Msg::ImportResponse(Err(err)) => {
+                link.send_message(Msg::ToggleLoading);
+                link.send_message(Msg::ShowErrorSnackbar(err));
+                false
+            }
+            Msg::ShowErrorSnackbar(err) => {
+                self.state_error = Some(err.to_string());
+                true
+            }
+            Msg::HideErrorSnackbar => {
+                self.state_error = None;
+                true
+            }
+            Msg::ToggleLoading => {
+                self.state_loading = !self.state_loading;
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link().clone();
+
+        html! {
+            <div class="box">
+                <h2>{ "Import" }</h2>
+                { self.render_form(ctx) }
+
+                {
+                    if let Some(report) = &self.report {
+                        html! {
+                            <>
+                                { self.render_mapping(ctx, report) }
+                                { Import::render_rows(report) }
+                            </>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+
+                <MatSnackbar
+                    open={self.state_error.is_some()}
+                    label_text={self.state_error.clone().unwrap_or("".to_string())}
+                    stacked=true>
+
+                    <span onclick={link.callback(|_| Msg::HideErrorSnackbar)} class="snackbar-dismiss-slot" slot="dismiss">
+                        <MatIconButton icon="close" />
+                    </span>
+                </MatSnackbar>
+            </div>
+        }
+    }
+}
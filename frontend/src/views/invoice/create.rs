@@ -1,15 +1,19 @@
 use crate::app::Route;
+use crate::views::auth;
+use crate::views::idempotency;
+use crate::views::problem;
 use chrono::{DateTime, NaiveDate, Utc};
 use common::contract::ContractResponse;
 use common::invoice::CreateInvoiceRequest;
+use common::page::MAX_PER_PAGE;
 use gloo_net::http::Request;
 use material_yew::list::GraphicType;
 use material_yew::select::ListIndex::Single;
 use material_yew::select::SelectedDetail;
 use material_yew::text_inputs::TextFieldType;
 use material_yew::{
-    MatButton, MatCircularProgress, MatIconButton, MatListItem, MatSelect, MatSnackbar,
-    MatTextField,
+    MatButton, MatCheckbox, MatCircularProgress, MatFormfield, MatIconButton, MatListItem,
+    MatSelect, MatSnackbar, MatTextField,
 };
 use validator::Validate;
 use wasm_bindgen::JsValue;
@@ -23,8 +27,12 @@ pub struct Create {
     state_issue_date: DateTime<Utc>,
     state_due_date: DateTime<Utc>,
     state_amount: f64,
+    state_bill_penalties: bool,
     state_contract_start_date: DateTime<Utc>,
     state_contract_end_date: DateTime<Utc>,
+    /// Key of the request, kept until the form changes so that resubmitting it after a
+    /// failure does not create the invoice twice.
+    state_idempotency_key: String,
     state_error: Option<String>,
     state_loading: bool,
 }
@@ -38,6 +46,7 @@ pub enum Msg {
     EditIssueDate(DateTime<Utc>),
     EditDueDate(DateTime<Utc>),
     EditAmount(f64),
+    EditBillPenalties(bool),
     ShowErrorSnackbar(anyhow::Error),
     HideErrorSnackbar,
     ToggleLoading,
@@ -108,7 +117,7 @@ impl Create {
                         value={self.state_issue_date.format("%Y-%m-%d").to_string()}
                         oninput={ctx.link().callback(|value: String| {
                             let date = NaiveDate::parse_from_str(value.as_str(), "%Y-%m-%d").unwrap();
-                            Msg::EditIssueDate(DateTime::from_utc(date.and_hms(0, 0, 0), Utc))
+                            Msg::EditIssueDate(date.and_hms_opt(0, 0, 0).unwrap().and_utc())
                         })}
                     />
 
@@ -121,7 +130,7 @@ impl Create {
                         value={self.state_due_date.format("%Y-%m-%d").to_string()}
                         oninput={ctx.link().callback(|value: String| {
                             let date = NaiveDate::parse_from_str(value.as_str(), "%Y-%m-%d").unwrap();
-                            Msg::EditDueDate(DateTime::from_utc(date.and_hms(0, 0, 0), Utc))
+                            Msg::EditDueDate(date.and_hms_opt(0, 0, 0).unwrap().and_utc())
                         })}
                     />
 
@@ -137,6 +146,13 @@ impl Create {
                             Msg::EditAmount(value.parse::<f64>().unwrap_or(0.0))
                         })}
                     />
+
+                    <MatFormfield label="Bill late payment penalties of overdue invoices">
+                        <MatCheckbox
+                            checked={self.state_bill_penalties}
+                            onchange={ctx.link().callback(Msg::EditBillPenalties)}
+                        />
+                    </MatFormfield>
                 </div>
 
                 <div class="row-flex">
@@ -169,11 +185,29 @@ impl Component for Create {
         Self {
             contracts: None,
             state_contract_id: 0,
-            state_issue_date: Utc::now().date().and_hms(0, 0, 0),
-            state_due_date: Utc::now().date().and_hms(0, 0, 0),
+            state_issue_date: Utc::now()
+                .date_naive()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc(),
+            state_due_date: Utc::now()
+                .date_naive()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc(),
             state_amount: 0.0,
-            state_contract_start_date: Utc::now().date().and_hms(0, 0, 0),
-            state_contract_end_date: Utc::now().date().and_hms(0, 0, 0),
+            state_bill_penalties: false,
+            state_contract_start_date: Utc::now()
+                .date_naive()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc(),
+            state_contract_end_date: Utc::now()
+                .date_naive()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc(),
+            state_idempotency_key: idempotency::key(),
             state_error: None,
             state_loading: false,
         }
@@ -191,6 +225,7 @@ impl Component for Create {
                     issue_date: self.state_issue_date,
                     due_date: self.state_due_date,
                     amount: self.state_amount,
+                    bill_penalties: self.state_bill_penalties,
                 };
 
                 let validation_result = state.validate();
@@ -204,13 +239,16 @@ impl Component for Create {
                 }
 
                 let invoice = state.clone();
+                let idempotency_key = self.state_idempotency_key.clone();
                 log::info!("Creating invoice: {:?}", invoice);
 
                 wasm_bindgen_futures::spawn_local(async move {
                     let invoice_json = JsValue::from(serde_json::to_string(&invoice).unwrap());
 
-                    let create_invoice_req = Request::post("http://localhost:8000/api/invoice")
+                    let create_invoice_req = Request::post("http://localhost:8000/api/v1/invoice")
+                        .header("Authorization", &auth::bearer())
                         .header("Content-Type", "application/json")
+                        .header("Idempotency-Key", &idempotency_key)
                         .body(invoice_json)
                         .expect("Failed to build request.");
 
@@ -254,7 +292,9 @@ impl Component for Create {
                 log::info!("Fetching contracts");
 
                 wasm_bindgen_futures::spawn_local(async move {
-                    let get_contracts_req = Request::get("http://localhost:8000/api/contract")
+                    let get_contracts_req = Request::get("http://localhost:8000/api/v1/contract")
+                        .header("Authorization", &auth::bearer())
+                        .query([("per_page", MAX_PER_PAGE.to_string())])
                         .header("Content-Type", "application/json");
 
                     let resp = get_contracts_req.send().await;
@@ -269,10 +309,11 @@ impl Component for Create {
 
                                 link.send_message(Msg::GetContractsResponse(contracts));
                             } else {
-                                link.send_message(Msg::GetContractsResponse(Err(anyhow::anyhow!(
-                                    "Failed to get contracts: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::GetContractsResponse(Err(problem::error(
+                                    "Failed to get contracts",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
@@ -294,6 +335,7 @@ impl Component for Create {
                 false
             }
             Msg::EditContractId(contract_id_index) => {
+                self.state_idempotency_key = idempotency::key();
                 if let Some(contracts) = self.contracts.as_ref() {
                     self.state_contract_start_date =
                         contracts[contract_id_index as usize].start_date;
@@ -312,17 +354,25 @@ impl Component for Create {
                 }
             }
             Msg::EditIssueDate(issue_date) => {
+                self.state_idempotency_key = idempotency::key();
                 self.state_issue_date = issue_date;
                 true
             }
             Msg::EditDueDate(due_date) => {
+                self.state_idempotency_key = idempotency::key();
                 self.state_due_date = due_date;
                 true
             }
             Msg::EditAmount(amount) => {
+                self.state_idempotency_key = idempotency::key();
                 self.state_amount = amount;
                 true
             }
+            Msg::EditBillPenalties(bill_penalties) => {
+                self.state_idempotency_key = idempotency::key();
+                self.state_bill_penalties = bill_penalties;
+                true
+            }
             Msg::ShowErrorSnackbar(err) => {
                 self.state_error = Some(err.to_string());
                 true
@@ -1,6 +1,10 @@
 use crate::app::{AppLink, Route};
-use common::invoice::{InvoiceResponse, InvoiceStatus};
+use crate::views::auth;
+use crate::views::problem;
+use common::etag;
+use common::invoice::{InvoiceLineResponse, InvoiceResponse, InvoiceStatus};
 use common::payment::PaymentResponse;
+use common::penalty::PenaltyResponse;
 use gloo_net::http::Request;
 use material_yew::{MatButton, MatCircularProgress, MatIconButton};
 use yew::{html, AttrValue, Component, Context, Html, Properties};
@@ -14,6 +18,8 @@ pub struct DetailProps {
 pub struct Detail {
     invoice: Option<InvoiceResponse>,
     payments: Option<Vec<PaymentResponse>>,
+    lines: Option<Vec<InvoiceLineResponse>>,
+    penalty: Option<PenaltyResponse>,
 }
 
 pub enum Msg {
@@ -21,7 +27,11 @@ pub enum Msg {
     GetResponse(Result<InvoiceResponse, anyhow::Error>),
     GetPaymentsRequest,
     GetPaymentsResponse(Result<Vec<PaymentResponse>, anyhow::Error>),
-    DeleteRequest(u32),
+    GetLinesRequest,
+    GetLinesResponse(Result<Vec<InvoiceLineResponse>, anyhow::Error>),
+    GetPenaltyRequest,
+    GetPenaltyResponse(Result<PenaltyResponse, anyhow::Error>),
+    DeleteRequest(u32, u32),
     DeleteResponse(Result<(), anyhow::Error>),
 }
 
@@ -29,6 +39,7 @@ impl Detail {
     fn render_invoice(&self, ctx: &Context<Detail>) -> Html {
         if let Some(invoice) = &self.invoice {
             let invoice_id = invoice.id;
+            let invoice_version = invoice.version;
 
             html! {
                 <table class="tftable" border="1">
@@ -40,6 +51,7 @@ impl Detail {
                             <th>{ "Due Date" }</th>
                             <th>{ "Amount" }</th>
                             <th>{ "Status" }</th>
+                            <th>{ "VAT Rate (%)" }</th>
                             <th>{ "Actions" }</th>
                         </tr>
                     </thead>
@@ -57,7 +69,11 @@ impl Detail {
                             <td>{ &invoice.amount }</td>
                             <td>{ &invoice.status }</td>
                             <td>
-                                 <button class="btn-danger" onclick={ctx.link().callback(move |_| Msg::DeleteRequest(invoice_id))}>
+                                { &invoice.vat_rate }
+                                { if invoice.reverse_charge { " (reverse charge)" } else { "" } }
+                            </td>
+                            <td>
+                                 <button class="btn-danger" onclick={ctx.link().callback(move |_| Msg::DeleteRequest(invoice_id, invoice_version))}>
                                      <MatIconButton icon="delete" />
                                  </button>
                             </td>
@@ -100,7 +116,87 @@ impl Detail {
         }
     }
 
-    fn render_payment(&self, ctx: &Context<Detail>, payment: &PaymentResponse) -> Html {
+    fn render_lines(&self) -> Html {
+        if let Some(lines) = &self.lines {
+            html! {
+                <table class="tftable" border="1">
+                    <thead>
+                        <tr>
+                            <th>{ "Type" }</th>
+                            <th>{ "Description" }</th>
+                            <th>{ "Amount" }</th>
+                        </tr>
+                    </thead>
+
+                    <tbody>
+                        { lines.iter().map(|line| html! {
+                            <tr>
+                                <td>{ &line.line_type }</td>
+                                <td>
+                                    {
+                                        if let Some(source_invoice_id) = line.source_invoice_id {
+                                            html! {
+                                                <AppLink to={Route::InvoiceDetail { id: source_invoice_id }}>
+                                                    { &line.description }
+                                                </AppLink>
+                                            }
+                                        } else {
+                                            html! { &line.description }
+                                        }
+                                    }
+                                </td>
+                                <td>{ &line.amount }</td>
+                            </tr>
+                        }).collect::<Html>() }
+                    </tbody>
+                </table>
+            }
+        } else {
+            html! {
+                <div>
+                    <MatCircularProgress indeterminate=true />
+                </div>
+            }
+        }
+    }
+
+    fn render_penalty(&self) -> Html {
+        if let Some(penalty) = &self.penalty {
+            html! {
+                <table class="tftable" border="1">
+                    <thead>
+                        <tr>
+                            <th>{ "Daily Rate (%)" }</th>
+                            <th>{ "Days Overdue" }</th>
+                            <th>{ "Outstanding Amount" }</th>
+                            <th>{ "Accrued Penalty" }</th>
+                            <th>{ "Billed Penalty" }</th>
+                            <th>{ "Unbilled Penalty" }</th>
+                        </tr>
+                    </thead>
+
+                    <tbody>
+                        <tr>
+                            <td>{ &penalty.penalty_rate }</td>
+                            <td>{ &penalty.days_overdue }</td>
+                            <td>{ &penalty.outstanding_amount }</td>
+                            <td>{ &penalty.accrued_penalty }</td>
+                            <td>{ &penalty.billed_penalty }</td>
+                            <td>{ &penalty.unbilled_penalty }</td>
+                        </tr>
+                    </tbody>
+                </table>
+            }
+        } else {
+            html! {
+                <div>
+                    <MatCircularProgress indeterminate=true />
+                </div>
+            }
+        }
+    }
+
+    fn render_payment(&self, _ctx: &Context<Detail>, payment: &PaymentResponse) -> Html {
         html! {
             <tr>
                 <td>{ &payment.id }</td>
@@ -118,10 +214,14 @@ impl Component for Detail {
     fn create(ctx: &Context<Self>) -> Self {
         ctx.link().send_message(Msg::GetRequest);
         ctx.link().send_message(Msg::GetPaymentsRequest);
+        ctx.link().send_message(Msg::GetLinesRequest);
+        ctx.link().send_message(Msg::GetPenaltyRequest);
 
         Self {
             invoice: None,
             payments: None,
+            lines: None,
+            penalty: None,
         }
     }
 
@@ -135,8 +235,9 @@ impl Component for Detail {
 
                 wasm_bindgen_futures::spawn_local(async move {
                     let get_invoice_req = Request::get(
-                        format!("http://localhost:8000/api/invoice/{}", props.id).as_str(),
+                        format!("http://localhost:8000/api/v1/invoice/{}", props.id).as_str(),
                     )
+                    .header("Authorization", &auth::bearer())
                     .header("Content-Type", "application/json");
 
                     let resp = get_invoice_req.send().await;
@@ -150,10 +251,11 @@ impl Component for Detail {
 
                                 link.send_message(Msg::GetResponse(invoice));
                             } else {
-                                link.send_message(Msg::GetResponse(Err(anyhow::anyhow!(
-                                    "Failed to get invoice: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::GetResponse(Err(problem::error(
+                                    "Failed to get invoice",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
@@ -179,8 +281,10 @@ impl Component for Detail {
 
                 wasm_bindgen_futures::spawn_local(async move {
                     let get_payments_req = Request::get(
-                        format!("http://localhost:8000/api/invoice/{}/payment", props.id).as_str(),
+                        format!("http://localhost:8000/api/v1/invoice/{}/payment", props.id)
+                            .as_str(),
                     )
+                    .header("Authorization", &auth::bearer())
                     .header("Content-Type", "application/json");
 
                     let resp = get_payments_req.send().await;
@@ -195,10 +299,11 @@ impl Component for Detail {
 
                                 link.send_message(Msg::GetPaymentsResponse(payments));
                             } else {
-                                link.send_message(Msg::GetPaymentsResponse(Err(anyhow::anyhow!(
-                                    "Failed to get payments: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::GetPaymentsResponse(Err(problem::error(
+                                    "Failed to get payments",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
@@ -219,14 +324,115 @@ impl Component for Detail {
                 log::error!("Failed to get payments: {}", err);
                 false
             }
-            Msg::DeleteRequest(id) => {
+            Msg::GetLinesRequest => {
+                log::info!("Fetching lines for invoice with id {}", props.id);
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let get_lines_req = Request::get(
+                        format!("http://localhost:8000/api/v1/invoice/{}/line", props.id).as_str(),
+                    )
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json");
+
+                    let resp = get_lines_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 200 {
+                                let lines =
+                                    resp.json::<Vec<InvoiceLineResponse>>()
+                                        .await
+                                        .map_err(|err| {
+                                            anyhow::anyhow!("Failed to parse response: {}", err)
+                                        });
+
+                                link.send_message(Msg::GetLinesResponse(lines));
+                            } else {
+                                link.send_message(Msg::GetLinesResponse(Err(problem::error(
+                                    "Failed to get invoice lines",
+                                    resp,
+                                )
+                                .await)));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::GetLinesResponse(Err(anyhow::anyhow!(
+                                "Failed to get invoice lines: {:?}",
+                                err
+                            ))));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::GetLinesResponse(Ok(lines)) => {
+                self.lines = Some(lines);
+                true
+            }
+            Msg::GetLinesResponse(Err(err)) => {
+                log::error!("Failed to get invoice lines: {}", err);
+                false
+            }
+            Msg::GetPenaltyRequest => {
+                log::info!(
+                    "Fetching late payment penalty for invoice with id {}",
+                    props.id
+                );
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let get_penalty_req = Request::get(
+                        format!("http://localhost:8000/api/v1/invoice/{}/penalty", props.id)
+                            .as_str(),
+                    )
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json");
+
+                    let resp = get_penalty_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 200 {
+                                let penalty = resp.json::<PenaltyResponse>().await.map_err(|err| {
+                                    anyhow::anyhow!("Failed to parse response: {}", err)
+                                });
+
+                                link.send_message(Msg::GetPenaltyResponse(penalty));
+                            } else {
+                                link.send_message(Msg::GetPenaltyResponse(Err(problem::error(
+                                    "Failed to get penalty",
+                                    resp,
+                                )
+                                .await)));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::GetPenaltyResponse(Err(anyhow::anyhow!(
+                                "Failed to get penalty: {:?}",
+                                err
+                            ))));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::GetPenaltyResponse(Ok(penalty)) => {
+                self.penalty = Some(penalty);
+                true
+            }
+            Msg::GetPenaltyResponse(Err(err)) => {
+                log::error!("Failed to get penalty: {}", err);
+                false
+            }
+            Msg::DeleteRequest(id, version) => {
                 log::info!("Deleting invoice with id {}", id);
 
                 wasm_bindgen_futures::spawn_local(async move {
                     let delete_invoice_req = Request::delete(
-                        format!("http://localhost:8000/api/invoice/{}", id).as_str(),
+                        format!("http://localhost:8000/api/v1/invoice/{}", id).as_str(),
                     )
-                    .header("Content-Type", "application/json");
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json")
+                    .header("If-Match", &etag::of(version));
 
                     let resp = delete_invoice_req.send().await;
 
@@ -235,10 +441,11 @@ impl Component for Detail {
                             if resp.status() == 204 {
                                 link.send_message(Msg::DeleteResponse(Ok(())));
                             } else {
-                                link.send_message(Msg::DeleteResponse(Err(anyhow::anyhow!(
-                                    "Failed to delete invoice: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::DeleteResponse(Err(problem::error(
+                                    "Failed to delete invoice",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
@@ -270,6 +477,12 @@ impl Component for Detail {
                 <h2>{ "Invoice details" }</h2>
                 { self.render_invoice(ctx) }
 
+                <h2>{ "Lines" }</h2>
+                { self.render_lines() }
+
+                <h2>{ "Late payment penalty" }</h2>
+                { self.render_penalty() }
+
                 <h2>{ "Payments" }</h2>
                 {
                     if let Some(InvoiceResponse { status: InvoiceStatus::Unpaid, .. }) = &self.invoice {
@@ -0,0 +1,209 @@
+use crate::app::Route;
+use crate::views::auth;
+use crate::views::problem;
+use common::user::{LoginRequest, LoginResponse};
+use gloo_net::http::Request;
+use material_yew::text_inputs::TextFieldType;
+use material_yew::{MatButton, MatCircularProgress, MatIconButton, MatSnackbar, MatTextField};
+use validator::Validate;
+use wasm_bindgen::JsValue;
+use web_sys::SubmitEvent;
+use yew::{html, Component, Context, Html};
+use yew_router::scope_ext::RouterScopeExt;
+
+pub struct Login {
+    state_username: String,
+    state_password: String,
+    state_error: Option<String>,
+    state_loading: bool,
+}
+
+pub enum Msg {
+    LoginRequest,
+    LoginResponse(Result<LoginResponse, anyhow::Error>),
+    EditUsername(String),
+    EditPassword(String),
+    ShowErrorSnackbar(anyhow::Error),
+    HideErrorSnackbar,
+    ToggleLoading,
+}
+
+impl Login {
+    fn render_form(&self, ctx: &Context<Login>) -> Html {
+        let onsubmit = ctx.link().callback(|e: SubmitEvent| {
+            e.prevent_default();
+            Msg::LoginRequest
+        });
+
+        html! {
+            <form {onsubmit}>
+                <div class="form-input">
+                    <MatTextField
+                            outlined=true
+                            label="Username"
+                            icon="person"
+                            required=true
+                            value={self.state_username.clone()}
+                            oninput={ctx.link().callback(Msg::EditUsername)}
+                    />
+
+                    <MatTextField
+                            outlined=true
+                            label="Password"
+                            icon="lock"
+                            required=true
+                            field_type={TextFieldType::Password}
+                            value={self.state_password.clone()}
+                            oninput={ctx.link().callback(Msg::EditPassword)}
+                    />
+                </div>
+
+                <div class="row-flex">
+                    <button class="btn-success" type="submit">
+                        <MatButton label="Sign in" raised=true />
+                    </button>
+
+                    {
+                        if self.state_loading {
+                            html! {
+                                <MatCircularProgress indeterminate=true />
+                            }
+                        } else {
+                            html! {}
+                        }
+                    }
+                </div>
+            </form>
+        }
+    }
+}
+
+impl Component for Login {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_: &Context<Self>) -> Self {
+        Self {
+            state_username: String::new(),
+            state_password: String::new(),
+            state_error: None,
+            state_loading: false,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let link = ctx.link().clone();
+
+        match msg {
+            Msg::LoginRequest => {
+                link.send_message(Msg::ToggleLoading);
+
+                let state = LoginRequest {
+                    username: self.state_username.trim().to_string(),
+                    password: self.state_password.clone(),
+                };
+
+                let validation_result = state.validate();
+
+                if validation_result.is_err() {
+                    link.send_message(Msg::LoginResponse(Err(anyhow::anyhow!(
+                        "Validation failed: {:?}",
+                        validation_result
+                    ))));
+                    return false;
+                }
+
+                log::info!("Signing in user {}", state.username);
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let login_json = JsValue::from(serde_json::to_string(&state).unwrap());
+
+                    let login_req = Request::post("http://localhost:8000/api/v1/auth/login")
+                        .header("Content-Type", "application/json")
+                        .body(login_json)
+                        .expect("Failed to build request.");
+
+                    let resp = login_req.send().await;
+
+                    match resp {
+                        Ok(resp) => {
+                            if resp.status() == 200 {
+                                let session = resp.json::<LoginResponse>().await.map_err(|err| {
+                                    anyhow::anyhow!("Failed to parse response: {:?}", err)
+                                });
+
+                                link.send_message(Msg::LoginResponse(session));
+                            } else {
+                                link.send_message(Msg::LoginResponse(Err(problem::error(
+                                    "Failed to sign in",
+                                    resp,
+                                )
+                                .await)));
+                            }
+                        }
+                        Err(err) => {
+                            link.send_message(Msg::LoginResponse(Err(anyhow::anyhow!(
+                                "Failed to send request: {:?}",
+                                err
+                            ))));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::LoginResponse(Ok(session)) => {
+                link.send_message(Msg::ToggleLoading);
+                auth::store(&session.token);
+                self.state_password.clear();
+                link.navigator().unwrap().push(&Route::Home);
+                false
+            }
+            Msg::LoginResponse(Err(err)) => {
+                link.send_message(Msg::ToggleLoading);
+                link.send_message(Msg::ShowErrorSnackbar(err));
+                false
+            }
+            Msg::EditUsername(username) => {
+                self.state_username = username;
+                true
+            }
+            Msg::EditPassword(password) => {
+                self.state_password = password;
+                true
+            }
+            Msg::ShowErrorSnackbar(err) => {
+                self.state_error = Some(err.to_string());
+                true
+            }
+            Msg::HideErrorSnackbar => {
+                self.state_error = None;
+                true
+            }
+            Msg::ToggleLoading => {
+                self.state_loading = !self.state_loading;
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link().clone();
+
+        html! {
+            <div class="box">
+                <h2>{ "Sign in" }</h2>
+                { self.render_form(ctx) }
+
+                <MatSnackbar
+                    open={self.state_error.is_some()}
+                    label_text={self.state_error.clone().unwrap_or("".to_string())}
+                    stacked=true>
+
+                    <span onclick={link.callback(|_| Msg::HideErrorSnackbar)} class="snackbar-dismiss-slot" slot="dismiss">
+                        <MatIconButton icon="close" />
+                    </span>
+                </MatSnackbar>
+            </div>
+        }
+    }
+}
@@ -1,6 +1,14 @@
+pub(crate) mod auth;
+pub(crate) mod conflict;
 pub(crate) mod contract;
 pub(crate) mod customer;
+pub(crate) mod export;
 pub(crate) mod home;
+pub(crate) mod idempotency;
+pub(crate) mod import;
 pub(crate) mod invoice;
+pub(crate) mod login;
 pub(crate) mod payment;
+pub(crate) mod problem;
+pub(crate) mod segment;
 pub(crate) mod subscription;
@@ -1,6 +1,10 @@
 use crate::app::Route;
-use chrono::{DateTime, Utc};
+use crate::views::auth;
+use crate::views::idempotency;
+use crate::views::problem;
+use chrono::Utc;
 use common::invoice::InvoiceResponse;
+use common::page::MAX_PER_PAGE;
 use common::payment::CreatePaymentRequest;
 use gloo_net::http::Request;
 use material_yew::list::GraphicType;
@@ -21,6 +25,9 @@ pub struct Create {
     invoices: Option<Vec<InvoiceResponse>>,
     state_invoice_id: u32,
     state_amount: f64,
+    /// Key of the request, kept until the form changes so that resubmitting it after a
+    /// failure does not create the payment twice.
+    state_idempotency_key: String,
     state_error: Option<String>,
     state_loading: bool,
 }
@@ -118,6 +125,7 @@ impl Component for Create {
             invoices: None,
             state_invoice_id: 0,
             state_amount: 0.0,
+            state_idempotency_key: idempotency::key(),
             state_error: None,
             state_loading: false,
         }
@@ -133,7 +141,11 @@ impl Component for Create {
                 let state = CreatePaymentRequest {
                     invoice_id: self.state_invoice_id,
                     amount: self.state_amount,
-                    payment_date: Utc::now().date().and_hms(0, 0, 0),
+                    payment_date: Utc::now()
+                        .date_naive()
+                        .and_hms_opt(0, 0, 0)
+                        .unwrap()
+                        .and_utc(),
                 };
 
                 let validation_result = state.validate();
@@ -147,13 +159,16 @@ impl Component for Create {
                 }
 
                 let payment = state.clone();
+                let idempotency_key = self.state_idempotency_key.clone();
                 log::info!("Creating payment: {:?}", payment);
 
                 wasm_bindgen_futures::spawn_local(async move {
                     let payment_json = JsValue::from(serde_json::to_string(&payment).unwrap());
 
-                    let create_payment_req = Request::post("http://localhost:8000/api/payment")
+                    let create_payment_req = Request::post("http://localhost:8000/api/v1/payment")
+                        .header("Authorization", &auth::bearer())
                         .header("Content-Type", "application/json")
+                        .header("Idempotency-Key", &idempotency_key)
                         .body(payment_json)
                         .expect("Failed to build request.");
 
@@ -195,9 +210,13 @@ impl Component for Create {
                 log::info!("Fetching invoices");
 
                 wasm_bindgen_futures::spawn_local(async move {
-                    let get_invoices_req =
-                        Request::get("http://localhost:8000/api/invoice?status=UNPAID")
-                            .header("Content-Type", "application/json");
+                    let get_invoices_req = Request::get("http://localhost:8000/api/v1/invoice")
+                        .header("Authorization", &auth::bearer())
+                        .query([
+                            ("status", "UNPAID".to_string()),
+                            ("per_page", MAX_PER_PAGE.to_string()),
+                        ])
+                        .header("Content-Type", "application/json");
 
                     let resp = get_invoices_req.send().await;
 
@@ -211,10 +230,11 @@ impl Component for Create {
 
                                 link.send_message(Msg::GetInvoicesResponse(invoices));
                             } else {
-                                link.send_message(Msg::GetInvoicesResponse(Err(anyhow::anyhow!(
-                                    "Failed to get invoices: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::GetInvoicesResponse(Err(problem::error(
+                                    "Failed to get invoices",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
@@ -236,6 +256,7 @@ impl Component for Create {
                 false
             }
             Msg::EditInvoiceId(invoice_id_index) => {
+                self.state_idempotency_key = idempotency::key();
                 if let Some(invoices) = self.invoices.as_ref() {
                     let invoice_id = invoices[invoice_id_index as usize].id;
                     self.state_invoice_id = invoice_id;
@@ -246,6 +267,7 @@ impl Component for Create {
                 }
             }
             Msg::EditAmount(amount) => {
+                self.state_idempotency_key = idempotency::key();
                 self.state_amount = amount;
                 true
             }
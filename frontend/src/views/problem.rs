@@ -0,0 +1,249 @@
+use crate::views::auth;
+use common::error::{ErrorCode, Problem};
+use gloo_net::http::Response;
+
+/// Languages the errors of the backend are shown in.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum Language {
+    English,
+    Romanian,
+}
+
+impl Language {
+    /// Preferred language of the browser, English unless it is Romanian.
+    pub(crate) fn current() -> Self {
+        let language = web_sys::window()
+            .and_then(|window| window.navigator().language())
+            .unwrap_or_default();
+
+        if language.starts_with("ro") {
+            Language::Romanian
+        } else {
+            Language::English
+        }
+    }
+}
+
+/// Problem details of a request that failed with `resp`, or else an error telling
+/// its status. The user is sent back to the login page once their session is over.
+pub(crate) async fn read(resp: Response) -> Result<Problem, anyhow::Error> {
+    let status = resp.status();
+    let status_text = resp.status_text();
+
+    if status == 401 && auth::token().is_some() {
+        auth::clear();
+        auth::redirect_to_login();
+    }
+
+    resp.json::<Problem>()
+        .await
+        .map_err(|_| anyhow::anyhow!("{} {}", status, status_text))
+}
+
+/// Error of the `action` that failed with the problem, told by the localized
+/// message of its code.
+pub(crate) fn failed(action: &str, problem: Result<Problem, anyhow::Error>) -> anyhow::Error {
+    match problem {
+        Ok(problem) => anyhow::anyhow!(
+            "{}: {} (ref. {})",
+            action,
+            describe(&problem),
+            problem.correlation_id
+        ),
+        Err(err) => anyhow::anyhow!("{}: {}", action, err),
+    }
+}
+
+/// Error of the `action` whose request failed with `resp`.
+pub(crate) async fn error(action: &str, resp: Response) -> anyhow::Error {
+    failed(action, read(resp).await)
+}
+
+/// Localized message of the problem, listing the fields that failed validation.
+pub(crate) fn describe(problem: &Problem) -> String {
+    let text = message(problem.code, Language::current());
+
+    match &problem.errors {
+        Some(errors) if !errors.is_empty() => {
+            let fields = errors
+                .iter()
+                .map(|error| error.field.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("{} ({})", text, fields)
+        }
+        _ => text.to_string(),
+    }
+}
+
+/// Message of the error code, in the language.
+pub(crate) fn message(code: ErrorCode, language: Language) -> &'static str {
+    let (english, romanian) = match code {
+        ErrorCode::NotFound => ("Not found", "Nu a fost găsit"),
+        ErrorCode::MethodNotAllowed => ("Operation not allowed", "Operație nepermisă"),
+        ErrorCode::InvalidBody => ("The request is malformed", "Cererea este incorectă"),
+        ErrorCode::ValidationFailed => ("Some fields are invalid", "Unele câmpuri sunt invalide"),
+        ErrorCode::InternalError => (
+            "Something went wrong, please try again later",
+            "A apărut o eroare, încercați din nou mai târziu",
+        ),
+        ErrorCode::DatabaseError => (
+            "The request could not be executed",
+            "Cererea nu a putut fi executată",
+        ),
+        ErrorCode::UniqueViolation => (
+            "A record with the same data already exists",
+            "Există deja o înregistrare cu aceleași date",
+        ),
+        ErrorCode::FkConflict => (
+            "The record is still used by other records",
+            "Înregistrarea este folosită de alte înregistrări",
+        ),
+        ErrorCode::CheckViolation => ("Some values are invalid", "Unele valori sunt invalide"),
+        ErrorCode::InvoiceAlreadyPaid => {
+            ("The invoice is already paid", "Factura este deja plătită")
+        }
+        ErrorCode::Overpayment => (
+            "You cannot pay more than what is left to pay of the invoice",
+            "Nu puteți plăti mai mult decât restul de plată al facturii",
+        ),
+        ErrorCode::CustomerNotFound => ("Customer not found", "Clientul nu a fost găsit"),
+        ErrorCode::AddressNotFound => ("Address not found", "Adresa nu a fost găsită"),
+        ErrorCode::ContactNotFound => ("Contact not found", "Contactul nu a fost găsit"),
+        ErrorCode::NoteNotFound => ("Note not found", "Nota nu a fost găsită"),
+        ErrorCode::ContractNotFound => ("Contract not found", "Contractul nu a fost găsit"),
+        ErrorCode::InvoiceNotFound => ("Invoice not found", "Factura nu a fost găsită"),
+        ErrorCode::PaymentNotFound => ("Payment not found", "Plata nu a fost găsită"),
+        ErrorCode::SubscriptionNotFound => {
+            ("Subscription not found", "Abonamentul nu a fost găsit")
+        }
+        ErrorCode::AddonNotFound => ("Add-on not found", "Opțiunea nu a fost găsită"),
+        ErrorCode::BundleNotFound => ("Bundle not found", "Pachetul nu a fost găsit"),
+        ErrorCode::PromotionNotFound => ("Promotion not found", "Promoția nu a fost găsită"),
+        ErrorCode::CouponNotFound => ("Coupon not found", "Cuponul nu a fost găsit"),
+        ErrorCode::TagNotFound => ("Tag not found", "Eticheta nu a fost găsită"),
+        ErrorCode::CustomFieldNotFound => (
+            "Custom field not found",
+            "Câmpul personalizat nu a fost găsit",
+        ),
+        ErrorCode::DepositNotFound => (
+            "The contract has no security deposit",
+            "Contractul nu are o garanție",
+        ),
+        ErrorCode::DuplicateCustomer => ("The customer already exists", "Clientul există deja"),
+        ErrorCode::CustomerAnonymized => ("The customer is anonymized", "Clientul este anonimizat"),
+        ErrorCode::SelfMerge => (
+            "A customer cannot be merged into itself",
+            "Un client nu poate fi unit cu el însuși",
+        ),
+        ErrorCode::CouponNotApplicable => (
+            "The coupon cannot be applied",
+            "Cuponul nu poate fi aplicat",
+        ),
+        ErrorCode::CreditCheckFailed => (
+            "The customer failed the credit check",
+            "Clientul nu a trecut verificarea de credit",
+        ),
+        ErrorCode::DepositRequired => (
+            "The customer requires a security deposit",
+            "Clientul trebuie să plătească o garanție",
+        ),
+        ErrorCode::DepositNotPending => (
+            "The security deposit is not pending",
+            "Garanția nu este în așteptare",
+        ),
+        ErrorCode::DepositNotRefundable => (
+            "The security deposit cannot be refunded",
+            "Garanția nu poate fi returnată",
+        ),
+        ErrorCode::DuplicateTag => ("The tag already exists", "Eticheta există deja"),
+        ErrorCode::UnknownTag => ("The tag does not exist", "Eticheta nu există"),
+        ErrorCode::DuplicateCustomField => (
+            "The custom field already exists",
+            "Câmpul personalizat există deja",
+        ),
+        ErrorCode::UnknownCustomField => (
+            "The custom field does not exist",
+            "Câmpul personalizat nu există",
+        ),
+        ErrorCode::InvalidCustomField => (
+            "The value of a custom field is invalid",
+            "Valoarea unui câmp personalizat este invalidă",
+        ),
+        ErrorCode::CustomFieldOptionInUse => (
+            "The option of the custom field is still in use",
+            "Opțiunea câmpului personalizat este încă folosită",
+        ),
+        ErrorCode::OutsideContractPeriod => (
+            "The dates are outside the period of the contract",
+            "Datele sunt în afara perioadei contractului",
+        ),
+        ErrorCode::NotAServiceAddress => (
+            "The address is not a service installation address of the customer",
+            "Adresa nu este o adresă de instalare a clientului",
+        ),
+        ErrorCode::ContractNotOfCustomer => (
+            "The contract does not belong to the customer",
+            "Contractul nu aparține clientului",
+        ),
+        ErrorCode::InvoiceNotOfCustomer => (
+            "The invoice does not belong to the customer",
+            "Factura nu aparține clientului",
+        ),
+        ErrorCode::PaymentBeforeInvoiceIssueDate => (
+            "The payment date is before the issue date of the invoice",
+            "Data plății este înaintea datei de emitere a facturii",
+        ),
+        ErrorCode::InvalidSort => (
+            "The list cannot be sorted so",
+            "Lista nu poate fi sortată astfel",
+        ),
+        ErrorCode::IfMatchRequired => (
+            "Reload the record before saving it",
+            "Reîncărcați înregistrarea înainte de a o salva",
+        ),
+        ErrorCode::VersionConflict => (
+            "The record has been changed by someone else",
+            "Înregistrarea a fost modificată de altcineva",
+        ),
+        ErrorCode::InvalidIdempotencyKey | ErrorCode::IdempotencyKeyReused => (
+            "The request could not be sent, please try again",
+            "Cererea nu a putut fi trimisă, încercați din nou",
+        ),
+        ErrorCode::IdempotencyKeyInProgress => (
+            "The request is still being processed",
+            "Cererea este încă în curs de procesare",
+        ),
+        ErrorCode::TooManyBulkItems => (
+            "Too many records at once",
+            "Prea multe înregistrări deodată",
+        ),
+        ErrorCode::InvalidImport => (
+            "The file cannot be imported",
+            "Fișierul nu poate fi importat",
+        ),
+        ErrorCode::Unauthenticated => (
+            "Your session has expired, please sign in again",
+            "Sesiunea a expirat, autentificați-vă din nou",
+        ),
+        ErrorCode::InvalidCredentials => (
+            "The username or password is wrong",
+            "Numele de utilizator sau parola sunt greșite",
+        ),
+        ErrorCode::AdminRequired => (
+            "Only administrators can do this",
+            "Doar administratorii pot face acest lucru",
+        ),
+        ErrorCode::UserNotFound => ("User not found", "Utilizatorul nu a fost găsit"),
+        ErrorCode::DuplicateUser => ("The user already exists", "Utilizatorul există deja"),
+        ErrorCode::OwnAccount => (
+            "You cannot delete, deactivate or demote your own account",
+            "Nu vă puteți șterge, dezactiva sau retrograda propriul cont",
+        ),
+    };
+
+    match language {
+        Language::English => english,
+        Language::Romanian => romanian,
+    }
+}
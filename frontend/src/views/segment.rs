@@ -0,0 +1,304 @@
+use crate::views::auth;
+use crate::views::problem;
+use common::field::{
+    CustomField, CustomFieldResponse, CustomFieldValueResponse, CustomFieldValues, Entity,
+    FieldType,
+};
+use common::tag::{TagAssignmentRequest, TagResponse};
+use gloo_net::http::Request;
+use material_yew::list::GraphicType;
+use material_yew::select::ListIndex::Single;
+use material_yew::select::SelectedDetail;
+use material_yew::text_inputs::TextFieldType;
+use material_yew::{
+    MatCheckbox, MatCircularProgress, MatFormfield, MatListItem, MatSelect, MatTextField,
+};
+use wasm_bindgen::JsValue;
+use yew::{html, AttrValue, Callback, Component, Context, Html, Properties};
+
+/// Tags and custom field values of a customer or contract, as edited in its forms.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Segmentation {
+    pub tags: Vec<String>,
+    pub fields: CustomFieldValues,
+}
+
+impl Segmentation {
+    /// Replaces the tags and custom field values of the customer or contract `id`.
+    pub async fn save(&self, entity: &Entity, id: u32) -> Result<(), anyhow::Error> {
+        let url = format!(
+            "http://localhost:8000/api/v1/{}/{}",
+            entity.to_string().to_lowercase(),
+            id
+        );
+
+        let tags = TagAssignmentRequest {
+            tags: self.tags.clone(),
+        };
+        let tags_json = JsValue::from(serde_json::to_string(&tags).unwrap());
+
+        let resp = Request::put(format!("{}/tag", url).as_str())
+            .header("Authorization", &auth::bearer())
+            .header("Content-Type", "application/json")
+            .body(tags_json)
+            .expect("Failed to build request.")
+            .send()
+            .await
+            .map_err(|err| anyhow::anyhow!("Failed to send request: {:?}", err))?;
+
+        if resp.status() != 200 {
+            return Err(problem::error("Failed to save tags", resp).await);
+        }
+
+        let fields_json = JsValue::from(serde_json::to_string(&self.fields).unwrap());
+
+        let resp = Request::put(format!("{}/field", url).as_str())
+            .header("Authorization", &auth::bearer())
+            .header("Content-Type", "application/json")
+            .body(fields_json)
+            .expect("Failed to build request.")
+            .send()
+            .await
+            .map_err(|err| anyhow::anyhow!("Failed to send request: {:?}", err))?;
+
+        if resp.status() != 200 {
+            return Err(problem::error("Failed to save custom fields", resp).await);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct SegmentProps {
+    pub entity: Entity,
+    /// Record whose current tags and values are loaded, `None` when creating one.
+    #[prop_or_default]
+    pub id: Option<u32>,
+    pub onchange: Callback<Segmentation>,
+}
+
+/// Editor of the tags and custom fields of a customer or contract, reporting every
+/// change to its parent, which saves them along with the record.
+pub struct Segment {
+    tags: Option<Vec<TagResponse>>,
+    fields: Option<Vec<CustomFieldResponse>>,
+    state: Segmentation,
+}
+
+pub enum Msg {
+    GetDefinitionsResponse(Result<(Vec<TagResponse>, Vec<CustomFieldResponse>), anyhow::Error>),
+    GetValuesResponse(Result<Segmentation, anyhow::Error>),
+    EditTag(String, bool),
+    EditField(String, String),
+}
+
+async fn get_json<T: serde::de::DeserializeOwned>(url: &str) -> Result<T, anyhow::Error> {
+    let resp = Request::get(url)
+        .header("Authorization", &auth::bearer())
+        .header("Content-Type", "application/json")
+        .send()
+        .await
+        .map_err(|err| anyhow::anyhow!("Failed to send request: {:?}", err))?;
+
+    if resp.status() != 200 {
+        return Err(anyhow::anyhow!("Failed to get {}: {:?}", url, resp));
+    }
+
+    resp.json()
+        .await
+        .map_err(|err| anyhow::anyhow!("Failed to parse response: {:?}", err))
+}
+
+impl Segment {
+    fn render_tags(&self, ctx: &Context<Segment>, tags: &[TagResponse]) -> Html {
+        html! {
+            <div class="row-flex">
+                {
+                    tags.iter().map(|tag| {
+                        let name = tag.name.clone();
+
+                        html! {
+                            <MatFormfield label={tag.name.clone()}>
+                                <MatCheckbox
+                                    checked={self.state.tags.contains(&tag.name)}
+                                    onchange={ctx.link().callback(move |checked| Msg::EditTag(name.clone(), checked))}
+                                />
+                            </MatFormfield>
+                        }
+                    }).collect::<Html>()
+                }
+            </div>
+        }
+    }
+
+    fn render_field(&self, ctx: &Context<Segment>, field: &CustomFieldResponse) -> Html {
+        let key = field.key.clone();
+        let value = self
+            .state
+            .fields
+            .get(&field.key)
+            .cloned()
+            .unwrap_or_default();
+
+        if field.field_type == FieldType::Enum {
+            let options = field.options.clone();
+
+            return html! {
+                <MatSelect
+                    label={field.label.clone()}
+                    outlined=true
+                    onselected={ctx.link().callback(move |e: SelectedDetail| {
+                        let value = match e.index {
+                            Single(Some(index)) if index > 0 => options[index - 1].clone(),
+                            _ => String::new(),
+                        };
+                        Msg::EditField(key.clone(), value)
+                    })}>
+                    <MatListItem value="" graphic={GraphicType::Icon} selected={value.is_empty()}>{ "-" }</MatListItem>
+                    {
+                        field.options.iter().map(|option| html! {
+                            <MatListItem value={option.clone()} graphic={GraphicType::Icon} selected={*option == value}>{ option }</MatListItem>
+                        }).collect::<Html>()
+                    }
+                </MatSelect>
+            };
+        }
+
+        let field_type = match field.field_type {
+            FieldType::Number => TextFieldType::Number,
+            FieldType::Date => TextFieldType::Date,
+            _ => TextFieldType::Text,
+        };
+
+        let definition = CustomField {
+            id: field.id,
+            entity: field.entity.clone(),
+            key: field.key.clone(),
+            label: field.label.clone(),
+            field_type: field.field_type.clone(),
+            options: field.options.clone(),
+            version: field.version,
+        };
+        let helper = definition
+            .parse_value(&value)
+            .err()
+            .filter(|_| !value.trim().is_empty())
+            .map(|e| AttrValue::from(e.to_string()));
+
+        html! {
+            <MatTextField
+                outlined=true
+                label={field.label.clone()}
+                {field_type}
+                helper={helper}
+                helper_persistent=true
+                value={value}
+                oninput={ctx.link().callback(move |value| Msg::EditField(key.clone(), value))}
+            />
+        }
+    }
+}
+
+impl Component for Segment {
+    type Message = Msg;
+    type Properties = SegmentProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let link = ctx.link().clone();
+        let props = ctx.props().clone();
+
+        wasm_bindgen_futures::spawn_local(async move {
+            let tags = get_json::<Vec<TagResponse>>("http://localhost:8000/api/v1/tag").await;
+            let fields = get_json::<Vec<CustomFieldResponse>>(
+                format!("http://localhost:8000/api/v1/field?entity={}", props.entity).as_str(),
+            )
+            .await;
+
+            link.send_message(Msg::GetDefinitionsResponse(
+                tags.and_then(|tags| fields.map(|fields| (tags, fields))),
+            ));
+
+            if let Some(id) = props.id {
+                let url = format!(
+                    "http://localhost:8000/api/v1/{}/{}",
+                    props.entity.to_string().to_lowercase(),
+                    id
+                );
+
+                let tags = get_json::<Vec<TagResponse>>(format!("{}/tag", url).as_str()).await;
+                let values =
+                    get_json::<Vec<CustomFieldValueResponse>>(format!("{}/field", url).as_str())
+                        .await;
+
+                link.send_message(Msg::GetValuesResponse(tags.and_then(|tags| {
+                    values.map(|values| Segmentation {
+                        tags: tags.into_iter().map(|tag| tag.name).collect(),
+                        fields: values
+                            .into_iter()
+                            .map(|value| (value.key, value.value))
+                            .collect(),
+                    })
+                })));
+            }
+        });
+
+        Self {
+            tags: None,
+            fields: None,
+            state: Segmentation::default(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::GetDefinitionsResponse(Ok((tags, fields))) => {
+                self.tags = Some(tags);
+                self.fields = Some(fields);
+                true
+            }
+            Msg::GetDefinitionsResponse(Err(err)) => {
+                log::error!("Failed to get tags and custom fields: {:?}", err);
+                false
+            }
+            Msg::GetValuesResponse(Ok(segmentation)) => {
+                self.state = segmentation;
+                ctx.props().onchange.emit(self.state.clone());
+                true
+            }
+            Msg::GetValuesResponse(Err(err)) => {
+                log::error!("Failed to get tags and custom field values: {:?}", err);
+                false
+            }
+            Msg::EditTag(name, checked) => {
+                self.state.tags.retain(|tag| *tag != name);
+                if checked {
+                    self.state.tags.push(name);
+                }
+                ctx.props().onchange.emit(self.state.clone());
+                true
+            }
+            Msg::EditField(key, value) => {
+                self.state.fields.insert(key, value);
+                ctx.props().onchange.emit(self.state.clone());
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        match (&self.tags, &self.fields) {
+            (Some(tags), Some(fields)) => html! {
+                <div class="form-input">
+                    { self.render_tags(ctx, tags) }
+                    { fields.iter().map(|field| self.render_field(ctx, field)).collect::<Html>() }
+                </div>
+            },
+            _ => html! {
+                <div>
+                    <MatCircularProgress indeterminate=true />
+                </div>
+            },
+        }
+    }
+}
@@ -1,4 +1,6 @@
 use crate::app::Route;
+use crate::views::auth;
+use crate::views::problem;
 use common::subscription::{SubscriptionRequest, SubscriptionType};
 use gloo_net::http::Request;
 use material_yew::list::{GraphicType, SelectedDetail};
@@ -189,7 +191,8 @@ impl Component for Create {
                         JsValue::from(serde_json::to_string(&subscription).unwrap());
 
                     let create_subscription_req =
-                        Request::post("http://localhost:8000/api/subscription")
+                        Request::post("http://localhost:8000/api/v1/subscription")
+                            .header("Authorization", &auth::bearer())
                             .header("Content-Type", "application/json")
                             .body(subscription_json)
                             .expect("Failed to build request.");
@@ -201,10 +204,11 @@ impl Component for Create {
                             if resp.status() == 201 {
                                 link.send_message(Msg::CreateResponse(Ok(())));
                             } else {
-                                link.send_message(Msg::CreateResponse(Err(anyhow::anyhow!(
-                                    "Failed to create subscription: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::CreateResponse(Err(problem::error(
+                                    "Failed to create subscription",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
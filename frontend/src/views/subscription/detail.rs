@@ -1,4 +1,7 @@
 use crate::app::{AppLink, Route};
+use crate::views::auth;
+use crate::views::problem;
+use common::etag;
 use common::subscription::SubscriptionResponse;
 use gloo_net::http::Request;
 use material_yew::{MatCircularProgress, MatIconButton};
@@ -17,7 +20,7 @@ pub struct Detail {
 pub enum Msg {
     GetRequest,
     GetResponse(Result<SubscriptionResponse, anyhow::Error>),
-    DeleteRequest(u32),
+    DeleteRequest(u32, u32),
     DeleteResponse(Result<(), anyhow::Error>),
 }
 
@@ -25,6 +28,7 @@ impl Detail {
     fn render_subscription(&self, ctx: &Context<Detail>) -> Html {
         if let Some(subscription) = &self.subscription {
             let sub_id = subscription.id;
+            let sub_version = subscription.version;
 
             html! {
                 <table class="tftable" border="1">
@@ -55,7 +59,7 @@ impl Detail {
                                     </button>
                                 </AppLink>
 
-                                <button class="btn-danger" onclick={ctx.link().callback(move |_| Msg::DeleteRequest(sub_id))}>
+                                <button class="btn-danger" onclick={ctx.link().callback(move |_| Msg::DeleteRequest(sub_id, sub_version))}>
                                     <MatIconButton icon="delete" />
                                 </button>
                             </td>
@@ -93,8 +97,9 @@ impl Component for Detail {
 
                 wasm_bindgen_futures::spawn_local(async move {
                     let get_subscription_req = Request::get(
-                        format!("http://localhost:8000/api/subscription/{}", props.id).as_str(),
+                        format!("http://localhost:8000/api/v1/subscription/{}", props.id).as_str(),
                     )
+                    .header("Authorization", &auth::bearer())
                     .header("Content-Type", "application/json");
 
                     let resp = get_subscription_req.send().await;
@@ -109,10 +114,11 @@ impl Component for Detail {
 
                                 link.send_message(Msg::GetResponse(subscription));
                             } else {
-                                link.send_message(Msg::GetResponse(Err(anyhow::anyhow!(
-                                    "Failed to get subscription: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::GetResponse(Err(problem::error(
+                                    "Failed to get subscription",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
@@ -133,14 +139,16 @@ impl Component for Detail {
                 log::error!("Error: {:?}", err);
                 false
             }
-            Msg::DeleteRequest(id) => {
+            Msg::DeleteRequest(id, version) => {
                 log::info!("Deleting subscription with id: {}", id);
 
                 wasm_bindgen_futures::spawn_local(async move {
                     let delete_subscription_req = Request::delete(
-                        format!("http://localhost:8000/api/subscription/{}", id).as_str(),
+                        format!("http://localhost:8000/api/v1/subscription/{}", id).as_str(),
                     )
-                    .header("Content-Type", "application/json");
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json")
+                    .header("If-Match", &etag::of(version));
 
                     let resp = delete_subscription_req.send().await;
 
@@ -149,10 +157,11 @@ impl Component for Detail {
                             if resp.status() == 204 {
                                 link.send_message(Msg::DeleteResponse(Ok(())));
                             } else {
-                                link.send_message(Msg::DeleteResponse(Err(anyhow::anyhow!(
-                                    "Failed to delete subscription: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::DeleteResponse(Err(problem::error(
+                                    "Failed to delete subscription",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
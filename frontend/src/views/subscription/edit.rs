@@ -1,4 +1,9 @@
 use crate::app::Route;
+use crate::views::auth;
+use crate::views::conflict::{Conflict, Resolution};
+use crate::views::problem;
+use common::etag;
+use common::patch;
 use common::subscription::{SubscriptionRequest, SubscriptionResponse, SubscriptionType};
 use gloo_net::http::Request;
 use material_yew::list::{GraphicType, SelectedDetail};
@@ -24,6 +29,9 @@ pub struct Edit {
     state_traffic: i32,
     state_price: f64,
     state_extra_traffic_price: f64,
+    state_original: Option<SubscriptionRequest>,
+    state_etag: String,
+    state_conflict: bool,
     state_error: Option<String>,
     state_loading: bool,
 }
@@ -38,12 +46,24 @@ pub enum Msg {
     EditTraffic(i32),
     EditPrice(f64),
     EditExtraTrafficPrice(f64),
+    EditConflict,
+    ResolveConflict(Resolution),
     ShowErrorSnackbar(anyhow::Error),
     HideErrorSnackbar,
     ToggleLoading,
 }
 
 impl Edit {
+    fn request(&self) -> SubscriptionRequest {
+        SubscriptionRequest {
+            description: self.state_description.clone(),
+            subscription_type: self.state_subscription_type.clone(),
+            traffic: self.state_traffic,
+            price: self.state_price,
+            extra_traffic_price: self.state_extra_traffic_price,
+        }
+    }
+
     fn render_form(&self, ctx: &Context<Edit>) -> Html {
         let onsubmit = ctx.link().callback(|e: SubmitEvent| {
             e.prevent_default();
@@ -162,6 +182,9 @@ impl Component for Edit {
             state_traffic: 0,
             state_price: 0.0,
             state_extra_traffic_price: 0.0,
+            state_original: None,
+            state_etag: String::new(),
+            state_conflict: false,
             state_error: None,
             state_loading: false,
         }
@@ -175,8 +198,9 @@ impl Component for Edit {
             Msg::GetRequest => {
                 wasm_bindgen_futures::spawn_local(async move {
                     let get_subscription_req = Request::get(
-                        format!("http://localhost:8000/api/subscription/{}", props.id).as_str(),
+                        format!("http://localhost:8000/api/v1/subscription/{}", props.id).as_str(),
                     )
+                    .header("Authorization", &auth::bearer())
                     .header("Content-Type", "application/json");
 
                     let resp = get_subscription_req.send().await;
@@ -190,10 +214,11 @@ impl Component for Edit {
 
                                 link.send_message(Msg::GetResponse(subscription));
                             } else {
-                                link.send_message(Msg::GetResponse(Err(anyhow::anyhow!(
-                                    "Failed to get subscription: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::GetResponse(Err(problem::error(
+                                    "Failed to get subscription",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
@@ -209,11 +234,13 @@ impl Component for Edit {
             }
             Msg::GetResponse(Ok(subscription)) => {
                 log::info!("Subscription: {:?}", subscription);
+                self.state_etag = etag::of(subscription.version);
                 self.state_description = subscription.description;
                 self.state_subscription_type = subscription.subscription_type;
                 self.state_traffic = subscription.traffic;
                 self.state_price = subscription.price;
                 self.state_extra_traffic_price = subscription.extra_traffic_price;
+                self.state_original = Some(self.request());
                 true
             }
             Msg::GetResponse(Err(err)) => {
@@ -224,13 +251,7 @@ impl Component for Edit {
             Msg::EditRequest => {
                 link.send_message(Msg::ToggleLoading);
 
-                let state = SubscriptionRequest {
-                    description: self.state_description.clone(),
-                    subscription_type: self.state_subscription_type.clone(),
-                    traffic: self.state_traffic,
-                    price: self.state_price,
-                    extra_traffic_price: self.state_extra_traffic_price,
-                };
+                let state = self.request();
 
                 let validation_result = state.validate();
 
@@ -243,17 +264,25 @@ impl Component for Edit {
                     return false;
                 }
 
-                let subscription = state.clone();
+                // only the fields changed since the subscription was loaded are sent
+                let subscription = patch::diff(
+                    &serde_json::to_value(&self.state_original).unwrap(),
+                    &serde_json::to_value(&state).unwrap(),
+                );
                 log::info!("Updating subscription with id: {}", props.id);
 
+                let if_match = self.state_etag.clone();
+
                 wasm_bindgen_futures::spawn_local(async move {
                     let subscription_json =
                         JsValue::from(serde_json::to_string(&subscription).unwrap());
 
-                    let create_subscription_req = Request::put(
-                        format!("http://localhost:8000/api/subscription/{}", props.id).as_str(),
+                    let create_subscription_req = Request::patch(
+                        format!("http://localhost:8000/api/v1/subscription/{}", props.id).as_str(),
                     )
-                    .header("Content-Type", "application/json")
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/merge-patch+json")
+                    .header("If-Match", &if_match)
                     .body(subscription_json)
                     .expect("Failed to build request.");
 
@@ -263,11 +292,14 @@ impl Component for Edit {
                         Ok(resp) => {
                             if resp.status() == 200 {
                                 link.send_message(Msg::EditResponse(Ok(())));
+                            } else if resp.status() == 412 {
+                                link.send_message(Msg::EditConflict);
                             } else {
-                                link.send_message(Msg::EditResponse(Err(anyhow::anyhow!(
-                                    "Failed to create subscription: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::EditResponse(Err(problem::error(
+                                    "Failed to create subscription",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
@@ -313,6 +345,23 @@ impl Component for Edit {
                 self.state_extra_traffic_price = extra_traffic_price;
                 true
             }
+            Msg::EditConflict => {
+                link.send_message(Msg::ToggleLoading);
+                self.state_conflict = true;
+                true
+            }
+            Msg::ResolveConflict(resolution) => {
+                self.state_conflict = false;
+                match resolution {
+                    Resolution::Reload => link.send_message(Msg::GetRequest),
+                    Resolution::Overwrite => {
+                        self.state_etag = etag::ANY.to_string();
+                        link.send_message(Msg::EditRequest);
+                    }
+                    Resolution::Dismiss => {}
+                }
+                true
+            }
             Msg::ShowErrorSnackbar(err) => {
                 self.state_error = Some(err.to_string());
                 true
@@ -336,6 +385,11 @@ impl Component for Edit {
                 <h2>{ "Edit subscription" }</h2>
                 { self.render_form(ctx) }
 
+                <Conflict
+                    open={self.state_conflict}
+                    entity="subscription"
+                    onresolve={link.callback(Msg::ResolveConflict)} />
+
                 <MatSnackbar
                     open={self.state_error.is_some()}
                     label_text={self.state_error.clone().unwrap_or("".to_string())}
@@ -1,21 +1,89 @@
 use crate::app::{AppLink, Route};
+use crate::views::auth;
+use crate::views::export::Export;
+use crate::views::problem;
+use common::etag;
+use common::page::MAX_PER_PAGE;
 use common::subscription::SubscriptionResponse;
 use gloo_net::http::Request;
-use material_yew::{MatButton, MatCircularProgress, MatIconButton};
+use material_yew::text_inputs::TextFieldType;
+use material_yew::{MatButton, MatCircularProgress, MatIconButton, MatTextField};
+use web_sys::SubmitEvent;
 use yew::{html, AttrValue, Component, Context, Html};
 
 pub struct List {
     subscriptions: Option<Vec<SubscriptionResponse>>,
+    state_type: String,
+    state_min_price: String,
+    state_max_price: String,
 }
 
 pub enum Msg {
     GetAllRequest,
+    EditType(String),
+    EditMinPrice(String),
+    EditMaxPrice(String),
     GetAllResponse(Result<Vec<SubscriptionResponse>, anyhow::Error>),
-    DeleteRequest(u32),
+    DeleteRequest(u32, u32),
     DeleteResponse(Result<(), anyhow::Error>),
 }
 
 impl List {
+    /// Filters of the list, sent along by its export as well.
+    fn filters(&self) -> Vec<(String, String)> {
+        [
+            ("type", self.state_type.to_uppercase()),
+            ("min_price", self.state_min_price.clone()),
+            ("max_price", self.state_max_price.clone()),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value.trim().to_string()))
+        .filter(|(_, value)| !value.is_empty())
+        .collect()
+    }
+
+    fn render_filters(&self, ctx: &Context<List>) -> Html {
+        let onsubmit = ctx.link().callback(|e: SubmitEvent| {
+            e.prevent_default();
+            Msg::GetAllRequest
+        });
+
+        html! {
+            <form {onsubmit}>
+                <div class="row-flex">
+                    <MatTextField
+                        outlined=true
+                        label="Type"
+                        icon="category"
+                        helper="e.g. MOBILE"
+                        value={self.state_type.clone()}
+                        oninput={ctx.link().callback(Msg::EditType)}
+                    />
+
+                    <MatTextField
+                        outlined=true
+                        label="Min price"
+                        field_type={TextFieldType::Number}
+                        value={self.state_min_price.clone()}
+                        oninput={ctx.link().callback(Msg::EditMinPrice)}
+                    />
+
+                    <MatTextField
+                        outlined=true
+                        label="Max price"
+                        field_type={TextFieldType::Number}
+                        value={self.state_max_price.clone()}
+                        oninput={ctx.link().callback(Msg::EditMaxPrice)}
+                    />
+
+                    <button class="btn-info" type="submit">
+                        <MatButton label="Filter" raised=true />
+                    </button>
+                </div>
+            </form>
+        }
+    }
+
     fn render_table(&self, ctx: &Context<List>) -> Html {
         if let Some(subs) = &self.subscriptions {
             html! {
@@ -48,6 +116,7 @@ impl List {
 
     fn render_item(&self, ctx: &Context<List>, sub: &SubscriptionResponse) -> Html {
         let sub_id = sub.id;
+        let sub_version = sub.version;
 
         html! {
             <tr>
@@ -70,7 +139,7 @@ impl List {
                         </button>
                     </AppLink>
 
-                    <button class="btn-danger" onclick={ctx.link().callback(move |_| Msg::DeleteRequest(sub_id))}>
+                    <button class="btn-danger" onclick={ctx.link().callback(move |_| Msg::DeleteRequest(sub_id, sub_version))}>
                         <MatIconButton icon="delete" />
                     </button>
                 </td>
@@ -87,6 +156,9 @@ impl Component for List {
         ctx.link().send_message(Msg::GetAllRequest);
         Self {
             subscriptions: None,
+            state_type: String::new(),
+            state_min_price: String::new(),
+            state_max_price: String::new(),
         }
     }
 
@@ -97,9 +169,18 @@ impl Component for List {
             Msg::GetAllRequest => {
                 log::info!("Requesting subscriptions");
 
+                let mut filters = self.filters();
+                filters.push(("per_page".to_string(), MAX_PER_PAGE.to_string()));
+
                 wasm_bindgen_futures::spawn_local(async move {
                     let get_subscriptions_req =
-                        Request::get("http://localhost:8000/api/subscription")
+                        Request::get("http://localhost:8000/api/v1/subscription")
+                            .header("Authorization", &auth::bearer())
+                            .query(
+                                filters
+                                    .iter()
+                                    .map(|(name, value)| (name.as_str(), value.as_str())),
+                            )
                             .header("Content-Type", "application/json");
 
                     let resp = get_subscriptions_req.send().await;
@@ -116,10 +197,11 @@ impl Component for List {
 
                                 link.send_message(Msg::GetAllResponse(subscriptions));
                             } else {
-                                link.send_message(Msg::GetAllResponse(Err(anyhow::anyhow!(
-                                    "Failed to get subscriptions: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::GetAllResponse(Err(problem::error(
+                                    "Failed to get subscriptions",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
@@ -138,14 +220,28 @@ impl Component for List {
                 true
             }
             Msg::GetAllResponse(Err(_)) => false,
-            Msg::DeleteRequest(id) => {
+            Msg::EditType(subscription_type) => {
+                self.state_type = subscription_type;
+                true
+            }
+            Msg::EditMinPrice(min_price) => {
+                self.state_min_price = min_price;
+                true
+            }
+            Msg::EditMaxPrice(max_price) => {
+                self.state_max_price = max_price;
+                true
+            }
+            Msg::DeleteRequest(id, version) => {
                 log::info!("Deleting subscription with id: {}", id);
 
                 wasm_bindgen_futures::spawn_local(async move {
                     let delete_subscription_req = Request::delete(
-                        format!("http://localhost:8000/api/subscription/{}", id).as_str(),
+                        format!("http://localhost:8000/api/v1/subscription/{}", id).as_str(),
                     )
-                    .header("Content-Type", "application/json");
+                    .header("Authorization", &auth::bearer())
+                    .header("Content-Type", "application/json")
+                    .header("If-Match", &etag::of(version));
 
                     let resp = delete_subscription_req.send().await;
 
@@ -154,10 +250,11 @@ impl Component for List {
                             if resp.status() == 204 {
                                 link.send_message(Msg::DeleteResponse(Ok(())));
                             } else {
-                                link.send_message(Msg::DeleteResponse(Err(anyhow::anyhow!(
-                                    "Failed to delete subscription: {:?}",
-                                    resp
-                                ))));
+                                link.send_message(Msg::DeleteResponse(Err(problem::error(
+                                    "Failed to delete subscription",
+                                    resp,
+                                )
+                                .await)));
                             }
                         }
                         Err(err) => {
@@ -189,7 +286,10 @@ impl Component for List {
                     <AppLink to={Route::SubscriptionCreate}>
                         <MatButton label="Create new subscription" icon={AttrValue::from("add")} raised=true />
                     </AppLink>
+
+                    <Export path="/api/v1/subscription" filters={self.filters()} />
                 </h3>
+                { self.render_filters(ctx) }
                 { self.render_table(ctx) }
             </div>
         }